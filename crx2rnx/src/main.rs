@@ -63,7 +63,7 @@ fn main() -> Result<(), rinex::Error> {
 
     let output_name = match cli.output_name() {
         Some(name) => name.clone(),
-        _ => rinex.standard_filename(cli.matches.get_flag("short"), suffix, None),
+        _ => rinex.standard_filename(cli.matches.get_flag("short"), suffix, None, None),
     };
 
     let outputpath = format!("{}/{}", workspace_path.to_string_lossy(), output_name);
@@ -1,7 +1,7 @@
 // filegen opmode
 use clap::Command;
 
-use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS};
+use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS, SHARED_OUTPUT_ARGS};
 
 pub fn subcommand() -> Command {
     Command::new("filegen")
@@ -28,6 +28,7 @@ rinex-cli \\
         )
         .next_help_heading("Production Environment")
         .args(SHARED_GENERAL_ARGS.iter())
+        .args(SHARED_OUTPUT_ARGS.iter())
         .next_help_heading("Data context")
         .args(SHARED_DATA_ARGS.iter())
 }
@@ -2,7 +2,7 @@
 use clap::{value_parser, Arg, ArgAction, Command};
 use std::path::PathBuf;
 
-use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS};
+use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS, SHARED_OUTPUT_ARGS};
 
 pub fn subcommand() -> Command {
     Command::new("merge")
@@ -47,6 +47,7 @@ rinex-cli \\
         )
         .next_help_heading("Production Environment")
         .args(SHARED_GENERAL_ARGS.iter())
+        .args(SHARED_OUTPUT_ARGS.iter())
         .next_help_heading("Data context")
         .args(SHARED_DATA_ARGS.iter())
 }
@@ -8,7 +8,8 @@ use lazy_static::lazy_static;
 
 use ::clap::{value_parser, Arg, ArgAction};
 
-use rinex::prod::{DataSource, FFU, PPU};
+use rinex::prelude::{DataSource, FFU, PPU};
+use std::path::PathBuf;
 
 /*
  * Arguments that are shared by all file operations.
@@ -32,6 +33,11 @@ Otherwise, this ecosystem prefers modern (longer) filenames that contain more in
             .long("gzip")
             .action(ArgAction::SetTrue)
             .help("Append .gz suffix and perform seamless Gzip compression."),
+        Arg::new("force")
+            .long("force")
+            .action(ArgAction::SetTrue)
+            .help("Overwrite files that already exist.
+Otherwise, existing files are preserved and the operation fails."),
         Arg::new("agency")
             .short('a')
             .long("agency")
@@ -54,6 +60,20 @@ In RINEX standards, we use \"RCVR\" when data was sampled from a hardware receiv
 Use \"STREAM\" for other stream data source, like RTCM for example.")
     ];
 
+    /*
+     * Output definition, for operations that generate a single file
+     */
+    pub static ref SHARED_OUTPUT_ARGS : Vec<Arg> = vec![
+        Arg::new("output")
+            .short('o')
+            .long("output")
+            .value_name("FILEPATH")
+            .value_parser(value_parser!(PathBuf))
+            .help("Custom output file path.
+Otherwise, the file name is determined from the production context and the file is generated in the session workspace.
+Use a \".json\" extension to dump the data in JSON format."),
+    ];
+
     pub static ref SHARED_DATA_ARGS : Vec<Arg> = vec![
         Arg::new("PPU")
             .long("ppu")
@@ -2,7 +2,7 @@
 use clap::{value_parser, Arg, ArgAction, Command};
 use std::path::PathBuf;
 
-use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS};
+use super::{SHARED_DATA_ARGS, SHARED_GENERAL_ARGS, SHARED_OUTPUT_ARGS};
 
 pub fn subcommand() -> Command {
     Command::new("sub")
@@ -24,6 +24,7 @@ This is typically used to compare two GNSS receivers together.",
         )
         .next_help_heading("Production Environment")
         .args(SHARED_GENERAL_ARGS.iter())
+        .args(SHARED_OUTPUT_ARGS.iter())
         .next_help_heading("Data context")
         .args(SHARED_DATA_ARGS.iter())
 }
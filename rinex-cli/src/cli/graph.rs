@@ -1,4 +1,5 @@
-use clap::{Arg, ArgAction, Command};
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
 
 pub fn subcommand() -> Command {
     Command::new("graph")
@@ -234,4 +235,38 @@ rinex-cli -f test_resources/IONEX/V1/CKMG0080.09I.gz -g --tec")
                 .action(ArgAction::SetTrue)
                 .help("Plot ionospheric delay per signal & SV, at latitude and longitude of signal sampling."),
         )
+        .next_help_heading("Figure rendering")
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Output path of the HTML graphs, rather than the session workspace.
+Each graph is rendered as <PATH>_<GRAPH>.html, for example --output figures/duth
+renders figures/duth_OBSERVATIONS.html. Missing directories are created."),
+        )
+        .arg(
+            Arg::new("width")
+                .long("width")
+                .value_name("PIXELS")
+                .value_parser(value_parser!(usize))
+                .requires("height")
+                .help("Figure width, in pixels. Requires --height. Otherwise, figures are autosized."),
+        )
+        .arg(
+            Arg::new("height")
+                .long("height")
+                .value_name("PIXELS")
+                .value_parser(value_parser!(usize))
+                .requires("width")
+                .help("Figure height, in pixels. Requires --width. Otherwise, figures are autosized."),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("[png,svg,jpeg,webp]")
+                .value_parser(["png", "svg", "jpeg", "webp"])
+                .help("Image format of the figure export (\"Download plot\" button). Default is png.
+Use svg for publication quality figures. Exported images follow --width and --height."),
+        )
 }
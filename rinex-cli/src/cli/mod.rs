@@ -17,6 +17,8 @@ mod identify;
 mod graph;
 // QC mode
 mod qc;
+// teqc like summary mode
+mod teqc;
 // positioning mode
 mod positioning;
 
@@ -96,8 +98,19 @@ impl Context {
      * Save HTML content, auto opens it if quiet (-q) is not turned on
      */
     pub fn render_html(&self, filename: &str, html: String) {
-        let path = self.workspace.join(filename);
-        let mut fd = self.create_file(&path);
+        self.render_html_at(&self.workspace.join(filename), html);
+    }
+    /*
+     * Save HTML content at given location, creating the parent directories.
+     * Auto opens it if quiet (-q) is not turned on
+     */
+    pub fn render_html_at(&self, path: &Path, html: String) {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).unwrap_or_else(|e| {
+                panic!("failed to create {}: {:?}", parent.display(), e);
+            });
+        }
+        let mut fd = self.create_file(path);
         write!(fd, "{}", html).unwrap_or_else(|e| {
             panic!("failed to render HTML content: {:?}", e);
         });
@@ -248,6 +261,7 @@ Otherwise it gets automatically picked up."))
                 .subcommand(qc::subcommand())
                 .subcommand(split::subcommand())
                 .subcommand(substract::subcommand())
+                .subcommand(teqc::subcommand())
                 .subcommand(time_binning::subcommand())
                 .get_matches()
             },
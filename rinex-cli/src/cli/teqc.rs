@@ -0,0 +1,40 @@
+// teqc like summary opmode
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
+
+pub fn subcommand() -> Command {
+    Command::new("teqc-report")
+        .long_flag("teqc-report")
+        .about("teqc like quality summary of each Observation RINEX file. See --teqc-report --help.")
+        .long_about(
+            "Summarizes each Observation RINEX file that was loaded, like teqc does:
+time frame, completeness, number of cycle slips and mean S1 signal strength.
+Each file is analyzed on its own, prior any preprocessing.
+When several files are loaded, an aggregate table (one row per file) follows.
+
+Example (1): summarize two files
+rinex-cli \\
+    -f test_resources/OBS/V3/DUTH0630.22O \\
+    -f test_resources/OBS/V3/VLNS0010.22O \\
+    --teqc-report
+
+Example (2): JSON output, with elevation dependent statistics
+rinex-cli \\
+    -f test_resources/OBS/V3/DUTH0630.22O \\
+    --teqc-report --pretty --nav test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+        )
+        .arg(
+            Arg::new("pretty")
+                .long("pretty")
+                .action(ArgAction::SetTrue)
+                .help("Print summaries as (pretty) JSON instead of ASCII."),
+        )
+        .arg(
+            Arg::new("nav")
+                .long("nav")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Navigation RINEX used to resolve elevation angles.
+Enables elevation dependent statistics, requires the receiver position to be defined in the Observation RINEX."),
+        )
+}
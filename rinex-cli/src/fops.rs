@@ -2,16 +2,23 @@ use crate::cli::Context;
 use crate::Error;
 use clap::ArgMatches;
 
-use std::path::PathBuf;
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 
-use rinex::{
-    prelude::{Duration, Epoch, ProductType, Rinex, RinexType},
-    preprocessing::*,
-    prod::{DataSource, DetailedProductionAttributes, ProductionAttributes, FFU, PPU},
-    Merge, Split,
-};
+use rinex::prelude::*;
+
+/*
+ * JSON description of a RINEX file
+ */
+#[derive(Serialize)]
+struct JsonRinex<'a> {
+    header: &'a Header,
+    record: &'a RinexRecord,
+}
 
 /*
  * Parses share RINEX production attributes.
@@ -91,7 +98,46 @@ fn output_filename(rinex: &Rinex, matches: &ArgMatches, prod: ProductionAttribut
     debug!("{:?}", prod);
 
     // Use smart determination
-    rinex.standard_filename(short, gzip, Some(prod))
+    rinex.standard_filename(short, gzip, Some(prod), None)
+}
+
+/*
+ * Returns the output path: either defined by User,
+ * or `default` file name within the session workspace
+ */
+fn output_path(ctx: &Context, matches: &ArgMatches, default: &str) -> PathBuf {
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => path.clone(),
+        None => ctx.workspace.join(default),
+    }
+}
+
+/*
+ * Generates `rinex` at given location, in JSON format
+ * in case of .json extension. Existing files are preserved,
+ * unless User specified --force.
+ */
+fn generate(rinex: &Rinex, path: &Path, matches: &ArgMatches) -> Result<(), Error> {
+    if path.exists() && !matches.get_flag("force") {
+        return Err(Error::OutputAlreadyExists(path.to_path_buf()));
+    }
+    let json = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+    if json {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(
+            writer,
+            &JsonRinex {
+                header: &rinex.header,
+                record: &rinex.record,
+            },
+        )?;
+    } else {
+        rinex.to_file(&path.to_string_lossy())?;
+    }
+    Ok(())
 }
 
 /*
@@ -101,7 +147,7 @@ fn output_filename(rinex: &Rinex, matches: &ArgMatches, prod: ProductionAttribut
 pub fn filegen(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
     let ctx_data = &ctx.data;
 
-    for product in [
+    let products: Vec<ProductType> = [
         ProductType::Observation,
         ProductType::DORIS,
         ProductType::MeteoObservation,
@@ -109,18 +155,28 @@ pub fn filegen(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         ProductType::HighPrecisionClock,
         ProductType::IONEX,
         ProductType::ANTEX,
-    ] {
+    ]
+    .into_iter()
+    .filter(|product| ctx_data.rinex(*product).is_some())
+    .collect();
+
+    if products.len() > 1 && matches.get_one::<PathBuf>("output").is_some() {
+        return Err(Error::AmbiguousOutputPath);
+    }
+
+    for product in products {
         if let Some(rinex) = ctx_data.rinex(product) {
             let prod = custom_prod_attributes(rinex, matches);
             let filename = output_filename(rinex, matches, prod);
 
-            let output_path = ctx.workspace.join(filename).to_string_lossy().to_string();
-
-            rinex.to_file(&output_path).unwrap_or_else(|_| {
-                panic!("failed to generate {} RINEX \"{}\"", product, output_path)
-            });
+            let output_path = output_path(ctx, matches, &filename);
+            generate(rinex, &output_path, matches)?;
 
-            info!("{} RINEX \"{}\" has been generated", product, output_path);
+            info!(
+                "{} RINEX \"{}\" has been generated",
+                product,
+                output_path.display()
+            );
         }
     }
     Ok(())
@@ -153,17 +209,13 @@ pub fn merge(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         _ => unimplemented!(),
     };
 
-    let suffix = merge_path
-        .file_name()
-        .expect("failed to determine output path")
-        .to_string_lossy()
-        .to_string();
-
-    let output_path = ctx.workspace.join(suffix).to_string_lossy().to_string();
+    let prod = custom_prod_attributes(&rinex_c, matches);
+    let filename = output_filename(&rinex_c, matches, prod);
 
-    rinex_c.to_file(&output_path)?;
+    let output_path = output_path(ctx, matches, &filename);
+    generate(&rinex_c, &output_path, matches)?;
 
-    info!("\"{}\" has been generated", output_path);
+    info!("\"{}\" has been generated", output_path.display());
     Ok(())
 }
 
@@ -240,7 +292,7 @@ pub fn split(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
                 .to_string_lossy()
                 .to_string();
 
-            rinex_a.to_file(&output)?;
+            generate(&rinex_a, Path::new(&output), matches)?;
             info!("\"{}\" has been generated", output);
 
             let first_epoch = rinex_b
@@ -271,7 +323,7 @@ pub fn split(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
                 .to_string_lossy()
                 .to_string();
 
-            rinex_b.to_file(&output)?;
+            generate(&rinex_b, Path::new(&output), matches)?;
             info!("{} RINEX \"{}\" has been generated", product, output);
         }
     }
@@ -331,7 +383,7 @@ pub fn time_binning(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
 
                 let output = ctx.workspace.join(&filename).to_string_lossy().to_string();
 
-                rinex.to_file(&output)?;
+                generate(&rinex, Path::new(&output), matches)?;
                 info!("{} RINEX \"{}\" has been generated", product, output);
 
                 first += *duration;
@@ -401,15 +453,10 @@ pub fn substract(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
 
     extension.push_str(&file_ext);
 
-    let fullpath = ctx
-        .workspace
-        .join(format!("DIFFERENCED.{}", extension))
-        .to_string_lossy()
-        .to_string();
-
-    rinex_c.to_file(&fullpath)?;
+    let fullpath = output_path(ctx, matches, &format!("DIFFERENCED.{}", extension));
+    generate(&rinex_c, &fullpath, matches)?;
 
-    info!("OBS RINEX \"{}\" has been generated", fullpath);
+    info!("OBS RINEX \"{}\" has been generated", fullpath.display());
     Ok(())
 }
 
@@ -3,16 +3,45 @@ use super::{
     build_timedomain_plot, build_world_map, Plot,
 };
 //use log::trace;
-use plotly::{layout::MapboxStyle, Trace};
+use plotly::{
+    configuration::{Configuration, ImageButtonFormats, ToImageButtonOptions},
+    layout::{Axis, MapboxStyle},
+    Trace,
+};
+
+/// Figure rendering options, applied to all plots of a [PlotContext]
+#[derive(Debug, Clone)]
+pub struct PlotSettings {
+    /// Figure (width, height) in pixels. Figures are autosized when undefined.
+    pub dimensions: Option<(usize, usize)>,
+    /// Image format of the figure export button
+    pub format: ImageButtonFormats,
+}
+
+impl Default for PlotSettings {
+    fn default() -> Self {
+        Self {
+            dimensions: None,
+            format: ImageButtonFormats::Png,
+        }
+    }
+}
 
 /// Plot Context
 pub struct PlotContext {
     plots: Vec<Plot>,
+    settings: PlotSettings,
 }
 
 impl PlotContext {
     pub fn new() -> Self {
-        Self { plots: Vec::new() }
+        Self {
+            plots: Vec::new(),
+            settings: PlotSettings::default(),
+        }
+    }
+    pub fn with_settings(self, settings: PlotSettings) -> Self {
+        Self { settings, ..self }
     }
     /*pub fn plot_item(&self) -> Option<&Plot> {
         self.plots.get(self.plots.len() - 1)
@@ -24,6 +53,17 @@ impl PlotContext {
     pub fn add_timedomain_plot(&mut self, title: &str, y_label: &str) {
         self.plots.push(build_timedomain_plot(title, y_label));
     }
+    /// Sets the (min, max) range of the Y axis of the latest time domain plot
+    pub fn set_y_range(&mut self, y_label: &str, range: (f64, f64)) {
+        let len = self.plots.len() - 1;
+        let layout = self.plots[len].layout().clone().y_axis(
+            Axis::new()
+                .title(y_label)
+                .zero_line(true)
+                .range(vec![range.0, range.1]),
+        );
+        self.plots[len].set_layout(layout);
+    }
     pub fn add_timedomain_2y_plot(&mut self, title: &str, y1_label: &str, y2_label: &str) {
         self.plots
             .push(build_timedomain_2y_plot(title, y1_label, y2_label));
@@ -62,6 +102,18 @@ impl PlotContext {
             /*if !tiny {
                 p.use_local_plotly();
             }*/
+            let mut options = ToImageButtonOptions::new().format(self.settings.format.clone());
+            if let Some((width, height)) = self.settings.dimensions {
+                let layout = p
+                    .layout()
+                    .clone()
+                    .auto_size(false)
+                    .width(width)
+                    .height(height);
+                p.set_layout(layout);
+                options = options.width(width).height(height);
+            }
+            p.set_configuration(Configuration::new().to_image_button_options(options));
             if index == 0 {
                 html.push_str(&p.to_html());
             } else {
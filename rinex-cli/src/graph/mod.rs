@@ -1,6 +1,6 @@
 use crate::{cli::Context, Error};
 use clap::ArgMatches;
-use rinex::observation::{Combination, Combine, Dcb};
+use rinex::prelude::{Combination, Combine, Dcb};
 
 use plotly::{
     common::{
@@ -11,12 +11,15 @@ use plotly::{
         MarkerSymbol,
         Mode,
     },
+    configuration::ImageButtonFormats,
     layout::{Axis, Center, DragMode, Mapbox, MapboxStyle, Margin},
     Layout, Plot, Scatter, Scatter3D,
 };
 
-use rand::Rng;
+use itertools::Itertools;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 use rinex::prelude::*;
 
@@ -26,7 +29,7 @@ use record::{
 };
 
 mod context;
-pub use context::PlotContext;
+pub use context::{PlotContext, PlotSettings};
 
 mod skyplot;
 use skyplot::skyplot;
@@ -41,7 +44,8 @@ pub use csv::csv_export_timedomain;
 
 /*
  * Generates N marker symbols to be used
- * to differentiate data
+ * to differentiate data. Symbols are picked in order,
+ * so the same data is always depicted the same way.
  */
 pub fn generate_markers(n: usize) -> Vec<MarkerSymbol> {
     //TODO lazy static
@@ -189,10 +193,9 @@ pub fn generate_markers(n: usize) -> Vec<MarkerSymbol> {
         "LineNW",
         "LineNWOpen",
     ];
-    let mut rng = rand::thread_rng();
     let mut ret: Vec<MarkerSymbol> = Vec::with_capacity(n);
-    for _ in 0..n {
-        let symbol = pool[rng.gen_range(0..25)];
+    for i in 0..n {
+        let symbol = pool[i % 25];
         let marker = match symbol {
             "Circle" => MarkerSymbol::Circle,
             "CircleOpen" => MarkerSymbol::CircleOpen,
@@ -234,6 +237,27 @@ pub fn generate_markers(n: usize) -> Vec<MarkerSymbol> {
     ret
 }
 
+/*
+ * Plotly default color sequence
+ */
+const COLORWAY: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+    "#bcbd22", "#17becf",
+];
+
+/*
+ * Assigns one color per vehicle, in PRN order,
+ * so a vehicle is depicted with the same color on all panels
+ */
+pub fn sv_colors(vehicles: impl Iterator<Item = SV>) -> HashMap<SV, &'static str> {
+    vehicles
+        .unique()
+        .sorted()
+        .enumerate()
+        .map(|(index, sv)| (sv, COLORWAY[index % COLORWAY.len()]))
+        .collect()
+}
+
 /*
  * builds a standard 2D plot single Y scale,
  * ready to plot data against time (`Epoch`)
@@ -488,6 +512,44 @@ fn atmosphere_plot(matches: &ArgMatches) -> bool {
     matches.get_flag("tropo") || matches.get_flag("tec") || matches.get_flag("ionod")
 }
 
+/*
+ * Figure rendering options, defined by User
+ */
+fn plot_settings(matches: &ArgMatches) -> PlotSettings {
+    let dimensions = match (
+        matches.get_one::<usize>("width"),
+        matches.get_one::<usize>("height"),
+    ) {
+        (Some(width), Some(height)) => Some((*width, *height)),
+        _ => None,
+    };
+    let format = match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("svg") => ImageButtonFormats::Svg,
+        Some("jpeg") => ImageButtonFormats::Jpeg,
+        Some("webp") => ImageButtonFormats::Webp,
+        _ => ImageButtonFormats::Png,
+    };
+    PlotSettings { dimensions, format }
+}
+
+/*
+ * Renders a graph family: either in the session workspace (<name>.html),
+ * or at the output path defined by User (<PATH>_<name>.html)
+ */
+fn render_graph(ctx: &Context, matches: &ArgMatches, name: &str, html: String) {
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => {
+            let prefix = path
+                .file_name()
+                .map(|prefix| prefix.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let path = path.with_file_name(format!("{}_{}.html", prefix, name));
+            ctx.render_html_at(&path, html);
+        },
+        None => ctx.render_html(&format!("{}.html", name), html),
+    }
+}
+
 pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
     /*
      * Prepare session:
@@ -498,11 +560,12 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
     if csv_export {
         ctx.create_subdir("CSV");
     }
+    let settings = plot_settings(matches);
     /*
      * Observations graphs
      */
     if matches.get_flag("obs") {
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         if ctx.data.has_observation() {
             record::plot_observations(ctx, &mut plot_ctx, csv_export);
         }
@@ -514,7 +577,7 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         }
 
         /* save observations */
-        ctx.render_html("OBSERVATIONS.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "OBSERVATIONS", plot_ctx.to_html());
     }
     /*
      * GNSS combinations graphs
@@ -525,7 +588,7 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             .observation()
             .ok_or(Error::MissingObservationRinex)?;
 
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         if matches.get_flag("if") {
             let combination = data.combine(Combination::IonosphereFree);
             plot_gnss_combination(
@@ -573,7 +636,7 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         }
 
         /* save combinations */
-        ctx.render_html("COMBINATIONS.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "COMBINATIONS", plot_ctx.to_html());
     }
     /*
      * DCB visualization
@@ -584,7 +647,7 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             .observation()
             .ok_or(Error::MissingObservationRinex)?;
 
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         let data = data.dcb();
         plot_gnss_dcb(
             &data,
@@ -594,7 +657,7 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
         );
 
         /* save DCB */
-        ctx.render_html("DCB.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "DCB", plot_ctx.to_html());
     }
     if matches.get_flag("mp") {
         let data = ctx
@@ -602,15 +665,15 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             .observation()
             .ok_or(Error::MissingObservationRinex)?;
 
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         let data = data.code_multipath();
         plot_gnss_code_mp(&data, &mut plot_ctx, "Code Multipath", "Meters of delay");
 
         /* save MP */
-        ctx.render_html("MULTIPATH.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "MULTIPATH", plot_ctx.to_html());
     }
     if navigation_plot(matches) {
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
 
         if matches.get_flag("skyplot") {
             let rx_ecef = ctx
@@ -631,21 +694,46 @@ pub fn graph_opmode(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             plot_residual_ephemeris(&ctx.data, &mut plot_ctx);
         }
         /* save NAV */
-        ctx.render_html("NAVIGATION.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "NAVIGATION", plot_ctx.to_html());
     }
     if matches.get_flag("sv-clock") {
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         plot_sv_nav_clock(&ctx.data, &mut plot_ctx);
 
         /* save CLK */
-        ctx.render_html("CLOCKS.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "CLOCKS", plot_ctx.to_html());
     }
     if atmosphere_plot(matches) {
-        let mut plot_ctx = PlotContext::new();
+        let mut plot_ctx = PlotContext::new().with_settings(settings.clone());
         plot_atmosphere_conditions(ctx, &mut plot_ctx, matches);
 
         /* save ATMOSPHERE */
-        ctx.render_html("ATMOSPHERE.html", plot_ctx.to_html());
+        render_graph(ctx, matches, "ATMOSPHERE", plot_ctx.to_html());
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::{generate_markers, sv_colors};
+    use rinex::prelude::SV;
+    use std::str::FromStr;
+    #[test]
+    fn deterministic_styles() {
+        let markers = serde_json::to_string(&generate_markers(30)).unwrap();
+        assert_eq!(
+            markers,
+            serde_json::to_string(&generate_markers(30)).unwrap()
+        );
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+        let colors = sv_colors([g02, e05, g01, g02].into_iter());
+        assert_eq!(colors.len(), 3);
+        // colors follow PRN order, not order of appearance
+        assert_eq!(colors, sv_colors([g01, g02, e05].into_iter()));
+        assert_ne!(colors[&g01], colors[&g02]);
+        assert_ne!(colors[&g02], colors[&e05]);
+    }
+}
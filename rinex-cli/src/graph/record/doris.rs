@@ -1,7 +1,7 @@
 use crate::cli::Context;
 use itertools::Itertools;
 use plotly::common::{Marker, MarkerSymbol, Mode, Visible};
-use rinex::{carrier::Carrier, prelude::*};
+use rinex::prelude::*;
 
 use crate::graph::{build_chart_epoch_axis, PlotContext};
 
@@ -7,8 +7,7 @@ use plotly::common::{
     Visible,
 };
 
-use rinex::carrier::Carrier;
-use rinex::navigation::Ephemeris;
+use rinex::prelude::{Carrier, Ephemeris};
 // use rinex::navigation::KbModel;
 use rinex::prelude::RnxContext;
 
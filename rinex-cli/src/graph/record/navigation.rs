@@ -1,6 +1,5 @@
 use crate::graph::{build_3d_chart_epoch_label, build_chart_epoch_axis, PlotContext};
 use plotly::common::{Mode, Visible};
-use rinex::navigation::Ephemeris;
 use rinex::prelude::*;
 
 use itertools::Itertools;
@@ -3,13 +3,22 @@ use plotly::{
     color::NamedColor,
     common::{Marker, MarkerSymbol, Mode, Visible},
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use rinex::{navigation::Ephemeris, prelude::*};
+use rinex::observation;
+use rinex::prelude::*;
 
-use crate::graph::{build_chart_epoch_axis, csv_export_timedomain, generate_markers, PlotContext};
+use crate::graph::{
+    build_chart_epoch_axis, csv_export_timedomain, generate_markers, sv_colors, PlotContext,
+};
+
+/*
+ * Observations per observable, per vehicle.
+ * Sorted, so plots are generated in a deterministic order.
+ */
+type Dataset = BTreeMap<String, BTreeMap<SV, Vec<(Epoch, f64)>>>;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum Physics {
     SSI,
     Doppler,
@@ -47,6 +56,60 @@ impl Physics {
     }
 }
 
+/*
+ * Observation datasets, gathered in a single iteration.
+ * RX OK or ERROR
+ *  per physics,
+ *   per observable (symbolized)
+ *      per vehicle (color map)
+ *      x: sampling timestamp,
+ *      y: observation (raw),
+ */
+#[derive(Default)]
+struct Datasets {
+    clk_offset_good: Vec<(Epoch, f64)>,
+    clk_offset_bad: Vec<(Epoch, f64)>,
+    good: HashMap<Physics, Dataset>,
+    bad: HashMap<Physics, Dataset>,
+    /// (min, max) observation per physics, so each physics has its own Y scale
+    y_ranges: HashMap<Physics, (f64, f64)>,
+}
+
+fn build_datasets(record: &observation::Record) -> Datasets {
+    let mut datasets = Datasets::default();
+    for ((epoch, flag), (clock_offset, vehicles)) in record {
+        let (clk_offset, dataset) = match flag.is_ok() {
+            true => (&mut datasets.clk_offset_good, &mut datasets.good),
+            false => (&mut datasets.clk_offset_bad, &mut datasets.bad),
+        };
+        if let Some(value) = clock_offset {
+            clk_offset.push((*epoch, *value));
+        }
+        for (sv, observations) in vehicles {
+            for (observable, data) in observations {
+                let physics = Physics::from_observable(observable);
+                datasets
+                    .y_ranges
+                    .entry(physics.clone())
+                    .and_modify(|(min, max)| {
+                        *min = min.min(data.obs);
+                        *max = max.max(data.obs);
+                    })
+                    .or_insert((data.obs, data.obs));
+                dataset
+                    .entry(physics)
+                    .or_default()
+                    .entry(observable.to_string())
+                    .or_default()
+                    .entry(*sv)
+                    .or_default()
+                    .push((*epoch, data.obs));
+            }
+        }
+    }
+    datasets
+}
+
 /*
  * Plots given Observation RINEX content
  */
@@ -55,89 +118,16 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
     let header = &obs_data.header;
     let record = obs_data.record.as_obs().unwrap(); // infaillible
 
-    /////////////////////////////////////////////////////
-    // Gather all data, complex type but single iteration..
-    // RX OK or ERROR
-    //  per physics,
-    //   per observable (symbolized)
-    //      per vehicle (color map)
-    //      x: sampling timestamp,
-    //      y: observation (raw),
-    /////////////////////////////////////////////////////
-    let mut clk_offset_good: Vec<(Epoch, f64)> = Vec::with_capacity(64);
-    let mut clk_offset_bad: Vec<(Epoch, f64)> = Vec::with_capacity(64);
-    let mut dataset_good: HashMap<Physics, HashMap<String, HashMap<SV, Vec<(Epoch, f64)>>>> =
-        HashMap::with_capacity(1024);
-    let mut dataset_bad: HashMap<Physics, HashMap<String, HashMap<SV, Vec<(Epoch, f64)>>>> =
-        HashMap::with_capacity(1024);
-
-    for ((epoch, flag), (clock_offset, vehicles)) in record {
-        if flag.is_ok() {
-            if let Some(value) = clock_offset {
-                clk_offset_good.push((*epoch, *value));
-            }
-            for (sv, observations) in vehicles {
-                for (observable, data) in observations {
-                    let observable_code = observable.to_string();
-                    let physics = Physics::from_observable(observable);
-                    let y = data.obs;
-
-                    if let Some(data) = dataset_good.get_mut(&physics) {
-                        if let Some(data) = data.get_mut(&observable_code) {
-                            if let Some(data) = data.get_mut(sv) {
-                                data.push((*epoch, y));
-                            } else {
-                                data.insert(*sv, vec![(*epoch, y)]);
-                            }
-                        } else {
-                            let mut map: HashMap<SV, Vec<(Epoch, f64)>> = HashMap::new();
-                            map.insert(*sv, vec![(*epoch, y)]);
-                            data.insert(observable_code, map);
-                        }
-                    } else {
-                        let mut map: HashMap<SV, Vec<(Epoch, f64)>> = HashMap::new();
-                        map.insert(*sv, vec![(*epoch, y)]);
-                        let mut mmap: HashMap<String, HashMap<SV, Vec<(Epoch, f64)>>> =
-                            HashMap::new();
-                        mmap.insert(observable_code, map);
-                        dataset_good.insert(physics, mmap);
-                    }
-                }
-            }
-        } else {
-            if let Some(value) = clock_offset {
-                clk_offset_bad.push((*epoch, *value));
-            }
-            for (sv, observations) in vehicles {
-                for (observable, data) in observations {
-                    let observable_code = observable.to_string();
-                    let physics = Physics::from_observable(observable);
-                    let y = data.obs;
+    let Datasets {
+        clk_offset_good,
+        clk_offset_bad,
+        good: dataset_good,
+        bad: dataset_bad,
+        y_ranges,
+    } = build_datasets(record);
 
-                    if let Some(data) = dataset_bad.get_mut(&physics) {
-                        if let Some(data) = data.get_mut(&observable_code) {
-                            if let Some(data) = data.get_mut(sv) {
-                                data.push((*epoch, y));
-                            } else {
-                                data.insert(*sv, vec![(*epoch, y)]);
-                            }
-                        } else {
-                            let mut map: HashMap<SV, Vec<(Epoch, f64)>> = HashMap::new();
-                            map.insert(*sv, vec![(*epoch, y)]);
-                            data.insert(observable_code, map);
-                        }
-                    } else {
-                        let mut map: HashMap<SV, Vec<(Epoch, f64)>> = HashMap::new();
-                        map.insert(*sv, vec![(*epoch, y)]);
-                        let mut mmap: HashMap<String, HashMap<SV, Vec<(Epoch, f64)>>> =
-                            HashMap::new();
-                        mmap.insert(observable_code, map);
-                        dataset_bad.insert(physics, mmap);
-                    }
-                }
-            }
-        }
-    }
+    // each vehicle is depicted with the same color, on every panel
+    let colors = sv_colors(obs_data.sv());
 
     /////////////////////////////
     // Plot Clock offset (if any)
@@ -180,6 +170,9 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
             let title = physics.plot_title();
             let y_label = physics.y_axis();
             plot_ctx.add_timedomain_plot(&title, &y_label);
+            if let Some(range) = y_ranges.get(&physics) {
+                plot_ctx.set_y_range(&y_label, *range);
+            }
 
             let markers = generate_markers(observables.len());
             for (index, (observable, vehicles)) in observables.iter().enumerate() {
@@ -208,7 +201,11 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
                         good_x,
                         good_y,
                     )
-                    .marker(Marker::new().symbol(markers[index].clone()))
+                    .marker(
+                        Marker::new()
+                            .symbol(markers[index].clone())
+                            .color(colors[sv]),
+                    )
                     .visible({
                         if index == 0 && sv_index == 0 {
                             Visible::True
@@ -264,6 +261,9 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
             plot_ctx.add_timedomain_2y_plot(&title, &y_label, "Elevation [Degrees]");
         } else {
             plot_ctx.add_timedomain_plot(&title, &y_label);
+            if let Some(range) = y_ranges.get(&Physics::SSI) {
+                plot_ctx.set_y_range(&y_label, *range);
+            }
         }
 
         // Plot Observations
@@ -320,7 +320,11 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
                             data_y,
                         )
                         .y_axis("y2")
-                        .marker(Marker::new().symbol(markers[index].clone()))
+                        .marker(
+                            Marker::new()
+                                .symbol(markers[index].clone())
+                                .color(colors[sv]),
+                        )
                         .visible({
                             if sv_index == 0 && index == 0 {
                                 Visible::True
@@ -357,7 +361,11 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
                             data_y,
                         )
                         .y_axis("y2")
-                        .marker(Marker::new().symbol(markers[index].clone()))
+                        .marker(
+                            Marker::new()
+                                .symbol(markers[index].clone())
+                                .color(colors[sv]),
+                        )
                         .visible({
                             if sv_index == 0 && index == 0 {
                                 Visible::True
@@ -375,7 +383,11 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
                     good_x,
                     good_y,
                 )
-                .marker(Marker::new().symbol(markers[index].clone()))
+                .marker(
+                    Marker::new()
+                        .symbol(markers[index].clone())
+                        .color(colors[sv]),
+                )
                 .y_axis("y1")
                 .visible({
                     if index == 0 && sv_index == 0 {
@@ -390,3 +402,59 @@ pub fn plot_observations(ctx: &Context, plot_ctx: &mut PlotContext, csv_export:
         trace!("{} observations", y_label);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{build_datasets, Physics};
+    use rinex::observation;
+    use rinex::prelude::*;
+    use std::collections::{BTreeMap, HashMap};
+    use std::str::FromStr;
+    #[test]
+    fn physics_datasets() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let mut record = observation::Record::new();
+        for k in 0..3 {
+            let mut observations = HashMap::new();
+            for (code, value) in [
+                ("C1C", 20.0E6),
+                ("L1C", 100.0E6),
+                ("D1C", -1000.0),
+                ("S1C", 40.0),
+                ("C2W", 21.0E6),
+            ] {
+                observations.insert(
+                    Observable::from_str(code).unwrap(),
+                    ObservationData::new(value + k as f64, None, None),
+                );
+            }
+            let mut vehicles = BTreeMap::new();
+            vehicles.insert(g01, observations);
+            let t = t0 + Duration::from_seconds(30.0 * k as f64);
+            record.insert((t, EpochFlag::Ok), (None, vehicles));
+        }
+
+        let datasets = build_datasets(&record);
+        assert!(datasets.clk_offset_good.is_empty());
+        assert!(datasets.bad.is_empty());
+        assert_eq!(datasets.good.len(), 4);
+        let observables =
+            |physics: Physics| -> Vec<String> { datasets.good[&physics].keys().cloned().collect() };
+        assert_eq!(observables(Physics::PseudoRange), ["C1C", "C2W"]);
+        assert_eq!(observables(Physics::Phase), ["L1C"]);
+        assert_eq!(observables(Physics::Doppler), ["D1C"]);
+        assert_eq!(observables(Physics::SSI), ["S1C"]);
+        assert_eq!(datasets.good[&Physics::Phase]["L1C"][&g01].len(), 3);
+
+        // each physics has its own Y scale
+        assert_eq!(datasets.y_ranges.len(), 4);
+        assert_eq!(
+            datasets.y_ranges[&Physics::PseudoRange],
+            (20.0E6, 21.0E6 + 2.0)
+        );
+        assert_eq!(datasets.y_ranges[&Physics::Phase], (100.0E6, 100.0E6 + 2.0));
+        assert_eq!(datasets.y_ranges[&Physics::Doppler], (-1000.0, -998.0));
+        assert_eq!(datasets.y_ranges[&Physics::SSI], (40.0, 42.0));
+    }
+}
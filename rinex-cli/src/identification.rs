@@ -1,10 +1,6 @@
 use clap::ArgMatches;
 
-use rinex::{
-    observation::SNR,
-    prelude::{Constellation, Epoch, Observable, ProductType, Rinex, RnxContext},
-    preprocessing::*,
-};
+use rinex::prelude::*;
 
 use std::str::FromStr;
 
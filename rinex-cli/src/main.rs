@@ -9,6 +9,7 @@ mod graph;
 mod identification; // high level identification/macros
 mod positioning;
 mod qc; // QC report generator // plotting operations // file operation helpers // graphical analysis // positioning + CGGTTS opmode
+mod summary; // teqc like summary
 
 mod preprocessing;
 use preprocessing::preprocess;
@@ -39,19 +40,29 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("rinex error")]
-    RinexError(#[from] rinex::Error),
+    RinexError(#[from] rinex::prelude::Error),
+    #[error("rinex formatting error")]
+    FormattingError(#[from] rinex::prelude::FormattingError),
     #[error("missing OBS RINEX")]
     MissingObservationRinex,
     #[error("missing (BRDC) NAV RINEX")]
     MissingNavigationRinex,
     #[error("merge ops failure")]
-    MergeError(#[from] rinex::merge::Error),
+    MergeError(#[from] rinex::prelude::MergeError),
     #[error("split ops failure")]
-    SplitError(#[from] rinex::split::Error),
+    SplitError(#[from] rinex::prelude::SplitError),
     #[error("failed to create QC report: permission denied!")]
     QcReportCreationError,
     #[error("positioning solver error")]
     PositioningSolverError(#[from] positioning::Error),
+    #[error("i/o error")]
+    StdioError(#[from] std::io::Error),
+    #[error("json serialization error")]
+    JsonError(#[from] serde_json::Error),
+    #[error("\"{}\" already exists: use --force to overwrite it", .0.display())]
+    OutputAlreadyExists(PathBuf),
+    #[error("custom output path is ambiguous when generating several products")]
+    AmbiguousOutputPath,
 }
 
 /*
@@ -103,11 +114,16 @@ fn user_data_parsing(cli: &Cli) -> RnxContext {
     }
 
     /*
-     * Load each individual file (`-f`)
+     * Load each individual file (`-f`), parsed concurrently
      */
-    for fp in cli.input_files() {
+    let input_files: Vec<&str> = cli
+        .input_files()
+        .into_iter()
+        .map(|fp| fp.as_str())
+        .collect();
+    for (fp, parsed) in input_files.iter().zip(Rinex::from_files(&input_files)) {
         let path = Path::new(fp);
-        if let Ok(rinex) = Rinex::from_path(path) {
+        if let Ok(rinex) = parsed {
             let loading = ctx.load_rinex(path, rinex);
             if loading.is_err() {
                 warn!(
@@ -269,6 +285,9 @@ pub fn main() -> Result<(), Error> {
         Some(("sub", submatches)) => {
             fops::substract(&ctx, submatches)?;
         },
+        Some(("teqc-report", submatches)) => {
+            summary::summary_report(&ctx, submatches)?;
+        },
         _ => error!("no opmode specified!"),
     }
     Ok(())
@@ -7,7 +7,7 @@ pub use post_process::{post_process, Error as PostProcessingError};
 
 use gnss::prelude::{Constellation, SV};
 
-use rinex::{carrier::Carrier, prelude::Observable};
+use rinex::prelude::{Carrier, Observable};
 
 use rtk::prelude::{
     Candidate,
@@ -115,7 +115,7 @@ where
         // let zwd_zdd = tropo_components(meteo_data, *t, rx_lat_ddeg);
 
         for (sv, observations) in vehicles {
-            let sv_eph = nav_data.sv_ephemeris(*sv, *t);
+            let sv_eph = nav_data.ephemeris_valid_at(*sv, *t);
 
             if sv_eph.is_none() {
                 warn!("{:?} ({}) : undetermined ephemeris", t, sv);
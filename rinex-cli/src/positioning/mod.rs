@@ -12,8 +12,7 @@ use cggtts::PostProcessingError as CGGTTSPostProcessingError;
 
 use clap::ArgMatches;
 use gnss::prelude::Constellation; // SV};
-use rinex::carrier::Carrier;
-use rinex::prelude::Rinex;
+use rinex::prelude::{Carrier, Rinex};
 
 use rtk::prelude::{
     BdModel, Carrier as RTKCarrier, Config, Duration, Epoch, Error as RTKError, KbModel, Method,
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 use gnss_rtk::prelude::{Epoch, InterpolationResult as RTKInterpolationResult, TimeScale, SV};
 
-use rinex::navigation::Ephemeris;
+use rinex::prelude::Ephemeris;
 
 pub struct Orbit<'a> {
     buffer: HashMap<SV, Vec<Ephemeris>>,
@@ -6,7 +6,7 @@ use gnss_rtk::prelude::{
     SUN_J2000, SV,
 };
 
-use rinex::carrier::Carrier;
+use rinex::prelude::Carrier;
 
 use anise::almanac::metaload::MetaFile;
 
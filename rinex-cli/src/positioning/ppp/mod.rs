@@ -11,7 +11,7 @@ use crate::{
 };
 use std::collections::BTreeMap;
 
-use rinex::{carrier::Carrier, observation::LliFlags, prelude::SV};
+use rinex::prelude::{Carrier, LliFlags, SV};
 
 mod post_process;
 pub use post_process::{post_process, Error as PostProcessingError};
@@ -56,7 +56,7 @@ where
         // }
 
         for (sv, observations) in vehicles {
-            let sv_eph = nav_data.sv_ephemeris(*sv, *t);
+            let sv_eph = nav_data.ephemeris_valid_at(*sv, *t);
             if sv_eph.is_none() {
                 error!("{} ({}) : undetermined ephemeris", t, sv);
                 continue; // can't proceed further
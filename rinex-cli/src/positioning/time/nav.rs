@@ -1,5 +1,5 @@
 use gnss_rtk::prelude::{Duration, Epoch, TimeScale, SV};
-use rinex::navigation::Ephemeris;
+use rinex::prelude::Ephemeris;
 use std::collections::HashMap;
 
 pub struct Time<'a> {
@@ -0,0 +1,265 @@
+//! teqc like summary opmode
+use clap::ArgMatches;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::{cli::Context, Error};
+use rinex::prelude::{Ephemeris, Epoch, LliFlags, ProductType, Rinex};
+
+/// Elevation band width, in degrees
+const ELEVATION_BAND_DEG: f64 = 10.0;
+
+/*
+ * Observations within a band of elevation angles
+ */
+#[derive(Debug, Default, Serialize)]
+struct ElevationBand {
+    /// Number of observations
+    observations: usize,
+    /// Mean S1 signal strength
+    mean_s1: Option<f64>,
+}
+
+/*
+ * Summary of a single Observation RINEX file
+ */
+#[derive(Debug, Serialize)]
+struct Summary {
+    file: String,
+    station: String,
+    first_epoch: Option<Epoch>,
+    last_epoch: Option<Epoch>,
+    /// Sampling interval, in seconds
+    sampling_interval: Option<f64>,
+    /// Possible number of epochs, within the time frame
+    expected_epochs: usize,
+    /// Number of epochs with observations
+    epochs: usize,
+    /// Completeness, in %
+    completeness: f64,
+    /// Number of cycle slips (loss of lock)
+    slips: usize,
+    /// Mean S1 signal strength
+    mean_s1: Option<f64>,
+    /// Statistics per band of elevation angles (lower bound, in degrees)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    elevation_bands: BTreeMap<u8, ElevationBand>,
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+impl Summary {
+    fn new(path: &Path, rinex: &Rinex, nav: Option<&Rinex>) -> Self {
+        let station = rinex
+            .header
+            .geodetic_marker
+            .as_ref()
+            .map(|marker| marker.name.clone())
+            .unwrap_or_default();
+
+        let (first, last) = (rinex.first_epoch(), rinex.last_epoch());
+        let sampling = rinex
+            .header
+            .sampling_interval
+            .or(rinex.dominant_sample_rate());
+
+        let epochs = rinex.epoch_ok().count();
+        let expected_epochs = match (first, last, sampling) {
+            (Some(first), Some(last), Some(sampling)) if sampling.to_seconds() > 0.0 => {
+                ((last - first).to_seconds() / sampling.to_seconds()).round() as usize + 1
+            },
+            _ => epochs,
+        };
+        let completeness = match expected_epochs {
+            0 => 0.0,
+            n => epochs as f64 * 100.0 / n as f64,
+        };
+
+        let slips = rinex
+            .lli()
+            .filter(|(_, _, _, lli)| lli.intersects(LliFlags::LOCK_LOSS))
+            .count();
+
+        let s1 = rinex
+            .observation()
+            .flat_map(|((t, _), (_, vehicles))| {
+                vehicles.iter().flat_map(move |(sv, observations)| {
+                    observations.iter().filter_map(move |(observable, data)| {
+                        if observable.to_string().starts_with("S1") {
+                            Some((*t, *sv, data.obs))
+                        } else {
+                            None
+                        }
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut elevation_bands = BTreeMap::<u8, ElevationBand>::new();
+        if let (Some(nav), Some(ground)) = (nav, rinex.header.ground_position) {
+            let ground = ground.to_ecef_wgs84();
+            // elevation angle of each vehicle, at each epoch
+            let mut elevations = HashMap::new();
+            for ((t, _), (_, vehicles)) in rinex.observation() {
+                for sv in vehicles.keys() {
+                    let elevation = nav
+                        .sv_ephemeris(*sv, *t)
+                        .and_then(|(_, eph)| eph.sv_position(*sv, *t))
+                        .map(|(x, y, z)| {
+                            Ephemeris::elevation_azimuth((x * 1.0E3, y * 1.0E3, z * 1.0E3), ground)
+                                .0
+                        });
+                    if let Some(elevation) = elevation.filter(|elev| *elev >= 0.0) {
+                        let band = ((elevation / ELEVATION_BAND_DEG).floor() * ELEVATION_BAND_DEG)
+                            .min(90.0 - ELEVATION_BAND_DEG)
+                            as u8;
+                        elevations.insert((*t, *sv), band);
+                        elevation_bands.entry(band).or_default().observations += vehicles[sv].len();
+                    }
+                }
+            }
+            for (band, stats) in elevation_bands.iter_mut() {
+                let values = s1
+                    .iter()
+                    .filter(|(t, sv, _)| elevations.get(&(*t, *sv)) == Some(band))
+                    .map(|(_, _, value)| *value)
+                    .collect::<Vec<_>>();
+                stats.mean_s1 = mean(&values);
+            }
+        }
+
+        Self {
+            file: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            station,
+            first_epoch: first,
+            last_epoch: last,
+            sampling_interval: sampling.map(|dt| dt.to_seconds()),
+            expected_epochs,
+            epochs,
+            completeness,
+            slips,
+            mean_s1: mean(&s1.iter().map(|(_, _, value)| *value).collect::<Vec<_>>()),
+            elevation_bands,
+        }
+    }
+    /*
+     * teqc like ASCII report
+     */
+    fn ascii(&self) -> String {
+        let mut report = format!("SUMMARY OF \"{}\"\n", self.file);
+        report.push_str(&format!("4-character ID          : {}\n", self.station));
+        if let Some(first) = &self.first_epoch {
+            report.push_str(&format!("Time of start of window : {}\n", first));
+        }
+        if let Some(last) = &self.last_epoch {
+            report.push_str(&format!("Time of  end  of window : {}\n", last));
+        }
+        if let Some(dt) = self.sampling_interval {
+            report.push_str(&format!("Observation interval    : {:.4} seconds\n", dt));
+        }
+        report.push_str(&format!(
+            "Possible # of epochs    : {}\n",
+            self.expected_epochs
+        ));
+        report.push_str(&format!("Epochs w/ observations  : {}\n", self.epochs));
+        report.push_str(&format!(
+            "Completeness            : {:.2} %\n",
+            self.completeness
+        ));
+        report.push_str(&format!("Total # of slips        : {}\n", self.slips));
+        if let Some(s1) = self.mean_s1 {
+            report.push_str(&format!("Mean S1                 : {:.2}\n", s1));
+        }
+        if !self.elevation_bands.is_empty() {
+            report.push_str("Elevation [°]   # obs   mean S1\n");
+            for (band, stats) in &self.elevation_bands {
+                let mean_s1 = match stats.mean_s1 {
+                    Some(s1) => format!("{:.2}", s1),
+                    None => "-".to_string(),
+                };
+                report.push_str(&format!(
+                    "  {:>2}-{:<2}      {:>7}   {:>7}\n",
+                    band,
+                    *band as f64 + ELEVATION_BAND_DEG,
+                    stats.observations,
+                    mean_s1
+                ));
+            }
+        }
+        report
+    }
+}
+
+/*
+ * Formats the date of the first epoch, as YYYY-MM-DD
+ */
+fn date(epoch: Option<Epoch>) -> String {
+    epoch
+        .map(|t| {
+            let (y, m, d, _, _, _, _) = t.to_gregorian_utc();
+            format!("{:04}-{:02}-{:02}", y, m, d)
+        })
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/*
+ * Summarizes each Observation RINEX file, like teqc does
+ */
+pub fn summary_report(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
+    let nav = match matches.get_one::<PathBuf>("nav") {
+        Some(path) => Some(Rinex::from_path(path)?),
+        None => None,
+    };
+
+    let files = ctx
+        .data
+        .files(ProductType::Observation)
+        .ok_or(Error::MissingObservationRinex)?;
+
+    let mut summaries = Vec::with_capacity(files.len());
+    for path in files.iter().sorted() {
+        let rinex = Rinex::from_path(path)?;
+        summaries.push(Summary::new(path, &rinex, nav.as_ref()));
+    }
+
+    if matches.get_flag("pretty") {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+        return Ok(());
+    }
+
+    for summary in &summaries {
+        println!("{}", summary.ascii());
+    }
+    if summaries.len() > 1 {
+        println!(
+            "{:<10} {:<10} {:>12} {:>7} {:>8}",
+            "STATION", "DATE", "COMPLETENESS", "#SLIPS", "MEAN S1"
+        );
+        for summary in &summaries {
+            let mean_s1 = match summary.mean_s1 {
+                Some(s1) => format!("{:.2}", s1),
+                None => "-".to_string(),
+            };
+            println!(
+                "{:<10} {:<10} {:>11.2}% {:>7} {:>8}",
+                summary.station,
+                date(summary.first_epoch),
+                summary.completeness,
+                summary.slips,
+                mean_s1
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use assert_cmd::Command;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn rinex_cli(workspace: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("rinex-cli").unwrap();
+    cmd.arg("-q").arg("-w").arg(workspace);
+    cmd
+}
+
+#[test]
+fn filegen_output() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("DUTH0630.22O");
+
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "filegen",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .success();
+    assert!(output.exists(), "output path was not honored");
+
+    // existing file is preserved
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "filegen",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .failure();
+
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "filegen",
+            "--force",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let parsed = rinex::prelude::Rinex::from_file(&output.to_string_lossy()).unwrap();
+    assert_eq!(parsed.epoch().count(), 3);
+}
+
+#[test]
+fn filegen_json_output() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("duth.json");
+
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "filegen",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(&output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert!(json.get("header").is_some());
+    assert!(json.get("record").is_some());
+}
+
+#[test]
+fn merge_output() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("merged.22O");
+
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/VLNS0010.22O",
+            "merge",
+            "../test_resources/OBS/V3/VLNS0630.22O",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let merged = rinex::prelude::Rinex::from_file(&output.to_string_lossy()).unwrap();
+    assert!(merged.is_merged());
+}
@@ -0,0 +1,34 @@
+use assert_cmd::Command;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn rinex_cli(workspace: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("rinex-cli").unwrap();
+    cmd.arg("-q").arg("-w").arg(workspace);
+    cmd
+}
+
+#[test]
+fn graph_output_path() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("figures").join("duth");
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "graph",
+            "--obs",
+        ])
+        .arg("--output")
+        .arg(&output)
+        .args(["--width", "800", "--height", "600", "--format", "svg"])
+        .assert()
+        .success();
+
+    let html = tmp.path().join("figures").join("duth_OBSERVATIONS.html");
+    let content = std::fs::read_to_string(&html)
+        .unwrap_or_else(|e| panic!("{} was not rendered: {:?}", html.display(), e));
+    assert!(content.contains("\"width\":800"));
+    assert!(content.contains("\"height\":600"));
+    assert!(content.contains("\"svg\""));
+}
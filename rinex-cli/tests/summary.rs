@@ -0,0 +1,62 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn rinex_cli(workspace: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("rinex-cli").unwrap();
+    cmd.arg("-q").arg("-w").arg(workspace);
+    cmd
+}
+
+#[test]
+fn teqc_report() {
+    let tmp = TempDir::new().unwrap();
+    rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "-f",
+            "../test_resources/OBS/V3/VLNS0010.22O",
+            "teqc-report",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4-character ID          : DUTH"))
+        // DUTH0630 is an excerpt: 3 epochs over a 57' window, 30s INTERVAL,
+        // that is 57 * 2 + 1 = 115 possible epochs and 3 / 115 = 2.61 % completeness,
+        // as teqc would report for this file
+        .stdout(predicate::str::contains(
+            "Observation interval    : 30.0000 s",
+        ))
+        .stdout(predicate::str::contains("Possible # of epochs    : 115"))
+        .stdout(predicate::str::contains("Epochs w/ observations  : 3\n"))
+        .stdout(predicate::str::contains("Completeness            : 2.61 %"))
+        // VLNS0010 is complete
+        .stdout(predicate::str::contains("Completeness            : 100.00"))
+        // aggregate table
+        .stdout(predicate::str::contains("STATION"))
+        .stdout(predicate::str::is_match("VLNS +2022-01-01 +100.00%").unwrap());
+}
+
+#[test]
+fn teqc_report_pretty() {
+    let tmp = TempDir::new().unwrap();
+    let output = rinex_cli(tmp.path())
+        .args([
+            "-f",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "teqc-report",
+            "--pretty",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let summary = &report[0];
+    assert_eq!(summary["station"], "DUTH");
+    assert_eq!(summary["expected_epochs"], 115);
+    assert_eq!(summary["epochs"], 3);
+    assert_eq!(summary["sampling_interval"], 30.0);
+}
@@ -1,7 +1,6 @@
 use horrorshow::{box_html, RenderBox};
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::str::FromStr;
 
 extern crate gnss_rs as gnss;
 
@@ -262,7 +261,7 @@ fn report_snr_statistics(
                 @ if observable.is_phase_observable() || observable.is_pseudorange_observable() {
                     td {
                         b {
-                            : format!("{:e}", SNR::from_str(&format!("{}", max.1)).unwrap())
+                            : format!("{:e}", SNR::from(max.1))
                         }
                         p {
                             : format!("@{}", max.0)
@@ -279,7 +278,7 @@ fn report_snr_statistics(
                 @ if observable.is_phase_observable() || observable.is_pseudorange_observable() {
                     td {
                         b {
-                            : format!("{:e}", SNR::from_str(&format!("{}", min.1)).unwrap())
+                            : format!("{:e}", SNR::from(min.1))
                         }
                         p {
                             : format!("@{}", min.0)
@@ -10,7 +10,9 @@ use rinex::{
 extern crate criterion;
 use criterion::{criterion_group, criterion_main, Criterion};
 
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufRead;
+use std::str::FromStr;
 
 /*struct CpuProfiler;
 
@@ -36,6 +38,11 @@ fn parse_file(fp: &str) {
     let _ = Rinex::from_file(fp).unwrap();
 }
 
+fn header_parsing(fp: &str) {
+    let mut reader = BufferedReader::new(fp).unwrap();
+    let _ = Header::new(&mut reader).unwrap();
+}
+
 fn text_decompression(textdiff: &mut TextDiff, data: &[&str]) {
     for data in data {
         let _r = textdiff.decompress(data);
@@ -188,6 +195,81 @@ fn decompression_benchmark(c: &mut Criterion) {
     group.finish(); /* conclude numdiff group */
 }
 
+/*
+ * Builds a synthetic 24h 30s OBS record, 40 vehicles tracking 8 observables:
+ * close to one million observations.
+ * Memory footprint is evaluated by the memory benchmark (benches/memory.rs).
+ */
+fn synthetic_obs_record() -> rinex::observation::Record {
+    let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+    let codes = ["C1C", "L1C", "D1C", "S1C", "C5Q", "L5Q", "D5Q", "S5Q"]
+        .iter()
+        .map(|c| Observable::from_str(c).unwrap())
+        .collect::<Vec<_>>();
+    let mut record = rinex::observation::Record::new();
+    for i in 0..2880 {
+        let t = t0 + Duration::from_seconds(30.0 * i as f64);
+        let mut vehicles = BTreeMap::<SV, HashMap<Observable, ObservationData>>::new();
+        for prn in 1..41 {
+            let constellation = match prn % 2 {
+                0 => Constellation::GPS,
+                _ => Constellation::Galileo,
+            };
+            let mut observations = HashMap::with_capacity(codes.len());
+            for (j, code) in codes.iter().enumerate() {
+                observations.insert(
+                    code.clone(),
+                    ObservationData::new(j as f64 * 1.0E6 + i as f64, None, None),
+                );
+            }
+            vehicles.insert(SV::new(constellation, prn / 2 + 1), observations);
+        }
+        record.insert((t, EpochFlag::Ok), (None, vehicles));
+    }
+    record
+}
+
+fn record_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("record");
+    group.sample_size(10);
+    group.bench_function("OBS/synthetic_24h_30s", |b| {
+        b.iter(|| {
+            let _record = synthetic_obs_record();
+        })
+    });
+    group.finish(); /* concludes record group */
+}
+
+/*
+ * Evaluates header and complete parsing performance,
+ * on 24h 30s mixed OBS V3 files
+ */
+fn parsing_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parsing");
+    let acor00esp = "../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx";
+    group.bench_function("header/acor00esp_r_2021", |b| {
+        b.iter(|| {
+            header_parsing(acor00esp);
+        })
+    });
+    group.bench_function("OBSv3/acor00esp_r_2021", |b| {
+        b.iter(|| {
+            parse_file(acor00esp);
+        })
+    });
+    #[cfg(feature = "flate2")]
+    {
+        let esbc00dnk = "../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+        group.sample_size(10);
+        group.bench_function("CRNXv3/esbc00dnk_r_2020", |b| {
+            b.iter(|| {
+                parse_file(esbc00dnk);
+            })
+        });
+    }
+    group.finish(); /* concludes parsing group */
+}
+
 /*
  * Evaluates parsing performance of plain RINEX parsing
 fn record_parsing_benchmark(c: &mut Criterion) {
@@ -277,6 +359,8 @@ fn record_parsing_benchmark(c: &mut Criterion) {
 
 fn benchmark(c: &mut Criterion) {
     decompression_benchmark(c);
+    parsing_benchmark(c);
+    record_benchmark(c);
     //record_parsing_benchmark(c);
     //processing_benchmark(c);
 }
@@ -0,0 +1,133 @@
+/*
+ * Observation record memory footprint.
+ * Counts the heap allocations (peak allocated bytes) while building
+ * a synthetic 24h 30s OBS record: 40 vehicles tracking 8 observables,
+ * close to one million observations. The record keyed by [Observable]
+ * is compared to the former representation, where each observation
+ * was keyed by a heap allocated code.
+ * Run with `cargo bench --bench memory`.
+ */
+use rinex::prelude::*;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/*
+ * System allocator, that tracks the allocated and peak allocated bytes
+ */
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/*
+ * Former observable representation: code stored as a String
+ */
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum StringObservable {
+    Phase(String),
+    Doppler(String),
+    SSI(String),
+    PseudoRange(String),
+}
+
+impl StringObservable {
+    fn new(code: &str) -> Self {
+        match &code[..1] {
+            "L" => Self::Phase(code.to_string()),
+            "D" => Self::Doppler(code.to_string()),
+            "S" => Self::SSI(code.to_string()),
+            _ => Self::PseudoRange(code.to_string()),
+        }
+    }
+}
+
+const CODES: [&str; 8] = ["C1C", "L1C", "D1C", "S1C", "C5Q", "L5Q", "D5Q", "S5Q"];
+
+type SyntheticRecord<K> =
+    BTreeMap<(Epoch, EpochFlag), (Option<f64>, BTreeMap<SV, HashMap<K, ObservationData>>)>;
+
+/*
+ * Builds the synthetic 24h 30s record, observations keyed by `codes`
+ */
+fn synthetic_record<K: Clone + Eq + Hash>(codes: &[K]) -> SyntheticRecord<K> {
+    let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+    let mut record = SyntheticRecord::<K>::new();
+    for i in 0..2880 {
+        let t = t0 + Duration::from_seconds(30.0 * i as f64);
+        let mut vehicles = BTreeMap::<SV, HashMap<K, ObservationData>>::new();
+        for prn in 1..41 {
+            let constellation = match prn % 2 {
+                0 => Constellation::GPS,
+                _ => Constellation::Galileo,
+            };
+            let mut observations = HashMap::with_capacity(codes.len());
+            for (j, code) in codes.iter().enumerate() {
+                observations.insert(
+                    code.clone(),
+                    ObservationData::new(j as f64 * 1.0E6 + i as f64, None, None),
+                );
+            }
+            vehicles.insert(SV::new(constellation, prn / 2 + 1), observations);
+        }
+        record.insert((t, EpochFlag::Ok), (None, vehicles));
+    }
+    record
+}
+
+/*
+ * Returns the peak allocated bytes while running `f`
+ */
+fn peak_allocation<T, F: FnOnce() -> T>(f: F) -> usize {
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let output = f();
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    drop(output);
+    peak
+}
+
+fn main() {
+    let observables = CODES
+        .iter()
+        .map(|code| Observable::from_str(code).unwrap())
+        .collect::<Vec<_>>();
+    let baseline = CODES
+        .iter()
+        .map(|code| StringObservable::new(code))
+        .collect::<Vec<_>>();
+
+    let peak = peak_allocation(|| synthetic_record(&observables));
+    let baseline_peak = peak_allocation(|| synthetic_record(&baseline));
+    let reduction = 100.0 * (1.0 - peak as f64 / baseline_peak as f64);
+
+    println!("OBS/synthetic_24h_30s peak allocation");
+    println!(
+        "  String keys     : {:>8.1} MB",
+        baseline_peak as f64 / 1.0E6
+    );
+    println!("  Observable keys : {:>8.1} MB", peak as f64 / 1.0E6);
+    println!("  reduction       : {:>8.1} %", reduction);
+    assert!(reduction >= 40.0, "memory regression: {:.1} %", reduction);
+}
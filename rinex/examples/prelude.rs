@@ -0,0 +1,31 @@
+//! Parses an Observation RINEX, only relying on the prelude.
+//! cargo run --example prelude
+use rinex::prelude::*;
+
+fn main() -> Result<(), Error> {
+    let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")?;
+    let version: Version = rnx.header.version;
+    let rinex_type: RinexType = rnx.header.rinex_type;
+    println!("{:?} RINEX V{}", rinex_type, version);
+
+    if let Some(constellation) = rnx.header.constellation {
+        println!("constellation: {}", constellation);
+    }
+
+    let record: &ObservationRecord = rnx.record.as_obs().expect("observation record");
+    for ((epoch, flag), (_, vehicles)) in record.iter() {
+        let epoch: Epoch = *epoch;
+        let flag: EpochFlag = *flag;
+        let svnn: Vec<SV> = vehicles.keys().copied().collect();
+        println!("{} ({:?}): {} vehicles", epoch, flag, svnn.len());
+        for (sv, observations) in vehicles.iter() {
+            for (observable, data) in observations.iter() {
+                let data: &ObservationData = data;
+                let ssi: Option<Ssi> = data.snr;
+                let observable: &Observable = observable;
+                println!("  {:x} {} {} {:?}", sv, observable, data.obs, ssi);
+            }
+        }
+    }
+    Ok(())
+}
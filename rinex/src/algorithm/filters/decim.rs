@@ -7,6 +7,8 @@ pub enum Error {
     TargetError(#[from] crate::algorithm::target::Error),
     #[error("failed to parse decimation attribute \"{0}\"")]
     AttributeParsingError(String),
+    #[error("decimation ratio should be strictly positive")]
+    NullRatio,
 }
 
 /// Decimation Filters type
@@ -29,7 +31,9 @@ pub struct DecimationFilter {
 pub trait Decimate {
     /// Decimate by a constant ratio.
     /// For example, if we decimate epochs {e_0, e_1, .., e_k, ..., e_n}
-    /// by 2, we get {e_0, e_2, ..., e_k, e_k+2, ..}.
+    /// by 2, we get {e_0, e_2, ..., e_k, e_k+2, ..}: epoch of index k
+    /// is retained when k % r == 0. Decimating by 1 is a no-op,
+    /// so is a null ratio ([DecimationFilter] parsing rejects it).
     /// Header sampling interval (if any) is automatically updated.
     /// ```
     /// use rinex::prelude::*;
@@ -95,6 +99,9 @@ impl std::str::FromStr for DecimationFilter {
                 dtype: DecimationType::DecimByInterval(dt),
             })
         } else if let Ok(r) = items[0].trim().parse::<u32>() {
+            if r == 0 {
+                return Err(Error::NullRatio);
+            }
             Ok(Self {
                 target: {
                     if items.len() > 1 {
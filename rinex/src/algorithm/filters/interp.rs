@@ -1,9 +1,27 @@
 use super::TargetItem;
-use crate::TimeSeries;
+use crate::{Duration, Epoch, TimeSeries};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Interpolation methods, used when resampling a record.
+/// Accuracy caveats:
+///  - [InterpMethod::Nearest] does not modify values, but introduces
+///    up to half a sampling period of timing error.
+///  - [InterpMethod::Linear] error grows with the curvature of the signal
+///    and the square of the sampling period. It is a good choice
+///    for smooth signals like pseudo ranges or phase observations,
+///    as long as no cycle slip occurred in between.
+///  - [InterpMethod::Lagrange] fits a polynomial through the n nearest
+///    samples. Low orders (3 to 7) are suited for clock biases. High orders
+///    tend to oscillate (Runge phenomenon), especially on noisy data.
+///    Order is reduced when not enough samples are available.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum InterpMethod {
+    /// Nearest sample
+    Nearest,
+    /// Linear interpolation between the two surrounding samples
     Linear,
+    /// Lagrangian interpolation on given number of samples
+    Lagrange(usize),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,3 +59,199 @@ pub trait Interpolate {
     fn interpolate(&self, series: TimeSeries) -> Self;
     fn interpolate_mut(&mut self, series: TimeSeries);
 }
+
+/// Resampling: values are interpolated onto a new time grid
+/// t0 + k * interval, within the original time frame.
+/// Original epochs are dropped. Data gaps larger than a threshold
+/// are not bridged: they remain in the resampled dataset.
+/// A null interval leaves the dataset untouched.
+pub trait Resample {
+    /// Resamples Self, bridging data gaps up to twice the original sampling period.
+    fn resample(&self, interval: Duration, method: InterpMethod) -> Self;
+    /// [Resample::resample] mutable implementation.
+    fn resample_mut(&mut self, interval: Duration, method: InterpMethod);
+    /// Resamples Self, data gaps larger than `max_gap` are not bridged.
+    fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) -> Self;
+    /// [Resample::resample_with_max_gap] mutable implementation.
+    fn resample_with_max_gap_mut(
+        &mut self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    );
+}
+
+/*
+ * Builds the resampling grid t0 + k * interval, within [t0, t1].
+ * Grid is empty if interval is null.
+ */
+pub(crate) fn resampling_grid(t0: Epoch, t1: Epoch, interval: Duration) -> Vec<Epoch> {
+    let mut grid = Vec::new();
+    if interval <= Duration::ZERO {
+        return grid;
+    }
+    let mut t = t0;
+    while t <= t1 {
+        grid.push(t);
+        t += interval;
+    }
+    grid
+}
+
+/*
+ * Default gap threshold: twice the dominant sampling period
+ */
+pub(crate) fn default_max_gap(epochs: &[Epoch]) -> Option<Duration> {
+    let mut histogram = BTreeMap::<Duration, usize>::new();
+    for dt in epochs.windows(2).map(|w| w[1] - w[0]) {
+        *histogram.entry(dt).or_default() += 1;
+    }
+    histogram
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, pop)| *pop)
+        .map(|(dt, _)| dt * 2.0)
+}
+
+/*
+ * Returns indexes of the samples surrounding t.
+ * Identical indexes on exact match.
+ * None when t is out of the series or in a gap larger than max_gap.
+ */
+fn bracket(epochs: &[Epoch], t: Epoch, max_gap: Duration) -> Option<(usize, usize)> {
+    match epochs.binary_search(&t) {
+        Ok(i) => Some((i, i)),
+        Err(i) => {
+            if i == 0 || i == epochs.len() || epochs[i] - epochs[i - 1] > max_gap {
+                None
+            } else {
+                Some((i - 1, i))
+            }
+        },
+    }
+}
+
+/*
+ * Index of the sample nearest to t (earliest on tie)
+ */
+pub(crate) fn nearest(epochs: &[Epoch], t: Epoch, max_gap: Duration) -> Option<usize> {
+    let (i0, i1) = bracket(epochs, t, max_gap)?;
+    if t - epochs[i0] <= epochs[i1] - t {
+        Some(i0)
+    } else {
+        Some(i1)
+    }
+}
+
+/*
+ * Interpolates the (epochs, values) series at t.
+ * Epochs must be sorted chronologically.
+ */
+pub(crate) fn interpolate(
+    epochs: &[Epoch],
+    values: &[f64],
+    t: Epoch,
+    method: InterpMethod,
+    max_gap: Duration,
+) -> Option<f64> {
+    let (i0, i1) = bracket(epochs, t, max_gap)?;
+    if i0 == i1 {
+        return Some(values[i0]);
+    }
+    let order = match method {
+        InterpMethod::Nearest => 1,
+        InterpMethod::Linear => 2,
+        InterpMethod::Lagrange(n) => n,
+    };
+    if order < 2 {
+        return nearest(epochs, t, max_gap).map(|i| values[i]);
+    }
+    // grow the window around [i0, i1], without bridging gaps
+    let (mut lo, mut hi) = (i0, i1);
+    while hi - lo + 1 < order {
+        let can_lo = lo > 0 && epochs[lo] - epochs[lo - 1] <= max_gap;
+        let can_hi = hi + 1 < epochs.len() && epochs[hi + 1] - epochs[hi] <= max_gap;
+        if can_lo && (!can_hi || i0 - lo <= hi - i1) {
+            lo -= 1;
+        } else if can_hi {
+            hi += 1;
+        } else {
+            break;
+        }
+    }
+    let x = (lo..=hi)
+        .map(|i| (epochs[i] - t).to_seconds())
+        .collect::<Vec<_>>();
+    let mut y = 0.0_f64;
+    for (j, xj) in x.iter().enumerate() {
+        let mut l = 1.0_f64;
+        for (m, xm) in x.iter().enumerate() {
+            if m != j {
+                l *= -xm / (xj - xm);
+            }
+        }
+        y += values[lo + j] * l;
+    }
+    Some(y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn interpolation() {
+        let t0 = Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let epochs = (0..10).map(|k| t0 + dt * k as f64).collect::<Vec<_>>();
+        // quadratic signal
+        let f = |t: Epoch| {
+            let x = (t - t0).to_seconds();
+            1.0 + 2.0 * x + 0.5 * x * x
+        };
+        let values = epochs.iter().map(|t| f(*t)).collect::<Vec<_>>();
+        let max_gap = dt * 2.0;
+
+        let t = t0 + Duration::from_seconds(45.0);
+        let nearest = interpolate(&epochs, &values, t, InterpMethod::Nearest, max_gap);
+        assert_eq!(nearest, Some(f(t0 + dt)));
+        let linear = interpolate(&epochs, &values, t, InterpMethod::Linear, max_gap).unwrap();
+        assert_eq!(linear, (f(t0 + dt) + f(t0 + dt * 2.0)) / 2.0);
+        let lagrange =
+            interpolate(&epochs, &values, t, InterpMethod::Lagrange(3), max_gap).unwrap();
+        assert!((lagrange - f(t)).abs() < 1.0E-6);
+
+        // exact match
+        let exact = interpolate(&epochs, &values, t0 + dt, InterpMethod::Linear, max_gap);
+        assert_eq!(exact, Some(f(t0 + dt)));
+
+        // out of time frame
+        let t = t0 - Duration::from_seconds(1.0);
+        assert!(interpolate(&epochs, &values, t, InterpMethod::Linear, max_gap).is_none());
+
+        // gaps are not bridged
+        let mut epochs = epochs.clone();
+        let mut values = values.clone();
+        epochs.remove(5);
+        epochs.remove(4);
+        values.remove(5);
+        values.remove(4);
+        let t = t0 + Duration::from_seconds(135.0);
+        assert!(interpolate(&epochs, &values, t, InterpMethod::Linear, max_gap).is_none());
+        // window does not extend over gaps
+        let t = t0 + Duration::from_seconds(75.0);
+        let lagrange =
+            interpolate(&epochs, &values, t, InterpMethod::Lagrange(5), max_gap).unwrap();
+        assert!((lagrange - f(t)).abs() < 1.0E-6);
+
+        let grid = resampling_grid(t0, t0 + dt, Duration::from_seconds(10.0));
+        assert_eq!(grid.len(), 4);
+        assert!(resampling_grid(t0, t0 + dt, Duration::ZERO).is_empty());
+        assert_eq!(default_max_gap(&epochs), Some(dt * 2.0));
+    }
+}
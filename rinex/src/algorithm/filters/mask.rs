@@ -462,10 +462,10 @@ mod test {
             MaskFilter {
                 operand: MaskOperand::Equals,
                 item: TargetItem::ObservableItem(vec![
-                    Observable::Phase("L1C".to_string()),
-                    Observable::SSI("S1C".to_string()),
-                    Observable::Doppler("D1P".to_string()),
-                    Observable::PseudoRange("C1W".to_string()),
+                    Observable::Phase(ObsCode::new("L1C")),
+                    Observable::SSI(ObsCode::new("S1C")),
+                    Observable::Doppler(ObsCode::new("D1P")),
+                    Observable::PseudoRange(ObsCode::new("C1W")),
                 ])
             }
         );
@@ -5,7 +5,8 @@ mod smoothing;
 
 use super::TargetItem;
 pub use decim::{Decimate, DecimationFilter, DecimationType};
-pub use interp::{InterpFilter, InterpMethod, Interpolate};
+pub(crate) use interp::{default_max_gap, interpolate, nearest, resampling_grid};
+pub use interp::{InterpFilter, InterpMethod, Interpolate, Resample};
 pub use mask::{Mask, MaskFilter, MaskOperand};
 pub use smoothing::{Smooth, SmoothingFilter, SmoothingType};
 
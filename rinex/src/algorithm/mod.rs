@@ -7,8 +7,10 @@ pub use target::TargetItem;
 
 pub use filters::{
     Decimate, DecimationFilter, DecimationType, Filter, InterpFilter, InterpMethod, Interpolate,
-    Mask, MaskFilter, MaskOperand, Preprocessing, Smooth, SmoothingFilter, SmoothingType,
+    Mask, MaskFilter, MaskOperand, Preprocessing, Resample, Smooth, SmoothingFilter, SmoothingType,
 };
 
+pub(crate) use filters::{default_max_gap, interpolate, nearest, resampling_grid};
+
 //pub use averaging::Averager;
 pub use derivative::Derivative;
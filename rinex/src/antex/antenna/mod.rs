@@ -77,6 +77,18 @@ impl Antenna {
             true
         }
     }
+    /// Returns the zenith (or nadir) angles [°] at which the
+    /// phase patterns of this antenna are sampled, in ascending order.
+    pub fn zenith_angles(&self) -> Vec<f64> {
+        let grid = &self.zenith_grid;
+        if grid.spacing <= 0.0 {
+            return vec![grid.start];
+        }
+        let n = ((grid.end - grid.start) / grid.spacing).round() as usize;
+        (0..=n)
+            .map(|i| grid.start + i as f64 * grid.spacing)
+            .collect()
+    }
     // /// Returns the mean phase center position.
     // /// If Self is a Receiver Antenna ([`RxAntenna`]),
     // /// the returned position is expressed as an offset to the
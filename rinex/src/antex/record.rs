@@ -21,12 +21,102 @@ pub(crate) fn is_new_epoch(content: &str) -> bool {
 }
 
 /// Phase pattern description.
-/// We currently do not support azimuth dependent phase patterns.
+/// Values are phase center variations in millimeters,
+/// sampled on the zenith grid of the [`Antenna`].
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum AntennaPhasePattern {
-    /// Azimuth Independent Phase pattern
+    /// Azimuth Independent Phase pattern (NOAZI)
     AzimuthIndependentPattern(Vec<f64>),
+    /// Azimuth Dependent Phase pattern: NOAZI values, followed by
+    /// one set of values per azimuth angle [°], in ascending order
+    AzimuthDependentPattern(Vec<f64>, Vec<(f64, Vec<f64>)>),
+}
+
+impl AntennaPhasePattern {
+    /// Returns true if this phase pattern is azimuth dependent
+    pub fn is_azimuth_dependent(&self) -> bool {
+        matches!(self, Self::AzimuthDependentPattern(_, _))
+    }
+    /// Returns the azimuth independent (NOAZI) values
+    pub fn noazi(&self) -> &[f64] {
+        match self {
+            Self::AzimuthIndependentPattern(values) => values,
+            Self::AzimuthDependentPattern(values, _) => values,
+        }
+    }
+    /// Interpolates the phase center variation [mm] at given
+    /// zenith (or nadir) angle and azimuth angle [°].
+    /// `zenith_grid` are the angles at which the pattern was sampled,
+    /// see [`Antenna::zenith_angles`], and is not required to be evenly spaced.
+    /// Azimuth is ignored for azimuth independent patterns.
+    /// Returns None if `zenith` is outside the calibrated grid.
+    pub fn interpolate(&self, zenith_grid: &[f64], zenith: f64, azimuth: f64) -> Option<f64> {
+        match self {
+            Self::AzimuthIndependentPattern(values) => interpolate_1d(zenith_grid, values, zenith),
+            Self::AzimuthDependentPattern(noazi, patterns) => {
+                if patterns.is_empty() {
+                    return interpolate_1d(zenith_grid, noazi, zenith);
+                }
+                let azimuth = azimuth.rem_euclid(360.0);
+                // bracketing azimuths, wrapping around 360°
+                let upper = patterns
+                    .iter()
+                    .position(|(az, _)| *az >= azimuth)
+                    .unwrap_or(0);
+                let lower = if upper == 0 {
+                    patterns.len() - 1
+                } else {
+                    upper - 1
+                };
+                let (az0, values0) = &patterns[lower];
+                let (az1, values1) = &patterns[upper];
+                let y0 = interpolate_1d(zenith_grid, values0, zenith)?;
+                let y1 = interpolate_1d(zenith_grid, values1, zenith)?;
+                if *az1 == azimuth {
+                    return Some(y1);
+                }
+                let span = (az1 - az0).rem_euclid(360.0);
+                if span == 0.0 {
+                    return Some(y0);
+                }
+                let dx = (azimuth - az0).rem_euclid(360.0);
+                Some(y0 + (y1 - y0) * dx / span)
+            },
+        }
+    }
+}
+
+/// Linear interpolation of `values`, sampled at `grid` (ascending)
+fn interpolate_1d(grid: &[f64], values: &[f64], x: f64) -> Option<f64> {
+    if grid.len() != values.len() || grid.is_empty() {
+        return None;
+    }
+    if x < grid[0] || x > grid[grid.len() - 1] {
+        return None;
+    }
+    let upper = grid.iter().position(|g| *g >= x)?;
+    if upper == 0 || grid[upper] == x {
+        return Some(values[upper]);
+    }
+    let (x0, x1) = (grid[upper - 1], grid[upper]);
+    let (y0, y1) = (values[upper - 1], values[upper]);
+    Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+/// Parses a pattern line, returns the azimuth angle (None for NOAZI)
+/// and the pattern values
+fn parse_pattern(line: &str) -> Option<(Option<f64>, Vec<f64>)> {
+    let mut items = line.split_ascii_whitespace();
+    let azimuth = match items.next()? {
+        "NOAZI" => None,
+        azimuth => Some(azimuth.parse::<f64>().ok()?),
+    };
+    let values = items
+        .map(|item| item.parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    Some((azimuth, values))
 }
 
 impl Default for AntennaPhasePattern {
@@ -44,10 +134,18 @@ pub struct FrequencyDependentData {
     /// or the Spacecraft Mass Center, if this is an [`SvAntenna`].
     pub apc_eccentricity: (f64, f64, f64),
     /// Antenna Phase Pattern.
-    /// We currently do not support Azimuth Dependent phase patterns.
     pub phase_pattern: AntennaPhasePattern,
 }
 
+impl FrequencyDependentData {
+    /// Returns the phase center variation [mm] at given zenith
+    /// (or nadir) and azimuth angles [°], interpolated from the phase pattern.
+    /// `zenith_grid` is obtained with [`Antenna::zenith_angles`].
+    pub fn pcv(&self, zenith_grid: &[f64], zenith: f64, azimuth: f64) -> Option<f64> {
+        self.phase_pattern.interpolate(zenith_grid, zenith, azimuth)
+    }
+}
+
 /// ANTEX RINEX record content.
 /// Data is a list of Antenna containing several [Frequency] items.
 /// We do not parse RMS frequencies at the moment, but it will
@@ -248,6 +346,7 @@ pub(crate) fn parse_antenna(
     let mut frequency = Carrier::default();
     let mut freq_data = FrequencyDependentData::default();
     let mut valid_from = Epoch::default();
+    let mut in_frequency = false;
 
     for line in lines {
         let (content, marker) = line.split_at(60);
@@ -307,10 +406,10 @@ pub(crate) fn parse_antenna(
             let sinex = content.split_at(20).0;
             antenna.sinex_code = sinex.trim().to_string();
         } else if marker.contains("DAZI") {
-            //let dazi = content.split_at(20).0.trim();
-            //if let Ok(dazi) = f64::from_str(dazi) {
-            //    antenna = antenna.with_dazi(dazi)
-            //}
+            let dazi = content.split_at(20).0.trim();
+            if let Ok(dazi) = f64::from_str(dazi) {
+                antenna = antenna.with_dazi(dazi)
+            }
         } else if marker.contains("# OF FREQUENCIES") {
             /*
              * we actually do not care about this field
@@ -320,6 +419,8 @@ pub(crate) fn parse_antenna(
             let svnn = content.split_at(10).0;
             let sv = SV::from_str(svnn.trim())?;
             frequency = carrier::Carrier::from_sv(sv)?;
+            freq_data = FrequencyDependentData::default();
+            in_frequency = true;
         } else if marker.contains("NORTH / EAST / UP") {
             let (north, rem) = content.split_at(10);
             let (east, rem) = rem.split_at(10);
@@ -363,11 +464,30 @@ pub(crate) fn parse_antenna(
             };
         } else if marker.contains("END OF FREQUENCY") {
             inner.insert(frequency, freq_data.clone());
+            in_frequency = false;
         } else if marker.contains("END OF ANTENNA") {
             break; // end of this block, considered as an `epoch`
                    // if we make a parallel with other types of RINEX
-        } else {
+        } else if in_frequency && !marker.contains("COMMENT") {
             // inside phase pattern
+            if let Some((azimuth, values)) = parse_pattern(line) {
+                let pattern = &mut freq_data.phase_pattern;
+                match azimuth {
+                    None => *pattern = AntennaPhasePattern::AzimuthIndependentPattern(values),
+                    Some(azimuth) => match pattern {
+                        AntennaPhasePattern::AzimuthDependentPattern(_, patterns) => {
+                            patterns.push((azimuth, values));
+                        },
+                        _ => {
+                            let noazi = pattern.noazi().to_vec();
+                            *pattern = AntennaPhasePattern::AzimuthDependentPattern(
+                                noazi,
+                                vec![(azimuth, values)],
+                            );
+                        },
+                    },
+                }
+            }
         }
         //    } else if marker.contains("SINEX CODE") {
         //        let sinex = content.split_at(10).0;
@@ -430,6 +550,32 @@ impl Merge for Record {
 mod test {
     use super::*;
     #[test]
+    fn pcv_interpolation() {
+        // non uniform zenith grid
+        let grid = [0.0, 5.0, 15.0, 30.0];
+        let noazi = AntennaPhasePattern::AzimuthIndependentPattern(vec![0.0, 1.0, 3.0, 0.0]);
+        assert_eq!(noazi.interpolate(&grid, 0.0, 0.0), Some(0.0));
+        assert_eq!(noazi.interpolate(&grid, 2.5, 0.0), Some(0.5));
+        assert_eq!(noazi.interpolate(&grid, 10.0, 123.0), Some(2.0));
+        assert_eq!(noazi.interpolate(&grid, 20.0, 0.0), Some(2.0));
+        assert_eq!(noazi.interpolate(&grid, 31.0, 0.0), None);
+        assert_eq!(noazi.interpolate(&grid[..2], 1.0, 0.0), None);
+
+        let pattern = AntennaPhasePattern::AzimuthDependentPattern(
+            vec![0.0, 1.0, 3.0, 0.0],
+            vec![
+                (0.0, vec![0.0, 2.0, 4.0, 0.0]),
+                (180.0, vec![0.0, 4.0, 8.0, 0.0]),
+                (360.0, vec![0.0, 2.0, 4.0, 0.0]),
+            ],
+        );
+        assert_eq!(pattern.interpolate(&grid, 5.0, 0.0), Some(2.0));
+        assert_eq!(pattern.interpolate(&grid, 5.0, 90.0), Some(3.0));
+        assert_eq!(pattern.interpolate(&grid, 5.0, 180.0), Some(4.0));
+        assert_eq!(pattern.interpolate(&grid, 5.0, -90.0), Some(3.0));
+        assert_eq!(pattern.interpolate(&grid, 10.0, 270.0), Some(4.5));
+    }
+    #[test]
     fn test_new_epoch() {
         let content = "                                                           START OF ANTENNA";
         assert!(is_new_epoch(content));
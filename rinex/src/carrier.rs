@@ -82,6 +82,8 @@ pub enum Error {
     UnknownObservable(String),
     #[error("unknown sv system")]
     UnknownSV(SV),
+    #[error("missing glonass frequency channel for {0}")]
+    MissingGlonassChannel(SV),
 }
 
 impl std::fmt::Display for Carrier {
@@ -95,7 +97,9 @@ impl std::fmt::Display for Carrier {
             Self::L6 => write!(f, "L6"),
             Self::G3 => write!(f, "L3"),
             Self::E1 => write!(f, "E1"),
-            Self::E5 | Self::E5a | Self::E5b => write!(f, "E5"),
+            Self::E5 => write!(f, "E5"),
+            Self::E5a => write!(f, "E5a"),
+            Self::E5b => write!(f, "E5b"),
             Self::E6 => write!(f, "E6"),
             Self::S => write!(f, "S"),
             // B1
@@ -134,6 +138,10 @@ impl std::str::FromStr for Carrier {
             Ok(Self::E1)
         } else if content.eq("E5") {
             Ok(Self::E5)
+        } else if content.eq("E5A") {
+            Ok(Self::E5a)
+        } else if content.eq("E5B") {
+            Ok(Self::E5b)
         } else if content.eq("E6") {
             Ok(Self::E6)
         /*
@@ -147,6 +155,8 @@ impl std::str::FromStr for Carrier {
             Ok(Self::G2(None))
         } else if content.eq("G2A") {
             Ok(Self::G2a)
+        } else if content.eq("G3") {
+            Ok(Self::G3)
         /*
          * BeiDou
          */
@@ -160,12 +170,19 @@ impl std::str::FromStr for Carrier {
             Ok(Self::B2)
         } else if content.eq("B2I") {
             Ok(Self::B2I)
+        } else if content.eq("B2A") {
+            Ok(Self::B2A)
         } else if content.eq("B2B") {
             Ok(Self::B2B)
         } else if content.eq("B3") {
             Ok(Self::B3)
         } else if content.eq("B3A") {
             Ok(Self::B3A)
+        /*
+         * IRNSS
+         */
+        } else if content.eq("S") {
+            Ok(Self::S)
         /*
          * DORIS
          */
@@ -203,8 +220,8 @@ impl Carrier {
             Self::G1(None) => 1602.0_f64,
             Self::G1(Some(c)) => 1602.0_f64 + (*c as f64 * 9.0 / 16.0),
             Self::G2a => 1248.06_f64,
-            Self::G2(None) => 1246.06_f64,
-            Self::G2(Some(c)) => 1246.06_f64 + (*c as f64 * 7.0 / 16.0),
+            Self::G2(None) => 1246.0_f64,
+            Self::G2(Some(c)) => 1246.0_f64 + (*c as f64 * 7.0 / 16.0),
             Self::G3 => 1202.025_f64,
             /*
              * BeiDou
@@ -227,16 +244,26 @@ impl Carrier {
         match self {
             Self::L1 | Self::G1(_) | Self::G1a | Self::E1 => 15.345_f64,
             Self::L2 | Self::G2(_) | Self::G2a => 11.0_f64,
-            Self::L5 | Self::E5 | Self::E5a | Self::E5b => 12.5_f64,
-            Self::G3 => todo!("G3 bandwidth is not known to this day"),
-            Self::E6 => todo!("E6 bandwidth is not known to this day"),
-            Self::L6 => todo!("L6 bandwidth is not known to this day"),
-            Self::S => todo!("S bandwidth is not known to this day"),
-            Self::B1I | Self::B1A | Self::B1C => todo!("B1X bandwidth is not known to this day"),
-            Self::B2 | Self::B2A | Self::B2I | Self::B2B => {
-                todo!("B2X bandwidth is not known to this day")
-            },
-            Self::B3 | Self::B3A => todo!("B3X bandwidth is not known to this day"),
+            Self::L5 => 12.5_f64,
+            Self::L6 => 42.0_f64,
+            Self::G3 => 20.46_f64,
+            /*
+             * Galileo
+             */
+            Self::E5 | Self::B2 => 51.15_f64,
+            Self::E5a | Self::E5b => 20.46_f64,
+            Self::E6 => 40.92_f64,
+            /*
+             * BeiDou
+             */
+            Self::B1I => 4.092_f64,
+            Self::B1A | Self::B1C => 32.736_f64,
+            Self::B2I | Self::B2A | Self::B2B => 20.46_f64,
+            Self::B3 | Self::B3A => 20.46_f64,
+            /*
+             * IRNSS
+             */
+            Self::S => 16.5_f64,
             Self::S1 => panic!("DORIS signal bandwidth"),
             Self::U2 => panic!("DORIS signal bandwidth"),
         }
@@ -312,8 +339,8 @@ impl Carrier {
     /// Converts to exact Glonass carrier
     pub fn with_glonass_offset(&self, offset: i8) -> Self {
         match self {
-            Self::L1 => Self::G1(Some(offset)),
-            Self::L2 => Self::G2(Some(offset)),
+            Self::L1 | Self::G1(_) => Self::G1(Some(offset)),
+            Self::L2 | Self::G2(_) => Self::G2(Some(offset)),
             other => *other,
         }
     }
@@ -662,7 +689,7 @@ impl Carrier {
                 if c.is_sbas() {
                     Self::from_geo_observable(observable)
                 } else {
-                    unreachable!("observable for {}", constellation);
+                    Err(Error::UnknownObservable(observable.to_string()))
                 }
             },
         }
@@ -741,6 +768,32 @@ mod test {
     use super::*;
     use std::str::FromStr;
     #[test]
+    fn modern_signals() {
+        for (carrier, frequency_mhz, bandwidth_mhz) in [
+            ("E5", 1191.795, 51.15),
+            ("E5a", 1176.45, 20.46),
+            ("E5b", 1207.14, 20.46),
+            ("E6", 1278.75, 40.92),
+            ("B1C", 1575.42, 32.736),
+            ("B2a", 1176.45, 20.46),
+            ("B2b", 1207.14, 20.46),
+            ("B3", 1268.52, 20.46),
+        ] {
+            let parsed = Carrier::from_str(carrier).unwrap();
+            assert_eq!(parsed.frequency_mhz(), frequency_mhz, "{}", carrier);
+            assert_eq!(parsed.bandwidth_mhz(), bandwidth_mhz, "{}", carrier);
+            assert_eq!(Carrier::from_str(&parsed.to_string()), Ok(parsed));
+        }
+        assert_eq!(Carrier::from_str("G3").unwrap().frequency_mhz(), 1202.025);
+
+        let c8q = Observable::from_str("C8Q").unwrap();
+        let e5 = Carrier::from_observable(Constellation::Galileo, &c8q).unwrap();
+        assert_eq!(e5, Carrier::E5);
+        let l7d = Observable::from_str("L7D").unwrap();
+        let b2b = Carrier::from_observable(Constellation::BeiDou, &l7d).unwrap();
+        assert_eq!(b2b.frequency_mhz(), 1207.14);
+    }
+    #[test]
     fn test_carrier() {
         assert!(Carrier::from_str("L1").is_ok());
         assert!(Carrier::from_str("C1").is_err());
@@ -948,4 +1001,32 @@ mod test {
             }
         }
     }
+    #[test]
+    fn unknown_observable() {
+        let l9z = Observable::from_str("L9Z").unwrap();
+        assert_eq!(
+            Carrier::from_observable(Constellation::GPS, &l9z),
+            Err(Error::UnknownObservable("L9Z".to_string()))
+        );
+        let g1 = Carrier::from_observable(
+            Constellation::Glonass,
+            &Observable::from_str("L1C").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(g1.with_glonass_offset(-4), Carrier::G1(Some(-4)));
+        assert_eq!(
+            g1.with_glonass_offset(-4).frequency_mhz(),
+            1602.0 - 4.0 * 9.0 / 16.0
+        );
+        let g2 = Carrier::from_observable(
+            Constellation::Glonass,
+            &Observable::from_str("L2P").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(g2.frequency_mhz(), 1246.0);
+        assert_eq!(
+            g2.with_glonass_offset(6).frequency_mhz(),
+            1246.0 + 6.0 * 7.0 / 16.0
+        );
+    }
 }
@@ -9,6 +9,11 @@ use std::str::FromStr;
 
 use crate::domes::Domes;
 
+#[cfg(feature = "clock")]
+use crate::prelude::Epoch;
+#[cfg(feature = "clock")]
+use std::collections::BTreeMap;
+
 /// Clocks `RINEX` specific header fields
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -122,3 +127,24 @@ impl HeaderFields {
         s
     }
 }
+
+/// Clock products analysis, used to study one clock
+/// or compare clock products from different analysis centers.
+#[cfg(feature = "clock")]
+#[cfg_attr(docrs, doc(cfg(feature = "clock")))]
+pub trait ClockAnalysis {
+    /// Returns the bias [s] time series of given clock.
+    /// When several profiles describe this clock at a given epoch,
+    /// analysis results (AR, AS) are preferred.
+    fn bias_series(&self, clock: &ClockType) -> BTreeMap<Epoch, f64>;
+    /// Estimates the drift [s/s] of given clock, by least squares linear fit
+    /// of its bias over the [start, end] window. Requires two samples at least.
+    fn drift_estimate(&self, clock: &ClockType, start: Epoch, end: Epoch) -> Option<f64>;
+    /// Expresses all clocks with respect to the `reference` clock:
+    /// its bias is subtracted from every clock bias (itself included), at each epoch.
+    /// Drifts are aligned as well, when the reference drift is known, and dropped otherwise.
+    /// Epochs where the reference clock is absent are dropped.
+    fn align_to_reference_mut(&mut self, reference: &ClockType);
+    /// Copies and aligns to `reference` clock. See [Self::align_to_reference_mut].
+    fn align_to_reference(&self, reference: &ClockType) -> Self;
+}
@@ -350,7 +350,7 @@ impl Split for Record {
         let r0 = self
             .iter()
             .flat_map(|(k, v)| {
-                if k <= &epoch {
+                if k < &epoch {
                     Some((*k, v.clone()))
                 } else {
                     None
@@ -360,7 +360,7 @@ impl Split for Record {
         let r1 = self
             .iter()
             .flat_map(|(k, v)| {
-                if k > &epoch {
+                if k >= &epoch {
                     Some((*k, v.clone()))
                 } else {
                     None
@@ -437,12 +437,63 @@ impl Preprocessing for Record {
         match f {
             Filter::Mask(mask) => self.mask_mut(mask),
             Filter::Smoothing(_) => todo!(),
-            Filter::Decimation(_) => todo!(),
+            // decimation applies to entire epochs
+            Filter::Decimation(filter) => match filter.dtype {
+                DecimationType::DecimByRatio(r) => self.decimate_by_ratio_mut(r),
+                DecimationType::DecimByInterval(dt) => self.decimate_by_interval_mut(dt),
+            },
             Filter::Interp(filter) => self.interpolate_mut(filter.series),
         }
     }
 }
 
+#[cfg(feature = "processing")]
+impl Decimate for Record {
+    fn decimate_by_ratio_mut(&mut self, r: u32) {
+        let mut i = 0;
+        self.retain(|_, _| {
+            let retained = (i % r.max(1)) == 0;
+            i += 1;
+            retained
+        });
+    }
+    fn decimate_by_ratio(&self, r: u32) -> Self {
+        let mut s = self.clone();
+        s.decimate_by_ratio_mut(r);
+        s
+    }
+    fn decimate_by_interval_mut(&mut self, interval: Duration) {
+        let mut last_retained = Option::<Epoch>::None;
+        self.retain(|e, _| {
+            if let Some(last) = last_retained {
+                let dt = *e - last;
+                if dt >= interval {
+                    last_retained = Some(*e);
+                    true
+                } else {
+                    false
+                }
+            } else {
+                last_retained = Some(*e);
+                true // always retain 1st epoch
+            }
+        });
+    }
+    fn decimate_by_interval(&self, interval: Duration) -> Self {
+        let mut s = self.clone();
+        s.decimate_by_interval_mut(interval);
+        s
+    }
+    fn decimate_match_mut(&mut self, rhs: &Self) {
+        self.retain(|e, _| rhs.get(e).is_some());
+    }
+    fn decimate_match(&self, rhs: &Self) -> Self {
+        let mut s = self.clone();
+        s.decimate_match_mut(rhs);
+        s
+    }
+}
+
 #[cfg(feature = "processing")]
 impl Interpolate for Record {
     fn interpolate(&self, series: TimeSeries) -> Self {
@@ -455,6 +506,148 @@ impl Interpolate for Record {
     }
 }
 
+#[cfg(feature = "processing")]
+impl Resample for Record {
+    fn resample(&self, interval: Duration, method: InterpMethod) -> Self {
+        let mut s = self.clone();
+        s.resample_mut(interval, method);
+        s
+    }
+    fn resample_mut(&mut self, interval: Duration, method: InterpMethod) {
+        let epochs = self.keys().copied().collect::<Vec<_>>();
+        if let Some(max_gap) = crate::algorithm::default_max_gap(&epochs) {
+            self.resample_with_max_gap_mut(interval, method, max_gap);
+        }
+    }
+    fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) -> Self {
+        let mut s = self.clone();
+        s.resample_with_max_gap_mut(interval, method, max_gap);
+        s
+    }
+    /*
+     * Clock bias is interpolated with given method,
+     * other fields are picked from the nearest sample.
+     */
+    fn resample_with_max_gap_mut(
+        &mut self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) {
+        if interval <= Duration::ZERO {
+            return; // null interval: nothing to resample onto
+        }
+        let mut series = BTreeMap::<ClockKey, (Vec<Epoch>, Vec<ClockProfile>, Vec<f64>)>::new();
+        for (e, keys) in self.iter() {
+            for (key, profile) in keys {
+                let (epochs, profiles, biases) = series.entry(key.clone()).or_default();
+                epochs.push(*e);
+                profiles.push(profile.clone());
+                biases.push(profile.bias);
+            }
+        }
+        let (t0, t1) = match (self.keys().next(), self.keys().next_back()) {
+            (Some(t0), Some(t1)) => (*t0, *t1),
+            _ => return,
+        };
+        let mut resampled = Record::new();
+        for t in crate::algorithm::resampling_grid(t0, t1, interval) {
+            let mut keys = BTreeMap::<ClockKey, ClockProfile>::new();
+            for (key, (epochs, profiles, biases)) in &series {
+                let nearest = match crate::algorithm::nearest(epochs, t, max_gap) {
+                    Some(nearest) => &profiles[nearest],
+                    None => continue,
+                };
+                if let Some(bias) =
+                    crate::algorithm::interpolate(epochs, biases, t, method, max_gap)
+                {
+                    keys.insert(
+                        key.clone(),
+                        ClockProfile {
+                            bias,
+                            ..nearest.clone()
+                        },
+                    );
+                }
+            }
+            if !keys.is_empty() {
+                resampled.insert(t, keys);
+            }
+        }
+        *self = resampled;
+    }
+}
+
+#[cfg(feature = "clock")]
+use super::ClockAnalysis;
+
+#[cfg(feature = "clock")]
+impl ClockAnalysis for Record {
+    fn bias_series(&self, clock: &ClockType) -> BTreeMap<Epoch, f64> {
+        self.iter()
+            .filter_map(|(t, clocks)| {
+                // keys are sorted: analysis results come first
+                clocks
+                    .iter()
+                    .find(|(key, _)| key.clock_type == *clock)
+                    .map(|(_, profile)| (*t, profile.bias))
+            })
+            .collect()
+    }
+    fn drift_estimate(&self, clock: &ClockType, start: Epoch, end: Epoch) -> Option<f64> {
+        let series: Vec<(f64, f64)> = self
+            .bias_series(clock)
+            .into_iter()
+            .filter(|(t, _)| *t >= start && *t <= end)
+            .map(|(t, bias)| ((t - start).to_seconds(), bias))
+            .collect();
+        if series.len() < 2 {
+            return None;
+        }
+        let n = series.len() as f64;
+        let t_mean = series.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let bias_mean = series.iter().map(|(_, bias)| bias).sum::<f64>() / n;
+        let (mut num, mut denom) = (0.0_f64, 0.0_f64);
+        for (t, bias) in &series {
+            num += (t - t_mean) * (bias - bias_mean);
+            denom += (t - t_mean).powi(2);
+        }
+        if denom > 0.0 {
+            Some(num / denom)
+        } else {
+            None
+        }
+    }
+    fn align_to_reference_mut(&mut self, reference: &ClockType) {
+        let ref_bias = self.bias_series(reference);
+        self.retain(|t, _| ref_bias.contains_key(t));
+        for (t, clocks) in self.iter_mut() {
+            let bias = ref_bias[t];
+            let drift = clocks
+                .iter()
+                .find(|(key, _)| key.clock_type == *reference)
+                .and_then(|(_, profile)| profile.drift);
+            for profile in clocks.values_mut() {
+                profile.bias -= bias;
+                profile.drift = match (profile.drift, drift) {
+                    (Some(lhs), Some(rhs)) => Some(lhs - rhs),
+                    _ => None,
+                };
+            }
+        }
+    }
+    fn align_to_reference(&self, reference: &ClockType) -> Self {
+        let mut s = self.clone();
+        s.align_to_reference_mut(reference);
+        s
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -483,6 +676,42 @@ mod test {
         assert!(!is_new_epoch(c));
     }
     #[test]
+    fn split_boundary() {
+        let key = ClockKey {
+            clock_type: ClockType::SV(SV::from_str("G01").unwrap()),
+            profile_type: ClockProfileType::AS,
+        };
+        let mut record = Record::new();
+        for (epoch, bias) in [
+            ("2019-01-08T00:00:00 GPST", 1.0E-4),
+            ("2019-01-08T00:00:30 GPST", 2.0E-4),
+            ("2019-01-08T00:01:00 GPST", 3.0E-4),
+        ] {
+            let profile = ClockProfile {
+                bias,
+                ..Default::default()
+            };
+            record.insert(
+                Epoch::from_str(epoch).unwrap(),
+                BTreeMap::from([(key.clone(), profile)]),
+            );
+        }
+
+        // epoch on the boundary is part of the second half
+        let boundary = Epoch::from_str("2019-01-08T00:00:30 GPST").unwrap();
+        let (r0, r1) = record.split(boundary).unwrap();
+        assert_eq!(r0.len(), 1);
+        assert_eq!(r1.len(), 2);
+        assert!(r0.keys().all(|e| *e < boundary));
+        assert_eq!(r1.keys().next(), Some(&boundary));
+
+        // between two epochs
+        let t = Epoch::from_str("2019-01-08T00:00:45 GPST").unwrap();
+        let (r0, r1) = record.split(t).unwrap();
+        assert_eq!(r0.len(), 2);
+        assert_eq!(r1.len(), 1);
+    }
+    #[test]
     fn parse_clk_v2_epoch() {
         for (descriptor, epoch, key, profile) in [
             (
@@ -46,6 +46,13 @@ impl std::str::FromStr for Domes {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.len() == 9 {
+            if !s.is_ascii() {
+                return Err(Error::InvalidFormat);
+            }
+            let digits = |content: &str| content.chars().all(|c| c.is_ascii_digit());
+            if !digits(&s[..5]) || !digits(&s[6..]) {
+                return Err(Error::InvalidFormat);
+            }
             let point = if s[5..6].eq("M") {
                 TrackingPoint::Monument
             } else if s[5..6].eq("S") {
@@ -113,5 +120,18 @@ mod test {
             // reciprocal
             assert_eq!(domes.to_string(), descriptor, "DOMES reciprocal failed");
         }
+        for descriptor in [
+            "10002X006",
+            "1000+M006",
+            "10002M+06",
+            "10002M0é",
+            "10002M06",
+        ] {
+            assert!(
+                Domes::from_str(descriptor).is_err(),
+                "{} is not a valid DOMES",
+                descriptor
+            );
+        }
     }
 }
@@ -43,6 +43,7 @@ pub struct HeaderFields {
     pub observables: Vec<Observable>,
     /// Data scaling, almost 100% of the time present in DORIS measurements.
     /// Allows some nano radians precision on phase data for example.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub scaling: HashMap<Observable, u16>,
     /// Reference stations present in this file
     pub stations: Vec<Station>,
@@ -306,7 +306,7 @@ impl Decimate for Record {
     fn decimate_by_ratio_mut(&mut self, r: u32) {
         let mut i = 0;
         self.retain(|_, _| {
-            let retained = (i % r) == 0;
+            let retained = (i % r.max(1)) == 0;
             i += 1;
             retained
         });
@@ -1,4 +1,5 @@
 //! Epoch parsing helpers
+use crate::observation::EpochFlag;
 use crate::types::Type;
 use hifitime::{
     Epoch, EpochError as HifitimeEpochError, ParsingError as HifitimeParsingError, TimeScale,
@@ -30,6 +31,8 @@ pub enum ParsingError {
     SecondsField(String),
     #[error("failed to parse nanos from \"{0}\"")]
     NanosecondsField(String),
+    #[error("failed to parse epoch flag from \"{0}\"")]
+    EpochFlag(String),
 }
 
 /*
@@ -242,6 +245,30 @@ pub(crate) fn parse_utc(s: &str) -> Result<Epoch, ParsingError> {
     parse_in_timescale(s, TimeScale::UTC)
 }
 
+/// Parses an [Epoch] described as "yyyy mm dd hh mm ss.sssssss"
+/// (2 digit years are tolerated), possibly followed by an [EpochFlag] digit,
+/// like in Observation RINEX. Epoch is interpreted in given [TimeScale],
+/// and the flag defaults to [EpochFlag::Ok] when omitted.
+/// ```
+/// use std::str::FromStr;
+/// use rinex::epoch::parse_flagged;
+/// use rinex::prelude::{Epoch, EpochFlag, TimeScale};
+/// let (epoch, flag) = parse_flagged("2022 03 04 00 28 30.0000000  1", TimeScale::GPST)
+///     .unwrap();
+/// assert_eq!(epoch, Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap());
+/// assert_eq!(flag, EpochFlag::PowerFailure);
+/// ```
+pub fn parse_flagged(content: &str, ts: TimeScale) -> Result<(Epoch, EpochFlag), ParsingError> {
+    let flag = match content.split_ascii_whitespace().nth(6) {
+        Some(flag) => {
+            EpochFlag::from_str(flag).map_err(|_| ParsingError::EpochFlag(flag.to_string()))?
+        },
+        None => EpochFlag::Ok,
+    };
+    let epoch = parse_in_timescale(content, ts)?;
+    Ok((epoch, flag))
+}
+
 /*
  * Until Hifitime provides a decomposition method in timescale other than UTC
  * we have this tweak to decompose %Y %M %D %HH %MM %SS and without nanoseconds
@@ -594,4 +621,28 @@ mod test {
             );
         }
     }
+    #[test]
+    fn epoch_parse_flagged() {
+        let t0 = Epoch::from_gregorian_utc(2021, 1, 1, 0, 0, 30, 0);
+        for (content, epoch, flag) in [
+            ("2021 01 01 00 00 30.0000000", t0, EpochFlag::Ok),
+            ("2021 01 01 00 00 30.0000000  0", t0, EpochFlag::Ok),
+            ("21  1  1  0  0 30.0000000  1", t0, EpochFlag::PowerFailure),
+            (
+                "2021 01 01 00 00 30.0000000  4",
+                t0,
+                EpochFlag::HeaderInformationFollows,
+            ),
+            ("21  1  1  0  0 30.0000000  6", t0, EpochFlag::CycleSlip),
+        ] {
+            let parsed = parse_flagged(content, TimeScale::UTC).unwrap();
+            assert_eq!(parsed, (epoch, flag), "\"{}\"", content);
+        }
+        let (epoch, flag) = parse_flagged("2021 01 01 00 00 30.0000000", TimeScale::GPST).unwrap();
+        assert_eq!(epoch, Epoch::from_str("2021-01-01T00:00:30 GPST").unwrap());
+        assert_eq!(flag, EpochFlag::Ok);
+
+        assert!(parse_flagged("2021 01 01 00 00 30.0000000  9", TimeScale::UTC).is_err());
+        assert!(parse_flagged("2021 01 01 00 00", TimeScale::UTC).is_err());
+    }
 }
@@ -0,0 +1,95 @@
+//! Numerical formatting helpers, matching RINEX (FORTRAN) conventions
+
+use crate::prelude::{Constellation, SV};
+
+/// Formats `value` in FORTRAN `D` notation: normalized mantissa
+/// (`0.1 <= |m| < 1`) with `mantissa_digits` digits,
+/// and a signed, two digits (at least) exponent.
+/// The result is not padded: right align it to the desired field width.
+/// ```
+/// use rinex::formatter::fortran_d;
+/// assert_eq!(fortran_d(12345.0, 12), "0.123450000000D+05");
+/// assert_eq!(fortran_d(-1.5E-9, 4), "-0.1500D-08");
+/// assert_eq!(fortran_d(0.0, 3), "0.000D+00");
+/// ```
+pub fn fortran_d(value: f64, mantissa_digits: usize) -> String {
+    let digits = mantissa_digits.max(1);
+    if value == 0.0 {
+        return format!("0.{}D+00", "0".repeat(digits));
+    }
+    // Rust scientific notation is normalized to 1 <= |m| < 10,
+    // and takes care of the rounding
+    let formatted = format!("{:.*E}", digits - 1, value);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('E').unwrap_or(0));
+    let exponent = exponent[1..].parse::<i32>().unwrap_or(0) + 1;
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let mantissa = mantissa.replace('.', "");
+    format!(
+        "{}0.{}D{}{:02}",
+        sign,
+        mantissa,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+/// Sort key of a vehicle in produced files: system letter order
+/// G, R, E, C, J, S, I (other systems come last), then PRN.
+/// [`SV`] ordering is defined upstream and does not follow this convention,
+/// so writers sort on this key instead.
+/// ```
+/// use std::str::FromStr;
+/// use rinex::prelude::SV;
+/// use rinex::formatter::sv_order;
+/// let mut vehicles = ["I01", "C05", "S23", "E11", "J02", "R07", "G30"]
+///     .map(|sv| SV::from_str(sv).unwrap());
+/// vehicles.sort_by_key(sv_order);
+/// assert_eq!(
+///     vehicles.map(|sv| format!("{:x}", sv)).join(""),
+///     "G30R07E11C05J02S23I01"
+/// );
+/// ```
+pub fn sv_order(sv: &SV) -> (u8, u8) {
+    let system = match sv.constellation {
+        Constellation::GPS => 0,
+        Constellation::Glonass => 1,
+        Constellation::Galileo => 2,
+        Constellation::BeiDou => 3,
+        Constellation::QZSS => 4,
+        c if c.is_sbas() => 5,
+        Constellation::IRNSS => 6,
+        _ => 7,
+    };
+    (system, sv.prn)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fortran_d, sv_order};
+    use crate::prelude::SV;
+    use std::str::FromStr;
+    #[test]
+    fn fortran_d_notation() {
+        assert_eq!(fortran_d(12345.0, 12), "0.123450000000D+05");
+        assert_eq!(fortran_d(-12345.0, 12), "-0.123450000000D+05");
+        assert_eq!(fortran_d(0.5, 3), "0.500D+00");
+        assert_eq!(fortran_d(1.0, 3), "0.100D+01");
+        assert_eq!(fortran_d(9.9996, 4), "0.1000D+02");
+        assert_eq!(fortran_d(1.862645149231E-09, 12), "0.186264514923D-08");
+        assert_eq!(fortran_d(1.0E120, 2), "0.10D+121");
+    }
+    #[test]
+    fn rinex_sv_order() {
+        let sorted = [
+            "G01", "G12", "R03", "E01", "E36", "C02", "J01", "S20", "S36", "I09",
+        ]
+        .map(|sv| SV::from_str(sv).unwrap());
+        let mut vehicles = sorted;
+        vehicles.reverse();
+        vehicles.sort_by_key(sv_order);
+        assert_eq!(vehicles, sorted);
+    }
+}
@@ -0,0 +1,68 @@
+//! Data gaps analysis
+use crate::prelude::{Duration, Epoch};
+
+/// [GapAnalysis] summarizes the data gaps of a record,
+/// with respect to a nominal sampling interval.
+/// See [crate::Rinex::gap_analysis].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GapAnalysis {
+    /// Nominal sampling interval the analysis was performed against
+    pub nominal: Option<Duration>,
+    /// Total time spanned by the record, from first to last epoch
+    pub span: Duration,
+    /// Number of epochs that were sampled
+    pub epochs: usize,
+    /// Number of data gaps, that is: intervals between two
+    /// successive epochs, larger than the nominal interval
+    pub gaps: usize,
+    /// Cumulated duration of all data gaps
+    pub cumulative_gap: Duration,
+    /// Largest data gap, as (starting epoch, duration)
+    pub largest_gap: Option<(Epoch, Duration)>,
+    /// Ratio of sampled epochs, over the number of epochs
+    /// that should have been sampled at the nominal interval, within `span`.
+    /// Ranges from 0.0 to 1.0.
+    pub completeness: f64,
+}
+
+impl GapAnalysis {
+    /*
+     * Analyzes given (sorted) list of epochs
+     */
+    pub(crate) fn new(epochs: &[Epoch], nominal: Option<Duration>) -> Self {
+        let mut analysis = Self {
+            nominal,
+            epochs: epochs.len(),
+            ..Default::default()
+        };
+        let (first, last) = match (epochs.first(), epochs.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return analysis,
+        };
+        analysis.span = last - first;
+
+        let nominal = match nominal {
+            Some(nominal) if nominal > Duration::ZERO => nominal,
+            _ => {
+                // gaps cannot be defined
+                analysis.completeness = 1.0;
+                return analysis;
+            },
+        };
+        for (ek, ekp1) in epochs.iter().zip(epochs.iter().skip(1)) {
+            let dt = *ekp1 - *ek;
+            if dt > nominal {
+                analysis.gaps += 1;
+                analysis.cumulative_gap += dt;
+                match analysis.largest_gap {
+                    Some((_, largest)) if largest >= dt => {},
+                    _ => analysis.largest_gap = Some((*ek, dt)),
+                }
+            }
+        }
+        let expected = (analysis.span.to_seconds() / nominal.to_seconds()).round() as usize + 1;
+        analysis.completeness = (epochs.len() as f64 / expected as f64).min(1.0);
+        analysis
+    }
+}
@@ -39,17 +39,14 @@ pub struct Antenna {
     pub model: String,
     /// Serial number / identification number
     pub sn: String,
-    /// Base / reference point coordinates
-    pub coords: Option<(f64, f64, f64)>,
-    /// Optionnal `h` eccentricity (height component),
-    /// referenced to base/reference point, in meter
-    pub height: Option<f64>,
-    /// Optionnal `eastern` eccentricity (eastern component),
-    /// referenced to base/reference point, in meter
-    pub eastern: Option<f64>,
-    /// Optionnal `northern` eccentricity (northern component),
-    /// referenced to base/reference point, in meter
-    pub northern: Option<f64>,
+    /// Optionnal (height, eastern, northern) eccentricities
+    /// of the antenna reference point, above the marker, in meter
+    /// (ANTENNA: DELTA H/E/N)
+    pub delta_hen: Option<(f64, f64, f64)>,
+    /// Optionnal (x, y, z) position of the antenna reference point
+    /// in a body fixed coordinate system, in meter
+    /// (ANTENNA: DELTA X/Y/Z)
+    pub delta_xyz: Option<(f64, f64, f64)>,
 }
 
 impl Antenna {
@@ -65,28 +62,16 @@ impl Antenna {
         s.sn = sn.to_string();
         s
     }
-    /// Sets reference/base coordinates (3D)
-    pub fn with_base_coordinates(&self, coords: (f64, f64, f64)) -> Self {
+    /// Sets antenna (height, eastern, northern) eccentricities
+    pub fn with_delta_hen(&self, hen: (f64, f64, f64)) -> Self {
         let mut s = self.clone();
-        s.coords = Some(coords);
+        s.delta_hen = Some(hen);
         s
     }
-    /// Sets antenna `h` eccentricity component
-    pub fn with_height(&self, h: f64) -> Self {
+    /// Sets antenna (x, y, z) body fixed position
+    pub fn with_delta_xyz(&self, xyz: (f64, f64, f64)) -> Self {
         let mut s = self.clone();
-        s.height = Some(h);
-        s
-    }
-    /// Sets antenna `eastern` coordinates component
-    pub fn with_eastern_component(&self, e: f64) -> Self {
-        let mut s = self.clone();
-        s.eastern = Some(e);
-        s
-    }
-    /// Sets antenna `northern` coordiantes component
-    pub fn with_northern_component(&self, n: f64) -> Self {
-        let mut s = self.clone();
-        s.northern = Some(n);
+        s.delta_xyz = Some(xyz);
         s
     }
 }
@@ -113,13 +98,10 @@ impl HtmlReport for Antenna {
                         : "SN#"
                     }
                     th {
-                        : "Base Coordinates"
+                        : "Delta H/E/N"
                     }
                     th {
-                        : "Height"
-                    }
-                    th {
-                        : "Eccentricity"
+                        : "Delta X/Y/Z"
                     }
                 }
                 tr {
@@ -130,27 +112,15 @@ impl HtmlReport for Antenna {
                         : self.sn.clone()
                     }
                     td {
-                        @ if let Some(coords) = self.coords {
-                            : format!("({}m, {}m, {}m) (ECEF)",
-                                coords.0, coords.1, coords.2)
-                        } else {
-                            : "Unknown"
-                        }
-                    }
-                    td {
-                        @ if let Some(h) = self.height {
-                            : format!("{} m", h)
+                        @ if let Some((h, e, n)) = self.delta_hen {
+                            : format!("{}m H, {}m E, {}m N", h, e, n)
                         } else {
                             : "Unknown"
                         }
                     }
                     td {
-                        @ if let Some(north) = self.northern {
-                            @ if let Some(east) = self.eastern {
-                                : format!("{}m N, {}m E", north, east)
-                            } else {
-                                : "Unknown"
-                            }
+                        @ if let Some((x, y, z)) = self.delta_xyz {
+                            : format!("({}m, {}m, {}m)", x, y, z)
                         } else {
                             : "Unknown"
                         }
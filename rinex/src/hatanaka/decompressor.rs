@@ -64,7 +64,13 @@ fn format_epoch(
                 // fits in a single line
                 result.push_str(systems);
                 if let Some(value) = clock_offset {
-                    result.push_str(&format!("  {:3.9}", (value as f64) / 1000.0_f64))
+                    // clock offset is found in columns 69-80
+                    result.push_str(&format!(
+                        "{:width$}{:12.9}",
+                        "",
+                        (value as f64) / 1000.0_f64,
+                        width = 68_usize.saturating_sub(result.len())
+                    ))
                 }
             } else {
                 // does not fit in a single line
@@ -155,8 +161,8 @@ impl Decompressor {
             offset += 2; // YYYY on 4 digits
         }
 
-        let (_, rem) = content.split_at(offset);
-        let (n, _) = rem.split_at(3);
+        // malformed descriptors are shorter than expected
+        let n = content.get(offset..offset + 3)?;
         if let Ok(n) = u16::from_str_radix(n.trim(), 10) {
             Some(n.into())
         } else {
@@ -192,8 +198,8 @@ impl Decompressor {
             1 => std::cmp::min(32 + 3 * (sv_ptr + 1), epoch.len()), // overflow protection
             _ => std::cmp::min(41 + 3 * (sv_ptr + 1), epoch.len()), // overflow protection
         };
-        let system = epoch.split_at(offset).0;
-        let (_, svnn) = system.split_at(system.len() - 3); // last 3 XXX
+        let system = epoch.get(..offset)?;
+        let svnn = system.get(system.len().checked_sub(3)?..)?; // last 3 XXX
         let svnn = svnn.trim();
         match crx_major > 2 {
             false => {
@@ -209,7 +215,7 @@ impl Decompressor {
                     },
                     constellation => {
                         // OLD + FIXED: constellation might be omitted.......
-                        if let Ok(prn) = u8::from_str_radix(svnn[1..].trim(), 10) {
+                        if let Ok(prn) = u8::from_str_radix(svnn.get(1..)?.trim(), 10) {
                             Some(SV {
                                 prn,
                                 constellation: *constellation,
@@ -316,11 +322,9 @@ impl Decompressor {
                      * this line is dedicated to clock offset description
                      */
                     let mut clock_offset: Option<i64> = None;
-                    if line.contains('&') {
+                    if let Some((n, value)) = line.split_once('&') {
                         // clock offset kernel (re)init
-                        let (n, rem) = line.split_at(1);
                         if let Ok(order) = u8::from_str_radix(n, 10) {
-                            let (_, value) = rem.split_at(1);
                             if let Ok(value) = i64::from_str_radix(value, 10) {
                                 self.clock_diff.init(order.into(), value)?;
                             } else {
@@ -368,7 +372,7 @@ impl Decompressor {
 
                 State::Body => {
                     let mut obs_ptr: usize = 0;
-                    let mut observations: Vec<Option<i64>> = Vec::with_capacity(16);
+                    let mut observations: Vec<Option<i64>> = Vec::new();
                     /*
                      * identify satellite we're dealing with
                      */
@@ -384,14 +388,15 @@ impl Decompressor {
                          * Build compress tools in case this vehicle is new
                          */
                         if self.sv_diff.get(&sv).is_none() {
-                            let mut inner: Vec<(NumDiff, TextDiff, TextDiff)> =
-                                Vec::with_capacity(16);
                             // this protects from malformed Headers or malformed Epoch descriptions
                             let codes = match sv.constellation.is_sbas() {
                                 true => observables.get(&Constellation::SBAS),
                                 false => observables.get(&sv.constellation),
                             };
                             if let Some(codes) = codes {
+                                // one kernel per observable declared in the header
+                                let mut inner: Vec<(NumDiff, TextDiff, TextDiff)> =
+                                    Vec::with_capacity(codes.len());
                                 for _ in codes {
                                     let mut kernels = (
                                         NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
@@ -414,6 +419,7 @@ impl Decompressor {
                             false => observables.get(&sv.constellation),
                         };
                         if let Some(codes) = codes {
+                            observations.reserve(codes.len());
                             while obs_ptr < codes.len() {
                                 if let Some(pos) = line.find(' ') {
                                     let content = &line[..pos];
@@ -434,24 +440,21 @@ impl Decompressor {
                                                 let order = u8::from_str_radix(order.trim(), 10)?;
                                                 //println!("ORDER {}", order); //DEBUG
                                                 let (_, data) = rem.split_at(1);
-                                                if let Ok(data) =
-                                                    i64::from_str_radix(data.trim(), 10)
-                                                {
-                                                    sv_diff[obs_ptr]
-                                                        .0 // observations only, at this point
-                                                        .init(order.into(), data)?;
-                                                    observations.push(Some(data));
-                                                }
+                                                let data = i64::from_str_radix(data.trim(), 10)
+                                                    .map_err(|_| Error::MalformedDifference)?;
+                                                sv_diff[obs_ptr]
+                                                    .0 // observations only, at this point
+                                                    .init(order.into(), data)?;
+                                                observations.push(Some(data));
                                             } else {
                                                 // regular compression
-                                                if let Ok(num) =
+                                                let num =
                                                     i64::from_str_radix(content.trim(), 10)
-                                                {
-                                                    let recovered = sv_diff[obs_ptr]
-                                                        .0 // observations only, at this point
-                                                        .decompress(num);
-                                                    observations.push(Some(recovered));
-                                                }
+                                                        .map_err(|_| Error::MalformedDifference)?;
+                                                let recovered = sv_diff[obs_ptr]
+                                                    .0 // observations only, at this point
+                                                    .decompress(num);
+                                                observations.push(Some(recovered));
                                             }
                                         }
                                     }
@@ -463,26 +466,31 @@ impl Decompressor {
                                      *  => try to parse one last obs
                                      */
                                     //println!("OBS \"{}\" - CONTENT \"{}\"", codes[obs_ptr], line); //DEBUG
-                                    if let Some(sv_diff) = self.sv_diff.get_mut(&sv) {
+                                    if line.trim().is_empty() {
+                                        /*
+                                         * missing observations
+                                         */
+                                        observations.push(None);
+                                    } else if let Some(sv_diff) = self.sv_diff.get_mut(&sv) {
                                         if let Some(marker) = line.find('&') {
                                             // kernel (re)initliaization
                                             let (order, rem) = line.split_at(marker);
                                             let order = u8::from_str_radix(order.trim(), 10)?;
                                             let (_, data) = rem.split_at(1);
-                                            if let Ok(data) = i64::from_str_radix(data.trim(), 10) {
-                                                sv_diff[obs_ptr]
-                                                    .0 // observations only, at this point
-                                                    .init(order.into(), data)?;
-                                                observations.push(Some(data));
-                                            }
+                                            let data = i64::from_str_radix(data.trim(), 10)
+                                                .map_err(|_| Error::MalformedDifference)?;
+                                            sv_diff[obs_ptr]
+                                                .0 // observations only, at this point
+                                                .init(order.into(), data)?;
+                                            observations.push(Some(data));
                                         } else {
                                             // regular compression
-                                            if let Ok(num) = i64::from_str_radix(line.trim(), 10) {
-                                                let recovered = sv_diff[obs_ptr]
-                                                    .0 // observations only, at this point
-                                                    .decompress(num);
-                                                observations.push(Some(recovered))
-                                            }
+                                            let num = i64::from_str_radix(line.trim(), 10)
+                                                .map_err(|_| Error::MalformedDifference)?;
+                                            let recovered = sv_diff[obs_ptr]
+                                                .0 // observations only, at this point
+                                                .decompress(num);
+                                            observations.push(Some(recovered))
                                         }
                                     } //svdiff
                                     line = ""; // avoid flags parsing: all flags omitted <=> content unchanged
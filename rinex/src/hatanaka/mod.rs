@@ -37,6 +37,8 @@ pub enum Error {
     VehicleIdentificationError,
     #[error("Malformed epoch content (#nb of observables)")]
     MalformedEpochBody,
+    #[error("Malformed differenced observation")]
+    MalformedDifference,
     #[error("numdiff error")]
     NumDiffError(#[from] numdiff::Error),
     #[error("sv parsing error")]
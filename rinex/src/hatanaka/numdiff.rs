@@ -3,10 +3,12 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("maximal compression order is 7")]
+    #[error("maximal compression order is 6")]
     MaximalCompressionOrder,
     #[error("order cannot be greater than {0}")]
     OrderTooBig(usize),
+    #[error("differentiation order should be at least 1")]
+    NullOrder,
 }
 
 /// `NumDiff` is a structure to compress    
@@ -24,29 +26,39 @@ pub struct NumDiff {
 
 impl NumDiff {
     pub const MAX_COMPRESSION_ORDER: usize = 6;
+    /// Highest order we tolerate when recovering data:
+    /// the history grows on kernel (re)initialization, up to this order.
+    pub const MAX_DECOMPRESSION_ORDER: usize = 16;
     /// Builds a new kernel structure.    
-    /// max: maximal Hatanaka order for this kernel to ever support.
-    /// We only support max <= Self::MAX_COMPRESSION_ORDER.
+    /// max: maximal Hatanaka order for this kernel to support when compressing.
+    /// We only support 0 < max <= Self::MAX_COMPRESSION_ORDER.
     /// For information, m = 5 is hardcoded in `CRN2RNX` and is a good compromise
     pub fn new(max: usize) -> Result<Self, Error> {
+        if max == 0 {
+            return Err(Error::NullOrder);
+        }
         if max > Self::MAX_COMPRESSION_ORDER {
             return Err(Error::MaximalCompressionOrder);
         }
-        let mut null = VecDeque::with_capacity(max);
-        for _ in 0..max {
-            null.push_back(0_i64);
-        }
         Ok(Self {
             m: 0,
             order: max,
-            history: null,
+            history: VecDeque::from(vec![0_i64; max + 1]),
         })
     }
 
     /// Initializes or reinitializes Self.
+    /// History is extended if `order` exceeds what Self was built for,
+    /// so data differentiated to a higher order is still recovered.
     pub fn init(&mut self, order: usize, data: i64) -> Result<(), Error> {
-        if order > self.history.len() {
-            return Err(Error::OrderTooBig(self.history.len()));
+        if order == 0 {
+            return Err(Error::NullOrder);
+        }
+        if order > Self::MAX_DECOMPRESSION_ORDER {
+            return Err(Error::OrderTooBig(Self::MAX_DECOMPRESSION_ORDER));
+        }
+        if order >= self.history.len() {
+            self.history.resize(order + 1, 0);
         }
         self.order = order;
         self.m = 0;
@@ -61,19 +73,21 @@ impl NumDiff {
 
     /// Decompresses given data
     pub fn decompress(&mut self, data: i64) -> i64 {
-        let x = &self.history;
         if self.m < self.order {
             self.m += 1;
         }
-        let result: i64 = match self.m {
-            1 => data + x[0],
-            2 => data + 2 * x[0] - x[1],
-            3 => data + 3 * x[0] - 3 * x[1] + x[2],
-            4 => data + 4 * x[0] - 6 * x[1] + 4 * x[2] - x[3],
-            5 => data + 5 * x[0] - 10 * x[1] + 10 * x[2] - 5 * x[3] + x[4],
-            6 => data + 6 * x[0] - 15 * x[1] + 20 * x[2] - 15 * x[3] + 6 * x[4] - x[5],
-            _ => unreachable!("m={} / order={}", self.m, self.order),
-        };
+        // x(n) = data - sum{k=1..m} (-1)^k C(m,k) x(n-k)
+        let mut result = data;
+        let mut binomial = 1_i64;
+        for k in 1..=self.m {
+            binomial = binomial * (self.m - k + 1) as i64 / k as i64;
+            let term = binomial.wrapping_mul(self.history[k - 1]);
+            if k % 2 == 1 {
+                result = result.wrapping_add(term);
+            } else {
+                result = result.wrapping_sub(term);
+            }
+        }
         self.rotate_history(result);
         result
     }
@@ -84,16 +98,21 @@ impl NumDiff {
             self.m += 1;
         }
         self.rotate_history(data);
-        let x = &self.history;
-        match self.m {
-            1 => x[0] - x[1],
-            2 => x[0] - 2 * x[1] + x[2],
-            3 => x[0] - 3 * x[1] + 3 * x[2] - x[3],
-            4 => x[0] - 4 * x[1] + 6 * x[2] - 4 * x[3] + x[4],
-            5 => x[0] - 5 * x[1] + 10 * x[2] - 10 * x[3] + 5 * x[4] - x[5],
-            6 => x[0] - 6 * x[1] + 15 * x[2] - 20 * x[3] + 15 * x[4] - 6 * x[5] + x[6],
-            _ => unreachable!(),
+        // sum{k=0..m} (-1)^k C(m,k) x(n-k)
+        let mut result = 0_i64;
+        let mut binomial = 1_i64;
+        for k in 0..=self.m {
+            if k > 0 {
+                binomial = binomial * (self.m - k + 1) as i64 / k as i64;
+            }
+            let term = binomial.wrapping_mul(self.history[k]);
+            if k % 2 == 0 {
+                result = result.wrapping_add(term);
+            } else {
+                result = result.wrapping_sub(term);
+            }
         }
+        result
     }
 }
 
@@ -161,4 +180,24 @@ mod test {
         assert_eq!(diff.compress(25128722574),-140);
         */
     }
+    #[test]
+    fn test_high_order() {
+        let data: Vec<i64> = (0..32_i64)
+            .map(|i| 20982937082 + i * i * i * 7 - i * 3)
+            .collect();
+        let mut compressor = NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER).unwrap();
+        let mut decompressor = NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER).unwrap();
+        // 8 exceeds what the kernels were built for
+        compressor.init(8, data[0]).unwrap();
+        decompressor.init(8, data[0]).unwrap();
+        for value in data.iter().skip(1) {
+            let compressed = compressor.compress(*value);
+            assert_eq!(decompressor.decompress(compressed), *value);
+        }
+        assert!(decompressor.init(0, 0).is_err());
+        assert!(decompressor
+            .init(NumDiff::MAX_DECOMPRESSION_ORDER + 1, 0)
+            .is_err());
+        assert!(NumDiff::new(0).is_err());
+    }
 }
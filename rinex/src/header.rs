@@ -6,13 +6,15 @@ use crate::{
     cospar::{Error as CosparError, COSPAR},
     domes::Domes,
     doris::{Error as DorisError, HeaderFields as DorisHeader, Station as DorisStation},
+    epoch,
+    epoch::epoch_decompose,
     fmt_comment, fmt_rinex,
     ground_position::GroundPosition,
     hardware::{Antenna, Rcvr, SvAntenna},
     ionex,
     leap::{Error as LeapParsingError, Leap},
     linspace::{Error as LinspaceError, Linspace},
-    marker::{GeodeticMarker, MarkerType},
+    marker::{GeodeticMarker, MarkerType, StationName},
     merge::{
         merge_mut_option, merge_mut_unique_map2d, merge_mut_unique_vec, merge_mut_vec,
         merge_time_of_first_obs, merge_time_of_last_obs, Error as MergeError, Merge,
@@ -29,11 +31,11 @@ use crate::{
     Constellation, Duration, TimeScale, SV,
 };
 
-use std::collections::HashMap;
-use std::io::prelude::*;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
 use hifitime::{Epoch, Unit};
+use itertools::Itertools;
 use thiserror::Error;
 
 use gnss::constellation::ParsingError as ConstellationParsingError;
@@ -67,7 +69,7 @@ pub struct PcvCompensation {
 
 /// Describes `RINEX` file header
 #[derive(Clone, Debug, Default, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Header {
     /// revision for this `RINEX`
     pub version: Version,
@@ -93,6 +95,7 @@ pub struct Header {
     /// optionnal [GeodeticMarker]
     pub geodetic_marker: Option<GeodeticMarker>,
     /// Glonass FDMA channels
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub glo_channels: HashMap<SV, i8>,
     /// Optional COSPAR number (launch information)
     pub cospar: Option<COSPAR>,
@@ -102,14 +105,16 @@ pub struct Header {
     // pub time_corrections: Option<gnss_time::Correction>,
     /// Station approximate coordinates
     pub ground_position: Option<GroundPosition>,
-    /// Optionnal observation wavelengths
-    pub wavelengths: Option<(u32, u32)>,
+    /// Optionnal wavelength factors (old RINEX)
+    pub wavelengths: Option<observation::WavelengthFactors>,
     /// Optionnal sampling interval (s)
     pub sampling_interval: Option<Duration>,
     /// Optionnal file license
     pub license: Option<String>,
     /// Optionnal Object Identifier (IoT)
     pub doi: Option<String>,
+    /// Optional number of files merged into this one (RINEX3)
+    pub merged_files: Option<u32>,
     /// Optionnal GPS/UTC time difference
     pub gps_utc_delta: Option<u32>,
     /// Optionnal Receiver information
@@ -177,6 +182,8 @@ pub enum ParsingError {
     UnknownReferenceIonex(#[from] ionex::system::Error),
     #[error("invalid crinex header \"{0}\"")]
     CrinexHeader(String),
+    #[error("observation rinex should not describe meteo sensors")]
+    ObsMeteoConflict,
     #[error("failed to parse datetime {0} field from \"{1}\"")]
     DateTimeParsing(String, String),
     #[error("failed to parse {0} integer value from \"{1}\"")]
@@ -191,6 +198,30 @@ pub enum ParsingError {
     DorisError(#[from] DorisError),
     #[error("failed to parse cospar number")]
     CosparError(#[from] CosparError),
+    #[error("missing END OF HEADER: truncated header")]
+    MissingEndOfHeader,
+}
+
+/// Parses "ANT # / TYPE" `content`, updating the possibly already described `antenna`.
+/// Also used to interprete the antenna changes described within OBS event blocks.
+pub(crate) fn parse_antenna_type(content: &str, antenna: Option<Antenna>) -> Antenna {
+    let (model, rem) = content.split_at(20);
+    let (sn, _) = rem.split_at(20);
+    antenna
+        .unwrap_or_default()
+        .with_model(model.trim())
+        .with_serial_number(sn.trim())
+}
+
+/// Parses "ANTENNA: DELTA H/E/N" `content` (H/E/N eccentricity components).
+pub(crate) fn parse_antenna_delta_hen(content: &str) -> Option<(f64, f64, f64)> {
+    let (h, rem) = content.split_at(15);
+    let (e, rem) = rem.split_at(15);
+    let (n, _) = rem.split_at(15);
+    let h = f64::from_str(h.trim()).ok()?;
+    let e = f64::from_str(e.trim()).ok()?;
+    let n = f64::from_str(n.trim()).ok()?;
+    Some((h, e, n))
 }
 
 fn parse_formatted_month(content: &str) -> Result<u8, ParsingError> {
@@ -232,10 +263,124 @@ macro_rules! parse_float_error {
     };
 }
 
+/*
+ * Header labels (columns 61-80) we know how to interprete,
+ * sorted alphabetically for quick identification.
+ */
+const LABELS: [&str; 73] = [
+    "# / TYPES OF DATA",
+    "# / TYPES OF OBSERV",
+    "# OF SATELLITES",
+    "# OF STATIONS",
+    "ANALYSIS CENTER",
+    "ANALYSIS CLK REF",
+    "ANT # / TYPE",
+    "ANTENNA: B.SIGHT XYZ",
+    "ANTENNA: DELTA H/E/N",
+    "ANTENNA: DELTA X/Y/Z",
+    "ANTENNA: PHASECENTER",
+    "ANTENNA: ZERODIR XYZ",
+    "ANTEX VERSION / SYST",
+    "APPROX POSITION XYZ",
+    "BASE RADIUS",
+    "CENTER OF MASS: XYZ",
+    "COSPAR NUMBER",
+    "CRINEX PROG / DATE",
+    "CRINEX VERS   / TYPE",
+    "DELTA-UTC: A0,A1,T,W",
+    "DESCRIPTION",
+    "DOI",
+    "ELEVATION CUTOFF",
+    "EPOCH OF FIRST MAP",
+    "EPOCH OF LAST MAP",
+    "EXPONENT",
+    "GLONASS COD/PHS/BIS",
+    "GLONASS SLOT / FRQ #",
+    "HGT1 / HGT2 / DHGT",
+    "INTERVAL",
+    "ION ALPHA",
+    "ION BETA",
+    "IONEX VERSION / TYPE",
+    "IONOSPHERIC CORR",
+    "L2 / L1 DATE OFFSET",
+    "LAT1 / LAT2 / DLAT",
+    "LEAP SECONDS",
+    "LICENSE OF USE",
+    "LON1 / LON2 / DLON",
+    "MAPPING FUCTION",
+    "MARKER NAME",
+    "MARKER NUMBER",
+    "MARKER TYPE",
+    "MERGED FILE",
+    "OBSERVABLES USED",
+    "OBSERVER / AGENCY",
+    "PCV TYPE / REFANT",
+    "PGM / RUN BY / DATE",
+    "PRN / # OF OBS",
+    "PRN / BIAS / RMS",
+    "RCV CLOCK OFFS APPL",
+    "REC # / TYPE / VERS",
+    "RINEX VERSION / TYPE",
+    "SENSOR MOD/TYPE/ACC",
+    "SENSOR POS XYZ/H",
+    "SIGNAL STRENGTH UNIT",
+    "STATION CLK REF",
+    "STATION INFORMATION",
+    "STATION NAME / NUM",
+    "STATION REFERENCE",
+    "SYS / # / OBS TYPES",
+    "SYS / DCBS APPLIED",
+    "SYS / PCVS APPLIED",
+    "SYS / PHASE SHIFT",
+    "SYS / PVCS APPLIED",
+    "SYS / SCALE FACTOR",
+    "TIME OF FIRST OBS",
+    "TIME OF LAST OBS",
+    "TIME REF STATION",
+    "TIME SYSTEM CORR",
+    "TIME SYSTEM ID",
+    "TYPE / SERIAL NO",
+    "WAVELENGTH FACT L1/2",
+];
+
 impl Header {
+    /*
+     * Identifies the label of a header line, from its columns 61-80.
+     * Labels are exact most of the time, but we tolerate content overflowing
+     * into this area, in which case we look for a known label
+     * (the first one, alphabetically) anywhere in this area.
+     */
+    fn label(marker: &str) -> &str {
+        let label = marker.trim();
+        match label {
+            "END OF HEADER" | "COMMENT" => label,
+            _ if LABELS.binary_search(&label).is_ok() => label,
+            _ => (0..label.len())
+                .filter(|start| label.is_char_boundary(*start))
+                .filter_map(|start| Self::label_prefix(&label[start..]))
+                .min()
+                .unwrap_or(label),
+        }
+    }
+    /*
+     * Returns the first known label (alphabetically) `content` starts with.
+     * Such labels sort right before `content`, among the labels
+     * that share its first character.
+     */
+    fn label_prefix(content: &str) -> Option<&str> {
+        let end = LABELS.partition_point(|known| *known <= content);
+        LABELS[..end]
+            .iter()
+            .rev()
+            .take_while(|known| known.as_bytes().first() == content.as_bytes().first())
+            .filter(|known| content.starts_with(*known))
+            .min()
+            .copied()
+    }
     /// Builds a `Header` from stream reader
     pub fn new(reader: &mut BufferedReader) -> Result<Header, ParsingError> {
         let mut rinex_type = Type::default();
+        let mut type_declared = false;
         let mut constellation: Option<Constellation> = None;
         let mut version = Version::default();
         let mut comments: Vec<String> = Vec::new();
@@ -245,7 +390,9 @@ impl Header {
         let mut observer = String::new();
         let mut agency = String::new();
         let mut license: Option<String> = None;
+        let mut wavelengths: Option<observation::WavelengthFactors> = None;
         let mut doi: Option<String> = None;
+        let mut merged_files: Option<u32> = None;
         let mut station_url = String::new();
         let mut geodetic_marker = Option::<GeodeticMarker>::None;
         let mut cospar = Option::<COSPAR>::None;
@@ -261,684 +408,818 @@ impl Header {
         let mut pcv_compensations: Vec<PcvCompensation> = Vec::new();
         // RINEX specific fields
         let mut current_constell: Option<Constellation> = None;
+        let mut scale_factor: Option<(Constellation, u16)> = None;
         let mut observation = ObservationHeader::default();
         let mut meteo = MeteoHeader::default();
+        let mut meteo_sensors = false;
+        let mut v2_observables: Vec<String> = Vec::new();
+        let mut prn_obs_counts: Vec<String> = Vec::new();
         let mut clock = clock::HeaderFields::default();
         let mut antex = antex::HeaderFields::default();
         let mut ionex = ionex::HeaderFields::default();
         let mut doris = DorisHeader::default();
 
-        // iterate on a line basis
-        let lines = reader.lines();
-        for l in lines {
-            let line = l.unwrap();
-            if line.len() < 60 {
+        // iterate on a line basis, reusing a single buffer
+        let mut buf = String::with_capacity(128);
+        let mut end_of_header = false;
+        while let Some(line) = reader.next_line(&mut buf) {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break, // interrupted stream
+            };
+            if line.len() < 60 || !line.is_char_boundary(60) {
                 continue; // --> invalid header content
             }
             let (content, marker) = line.split_at(60);
-            ///////////////////////////////
-            // [0] END OF HEADER
-            //     --> done parsing
-            ///////////////////////////////
-            if marker.trim().eq("END OF HEADER") {
-                break;
+            if !content.is_ascii() && Self::label(marker) != "COMMENT" {
+                continue; // --> corrupt fixed width content
             }
-            ///////////////////////////////
-            // [0*] COMMENTS
-            ///////////////////////////////
-            if marker.trim().eq("COMMENT") {
-                // --> storing might be useful
-                comments.push(content.trim().to_string());
-                continue;
-
-            //////////////////////////////////////
-            // [1] CRINEX Special fields
-            /////////////////////////////////////
-            } else if marker.contains("CRINEX VERS") {
-                let version = content.split_at(20).0;
-                let version = version.trim();
-                let crinex_revision = Version::from_str(version).or(Err(
-                    ParsingError::VersionParsing(format!("CRINEX VERS: \"{}\"", version)),
-                ))?;
-
-                observation.crinex = Some(Crinex::default().with_version(crinex_revision));
-            } else if marker.contains("CRINEX PROG / DATE") {
-                Self::parse_crinex_prog_date(content, &mut observation)?;
-
-            ////////////////////////////////////////
-            // [2] ANTEX special header
-            ////////////////////////////////////////
-            } else if marker.contains("ANTEX VERSION / SYST") {
-                let (vers, system) = content.split_at(8);
-                let vers = vers.trim();
-                version = Version::from_str(vers).or(Err(ParsingError::VersionParsing(
-                    format!("ANTEX VERSION / SYST: \"{}\"", vers),
-                )))?;
-
-                if let Ok(constell) = Constellation::from_str(system.trim()) {
-                    constellation = Some(constell)
-                }
-                rinex_type = Type::AntennaData;
-            } else if marker.contains("PCV TYPE / REFANT") {
-                let (pcv_str, rem) = content.split_at(20);
-                let (rel_type, rem) = rem.split_at(20);
-                let (ref_sn, _) = rem.split_at(20);
-                if let Ok(mut pcv) = antex::Pcv::from_str(pcv_str.trim()) {
-                    if pcv.is_relative() {
-                        // try to parse "Relative Type"
-                        if !rel_type.trim().is_empty() {
-                            pcv = pcv.with_relative_type(rel_type.trim());
+            match Self::label(marker) {
+                ///////////////////////////////
+                // [0] END OF HEADER
+                //     --> done parsing
+                ///////////////////////////////
+                "END OF HEADER" => {
+                    end_of_header = true;
+                    break;
+                },
+                ///////////////////////////////
+                // [0*] COMMENTS
+                ///////////////////////////////
+                "COMMENT" => {
+                    // --> storing might be useful
+                    comments.push(content.trim().to_string());
+                },
+                //////////////////////////////////////
+                // [1] CRINEX Special fields
+                /////////////////////////////////////
+                "CRINEX VERS   / TYPE" => {
+                    let version = content.split_at(20).0;
+                    let version = version.trim();
+                    let crinex_revision = Version::from_str(version).or(Err(
+                        ParsingError::VersionParsing(format!("CRINEX VERS: \"{}\"", version)),
+                    ))?;
+
+                    observation.crinex = Some(Crinex::default().with_version(crinex_revision));
+                },
+                "CRINEX PROG / DATE" => {
+                    Self::parse_crinex_prog_date(content, &mut observation)?;
+                },
+                ////////////////////////////////////////
+                // [2] ANTEX special header
+                ////////////////////////////////////////
+                "ANTEX VERSION / SYST" => {
+                    let (vers, system) = content.split_at(8);
+                    let vers = vers.trim();
+                    version = Version::from_str(vers).or(Err(ParsingError::VersionParsing(
+                        format!("ANTEX VERSION / SYST: \"{}\"", vers),
+                    )))?;
+
+                    if let Ok(constell) = Constellation::from_str(system.trim()) {
+                        constellation = Some(constell)
+                    }
+                    rinex_type = Type::AntennaData;
+                },
+                "PCV TYPE / REFANT" => {
+                    let (pcv_str, rem) = content.split_at(20);
+                    let (rel_type, rem) = rem.split_at(20);
+                    let (ref_sn, _) = rem.split_at(20);
+                    if let Ok(mut pcv) = antex::Pcv::from_str(pcv_str.trim()) {
+                        if pcv.is_relative() {
+                            // try to parse "Relative Type"
+                            if !rel_type.trim().is_empty() {
+                                pcv = pcv.with_relative_type(rel_type.trim());
+                            }
                         }
+                        antex = antex.with_pcv_type(pcv);
                     }
-                    antex = antex.with_pcv_type(pcv);
-                }
-                if !ref_sn.trim().is_empty() {
-                    antex = antex.with_reference_antenna_sn(ref_sn.trim());
-                }
-            } else if marker.contains("TYPE / SERIAL NO") {
-                let items: Vec<&str> = content.split_ascii_whitespace().collect();
-                if items.len() == 2 {
-                    // Receiver antenna information
-                    // like standard RINEX
-                    let (model, rem) = content.split_at(20);
-                    let (sn, _) = rem.split_at(20);
-                    if let Some(a) = &mut rcvr_antenna {
-                        *a = a.with_model(model.trim()).with_serial_number(sn.trim());
-                    } else {
-                        rcvr_antenna = Some(
-                            Antenna::default()
-                                .with_model(model.trim())
-                                .with_serial_number(sn.trim()),
-                        );
+                    if !ref_sn.trim().is_empty() {
+                        antex = antex.with_reference_antenna_sn(ref_sn.trim());
                     }
-                } else if items.len() == 4 {
-                    // Space Vehicle antenna information
-                    // ANTEX RINEX specific
-                    let (model, rem) = content.split_at(10);
-                    let (svnn, rem) = rem.split_at(10);
-                    let (cospar, _) = rem.split_at(10);
-                    if let Ok(sv) = SV::from_str(svnn.trim()) {
-                        if let Some(a) = &mut sv_antenna {
-                            *a = a
-                                .with_sv(sv)
-                                .with_model(model.trim())
-                                .with_cospar(cospar.trim());
+                },
+                "TYPE / SERIAL NO" => {
+                    let items: Vec<&str> = content.split_ascii_whitespace().collect();
+                    if items.len() == 2 {
+                        // Receiver antenna information
+                        // like standard RINEX
+                        let (model, rem) = content.split_at(20);
+                        let (sn, _) = rem.split_at(20);
+                        if let Some(a) = &mut rcvr_antenna {
+                            *a = a.with_model(model.trim()).with_serial_number(sn.trim());
                         } else {
-                            sv_antenna = Some(
-                                SvAntenna::default()
-                                    .with_sv(sv)
+                            rcvr_antenna = Some(
+                                Antenna::default()
                                     .with_model(model.trim())
-                                    .with_cospar(cospar.trim()),
+                                    .with_serial_number(sn.trim()),
                             );
                         }
+                    } else if items.len() == 4 {
+                        // Space Vehicle antenna information
+                        // ANTEX RINEX specific
+                        let (model, rem) = content.split_at(10);
+                        let (svnn, rem) = rem.split_at(10);
+                        let (cospar, _) = rem.split_at(10);
+                        if let Ok(sv) = SV::from_str(svnn.trim()) {
+                            if let Some(a) = &mut sv_antenna {
+                                *a = a
+                                    .with_sv(sv)
+                                    .with_model(model.trim())
+                                    .with_cospar(cospar.trim());
+                            } else {
+                                sv_antenna = Some(
+                                    SvAntenna::default()
+                                        .with_sv(sv)
+                                        .with_model(model.trim())
+                                        .with_cospar(cospar.trim()),
+                                );
+                            }
+                        }
+                    }
+                },
+                //////////////////////////////////////
+                // [2] IONEX special header
+                //////////////////////////////////////
+                "IONEX VERSION / TYPE" => {
+                    let (vers_str, rem) = line.split_at(20);
+                    let (type_str, rem) = rem.split_at(20);
+                    let (system_str, _) = rem.split_at(20);
+
+                    let vers_str = vers_str.trim();
+                    version = Version::from_str(vers_str).or(Err(ParsingError::VersionParsing(
+                        format!("IONEX VERSION / TYPE : \"{}\"", vers_str),
+                    )))?;
+
+                    rinex_type = Type::from_str(type_str.trim())?;
+                    let ref_system = ionex::RefSystem::from_str(system_str.trim())?;
+                    ionex = ionex.with_reference_system(ref_system);
+                },
+                ///////////////////////////////////////
+                // ==> from now on
+                // RINEX standard / shared attributes
+                ///////////////////////////////////////
+                "RINEX VERSION / TYPE" => {
+                    let (vers, rem) = line.split_at(20);
+                    let (type_str, rem) = rem.split_at(20);
+                    let (constell_str, _) = rem.split_at(20);
+
+                    let type_str = type_str.trim();
+                    let constell_str = constell_str.trim();
+
+                    // File type identification
+                    type_declared = true;
+                    if type_str == "O" && constell_str == "D" {
+                        rinex_type = Type::DORIS;
+                    } else {
+                        rinex_type = Type::from_str(type_str)?;
                     }
-                }
-
-            //////////////////////////////////////
-            // [2] IONEX special header
-            //////////////////////////////////////
-            } else if marker.contains("IONEX VERSION / TYPE") {
-                let (vers_str, rem) = line.split_at(20);
-                let (type_str, rem) = rem.split_at(20);
-                let (system_str, _) = rem.split_at(20);
-
-                let vers_str = vers_str.trim();
-                version = Version::from_str(vers_str).or(Err(ParsingError::VersionParsing(
-                    format!("IONEX VERSION / TYPE : \"{}\"", vers_str),
-                )))?;
-
-                rinex_type = Type::from_str(type_str.trim())?;
-                let ref_system = ionex::RefSystem::from_str(system_str.trim())?;
-                ionex = ionex.with_reference_system(ref_system);
-
-            ///////////////////////////////////////
-            // ==> from now on
-            // RINEX standard / shared attributes
-            ///////////////////////////////////////
-            } else if marker.contains("RINEX VERSION / TYPE") {
-                let (vers, rem) = line.split_at(20);
-                let (type_str, rem) = rem.split_at(20);
-                let (constell_str, _) = rem.split_at(20);
-
-                let type_str = type_str.trim();
-                let constell_str = constell_str.trim();
-
-                // File type identification
-                if type_str == "O" && constell_str == "D" {
-                    rinex_type = Type::DORIS;
-                } else {
-                    rinex_type = Type::from_str(type_str)?;
-                }
 
-                // Determine (file) Constellation
-                //  1. NAV SPECIAL CASE
-                //  2. OTHER
-                match rinex_type {
-                    Type::NavigationData => {
-                        if type_str.contains("GLONASS") {
-                            // old GLONASS NAV : no constellation field
-                            constellation = Some(Constellation::Glonass);
-                        } else if type_str.contains("GPS NAV DATA") {
-                            constellation = Some(Constellation::GPS);
-                        } else if type_str.contains("IRNSS NAV DATA") {
-                            constellation = Some(Constellation::IRNSS);
-                        } else if type_str.contains("GNSS NAV DATA") {
-                            constellation = Some(Constellation::Mixed);
-                        } else if type_str.eq("NAVIGATION DATA") {
-                            if constell_str.is_empty() {
-                                // old GPS NAVIGATION DATA
+                    // Determine (file) Constellation
+                    //  1. NAV SPECIAL CASE
+                    //  2. OTHER
+                    match rinex_type {
+                        Type::NavigationData => {
+                            if type_str.contains("GLONASS") {
+                                // old GLONASS NAV : no constellation field
+                                constellation = Some(Constellation::Glonass);
+                            } else if type_str.contains("GPS NAV DATA") {
                                 constellation = Some(Constellation::GPS);
-                            } else {
-                                // Modern NAVIGATION DATA
-                                if let Ok(c) = Constellation::from_str(constell_str) {
-                                    constellation = Some(c);
+                            } else if type_str.contains("IRNSS NAV DATA") {
+                                constellation = Some(Constellation::IRNSS);
+                            } else if type_str.contains("GNSS NAV DATA") {
+                                constellation = Some(Constellation::Mixed);
+                            } else if type_str.eq("NAVIGATION DATA") {
+                                if constell_str.is_empty() {
+                                    // old GPS NAVIGATION DATA
+                                    constellation = Some(Constellation::GPS);
+                                } else {
+                                    // Modern NAVIGATION DATA
+                                    if let Ok(c) = Constellation::from_str(constell_str) {
+                                        constellation = Some(c);
+                                    }
                                 }
                             }
-                        }
-                    },
-                    Type::MeteoData | Type::DORIS => {
-                        // no constellation associated to them
-                    },
-                    _ => {
-                        // any other
-                        // regular files
-                        if let Ok(c) = Constellation::from_str(constell_str) {
-                            constellation = Some(c);
-                        }
-                    },
-                }
-                /*
-                 * Parse version descriptor
-                 */
-                let vers = vers.trim();
-                version = Version::from_str(vers).or(Err(ParsingError::VersionParsing(
-                    format!("RINEX VERSION / TYPE \"{}\"", vers),
-                )))?;
+                        },
+                        Type::MeteoData | Type::DORIS => {
+                            // no constellation associated to them
+                        },
+                        _ => {
+                            // any other
+                            // regular files
+                            if let Ok(c) = Constellation::from_str(constell_str) {
+                                constellation = Some(c);
+                            }
+                        },
+                    }
+                    /*
+                     * Parse version descriptor
+                     */
+                    let vers = vers.trim();
+                    version = Version::from_str(vers).or(Err(ParsingError::VersionParsing(
+                        format!("RINEX VERSION / TYPE \"{}\"", vers),
+                    )))?;
 
-                if !version.is_supported() {
-                    return Err(ParsingError::VersionNotSupported(vers.to_string()));
-                }
-            } else if marker.contains("PGM / RUN BY / DATE") {
-                let (pgm, rem) = line.split_at(20);
-                program = pgm.trim().to_string();
-                let (rb, rem) = rem.split_at(20);
-                run_by = match rb.trim().eq("") {
-                    true => String::from("Unknown"),
-                    false => rb.trim().to_string(),
-                };
-                let (date_str, _) = rem.split_at(20);
-                date = date_str.trim().to_string();
-            } else if marker.contains("MARKER NAME") {
-                let name = content.split_at(20).0.trim();
-                geodetic_marker = Some(GeodeticMarker::default().with_name(name));
-            } else if marker.contains("MARKER NUMBER") {
-                let number = content.split_at(20).0.trim();
-                if let Some(ref mut marker) = geodetic_marker {
-                    *marker = marker.with_number(number);
-                }
-            } else if marker.contains("MARKER TYPE") {
-                let code = content.split_at(20).0.trim();
-                if let Ok(mtype) = MarkerType::from_str(code) {
+                    if !version.is_supported() {
+                        return Err(ParsingError::VersionNotSupported(vers.to_string()));
+                    }
+                },
+                "PGM / RUN BY / DATE" => {
+                    let (pgm, rem) = line.split_at(20);
+                    program = pgm.trim().to_string();
+                    let (rb, rem) = rem.split_at(20);
+                    run_by = match rb.trim().eq("") {
+                        true => String::from("Unknown"),
+                        false => rb.trim().to_string(),
+                    };
+                    let (date_str, _) = rem.split_at(20);
+                    date = date_str.trim().to_string();
+                },
+                "MARKER NAME" => {
+                    let name = content.split_at(20).0.trim();
+                    geodetic_marker = Some(GeodeticMarker::default().with_name(name));
+                },
+                "MARKER NUMBER" => {
+                    let number = content.split_at(20).0.trim();
                     if let Some(ref mut marker) = geodetic_marker {
-                        marker.marker_type = Some(mtype);
+                        *marker = marker.with_number(number);
                     }
-                }
-            } else if marker.contains("OBSERVER / AGENCY") {
-                let (obs, ag) = content.split_at(20);
-                observer = obs.trim().to_string();
-                agency = ag.trim().to_string();
-            } else if marker.contains("REC # / TYPE / VERS") {
-                if let Ok(receiver) = Rcvr::from_str(content) {
-                    rcvr = Some(receiver);
-                }
-            } else if marker.contains("SYS / PCVS APPLIED") {
-                let (gnss, rem) = content.split_at(2);
-                let (program, rem) = rem.split_at(18);
-                let (url, _) = rem.split_at(40);
-
-                let gnss = gnss.trim();
-                let gnss = Constellation::from_str(gnss.trim())?;
-
-                let pcv = PcvCompensation {
-                    program: {
-                        let program = program.trim();
-                        if program.eq("") {
-                            String::from("Unknown")
-                        } else {
-                            program.to_string()
+                },
+                "MARKER TYPE" => {
+                    let code = content.split_at(20).0.trim();
+                    // user defined types are allowed
+                    if !code.is_empty() {
+                        match MarkerType::from_str(code) {
+                            Ok(mtype) => {
+                                if let Some(ref mut marker) = geodetic_marker {
+                                    marker.marker_type = Some(mtype);
+                                }
+                            },
+                            Err(e) => log::warn!("{}: MARKER TYPE ignored", e),
                         }
-                    },
-                    constellation: gnss,
-                    url: {
-                        let url = url.trim();
-                        if url.eq("") {
-                            String::from("Unknown")
+                    }
+                },
+                "OBSERVER / AGENCY" => {
+                    let (obs, ag) = content.split_at(20);
+                    observer = obs.trim().to_string();
+                    agency = ag.trim().to_string();
+                },
+                "REC # / TYPE / VERS" => {
+                    if let Ok(receiver) = Rcvr::from_str(content) {
+                        rcvr = Some(receiver);
+                    }
+                },
+                "SYS / PCVS APPLIED" => {
+                    let (gnss, rem) = content.split_at(2);
+                    let (program, rem) = rem.split_at(18);
+                    let (url, _) = rem.split_at(40);
+
+                    let gnss = gnss.trim();
+                    let gnss = Constellation::from_str(gnss.trim())?;
+
+                    let pcv = PcvCompensation {
+                        program: {
+                            let program = program.trim();
+                            if program.eq("") {
+                                String::from("Unknown")
+                            } else {
+                                program.to_string()
+                            }
+                        },
+                        constellation: gnss,
+                        url: {
+                            let url = url.trim();
+                            if url.eq("") {
+                                String::from("Unknown")
+                            } else {
+                                url.to_string()
+                            }
+                        },
+                    };
+
+                    pcv_compensations.push(pcv);
+                },
+                "SYS / DCBS APPLIED" => {
+                    let (gnss, rem) = content.split_at(2);
+                    let (program, rem) = rem.split_at(18);
+                    let (url, _) = rem.split_at(40);
+
+                    let gnss = gnss.trim();
+                    let gnss = Constellation::from_str(gnss.trim())?;
+
+                    let dcb = DcbCompensation {
+                        program: {
+                            let program = program.trim();
+                            if program.eq("") {
+                                String::from("Unknown")
+                            } else {
+                                program.to_string()
+                            }
+                        },
+                        constellation: gnss,
+                        url: {
+                            let url = url.trim();
+                            if url.eq("") {
+                                String::from("Unknown")
+                            } else {
+                                url.to_string()
+                            }
+                        },
+                    };
+
+                    dcb_compensations.push(dcb);
+                },
+                "SYS / SCALE FACTOR" => {
+                    let (gnss, rem) = content.split_at(2);
+                    let gnss = gnss.trim();
+
+                    let (constell, scaling, rem) = if gnss.is_empty() {
+                        // more than 12 observables:
+                        // continuation of the previous line
+                        match scale_factor {
+                            Some((constell, scaling)) => (constell, scaling, rem.split_at(8).1),
+                            None => continue,
+                        }
+                    } else {
+                        /*
+                         * DORIS measurement special case, otherwise, standard OBS_RINEX
+                         */
+                        let constell = if gnss.eq("D") {
+                            Constellation::Mixed // scaling applies to all measurements
                         } else {
-                            url.to_string()
+                            Constellation::from_str(gnss)?
+                        };
+
+                        // Parse scaling factor
+                        let (factor, rem) = rem.split_at(6);
+                        let factor = factor.trim();
+                        let scaling = factor
+                            .parse::<u16>()
+                            .or(Err(parse_int_error!("SYS / SCALE FACTOR", factor)))?;
+
+                        // parse end of line
+                        let (num, rem) = rem.split_at(3);
+                        if rinex_type != Type::DORIS && matches!(num.trim(), "" | "0") {
+                            // no observable specified:
+                            // applies to all observables of this system
+                            observation.data_scaling.insert(constell, scaling);
                         }
-                    },
-                };
+                        scale_factor = Some((constell, scaling));
+                        (constell, scaling, rem)
+                    };
 
-                pcv_compensations.push(pcv);
-            } else if marker.contains("SYS / DCBS APPLIED") {
-                let (gnss, rem) = content.split_at(2);
-                let (program, rem) = rem.split_at(18);
-                let (url, _) = rem.split_at(40);
+                    for observable_str in rem.split_ascii_whitespace() {
+                        let observable = Observable::from_str(observable_str)?;
 
-                let gnss = gnss.trim();
-                let gnss = Constellation::from_str(gnss.trim())?;
-
-                let dcb = DcbCompensation {
-                    program: {
-                        let program = program.trim();
-                        if program.eq("") {
-                            String::from("Unknown")
+                        // latch scaling value
+                        if rinex_type == Type::DORIS {
+                            doris.with_scaling(observable, scaling);
                         } else {
-                            program.to_string()
+                            observation.with_scaling(constell, observable, scaling);
                         }
-                    },
-                    constellation: gnss,
-                    url: {
-                        let url = url.trim();
-                        if url.eq("") {
-                            String::from("Unknown")
+                    }
+                },
+                "SENSOR MOD/TYPE/ACC" => {
+                    meteo_sensors = true;
+                    if let Ok(sensor) = meteo::sensor::Sensor::from_str(content) {
+                        // position may have been described first
+                        match meteo
+                            .sensors
+                            .iter_mut()
+                            .find(|s| s.observable == sensor.observable && s.model.is_none())
+                        {
+                            Some(s) => {
+                                s.model = sensor.model;
+                                s.sensor_type = sensor.sensor_type;
+                                s.accuracy = sensor.accuracy;
+                            },
+                            None => meteo.sensors.push(sensor),
+                        }
+                    }
+                },
+                "SENSOR POS XYZ/H" => {
+                    /*
+                     * Meteo: sensor position information
+                     */
+                    let (x, rem) = content.split_at(14);
+                    let (y, rem) = rem.split_at(14);
+                    let (z, rem) = rem.split_at(14);
+                    let (h, phys) = rem.split_at(14);
+
+                    let phys = phys.trim();
+                    let observable = Observable::from_str(phys)?;
+
+                    let x = x.trim();
+                    let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("SENSOR POS X"),
+                        x.to_string(),
+                    )))?;
+
+                    let y = y.trim();
+                    let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("SENSOR POS Y"),
+                        y.to_string(),
+                    )))?;
+
+                    let z = z.trim();
+                    let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("SENSOR POS Z"),
+                        z.to_string(),
+                    )))?;
+
+                    let h = h.trim();
+                    let h = f64::from_str(h).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("SENSOR POS H"),
+                        h.to_string(),
+                    )))?;
+
+                    match meteo
+                        .sensors
+                        .iter_mut()
+                        .find(|sensor| sensor.observable == observable)
+                    {
+                        Some(sensor) => sensor.position = Some((x, y, z, h)),
+                        None => {
+                            // sensor described afterwards
+                            meteo.sensors.push(
+                                meteo::sensor::Sensor::new(observable).with_position((x, y, z, h)),
+                            );
+                        },
+                    }
+                },
+                "LEAP SECONDS" => {
+                    let leap_str = content.split_at(40).0.trim();
+                    if let Ok(lleap) = Leap::from_str(leap_str) {
+                        leap = Some(lleap)
+                    }
+                },
+                "DOI" => doi = Some(content.trim().to_string()),
+                "MERGED FILE" => {
+                    // nb# of merged files
+                    let n = content.split_at(20).0.trim();
+                    let n = u32::from_str(n).or(Err(parse_int_error!("MERGED FILE", n)))?;
+                    merged_files = Some(n);
+                },
+                "STATION INFORMATION" => station_url = content.trim().to_string(),
+                "LICENSE OF USE" => license = Some(content.trim().to_string()),
+                "WAVELENGTH FACT L1/2" => {
+                    Self::parse_wavelength_factors(content, constellation, &mut wavelengths)?;
+                },
+                "APPROX POSITION XYZ" => {
+                    // station base coordinates
+                    let items: Vec<&str> = content.split_ascii_whitespace().collect();
+                    let x = items[0].trim();
+                    let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("APPROX POSITION X"),
+                        x.to_string(),
+                    )))?;
+
+                    let y = items[1].trim();
+                    let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("APPROX POSITION Y"),
+                        y.to_string(),
+                    )))?;
+
+                    let z = items[2].trim();
+                    let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("APPROX POSITION Z"),
+                        z.to_string(),
+                    )))?;
+
+                    ground_position = Some(GroundPosition::from_ecef_wgs84((x, y, z)));
+                },
+                "ANT # / TYPE" => {
+                    rcvr_antenna = Some(parse_antenna_type(content, rcvr_antenna.take()));
+                },
+                "ANTENNA: DELTA X/Y/Z" => {
+                    // Antenna reference point, body fixed coordinates
+                    let items: Vec<&str> = content.split_ascii_whitespace().collect();
+
+                    let x = items[0].trim();
+                    let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("ANTENNA DELTA X"),
+                        x.to_string(),
+                    )))?;
+
+                    let y = items[1].trim();
+                    let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("ANTENNA DELTA Y"),
+                        y.to_string(),
+                    )))?;
+
+                    let z = items[2].trim();
+                    let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
+                        String::from("ANTENNA DELTA Z"),
+                        z.to_string(),
+                    )))?;
+
+                    if let Some(ant) = &mut rcvr_antenna {
+                        *ant = ant.with_delta_xyz((x, y, z));
+                    } else {
+                        rcvr_antenna = Some(Antenna::default().with_delta_xyz((x, y, z)));
+                    }
+                },
+                "ANTENNA: DELTA H/E/N" => {
+                    // Antenna H/E/N eccentricity components
+                    if let Some(delta_hen) = parse_antenna_delta_hen(content) {
+                        if let Some(a) = &mut rcvr_antenna {
+                            *a = a.with_delta_hen(delta_hen);
                         } else {
-                            url.to_string()
+                            rcvr_antenna = Some(Antenna::default().with_delta_hen(delta_hen));
                         }
-                    },
-                };
-
-                dcb_compensations.push(dcb);
-            } else if marker.contains("SYS / SCALE FACTOR") {
-                // TODO:
-                //   This will not work in case several observables
-                //   are declaredn which will required to analyze more than 1 line
-                let (gnss, rem) = content.split_at(2);
-                let gnss = gnss.trim();
-
-                /*
-                 * DORIS measurement special case, otherwise, standard OBS_RINEX
-                 */
-                let constell = if gnss.eq("D") {
-                    Constellation::Mixed // scaling applies to all measurements
-                } else {
-                    Constellation::from_str(gnss)?
-                };
-
-                // Parse scaling factor
-                let (factor, rem) = rem.split_at(6);
-                let factor = factor.trim();
-                let scaling = factor
-                    .parse::<u16>()
-                    .or(Err(parse_int_error!("SYS / SCALE FACTOR", factor)))?;
-
-                // parse end of line
-                let (_num, rem) = rem.split_at(3);
-                for observable_str in rem.split_ascii_whitespace() {
-                    let observable = Observable::from_str(observable_str)?;
+                    }
+                },
+                "ANTENNA: B.SIGHT XYZ" => {
+                    //TODO
+                },
+                "ANTENNA: ZERODIR XYZ" => {
+                    //TODO
+                },
+                "ANTENNA: PHASECENTER" => {
+                    //TODO
+                },
+                "CENTER OF MASS: XYZ" => {
+                    //TODO
+                },
+                "RCV CLOCK OFFS APPL" => {
+                    let value = content.split_at(20).0.trim();
+                    let n = i32::from_str_radix(value, 10)
+                        .or(Err(parse_int_error!("RCV CLOCK OFFS APPL", value)))?;
 
-                    // latch scaling value
+                    observation.clock_offset_applied = n > 0;
+                },
+                "# OF SATELLITES" => {
+                    // TODO: IONEX files use it to describe the number of satellites
+                    let n = content.split_ascii_whitespace().next().unwrap_or("");
+                    if let Ok(n) = n.parse::<u16>() {
+                        observation.nb_satellites = Some(n);
+                    }
+                },
+                "PRN / # OF OBS" => {
+                    // resolved once the list of observables is known
+                    prn_obs_counts.push(content.to_string());
+                },
+                "SYS / PHASE SHIFT" => {
+                    //TODO
+                },
+                "SYS / PVCS APPLIED" => {
+                    // RINEX::ClockData specific
+                    // + satellite system (G/R/E/C/I/J/S)
+                    // + programe name to apply Phase Center Variation
+                    // + source of corrections (url)
+                    // <o repeated for each satellite system
+                    // <o blank field when no corrections applied
+                },
+                "TIME OF FIRST OBS" => {
+                    let mut time_of_first_obs = Self::parse_time_of_obs(content)?;
+                    match constellation {
+                        Some(Constellation::Mixed) | None => {},
+                        Some(c) => {
+                            // in case of OLD RINEX : fixed constellation
+                            //  use that information, as it may be omitted in the TIME OF OBS header
+                            time_of_first_obs.time_scale = c
+                                .timescale()
+                                .ok_or(ParsingError::TimescaleParsing(c.to_string()))?;
+                        },
+                    }
                     if rinex_type == Type::DORIS {
-                        doris.with_scaling(observable, scaling);
+                        doris.time_of_first_obs = Some(time_of_first_obs);
                     } else {
-                        observation.with_scaling(constell, observable, scaling);
+                        observation = observation.with_time_of_first_obs(time_of_first_obs);
                     }
-                }
-            } else if marker.contains("SENSOR MOD/TYPE/ACC") {
-                if let Ok(sensor) = meteo::sensor::Sensor::from_str(content) {
-                    meteo.sensors.push(sensor)
-                }
-            } else if marker.contains("SENSOR POS XYZ/H") {
-                /*
-                 * Meteo: sensor position information
-                 */
-                let (x, rem) = content.split_at(14);
-                let (y, rem) = rem.split_at(14);
-                let (z, rem) = rem.split_at(14);
-                let (h, phys) = rem.split_at(14);
-
-                let phys = phys.trim();
-                let observable = Observable::from_str(phys)?;
-
-                let x = x.trim();
-                let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("SENSOR POS X"),
-                    x.to_string(),
-                )))?;
-
-                let y = y.trim();
-                let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("SENSOR POS Y"),
-                    y.to_string(),
-                )))?;
-
-                let z = z.trim();
-                let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("SENSOR POS Z"),
-                    z.to_string(),
-                )))?;
-
-                let h = h.trim();
-                let h = f64::from_str(h).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("SENSOR POS H"),
-                    h.to_string(),
-                )))?;
-
-                for sensor in meteo.sensors.iter_mut() {
-                    if sensor.observable == observable {
-                        *sensor = sensor.with_position((x, y, z, h))
+                },
+                "TIME OF LAST OBS" => {
+                    let mut time_of_last_obs = Self::parse_time_of_obs(content)?;
+                    match constellation {
+                        Some(Constellation::Mixed) | None => {},
+                        Some(c) => {
+                            // in case of OLD RINEX : fixed constellation
+                            //  use that information, as it may be omitted in the TIME OF OBS header
+                            time_of_last_obs.time_scale = c
+                                .timescale()
+                                .ok_or(ParsingError::TimescaleParsing(c.to_string()))?;
+                        },
                     }
-                }
-            } else if marker.contains("LEAP SECOND") {
-                let leap_str = content.split_at(40).0.trim();
-                if let Ok(lleap) = Leap::from_str(leap_str) {
-                    leap = Some(lleap)
-                }
-            } else if marker.contains("DOI") {
-                let (content, _) = content.split_at(40); //  TODO: confirm please
-                doi = Some(content.trim().to_string())
-            } else if marker.contains("MERGED FILE") {
-                //TODO V > 3
-                // nb# of merged files
-            } else if marker.contains("STATION INFORMATION") {
-                let url = content.split_at(40).0; //TODO confirm please
-                station_url = url.trim().to_string()
-            } else if marker.contains("LICENSE OF USE") {
-                let lic = content.split_at(40).0; //TODO confirm please
-                license = Some(lic.trim().to_string())
-            } else if marker.contains("WAVELENGTH FACT L1/2") {
-                //TODO
-            } else if marker.contains("APPROX POSITION XYZ") {
-                // station base coordinates
-                let items: Vec<&str> = content.split_ascii_whitespace().collect();
-                let x = items[0].trim();
-                let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("APPROX POSITION X"),
-                    x.to_string(),
-                )))?;
-
-                let y = items[1].trim();
-                let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("APPROX POSITION Y"),
-                    y.to_string(),
-                )))?;
-
-                let z = items[2].trim();
-                let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("APPROX POSITION Z"),
-                    z.to_string(),
-                )))?;
-
-                ground_position = Some(GroundPosition::from_ecef_wgs84((x, y, z)));
-            } else if marker.contains("ANT # / TYPE") {
-                let (model, rem) = content.split_at(20);
-                let (sn, _) = rem.split_at(20);
-                if let Some(a) = &mut rcvr_antenna {
-                    *a = a.with_model(model.trim()).with_serial_number(sn.trim());
-                } else {
-                    rcvr_antenna = Some(
-                        Antenna::default()
-                            .with_model(model.trim())
-                            .with_serial_number(sn.trim()),
-                    );
-                }
-            } else if marker.contains("ANTENNA: DELTA X/Y/Z") {
-                // Antenna Base/Reference Coordinates
-                let items: Vec<&str> = content.split_ascii_whitespace().collect();
-
-                let x = items[0].trim();
-                let x = f64::from_str(x).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("ANTENNA DELTA X"),
-                    x.to_string(),
-                )))?;
-
-                let y = items[1].trim();
-                let y = f64::from_str(y).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("ANTENNA DELTA Y"),
-                    y.to_string(),
-                )))?;
-
-                let z = items[2].trim();
-                let z = f64::from_str(z).or(Err(ParsingError::CoordinatesParsing(
-                    String::from("ANTENNA DELTA Z"),
-                    z.to_string(),
-                )))?;
-
-                if let Some(ant) = &mut rcvr_antenna {
-                    *ant = ant.with_base_coordinates((x, y, z));
-                } else {
-                    rcvr_antenna = Some(Antenna::default().with_base_coordinates((x, y, z)));
-                }
-            } else if marker.contains("ANTENNA: DELTA H/E/N") {
-                // Antenna H/E/N eccentricity components
-                let (h, rem) = content.split_at(15);
-                let (e, rem) = rem.split_at(15);
-                let (n, _) = rem.split_at(15);
-                if let Ok(h) = f64::from_str(h.trim()) {
-                    if let Ok(e) = f64::from_str(e.trim()) {
-                        if let Ok(n) = f64::from_str(n.trim()) {
-                            if let Some(a) = &mut rcvr_antenna {
-                                *a = a
-                                    .with_height(h)
-                                    .with_eastern_component(e)
-                                    .with_northern_component(n);
-                            } else {
-                                rcvr_antenna = Some(
-                                    Antenna::default()
-                                        .with_height(h)
-                                        .with_eastern_component(e)
-                                        .with_northern_component(n),
-                                );
-                            }
+                    if rinex_type == Type::DORIS {
+                        doris.time_of_last_obs = Some(time_of_last_obs);
+                    } else {
+                        observation = observation.with_time_of_last_obs(time_of_last_obs);
+                    }
+                },
+                "# / TYPES OF OBSERV" => {
+                    // these observations can serve both Observation & Meteo RINEX:
+                    // they are resolved once the complete header is known
+                    v2_observables.push(content.to_string());
+                },
+                "SYS / # / OBS TYPES" => {
+                    match rinex_type {
+                        Type::ObservationData => {
+                            Self::parse_v3_observables(
+                                content,
+                                &mut current_constell,
+                                &mut observation,
+                            );
+                        },
+                        Type::DORIS => {
+                            /* in DORIS RINEX, observations are not tied to a particular constellation */
+                            Self::parse_doris_observables(content, &mut doris);
+                        },
+                        _ => {},
+                    }
+                },
+                "ANALYSIS CENTER" => {
+                    let (code, agency) = content.split_at(3);
+                    clock = clock.igs(code.trim());
+                    clock = clock.full_name(agency.trim());
+                },
+                "ANALYSIS CLK REF" => {
+                    let ck = WorkClock::parse(version, content);
+                    clock = clock.work_clock(ck);
+                },
+                "# / TYPES OF DATA" => {
+                    let (n, r) = content.split_at(6);
+                    let n = n.trim();
+                    let n = n
+                        .parse::<u8>()
+                        .or(Err(parse_int_error!("# / TYPES OF DATA", n)))?;
+
+                    let mut rem = r;
+                    for _ in 0..n {
+                        let (code, r) = rem.split_at(6);
+                        if let Ok(c) = ClockProfileType::from_str(code.trim()) {
+                            clock.codes.push(c);
                         }
+                        rem = r;
                     }
-                }
-            } else if marker.contains("ANTENNA: B.SIGHT XYZ") {
-                //TODO
-            } else if marker.contains("ANTENNA: ZERODIR XYZ") {
-                //TODO
-            } else if marker.contains("ANTENNA: PHASECENTER") {
-                //TODO
-            } else if marker.contains("CENTER OF MASS: XYZ") {
-                //TODO
-            } else if marker.contains("RCV CLOCK OFFS APPL") {
-                let value = content.split_at(20).0.trim();
-                let n = i32::from_str_radix(value, 10)
-                    .or(Err(parse_int_error!("RCV CLOCK OFFS APPL", value)))?;
-
-                observation.clock_offset_applied = n > 0;
-            } else if marker.contains("# OF SATELLITES") {
-                // ---> we don't need this info,
-                //     user can determine it by analyzing the record
-            } else if marker.contains("PRN / # OF OBS") {
-                // ---> we don't need this info,
-                //     user can determine it by analyzing the record
-            } else if marker.contains("SYS / PHASE SHIFT") {
-                //TODO
-            } else if marker.contains("SYS / PVCS APPLIED") {
-                // RINEX::ClockData specific
-                // + satellite system (G/R/E/C/I/J/S)
-                // + programe name to apply Phase Center Variation
-                // + source of corrections (url)
-                // <o repeated for each satellite system
-                // <o blank field when no corrections applied
-            } else if marker.contains("TIME OF FIRST OBS") {
-                let mut time_of_first_obs = Self::parse_time_of_obs(content)?;
-                match constellation {
-                    Some(Constellation::Mixed) | None => {},
-                    Some(c) => {
-                        // in case of OLD RINEX : fixed constellation
-                        //  use that information, as it may be omitted in the TIME OF OBS header
-                        time_of_first_obs.time_scale = c
-                            .timescale()
-                            .ok_or(ParsingError::TimescaleParsing(c.to_string()))?;
-                    },
-                }
-                if rinex_type == Type::DORIS {
-                    doris.time_of_first_obs = Some(time_of_first_obs);
-                } else {
-                    observation = observation.with_time_of_first_obs(time_of_first_obs);
-                }
-            } else if marker.contains("TIME OF LAST OBS") {
-                let mut time_of_last_obs = Self::parse_time_of_obs(content)?;
-                match constellation {
-                    Some(Constellation::Mixed) | None => {},
-                    Some(c) => {
-                        // in case of OLD RINEX : fixed constellation
-                        //  use that information, as it may be omitted in the TIME OF OBS header
-                        time_of_last_obs.time_scale = c
-                            .timescale()
-                            .ok_or(ParsingError::TimescaleParsing(c.to_string()))?;
-                    },
-                }
-                if rinex_type == Type::DORIS {
-                    doris.time_of_last_obs = Some(time_of_last_obs);
-                } else {
-                    observation = observation.with_time_of_last_obs(time_of_last_obs);
-                }
-            } else if marker.contains("TYPES OF OBS") {
-                // these observations can serve both Observation & Meteo RINEX
-                Self::parse_v2_observables(content, constellation, &mut meteo, &mut observation);
-            } else if marker.contains("SYS / # / OBS TYPES") {
-                match rinex_type {
-                    Type::ObservationData => {
-                        Self::parse_v3_observables(
-                            content,
-                            &mut current_constell,
-                            &mut observation,
-                        );
-                    },
-                    Type::DORIS => {
-                        /* in DORIS RINEX, observations are not tied to a particular constellation */
-                        Self::parse_doris_observables(content, &mut doris);
-                    },
-                    _ => {},
-                }
-            } else if marker.contains("ANALYSIS CENTER") {
-                let (code, agency) = content.split_at(3);
-                clock = clock.igs(code.trim());
-                clock = clock.full_name(agency.trim());
-            } else if marker.contains("ANALYSIS CLK REF") {
-                let ck = WorkClock::parse(version, content);
-                clock = clock.work_clock(ck);
-            } else if marker.contains("# / TYPES OF DATA") {
-                let (n, r) = content.split_at(6);
-                let n = n.trim();
-                let n = n
-                    .parse::<u8>()
-                    .or(Err(parse_int_error!("# / TYPES OF DATA", n)))?;
-
-                let mut rem = r;
-                for _ in 0..n {
-                    let (code, r) = rem.split_at(6);
-                    if let Ok(c) = ClockProfileType::from_str(code.trim()) {
-                        clock.codes.push(c);
+                },
+                "STATION NAME / NUM" => {
+                    let (name, domes) = content.split_at(4);
+                    clock = clock.site(name.trim());
+                    if let Ok(domes) = Domes::from_str(domes.trim()) {
+                        clock = clock.domes(domes);
                     }
-                    rem = r;
-                }
-            } else if marker.contains("STATION NAME / NUM") {
-                let (name, domes) = content.split_at(4);
-                clock = clock.site(name.trim());
-                if let Ok(domes) = Domes::from_str(domes.trim()) {
-                    clock = clock.domes(domes);
-                }
-            } else if marker.contains("STATION CLK REF") {
-                clock = clock.refclock(content.trim());
-            } else if marker.contains("SIGNAL STRENGHT UNIT") {
-                //TODO
-            } else if marker.contains("INTERVAL") {
-                let intv_str = content.split_at(20).0.trim();
-                if let Ok(interval) = f64::from_str(intv_str) {
-                    if interval > 0.0 {
-                        // INTERVAL = '0' may exist, in case
-                        // of Varying TEC map intervals
-                        sampling_interval = Some(Duration::from_seconds(interval));
+                },
+                "STATION CLK REF" => {
+                    clock = clock.refclock(content.trim());
+                },
+                "SIGNAL STRENGTH UNIT" => {
+                    let unit = content.split_at(20).0.trim();
+                    if !unit.is_empty() {
+                        observation.signal_strength_unit = Some(unit.to_string());
                     }
-                }
-            } else if marker.contains("COSPAR NUMBER") {
-                cospar = Some(COSPAR::from_str(content.trim())?);
-            } else if marker.contains("GLONASS SLOT / FRQ #") {
-                //TODO
-                // This should be used when dealing with Glonass carriers
-
-                let slots = content.split_at(4).1.trim();
-                for i in 0..num_integer::div_ceil(slots.len(), 7) {
-                    let svnn = &slots[i * 7..i * 7 + 4];
-                    let chx = &slots[i * 7 + 4..std::cmp::min(i * 7 + 4 + 3, slots.len())];
-                    if let Ok(svnn) = SV::from_str(svnn.trim()) {
-                        if let Ok(chx) = chx.trim().parse::<i8>() {
-                            glo_channels.insert(svnn, chx);
+                },
+                "INTERVAL" => {
+                    let intv_str = content.split_at(20).0.trim();
+                    if let Ok(interval) = f64::from_str(intv_str) {
+                        if interval > 0.0 {
+                            // INTERVAL = '0' may exist, in case
+                            // of Varying TEC map intervals
+                            sampling_interval = Some(Duration::from_seconds(interval));
                         }
                     }
-                }
-            } else if marker.contains("GLONASS COD/PHS/BIS") {
-                //TODO
-                // This will help RTK solving against GLONASS SV
-            } else if marker.contains("ION ALPHA") {
-                // RINEX v2 Ionospheric correction. We tolerate BETA/ALPHA order mixup, as per
-                // RINEX v2 standards [https://files.igs.org/pub/data/format/rinex211.txt] paragraph 5.2.
-                match IonMessage::from_rinex2_header(content, marker) {
-                    Ok(IonMessage::KlobucharModel(KbModel {
-                        alpha,
-                        beta,
-                        region,
-                    })) => {
-                        // Support GPS|GLO|BDS|GAL|QZSS|SBAS|IRNSS
-                        for c in [
-                            Constellation::GPS,
-                            Constellation::Glonass,
-                            Constellation::BeiDou,
-                            Constellation::Galileo,
-                            Constellation::IRNSS,
-                            Constellation::QZSS,
-                            Constellation::SBAS,
-                        ] {
-                            if let Some(correction) = ionod_corrections.get_mut(&c) {
-                                // Only Klobuchar models in RINEX2
-                                let kb_model = correction.as_klobuchar_mut().unwrap();
-                                kb_model.alpha = alpha;
-                                kb_model.region = region;
-                            } else {
-                                ionod_corrections.insert(
-                                    c,
-                                    IonMessage::KlobucharModel(KbModel {
-                                        alpha,
-                                        beta,
-                                        region,
-                                    }),
-                                );
+                },
+                "COSPAR NUMBER" => {
+                    cospar = Some(COSPAR::from_str(content.trim())?);
+                },
+                "GLONASS SLOT / FRQ #" => {
+                    //TODO
+                    // This should be used when dealing with Glonass carriers
+
+                    let slots = content.split_at(4).1.trim();
+                    for i in 0..num_integer::div_ceil(slots.len(), 7) {
+                        let svnn = &slots[i * 7..i * 7 + 4];
+                        let chx = &slots[i * 7 + 4..std::cmp::min(i * 7 + 4 + 3, slots.len())];
+                        if let Ok(svnn) = SV::from_str(svnn.trim()) {
+                            if let Ok(chx) = chx.trim().parse::<i8>() {
+                                glo_channels.insert(svnn, chx);
                             }
                         }
-                    },
-                    _ => {},
-                }
-            } else if marker.contains("ION BETA") {
-                // RINEX v2 Ionospheric correction. We are flexible in their order of appearance,
-                // RINEX v2 standards do NOT guarantee that (header fields are free order).
-                // [https://files.igs.org/pub/data/format/rinex211.txt] paragraph 5.2.
-                match IonMessage::from_rinex2_header(content, marker) {
-                    Ok(IonMessage::KlobucharModel(KbModel {
-                        alpha,
-                        beta,
-                        region,
-                    })) => {
-                        // Support GPS|GLO|BDS|GAL|QZSS|SBAS|IRNSS
-                        for c in [
-                            Constellation::GPS,
-                            Constellation::Glonass,
-                            Constellation::BeiDou,
-                            Constellation::Galileo,
-                            Constellation::IRNSS,
-                            Constellation::QZSS,
-                            Constellation::SBAS,
-                        ] {
-                            if let Some(correction) = ionod_corrections.get_mut(&c) {
-                                // Only Klobuchar models in RINEX2
-                                let kb_model = correction.as_klobuchar_mut().unwrap();
-                                kb_model.beta = beta;
+                    }
+                },
+                "GLONASS COD/PHS/BIS" => {
+                    //TODO
+                    // This will help RTK solving against GLONASS SV
+                },
+                "ION ALPHA" => {
+                    // RINEX v2 Ionospheric correction. We tolerate BETA/ALPHA order mixup, as per
+                    // RINEX v2 standards [https://files.igs.org/pub/data/format/rinex211.txt] paragraph 5.2.
+                    match IonMessage::from_rinex2_header(content, marker) {
+                        Ok(IonMessage::KlobucharModel(KbModel {
+                            alpha,
+                            beta,
+                            region,
+                        })) => {
+                            // Support GPS|GLO|BDS|GAL|QZSS|SBAS|IRNSS
+                            for c in [
+                                Constellation::GPS,
+                                Constellation::Glonass,
+                                Constellation::BeiDou,
+                                Constellation::Galileo,
+                                Constellation::IRNSS,
+                                Constellation::QZSS,
+                                Constellation::SBAS,
+                            ] {
+                                if let Some(correction) = ionod_corrections.get_mut(&c) {
+                                    // Only Klobuchar models in RINEX2
+                                    if let Some(kb_model) = correction.as_klobuchar_mut() {
+                                        kb_model.alpha = alpha;
+                                        kb_model.region = region;
+                                    }
+                                } else {
+                                    ionod_corrections.insert(
+                                        c,
+                                        IonMessage::KlobucharModel(KbModel {
+                                            alpha,
+                                            beta,
+                                            region,
+                                        }),
+                                    );
+                                }
+                            }
+                        },
+                        _ => {},
+                    }
+                },
+                "ION BETA" => {
+                    // RINEX v2 Ionospheric correction. We are flexible in their order of appearance,
+                    // RINEX v2 standards do NOT guarantee that (header fields are free order).
+                    // [https://files.igs.org/pub/data/format/rinex211.txt] paragraph 5.2.
+                    match IonMessage::from_rinex2_header(content, marker) {
+                        Ok(IonMessage::KlobucharModel(KbModel {
+                            alpha,
+                            beta,
+                            region,
+                        })) => {
+                            // Support GPS|GLO|BDS|GAL|QZSS|SBAS|IRNSS
+                            for c in [
+                                Constellation::GPS,
+                                Constellation::Glonass,
+                                Constellation::BeiDou,
+                                Constellation::Galileo,
+                                Constellation::IRNSS,
+                                Constellation::QZSS,
+                                Constellation::SBAS,
+                            ] {
+                                if let Some(correction) = ionod_corrections.get_mut(&c) {
+                                    // Only Klobuchar models in RINEX2
+                                    if let Some(kb_model) = correction.as_klobuchar_mut() {
+                                        kb_model.beta = beta;
+                                    }
+                                } else {
+                                    ionod_corrections.insert(
+                                        c,
+                                        IonMessage::KlobucharModel(KbModel {
+                                            alpha,
+                                            beta,
+                                            region,
+                                        }),
+                                    );
+                                }
+                            }
+                        },
+                        _ => {},
+                    }
+                },
+                "IONOSPHERIC CORR" => {
+                    /*
+                     * RINEX3 IONOSPHERIC CORRECTION
+                     * We support both model in all RINEX2|RINEX3 constellations.
+                     * RINEX4 replaces that with actual file content (body) for improved correction accuracy.
+                     * The description requires 2 lines when dealing with KB model and we tolerate order mixup.
+                     */
+                    let model_id = content.split_at(5).0;
+                    if model_id.len() < 3 {
+                        /* BAD RINEX */
+                        continue;
+                    }
+                    let constell_id = &model_id[..3];
+                    let constell = match constell_id {
+                        "GPS" => Constellation::GPS,
+                        "GAL" => Constellation::Galileo,
+                        "BDS" => Constellation::BeiDou,
+                        "QZS" => Constellation::QZSS,
+                        "IRN" => Constellation::IRNSS,
+                        "GLO" => Constellation::Glonass,
+                        _ => continue,
+                    };
+                    match IonMessage::from_rinex3_header(content) {
+                        Ok(IonMessage::KlobucharModel(KbModel {
+                            alpha,
+                            beta,
+                            region,
+                        })) => {
+                            // KB requires two lines
+                            if let Some(ionod_model) = ionod_corrections.get_mut(&constell) {
+                                if let Some(kb_model) = ionod_model.as_klobuchar_mut() {
+                                    if model_id.ends_with('A') {
+                                        kb_model.alpha = alpha;
+                                        kb_model.region = region;
+                                    } else {
+                                        kb_model.beta = beta;
+                                    }
+                                }
                             } else {
+                                // latch new model
                                 ionod_corrections.insert(
-                                    c,
+                                    constell,
                                     IonMessage::KlobucharModel(KbModel {
                                         alpha,
                                         beta,
@@ -946,150 +1227,154 @@ impl Header {
                                     }),
                                 );
                             }
-                        }
-                    },
-                    _ => {},
-                }
-            } else if marker.contains("IONOSPHERIC CORR") {
+                        },
+                        Ok(ion) => {
+                            ionod_corrections.insert(constell, ion);
+                        },
+                        _ => {},
+                    }
+                },
+                "TIME SYSTEM CORR" => {
+                    // GPUT 0.2793967723E-08 0.000000000E+00 147456 1395
+                    /*
+                     * V3 Time System correction description
+                     */
+                    //if let Ok((ts, ts, corr)) = gnss_time::decode_time_system_corr(content) {
+                    //    time_corrections.insert(ts, (ts, corr));
+                    //}
+                },
+                "TIME SYSTEM ID" => {
+                    let timescale = content.trim();
+                    let ts = TimeScale::from_str(timescale)
+                        .or(Err(ParsingError::TimescaleParsing(timescale.to_string())))?;
+                    clock = clock.timescale(ts);
+                },
+                "DELTA-UTC: A0,A1,T,W" => {
+                    //TODO
+                    //0.931322574615D-09 0.355271367880D-14   233472     1930 DELTA-UTC: A0,A1,T,W
+                },
+                "DESCRIPTION" => {
+                    // IONEX description
+                    // <o
+                    //   if "DESCRIPTION" is to be encountered in other RINEX
+                    //   we can safely test RinexType here because its already been determined
+                    ionex = ionex.with_description(content.trim())
+                },
+                "EPOCH OF FIRST MAP" => {
+                    // IONEX
+                    if let Ok(epoch) = epoch::parse_utc(content.trim()) {
+                        ionex = ionex.with_epoch_of_first_map(epoch);
+                    }
+                },
+                "EPOCH OF LAST MAP" => {
+                    // IONEX
+                    if let Ok(epoch) = epoch::parse_utc(content.trim()) {
+                        ionex = ionex.with_epoch_of_last_map(epoch);
+                    }
+                },
+                "OBSERVABLES USED" => {
+                    // IONEX observables
+                    ionex = ionex.with_observables(content.trim())
+                },
+                "ELEVATION CUTOFF" => {
+                    if let Ok(f) = f32::from_str(content.trim()) {
+                        ionex = ionex.with_elevation_cutoff(f);
+                    }
+                },
+                "BASE RADIUS" => {
+                    if let Ok(f) = f32::from_str(content.trim()) {
+                        ionex = ionex.with_base_radius(f);
+                    }
+                },
+                "MAPPING FUCTION" => {
+                    if let Ok(mf) = ionex::MappingFunction::from_str(content.trim()) {
+                        ionex = ionex.with_mapping_function(mf);
+                    }
+                },
+                "# OF STATIONS" => {
+                    // IONEX
+                    if let Ok(u) = content.trim().parse::<u32>() {
+                        ionex = ionex.with_nb_stations(u)
+                    }
+                },
                 /*
-                 * RINEX3 IONOSPHERIC CORRECTION
-                 * We support both model in all RINEX2|RINEX3 constellations.
-                 * RINEX4 replaces that with actual file content (body) for improved correction accuracy.
-                 * The description requires 2 lines when dealing with KB model and we tolerate order mixup.
+                 * Initial TEC map scaling
                  */
-                let model_id = content.split_at(5).0;
-                if model_id.len() < 3 {
-                    /* BAD RINEX */
-                    continue;
-                }
-                let constell_id = &model_id[..3];
-                let constell = match constell_id {
-                    "GPS" => Constellation::GPS,
-                    "GAL" => Constellation::Galileo,
-                    "BDS" => Constellation::BeiDou,
-                    "QZS" => Constellation::QZSS,
-                    "IRN" => Constellation::IRNSS,
-                    "GLO" => Constellation::Glonass,
-                    _ => continue,
-                };
-                match IonMessage::from_rinex3_header(content) {
-                    Ok(IonMessage::KlobucharModel(KbModel {
-                        alpha,
-                        beta,
-                        region,
-                    })) => {
-                        // KB requires two lines
-                        if let Some(ionod_model) = ionod_corrections.get_mut(&constell) {
-                            let kb_model = ionod_model.as_klobuchar_mut().unwrap();
-                            if model_id.ends_with('A') {
-                                kb_model.alpha = alpha;
-                                kb_model.region = region;
-                            } else {
-                                kb_model.beta = beta;
-                            }
-                        } else {
-                            // latch new model
-                            ionod_corrections.insert(
-                                constell,
-                                IonMessage::KlobucharModel(KbModel {
-                                    alpha,
-                                    beta,
-                                    region,
-                                }),
-                            );
-                        }
-                    },
-                    Ok(ion) => {
-                        ionod_corrections.insert(constell, ion);
-                    },
-                    _ => {},
-                }
-            } else if marker.contains("TIME SYSTEM CORR") {
-                // GPUT 0.2793967723E-08 0.000000000E+00 147456 1395
+                "EXPONENT" => {
+                    if let Ok(e) = content.trim().parse::<i8>() {
+                        ionex = ionex.with_exponent(e);
+                    }
+                },
                 /*
-                 * V3 Time System correction description
+                 * Ionex Grid Definition
                  */
-                //if let Ok((ts, ts, corr)) = gnss_time::decode_time_system_corr(content) {
-                //    time_corrections.insert(ts, (ts, corr));
-                //}
-            } else if marker.contains("TIME SYSTEM ID") {
-                let timescale = content.trim();
-                let ts = TimeScale::from_str(timescale)
-                    .or(Err(ParsingError::TimescaleParsing(timescale.to_string())))?;
-                clock = clock.timescale(ts);
-            } else if marker.contains("DELTA-UTC") {
-                //TODO
-                //0.931322574615D-09 0.355271367880D-14   233472     1930 DELTA-UTC: A0,A1,T,W
-            } else if marker.contains("DESCRIPTION") {
-                // IONEX description
-                // <o
-                //   if "DESCRIPTION" is to be encountered in other RINEX
-                //   we can safely test RinexType here because its already been determined
-                ionex = ionex.with_description(content.trim())
-            } else if marker.contains("OBSERVABLES USED") {
-                // IONEX observables
-                ionex = ionex.with_observables(content.trim())
-            } else if marker.contains("ELEVATION CUTOFF") {
-                if let Ok(f) = f32::from_str(content.trim()) {
-                    ionex = ionex.with_elevation_cutoff(f);
-                }
-            } else if marker.contains("BASE RADIUS") {
-                if let Ok(f) = f32::from_str(content.trim()) {
-                    ionex = ionex.with_base_radius(f);
-                }
-            } else if marker.contains("MAPPING FUCTION") {
-                if let Ok(mf) = ionex::MappingFunction::from_str(content.trim()) {
-                    ionex = ionex.with_mapping_function(mf);
-                }
-            } else if marker.contains("# OF STATIONS") {
-                // IONEX
-                if let Ok(u) = content.trim().parse::<u32>() {
-                    ionex = ionex.with_nb_stations(u)
-                }
-            } else if marker.contains("# OF SATELLITES") {
-                // IONEX
-                if let Ok(u) = content.trim().parse::<u32>() {
-                    ionex = ionex.with_nb_satellites(u)
-                }
-            /*
-             * Initial TEC map scaling
-             */
-            } else if marker.contains("EXPONENT") {
-                if let Ok(e) = content.trim().parse::<i8>() {
-                    ionex = ionex.with_exponent(e);
-                }
+                "HGT1 / HGT2 / DHGT" => {
+                    let grid = Self::parse_grid(content)?;
+                    ionex = ionex.with_altitude_grid(grid);
+                },
+                "LAT1 / LAT2 / DLAT" => {
+                    let grid = Self::parse_grid(content)?;
+                    ionex = ionex.with_latitude_grid(grid);
+                },
+                "LON1 / LON2 / DLON" => {
+                    let grid = Self::parse_grid(content)?;
+                    ionex = ionex.with_longitude_grid(grid);
+                },
+                "PRN / BIAS / RMS" => {
+                    // differential PR code analysis
+                    //TODO
+                },
+                "L2 / L1 DATE OFFSET" => {
+                    // DORIS special case
+                    let content = content[1..].trim();
+                    let l2l1_date_offset = content
+                        .parse::<f64>()
+                        .or(Err(parse_float_error!("doris l2/l1 date offset", content)))?;
+
+                    doris.l2_l1_date_offset = Duration::from_microseconds(l2l1_date_offset);
+                },
+                "STATION REFERENCE" => {
+                    // DORIS special case
+                    let station = DorisStation::from_str(content.trim())?;
+                    doris.stations.push(station);
+                },
+                "TIME REF STATION" => {
+                    // DORIS special case (TODO)
+                },
+                _ => {},
+            }
+        }
 
-            /*
-             * Ionex Grid Definition
-             */
-            } else if marker.contains("HGT1 / HGT2 / DHGT") {
-                let grid = Self::parse_grid(content)?;
-                ionex = ionex.with_altitude_grid(grid);
-            } else if marker.contains("LAT1 / LAT2 / DLAT") {
-                let grid = Self::parse_grid(content)?;
-                ionex = ionex.with_latitude_grid(grid);
-            } else if marker.contains("LON1 / LON2 / DLON") {
-                let grid = Self::parse_grid(content)?;
-                ionex = ionex.with_longitude_grid(grid);
-            } else if marker.contains("PRN / BIAS / RMS") {
-                // differential PR code analysis
-                //TODO
-            } else if marker.contains("L2 / L1 DATE OFFSET") {
-                // DORIS special case
-                let content = content[1..].trim();
-                let l2l1_date_offset = content
-                    .parse::<f64>()
-                    .or(Err(parse_float_error!("doris l2/l1 date offset", content)))?;
-
-                doris.l2_l1_date_offset = Duration::from_microseconds(l2l1_date_offset);
-            } else if marker.contains("STATION REFERENCE") {
-                // DORIS special case
-                let station = DorisStation::from_str(content.trim())?;
-                doris.stations.push(station);
-            } else if marker.contains("TIME REF STATION") {
-                // DORIS special case (TODO)
+        if !end_of_header {
+            // interrupted stream or truncated file
+            return Err(ParsingError::MissingEndOfHeader);
+        }
+
+        /*
+         * Observation / Meteo disambiguation:
+         * SENSOR MOD/TYPE/ACC only exists in Meteo RINEX
+         */
+        let is_meteo = match rinex_type {
+            Type::MeteoData => true,
+            Type::ObservationData if meteo_sensors => {
+                if type_declared {
+                    return Err(ParsingError::ObsMeteoConflict);
+                }
+                rinex_type = Type::MeteoData;
+                true
+            },
+            _ => false,
+        };
+        for content in v2_observables.iter() {
+            if is_meteo {
+                Self::parse_v2_observables(content, None, &mut meteo, &mut observation);
+            } else {
+                // blank V2 system field means GPS
+                let constellation = constellation.or(Some(Constellation::GPS));
+                Self::parse_v2_observables(content, constellation, &mut meteo, &mut observation);
             }
         }
+        Self::parse_prn_obs_counts(&prn_obs_counts, constellation, &mut observation);
 
         Ok(Header {
             version,
@@ -1104,6 +1389,7 @@ impl Header {
             observer,
             license,
             doi,
+            merged_files,
             station_url,
             rcvr,
             cospar,
@@ -1113,7 +1399,7 @@ impl Header {
             ionod_corrections,
             dcb_compensations,
             pcv_compensations,
-            wavelengths: None,
+            wavelengths,
             gps_utc_delta: None,
             sampling_interval,
             rcvr_antenna,
@@ -1164,6 +1450,36 @@ impl Header {
         })
     }
 
+    /// Returns the [MarkerType] described in the MARKER TYPE field, if any.
+    pub fn marker_type(&self) -> Option<MarkerType> {
+        self.geodetic_marker.as_ref()?.marker_type
+    }
+
+    /// Returns the [`Domes`] identifier of the station, when the
+    /// marker number (or the Clock RINEX station number) is a valid DOMES number.
+    pub fn domes(&self) -> Option<Domes> {
+        if let Some(domes) = self.geodetic_marker.as_ref().and_then(|m| m.domes()) {
+            return Some(domes);
+        }
+        self.clock.as_ref().and_then(|clock| clock.domes)
+    }
+
+    /// Decomposes the IGS 9 character station name, when the MARKER NAME
+    /// follows this convention (like "AMEL00NLD").
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::marker::GeodeticMarker;
+    /// let mut header = Header::default();
+    /// header.geodetic_marker = Some(GeodeticMarker::default().with_name("AMEL00NLD"));
+    /// let station = header.station_9char()
+    ///     .unwrap();
+    /// assert_eq!(station.monument, "AMEL");
+    /// assert_eq!(station.country, "NLD");
+    /// ```
+    pub fn station_9char(&self) -> Option<StationName> {
+        StationName::from_str(self.geodetic_marker.as_ref()?.name.trim()).ok()
+    }
+
     /// Returns true if self is a `Compressed RINEX`
     pub fn is_crinex(&self) -> bool {
         if let Some(obs) = &self.obs {
@@ -1221,6 +1537,117 @@ impl Header {
         s
     }
 
+    /// Returns the production date (PGM / RUN BY / DATE), expressed in UTC.
+    /// Supports the V2 `dd-mmm-yy hh:mm[:ss]` (and `yy/mm/dd hh:mm:ss`) formats,
+    /// and the V3 `yyyymmdd hhmmss zone` format.
+    /// Returns None when the date is not recognized, or when it is not expressed
+    /// in UTC (for example `LCL`, local time), since the local time zone is unknown.
+    /// Dates without zone are considered UTC.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let datetime = rnx.header.production_datetime()
+    ///     .unwrap();
+    /// assert_eq!(datetime.to_gregorian_utc(), (2022, 3, 5, 0, 0, 26, 0));
+    /// ```
+    pub fn production_datetime(&self) -> Option<Epoch> {
+        let digits = |s: &str| -> String { s.chars().filter(|c| c.is_ascii_digit()).collect() };
+        let two_digits_year = |y: i32| -> i32 {
+            match y {
+                y if y < 80 => y + 2000,
+                y if y < 100 => y + 1900,
+                y => y,
+            }
+        };
+        let mut items = self.date.split_ascii_whitespace();
+        let date = items.next()?;
+        let time = items.collect::<String>();
+        let zone = time
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect::<String>();
+        match zone.to_ascii_uppercase().as_str() {
+            "" | "UTC" | "U" => {},
+            _ => return None, // local or unknown time zone
+        }
+        let time = digits(&time);
+        let (y, m, d) = if date.contains('-') {
+            // V2: dd-mmm-yy
+            let mut date = date.split('-');
+            let d = date.next()?.parse::<u8>().ok()?;
+            let m = date.next()?;
+            let m = format!(
+                "{}{}",
+                m.get(..1)?.to_uppercase(),
+                m.get(1..)?.to_lowercase()
+            );
+            let m = parse_formatted_month(&m).ok()?;
+            let y = two_digits_year(date.next()?.parse::<i32>().ok()?);
+            (y, m, d)
+        } else if date.contains('/') {
+            // V2: yy/mm/dd
+            let mut date = date.split('/');
+            let y = two_digits_year(date.next()?.parse::<i32>().ok()?);
+            let m = date.next()?.parse::<u8>().ok()?;
+            let d = date.next()?.parse::<u8>().ok()?;
+            (y, m, d)
+        } else {
+            // V3: yyyymmdd
+            let date = digits(date);
+            if date.len() != 8 {
+                return None;
+            }
+            (
+                date[..4].parse::<i32>().ok()?,
+                date[4..6].parse::<u8>().ok()?,
+                date[6..].parse::<u8>().ok()?,
+            )
+        };
+        let (hh, mm, ss) = match time.len() {
+            4 => (&time[..2], &time[2..], "0"),
+            6 => (&time[..2], &time[2..4], &time[4..]),
+            _ => return None,
+        };
+        Epoch::maybe_from_gregorian_utc(
+            y,
+            m,
+            d,
+            hh.parse::<u8>().ok()?,
+            mm.parse::<u8>().ok()?,
+            ss.parse::<u8>().ok()?,
+            0,
+        )
+        .ok()
+    }
+
+    /// Sets the production date (PGM / RUN BY / DATE) to current UTC time,
+    /// formatted according to the RINEX revision: `dd-mmm-yy hh:mm`
+    /// prior V3, `yyyymmdd hhmmss UTC` otherwise.
+    /// Set the revision first, with [Self::with_version].
+    pub fn with_production_now(&self) -> Self {
+        let mut s = self.clone();
+        let (y, m, d, hh, mm, ss, _) = epoch::now().to_gregorian_utc();
+        s.date = match self.version.major {
+            1 | 2 => {
+                const MONTHS: [&str; 12] = [
+                    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV",
+                    "DEC",
+                ];
+                format!(
+                    "{:02}-{}-{:02} {:02}:{:02}",
+                    d,
+                    MONTHS[(m as usize).clamp(1, 12) - 1],
+                    y % 100,
+                    hh,
+                    mm
+                )
+            },
+            _ => format!("{:04}{:02}{:02} {:02}{:02}{:02} UTC", y, m, d, hh, mm, ss),
+        };
+        s
+    }
+
     /// Adds crinex generation attributes to self,
     /// has no effect if this is not an Observation Data header.
     pub fn with_crinex(&self, c: Crinex) -> Self {
@@ -1368,7 +1795,18 @@ impl Header {
                         )
                     )
                 },
-                _ => panic!("constellation must be specified when formatting a NavigationData"),
+                None => {
+                    // best effort: constellation field is left blank,
+                    // see [crate::Rinex::validate]
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!("{:6}.{:02}           NAVIGATION DATA", major, minor),
+                            "RINEX VERSION / TYPE"
+                        )
+                    )
+                },
             },
             Type::ObservationData => match self.constellation {
                 Some(c) => {
@@ -1384,7 +1822,18 @@ impl Header {
                         )
                     )
                 },
-                _ => panic!("constellation must be specified when formatting ObservationData"),
+                None => {
+                    // best effort: constellation field is left blank,
+                    // see [crate::Rinex::validate]
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!("{:6}.{:02}           OBSERVATION DATA", major, minor),
+                            "RINEX VERSION / TYPE"
+                        )
+                    )
+                },
             },
             Type::MeteoData => {
                 writeln!(
@@ -1406,9 +1855,50 @@ impl Header {
                     )
                 )
             },
-            Type::DORIS => todo!("doris formatting"),
-            Type::AntennaData => todo!("antex formatting"),
-            Type::IonosphereMaps => todo!("ionex formatting"),
+            Type::DORIS => {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{:02}           O                   D", major, minor),
+                        "RINEX VERSION / TYPE"
+                    )
+                )
+            },
+            Type::AntennaData => {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{}            M", major, minor),
+                        "ANTEX VERSION / SYST"
+                    )
+                )
+            },
+            Type::IonosphereMaps => {
+                let reference = match &self.ionex {
+                    Some(ionex) => match &ionex.reference {
+                        ionex::RefSystem::GnssConstellation(Constellation::Mixed) => {
+                            "GNSS".to_string()
+                        },
+                        ionex::RefSystem::GnssConstellation(c) => format!("{:X}", c),
+                        ionex::RefSystem::ObservationSystem(system) => format!("{:?}", system),
+                        ionex::RefSystem::Model(model) => format!("{:?}", model),
+                    },
+                    None => "GNSS".to_string(),
+                };
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!(
+                            "{:6}.{:01}            IONOSPHERE MAPS     {:<20}",
+                            major, minor, reference
+                        ),
+                        "IONEX VERSION / TYPE"
+                    )
+                )
+            },
         }
     }
     /*
@@ -1532,9 +2022,37 @@ impl Header {
                 writeln!(f, "{}", fmt_rinex("NONE", "MAPPING FUNCTION"))?;
             }
             // time of first map
-            writeln!(f, "{}", fmt_rinex("TODO", "EPOCH OF FIRST MAP"))?;
+            let (y, m, d, hh, mm, ss, _) = epoch_decompose(ionex.epoch_of_first_map);
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(
+                    &format!("{:6}{:6}{:6}{:6}{:6}{:6}", y, m, d, hh, mm, ss),
+                    "EPOCH OF FIRST MAP"
+                )
+            )?;
             // time of last map
-            writeln!(f, "{}", fmt_rinex("TODO", "EPOCH OF LAST MAP"))?;
+            let (y, m, d, hh, mm, ss, _) = epoch_decompose(ionex.epoch_of_last_map);
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(
+                    &format!("{:6}{:6}{:6}{:6}{:6}{:6}", y, m, d, hh, mm, ss),
+                    "EPOCH OF LAST MAP"
+                )
+            )?;
+            // base radius
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("{:8.1}", ionex.base_radius), "BASE RADIUS")
+            )?;
+            // data scaling
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("{:6}", ionex.exponent), "EXPONENT")
+            )?;
         }
         Ok(())
     }
@@ -1601,11 +2119,44 @@ impl Header {
              */
             match self.version.major {
                 1 | 2 => {
+                    /*
+                     * Wavelength factors: vehicles that share the same
+                     * factors are gathered, 7 per line
+                     */
+                    if let Some(wavelengths) = &self.wavelengths {
+                        let (l1, l2) = wavelengths.default;
+                        writeln!(
+                            f,
+                            "{}",
+                            fmt_rinex(&format!("{:6}{:6}", l1, l2), "WAVELENGTH FACT L1/2")
+                        )?;
+                        let mut groups = BTreeMap::<(u8, u8), Vec<SV>>::new();
+                        for (sv, factors) in wavelengths.sv.iter().sorted_by_key(|(sv, _)| **sv) {
+                            groups.entry(*factors).or_default().push(*sv);
+                        }
+                        for ((l1, l2), vehicles) in groups {
+                            for vehicles in vehicles.chunks(7) {
+                                let mut descriptor =
+                                    format!("{:6}{:6}{:6}", l1, l2, vehicles.len());
+                                for sv in vehicles {
+                                    descriptor.push_str(&format!(
+                                        "   {:x}{:2}",
+                                        sv.constellation, sv.prn
+                                    ));
+                                }
+                                writeln!(f, "{}", fmt_rinex(&descriptor, "WAVELENGTH FACT L1/2"))?;
+                            }
+                        }
+                    }
                     /*
                      * List of observables
                      */
                     let mut descriptor = String::new();
-                    if let Some((_constell, observables)) = obs.codes.iter().next() {
+                    if let Some((_constell, observables)) = obs
+                        .codes
+                        .iter()
+                        .min_by_key(|(constell, _)| format!("{:x}", constell))
+                    {
                         descriptor.push_str(&format!("{:6}", observables.len()));
                         for (i, observable) in observables.iter().enumerate() {
                             if (i % 9) == 0 && i > 0 {
@@ -1618,9 +2169,14 @@ impl Header {
                 },
                 _ => {
                     /*
-                     * List of observables
+                     * List of observables:
+                     * sorted by constellation code, for reproducible production
                      */
-                    for (constell, observables) in &obs.codes {
+                    for (constell, observables) in obs
+                        .codes
+                        .iter()
+                        .sorted_by_key(|(constell, _)| format!("{:x}", constell))
+                    {
                         let mut descriptor = String::new();
                         descriptor.push_str(&format!("{:x}{:5}", constell, observables.len()));
                         for (i, observable) in observables.iter().enumerate() {
@@ -1633,12 +2189,91 @@ impl Header {
                     }
                 },
             }
+            if let Some(n) = obs.nb_satellites {
+                writeln!(f, "{}", fmt_rinex(&format!("{:6}", n), "# OF SATELLITES"))?;
+            }
+            if self.version.major > 2 {
+                if let Some(unit) = &obs.signal_strength_unit {
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(&format!("{:<20}", unit), "SIGNAL STRENGTH UNIT")
+                    )?;
+                }
+                self.fmt_scale_factors(f, obs)?;
+            }
+            for (sv, counts) in obs.prn_obs_counts.iter().sorted_by_key(|(sv, _)| **sv) {
+                let constell = match sv.constellation.is_sbas() {
+                    true => Constellation::SBAS,
+                    false => sv.constellation,
+                };
+                let codes = match obs.codes.get(&constell) {
+                    Some(codes) => codes,
+                    None => continue,
+                };
+                let mut descriptor = format!("   {:x}", sv);
+                for (i, code) in codes.iter().enumerate() {
+                    if (i % 9) == 0 && i > 0 {
+                        writeln!(f, "{}", fmt_rinex(&descriptor, "PRN / # OF OBS"))?;
+                        descriptor = "      ".to_string(); // TAB
+                    }
+                    match counts.get(&code.to_string()) {
+                        Some(n) => descriptor.push_str(&format!("{:6}", n)),
+                        None => descriptor.push_str("      "),
+                    }
+                }
+                writeln!(f, "{}", fmt_rinex(&descriptor, "PRN / # OF OBS"))?;
+            }
             // must take place after list of observables:
             //  TODO DCBS compensations
             //  TODO PCVs compensations
         }
         Ok(())
     }
+    /*
+     * Format SYS / SCALE FACTOR: system wide factors first,
+     * then observables sharing the same factor, 12 per line
+     */
+    fn fmt_scale_factors(
+        &self,
+        f: &mut std::fmt::Formatter,
+        obs: &ObservationHeader,
+    ) -> std::fmt::Result {
+        for (constell, scaling) in obs
+            .data_scaling
+            .iter()
+            .sorted_by_key(|(constell, _)| format!("{:x}", constell))
+        {
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(
+                    &format!("{:x} {:4}", constell, scaling),
+                    "SYS / SCALE FACTOR"
+                )
+            )?;
+        }
+        let mut groups = BTreeMap::<(String, u16), Vec<String>>::new();
+        for ((constell, observable), scaling) in obs.scaling.iter() {
+            groups
+                .entry((format!("{:x}", constell), *scaling))
+                .or_default()
+                .push(observable.to_string());
+        }
+        for ((constell, scaling), mut observables) in groups {
+            observables.sort();
+            let mut descriptor = format!("{} {:4}  {:2}", constell, scaling, observables.len());
+            for (i, observable) in observables.iter().enumerate() {
+                if (i % 12) == 0 && i > 0 {
+                    writeln!(f, "{}", fmt_rinex(&descriptor, "SYS / SCALE FACTOR"))?;
+                    descriptor = "          ".to_string(); // TAB
+                }
+                descriptor.push_str(&format!(" {}", observable));
+            }
+            writeln!(f, "{}", fmt_rinex(&descriptor, "SYS / SCALE FACTOR"))?;
+        }
+        Ok(())
+    }
     /*
      * Format all comments
      */
@@ -1648,6 +2283,63 @@ impl Header {
         }
         Ok(())
     }
+    /*
+     * Parse WAVELENGTH FACT L1/2: first descriptor is the default
+     * (L1, L2) factors, following descriptors list up to 7 vehicles
+     */
+    fn parse_wavelength_factors(
+        content: &str,
+        constellation: Option<Constellation>,
+        wavelengths: &mut Option<observation::WavelengthFactors>,
+    ) -> Result<(), ParsingError> {
+        // blank fields are zeros
+        let factor = |field: &str| -> Result<u8, ParsingError> {
+            match field.trim() {
+                "" => Ok(0),
+                field => field
+                    .parse::<u8>()
+                    .or(Err(parse_int_error!("WAVELENGTH FACT L1/2", field))),
+            }
+        };
+        let (l1, rem) = content.split_at(std::cmp::min(6, content.len()));
+        let (l2, rem) = rem.split_at(std::cmp::min(6, rem.len()));
+        let (l1, l2) = (factor(l1)?, factor(l2)?);
+        let (nb_sv, mut rem) = rem.split_at(std::cmp::min(6, rem.len()));
+        let nb_sv = factor(nb_sv)?;
+
+        let wavelengths = match wavelengths {
+            Some(wavelengths) => wavelengths,
+            None => {
+                // default factors come first
+                *wavelengths = Some(observation::WavelengthFactors {
+                    default: (l1, l2),
+                    ..Default::default()
+                });
+                return Ok(());
+            },
+        };
+
+        for _ in 0..std::cmp::min(nb_sv, 7) {
+            if rem.len() < 6 {
+                break;
+            }
+            let (item, r) = rem.split_at(6);
+            rem = r;
+            // 3X,A1,I2: blank system identifier means GPS,
+            // unless the file is dedicated to another constellation
+            let (system, prn) = item.split_at(4);
+            let constellation = match system.trim() {
+                "" => match constellation {
+                    Some(Constellation::Mixed) | None => Constellation::GPS,
+                    Some(constellation) => constellation,
+                },
+                system => Constellation::from_str(system)?,
+            };
+            let sv = SV::new(constellation, factor(prn)?);
+            wavelengths.sv.insert(sv, (l1, l2));
+        }
+        Ok(())
+    }
     /*
      * Parse IONEX grid
      */
@@ -1692,11 +2384,12 @@ impl Header {
         line: &str,
         observation: &mut ObservationHeader,
     ) -> Result<(), ParsingError> {
-        assert!(
-            observation.crinex.is_some(),
-            "badly formed CRINEX: CRINEX VERS/TYPE expected as first header"
-        );
-        let mut crinex = observation.crinex.clone().unwrap();
+        let mut crinex = observation
+            .crinex
+            .clone()
+            .ok_or(ParsingError::CrinexHeader(String::from(
+                "CRINEX VERS / TYPE expected as first header",
+            )))?;
 
         let (prog, rem) = line.split_at(20);
         crinex = crinex.with_prog(prog.trim());
@@ -1850,6 +2543,65 @@ impl Header {
             }
         }
     }
+    /*
+     * Parse PRN / # OF OBS lines: 9 counts per line, in order of the
+     * observables declared for that constellation, possibly continued
+     * on following lines. Blank fields are not stored.
+     */
+    fn parse_prn_obs_counts(
+        lines: &[String],
+        constellation: Option<Constellation>,
+        observation: &mut ObservationHeader,
+    ) {
+        let mut current: Option<(SV, usize)> = None;
+        for line in lines {
+            let field = |start: usize| -> &str {
+                line.get(start..(start + 6).min(line.len()))
+                    .unwrap_or("")
+                    .trim()
+            };
+            let sv = line.get(3..6).unwrap_or("");
+            if !sv.trim().is_empty() {
+                // blank V2 system field means GPS
+                let constell = match sv[..1].trim() {
+                    "" => match constellation {
+                        Some(Constellation::Mixed) | None => Some(Constellation::GPS),
+                        c => c,
+                    },
+                    c => Constellation::from_str(c).ok(),
+                };
+                let prn = sv[1..].trim().parse::<u8>().ok();
+                current = match (constell, prn) {
+                    (Some(constellation), Some(prn)) => Some((SV { constellation, prn }, 0)),
+                    _ => None,
+                };
+            }
+            let (sv, index) = match current.as_mut() {
+                Some((sv, index)) => (*sv, index),
+                None => continue,
+            };
+            let constell = match sv.constellation.is_sbas() {
+                true => Constellation::SBAS,
+                false => sv.constellation,
+            };
+            let codes = match observation.codes.get(&constell) {
+                Some(codes) => codes.clone(),
+                None => continue,
+            };
+            for i in 0..9 {
+                if let Some(code) = codes.get(*index + i) {
+                    if let Ok(n) = field(6 + i * 6).parse::<u32>() {
+                        observation
+                            .prn_obs_counts
+                            .entry(sv)
+                            .or_default()
+                            .insert(code.to_string(), n);
+                    }
+                }
+            }
+            *index += 9;
+        }
+    }
     /*
      * Parse list of DORIS observables
      */
@@ -1896,11 +2648,33 @@ impl std::fmt::Display for Header {
             )
         )?;
 
+        // modern optional descriptors
+        if self.version.major > 2 {
+            if let Some(doi) = &self.doi {
+                writeln!(f, "{}", fmt_rinex(doi, "DOI"))?;
+            }
+            if let Some(license) = &self.license {
+                writeln!(f, "{}", fmt_rinex(license, "LICENSE OF USE"))?;
+            }
+            if let Some(n) = self.merged_files {
+                writeln!(f, "{}", fmt_rinex(&format!("{:>4}", n), "MERGED FILE"))?;
+            }
+            if !self.station_url.is_empty() {
+                writeln!(f, "{}", fmt_rinex(&self.station_url, "STATION INFORMATION"))?;
+            }
+        }
+
         if let Some(marker) = &self.geodetic_marker {
             writeln!(f, "{}", fmt_rinex(&marker.name, "MARKER NAME"))?;
             if let Some(number) = marker.number() {
                 writeln!(f, "{}", fmt_rinex(&number, "MARKER NUMBER"))?;
             }
+            // MARKER TYPE was introduced in RINEX3
+            if let Some(marker_type) = marker.marker_type {
+                if self.version.major > 2 {
+                    writeln!(f, "{}", fmt_rinex(&marker_type.to_string(), "MARKER TYPE"))?;
+                }
+            }
         }
 
         // APRIORI POS
@@ -1922,29 +2696,26 @@ impl std::fmt::Display for Header {
                     "ANT # / TYPE"
                 )
             )?;
-            if let Some(coords) = &antenna.coords {
+            if let Some((h, e, n)) = antenna.delta_hen {
                 writeln!(
                     f,
                     "{}",
                     fmt_rinex(
-                        &format!("{:14.4}{:14.4}{:14.4}", coords.0, coords.1, coords.2),
-                        "APPROX POSITION XYZ"
+                        &format!("{:14.4}{:14.4}{:14.4}", h, e, n),
+                        "ANTENNA: DELTA H/E/N"
+                    )
+                )?;
+            }
+            if let Some((x, y, z)) = antenna.delta_xyz {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:14.4}{:14.4}{:14.4}", x, y, z),
+                        "ANTENNA: DELTA X/Y/Z"
                     )
                 )?;
             }
-            writeln!(
-                f,
-                "{}",
-                fmt_rinex(
-                    &format!(
-                        "{:14.4}{:14.4}{:14.4}",
-                        antenna.height.unwrap_or(0.0),
-                        antenna.eastern.unwrap_or(0.0),
-                        antenna.northern.unwrap_or(0.0)
-                    ),
-                    "ANTENNA: DELTA H/E/N"
-                )
-            )?;
         }
         // RCVR
         if let Some(rcvr) = &self.rcvr {
@@ -2001,20 +2772,41 @@ impl std::fmt::Display for Header {
 
 impl Header {
     /*
-     * Macro to be used when marking Self as Merged file
+     * Pseudo standard FILE MERGE comment, stamped with the merge boundary
+     * in our canonical format: "%Y%m%d %H%M%S UTC".
+     * Sub-second boundaries are truncated, see Rinex::merge_boundaries()
      */
-    fn merge_comment(timestamp: Epoch) -> String {
-        let (y, m, d, hh, mm, ss, _) = timestamp.to_gregorian_utc();
+    pub(crate) fn merge_comment(boundary: Epoch) -> String {
+        let (y, m, d, hh, mm, ss, _) = boundary.to_gregorian_utc();
         format!(
-            "rustrnx-{:<11} FILE MERGE          {}{}{} {}{}{} {:x}",
+            "{:<20}{:<20}{:04}{:02}{:02} {:02}{:02}{:02} UTC",
+            format!("rustrnx-{}", env!("CARGO_PKG_VERSION")),
+            "FILE MERGE",
+            y,
+            m,
+            d,
+            hh,
+            mm,
+            ss,
+        )
+    }
+    /*
+     * Pseudo standard comment, describing a repaired millisecond jump.
+     * Kept within the 60 byte comment field.
+     */
+    pub(crate) fn ms_jump_comment(epoch: Epoch, ms: i64) -> String {
+        let (y, m, d, hh, mm, ss, _) = epoch_decompose(epoch);
+        format!(
+            "rustrnx-{} MS JUMP {:+}ms {:04}{:02}{:02} {:02}{:02}{:02} {}",
             env!("CARGO_PKG_VERSION"),
+            ms,
             y,
             m,
             d,
             hh,
             mm,
             ss,
-            timestamp.time_scale
+            epoch.time_scale,
         )
     }
 }
@@ -2063,6 +2855,11 @@ impl Merge for Header {
         merge_mut_option(&mut self.geodetic_marker, &rhs.geodetic_marker);
         merge_mut_option(&mut self.license, &rhs.license);
         merge_mut_option(&mut self.doi, &rhs.doi);
+        // MERGED FILE counts the merged files (RINEX3), unflagged files count once
+        if self.version.major > 2 {
+            self.merged_files =
+                Some(self.merged_files.unwrap_or(1) + rhs.merged_files.unwrap_or(1));
+        }
         merge_mut_option(&mut self.leap, &rhs.leap);
         merge_mut_option(&mut self.gps_utc_delta, &rhs.gps_utc_delta);
         merge_mut_option(&mut self.rcvr, &rhs.rcvr);
@@ -2136,6 +2933,11 @@ impl Merge for Header {
                 merge_mut_unique_map2d(&mut lhs.codes, &rhs.codes);
                 merge_time_of_first_obs(&mut lhs.time_of_first_obs, &rhs.time_of_first_obs);
                 merge_time_of_last_obs(&mut lhs.time_of_last_obs, &rhs.time_of_last_obs);
+                for (t, change) in &rhs.equipment_changes {
+                    lhs.equipment_changes
+                        .entry(*t)
+                        .or_insert_with(|| change.clone());
+                }
                 // TODO: lhs.clock_offset_applied |= rhs.clock_offset_applied;
             }
         }
@@ -2188,10 +2990,6 @@ impl Merge for Header {
                 }
             }
         }
-        // add special comment
-        let now = Epoch::now()?;
-        let merge_comment = Self::merge_comment(now);
-        self.comments.push(merge_comment);
         Ok(())
     }
 }
@@ -2261,7 +3059,124 @@ impl HtmlReport for Header {
 
 #[cfg(test)]
 mod test {
-    use super::parse_formatted_month;
+    use super::{parse_formatted_month, Header, LABELS};
+    #[test]
+    fn header_labels() {
+        assert!(
+            LABELS.windows(2).all(|w| w[0] < w[1]),
+            "LABELS must be sorted"
+        );
+        for (marker, expected) in [
+            ("RINEX VERSION / TYPE", "RINEX VERSION / TYPE"),
+            ("  COMMENT   ", "COMMENT"),
+            ("E+00 ANALYSIS CLK REF", "ANALYSIS CLK REF"),
+            ("LEAP SECONDS GNSS", "LEAP SECONDS"),
+            ("UNKNOWN LABEL", "UNKNOWN LABEL"),
+            ("# TIME REF STATIONS", "TIME REF STATION"),
+            ("ION ALPHA ION BETA", "ION ALPHA"),
+            ("SYS / # / OBS TYPES X", "SYS / # / OBS TYPES"),
+        ] {
+            assert_eq!(Header::label(marker), expected);
+        }
+    }
+    /*
+     * Label identification matches the reference (linear) search
+     * on every header line of the fixture suite
+     */
+    #[test]
+    fn header_labels_equivalence() {
+        fn reference(marker: &str) -> &str {
+            let label = marker.trim();
+            match label {
+                "END OF HEADER" | "COMMENT" => label,
+                _ if LABELS.contains(&label) => label,
+                _ => LABELS
+                    .iter()
+                    .find(|known| label.contains(*known))
+                    .copied()
+                    .unwrap_or(label),
+            }
+        }
+        let mut dirs = vec![std::path::PathBuf::from(
+            env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources",
+        )];
+        let mut nb_lines = 0;
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let bytes = std::fs::read(&path).unwrap();
+                let content = String::from_utf8_lossy(&bytes);
+                for line in content.lines() {
+                    if let Some(marker) = line.get(60..) {
+                        assert_eq!(
+                            Header::label(marker),
+                            reference(marker),
+                            "{}: \"{}\"",
+                            path.display(),
+                            line
+                        );
+                        nb_lines += 1;
+                    }
+                }
+            }
+        }
+        assert!(nb_lines > 0);
+    }
+    #[test]
+    fn production_datetime() {
+        use crate::version::Version;
+        use hifitime::Epoch;
+        for (date, expected) in [
+            ("20220305 000026 UTC", (2022, 3, 5, 0, 0, 26)),
+            ("20210102 00:01:40UTC", (2021, 1, 2, 0, 1, 40)),
+            ("20170312  052227 U", (2017, 3, 12, 5, 22, 27)),
+            ("20-JAN-22 04:21", (2022, 1, 20, 4, 21, 0)),
+            ("3-APR-96 00:10", (1996, 4, 3, 0, 10, 0)),
+            ("21-Dec-21 00:59:45", (2021, 12, 21, 0, 59, 45)),
+            ("95/01/02 10:09:47", (1995, 1, 2, 10, 9, 47)),
+        ] {
+            let mut header = Header::default();
+            header.date = date.to_string();
+            let (y, m, d, hh, mm, ss) = expected;
+            assert_eq!(
+                header.production_datetime(),
+                Some(Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, 0)),
+                "{}",
+                date
+            );
+        }
+        for date in [
+            "",
+            "Unknown",
+            "20223005 000000 UTC",
+            "20220305 000026 LCL",
+            "20220305 00:00:26LCL",
+            "20220305 000026 CET",
+        ] {
+            let mut header = Header::default();
+            header.date = date.to_string();
+            assert!(header.production_datetime().is_none(), "{}", date);
+        }
+
+        // production
+        let header = Header::default()
+            .with_version(Version::new(3, 5))
+            .with_production_now();
+        let datetime = header.production_datetime().unwrap();
+        assert_eq!(header.date.len(), 19);
+        assert!(header.date.ends_with(" UTC"));
+        assert!((crate::epoch::now() - datetime).to_seconds() < 60.0);
+
+        let header = Header::default()
+            .with_version(Version::new(2, 11))
+            .with_production_now();
+        assert_eq!(header.date.len(), 15);
+        assert!(header.production_datetime().is_some());
+    }
     #[test]
     fn formatted_month_parser() {
         for (desc, expected) in [("Jan", 1), ("Feb", 2), ("Mar", 3), ("Nov", 11), ("Dec", 12)] {
@@ -48,5 +48,10 @@ mod test {
         let grid = Linspace::new(1.0, 10.0, 1.0).unwrap();
         assert_eq!(grid.length(), 10);
         assert!(!grid.is_single_point());
+        // regional grids
+        assert!(Linspace::new(72.5, 35.0, -2.5).is_ok());
+        assert!(Linspace::new(170.0, 190.0, 5.0).is_ok());
+        assert!(Linspace::new(72.5, 35.0, 2.5).is_err());
+        assert!(Linspace::new(72.5, 35.0, -4.0).is_err());
     }
 }
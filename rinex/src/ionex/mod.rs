@@ -76,6 +76,7 @@ pub struct HeaderFields {
     pub exponent: i8,
     /// Differential Code Biases (DBCs),
     /// per Vehicle #PRN, (Bias and RMS bias) values.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub dcbs: HashMap<BiasSource, (f64, f64)>,
 }
 
@@ -107,6 +108,18 @@ impl HeaderFields {
         s.reference = reference;
         s
     }
+    /// Copies and sets Epoch of first map
+    pub fn with_epoch_of_first_map(&self, epoch: Epoch) -> Self {
+        let mut s = self.clone();
+        s.epoch_of_first_map = epoch;
+        s
+    }
+    /// Copies and sets Epoch of last map
+    pub fn with_epoch_of_last_map(&self, epoch: Epoch) -> Self {
+        let mut s = self.clone();
+        s.epoch_of_last_map = epoch;
+        s
+    }
     /// Copies and sets exponent / scaling to currently use
     pub fn with_exponent(&self, e: i8) -> Self {
         let mut s = self.clone();
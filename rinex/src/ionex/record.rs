@@ -1,7 +1,9 @@
 use crate::{merge, merge::Merge, prelude::*, split, split::Split};
 
 use crate::epoch;
+use crate::{epoch::epoch_decompose, fmt_rinex, ionex::HeaderFields};
 use hifitime::Duration;
+use itertools::Itertools;
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use thiserror::Error;
@@ -50,8 +52,8 @@ pub type TECPlane = HashMap<(i32, i32), TEC>;
 ///     assert_eq!(params.grid.longitude.start, -180.0);
 ///     assert_eq!(params.grid.longitude.end, 180.0);
 ///     assert_eq!(params.grid.longitude.spacing, 5.0); // longitude granularity (degrees)
-///     assert_eq!(params.exponent, -1); // data scaling. May vary accross epochs.
-///                             // so this is only the last value encountered
+///     assert_eq!(params.exponent, -1); // data scaling. May be overwritten
+///                             // within each map
 ///     assert_eq!(params.elevation_cutoff, 0.0);
 ///     assert_eq!(params.mapping, None); // no mapping function
 /// }
@@ -102,7 +104,7 @@ pub(crate) fn parse_plane(
     // this can't fail at this point
     let ionex = header
         .ionex
-        .as_mut()
+        .as_ref()
         .expect("faulty ionex context: missing specific header definitions");
 
     // current {lat, lon} within current grid def.
@@ -110,6 +112,8 @@ pub(crate) fn parse_plane(
     let mut longitude = 0_i32;
     let mut altitude = 0_i32;
     let mut dlon = (ionex.grid.longitude.spacing * 1000.0) as i32;
+    // header scaling, possibly overwritten for this map only
+    let mut exponent = ionex.exponent;
 
     for line in lines {
         if line.len() > 60 {
@@ -163,19 +167,19 @@ pub(crate) fn parse_plane(
                     h.to_string(),
                 )))?;
 
-                altitude = (alt.round() * 100.0_f64) as i32;
-                latitude = (lat.round() * 1000.0_f64) as i32;
-                longitude = (lon1.round() * 1000.0_f64) as i32;
-                dlon = (dlon_f64.round() * 1000.0_f64) as i32;
+                altitude = (alt * 100.0_f64).round() as i32;
+                latitude = (lat * 1000.0_f64).round() as i32;
+                longitude = (lon1 * 1000.0_f64).round() as i32;
+                dlon = (dlon_f64 * 1000.0_f64).round() as i32;
 
                 // debug
                 // println!("NEW GRID : h: {} lat : {} lon : {}, dlon: {}", altitude, latitude, longitude, dlon);
             } else if marker.contains("EPOCH OF CURRENT MAP") {
                 epoch = epoch::parse_utc(content)?;
             } else if marker.contains("EXPONENT") {
-                // update current scaling
+                // current map scaling
                 if let Ok(e) = content.trim().parse::<i8>() {
-                    ionex.exponent = e;
+                    exponent = e;
                 }
             } else {
                 // parsing TEC values
@@ -183,7 +187,7 @@ pub(crate) fn parse_plane(
                     if let Ok(v) = item.trim().parse::<i32>() {
                         let mut value = v as f64;
                         // current scaling
-                        value *= 10.0_f64.powf(ionex.exponent as f64);
+                        value *= 10.0_f64.powf(exponent as f64);
 
                         let tec = match is_rms_plane {
                             true => {
@@ -213,7 +217,7 @@ pub(crate) fn parse_plane(
                 if let Ok(v) = item.trim().parse::<i32>() {
                     let mut value = v as f64;
                     // current scaling
-                    value *= 10.0_f64.powf(ionex.exponent as f64);
+                    value *= 10.0_f64.powf(exponent as f64);
 
                     let tec = match is_rms_plane {
                         true => {
@@ -240,6 +244,211 @@ pub(crate) fn parse_plane(
     Ok((epoch, altitude, plane))
 }
 
+/*
+ * Snaps [min, max] to the grid nodes that enclose it,
+ * clamped to the grid boundaries. Nodes must be sorted.
+ */
+#[cfg(feature = "ionex")]
+fn snap_bounds(nodes: &[i32], min: i32, max: i32) -> Option<(i32, i32)> {
+    let first = *nodes.first()?;
+    let last = *nodes.last()?;
+    let lower = nodes.iter().rev().find(|n| **n <= min).unwrap_or(&first);
+    let upper = nodes.iter().find(|n| **n >= max).unwrap_or(&last);
+    Some((*lower, *upper))
+}
+
+/*
+ * Retains grid nodes within the given latitude band [ddeg].
+ * Returns the (south, north) boundaries [mdeg] of the new grid.
+ */
+#[cfg(feature = "ionex")]
+pub(crate) fn retain_latitude_band_mut(
+    rec: &mut Record,
+    lat_min: f64,
+    lat_max: f64,
+) -> Option<(i32, i32)> {
+    let (min, max) = (lat_min.min(lat_max), lat_min.max(lat_max));
+    let nodes: Vec<i32> = rec
+        .values()
+        .flat_map(|plane| plane.keys().map(|(lat, _)| *lat))
+        .unique()
+        .sorted()
+        .collect();
+    let (south, north) = snap_bounds(
+        &nodes,
+        (min * 1000.0).round() as i32,
+        (max * 1000.0).round() as i32,
+    )?;
+    for plane in rec.values_mut() {
+        plane.retain(|(lat, _), _| *lat >= south && *lat <= north);
+    }
+    Some((south, north))
+}
+
+/*
+ * Retains grid nodes within the given longitude band [ddeg].
+ * When lon_min > lon_max, the band crosses the ±180° meridian:
+ * western nodes are then shifted by 360° so the grid remains contiguous.
+ * Returns the (west, east) boundaries [mdeg] of the new grid.
+ */
+#[cfg(feature = "ionex")]
+pub(crate) fn retain_longitude_band_mut(
+    rec: &mut Record,
+    lon_min: f64,
+    lon_max: f64,
+) -> Option<(i32, i32)> {
+    let nodes: Vec<i32> = rec
+        .values()
+        .flat_map(|plane| plane.keys().map(|(_, lon)| *lon))
+        .unique()
+        .sorted()
+        .collect();
+    let min = (lon_min * 1000.0).round() as i32;
+    let max = (lon_max * 1000.0).round() as i32;
+    if min <= max {
+        let (west, east) = snap_bounds(&nodes, min, max)?;
+        for plane in rec.values_mut() {
+            plane.retain(|(_, lon), _| *lon >= west && *lon <= east);
+        }
+        Some((west, east))
+    } else {
+        let (west, _) = snap_bounds(&nodes, min, min)?;
+        let (_, east) = snap_bounds(&nodes, max, max)?;
+        for plane in rec.values_mut() {
+            let wrapped: Vec<((i32, i32), TEC)> = plane
+                .iter()
+                .filter(|((_, lon), _)| *lon <= east)
+                .map(|((lat, lon), tec)| ((*lat, *lon + 360_000), tec.clone()))
+                .collect();
+            plane.retain(|(_, lon), _| *lon >= west);
+            for (coords, tec) in wrapped {
+                // the ±180° meridian may already be described
+                plane.entry(coords).or_insert(tec);
+            }
+        }
+        Some((west, east + 360_000))
+    }
+}
+
+/*
+ * Returns the exponent that maximizes the precision of given values,
+ * without overflowing the I5 data fields. 9999 is reserved
+ * for missing values. Returns `default` when no value is provided.
+ */
+pub(crate) fn best_exponent(values: impl Iterator<Item = f64>, default: i8) -> i8 {
+    let max = values.fold(0.0_f64, |max, v| max.max(v.abs()));
+    if max == 0.0 {
+        return default;
+    }
+    let mut exponent = -9_i8;
+    while exponent < 9 && (max / 10.0_f64.powi(exponent as i32)).round() > 9998.0 {
+        exponent += 1;
+    }
+    exponent
+}
+
+/*
+ * Formats one TEC map (or RMS map), at given Epoch,
+ * comprising all given planes. Values are scaled
+ * with the exponent that best suits this map, which is declared
+ * within the map when it differs from the header exponent.
+ */
+pub(crate) fn fmt_map(
+    index: usize,
+    epoch: Epoch,
+    planes: &[(i32, &TECPlane)],
+    header: &HeaderFields,
+    is_rms_map: bool,
+) -> String {
+    let map = if is_rms_map { "RMS" } else { "TEC" };
+    let exponent = best_exponent(
+        planes.iter().flat_map(|(_, plane)| {
+            plane.values().filter_map(move |tec| match is_rms_map {
+                true => tec.rms,
+                false => Some(tec.tec),
+            })
+        }),
+        header.exponent,
+    );
+    let scaling = 10.0_f64.powi(exponent as i32);
+    let mut lines = String::with_capacity(8192);
+
+    lines.push_str(&fmt_rinex(
+        &format!("{:6}", index),
+        &format!("START OF {} MAP", map),
+    ));
+    lines.push('\n');
+
+    let (y, m, d, hh, mm, ss, _) = epoch_decompose(epoch);
+    lines.push_str(&fmt_rinex(
+        &format!("{:6}{:6}{:6}{:6}{:6}{:6}", y, m, d, hh, mm, ss),
+        "EPOCH OF CURRENT MAP",
+    ));
+    lines.push('\n');
+
+    if exponent != header.exponent {
+        lines.push_str(&fmt_rinex(&format!("{:6}", exponent), "EXPONENT"));
+        lines.push('\n');
+    }
+
+    for (altitude, plane) in planes {
+        // follow grid definition
+        let latitudes = plane.keys().map(|(lat, _)| *lat).unique().sorted();
+        let latitudes: Vec<i32> = if header.grid.latitude.spacing < 0.0 {
+            latitudes.rev().collect()
+        } else {
+            latitudes.collect()
+        };
+        for latitude in latitudes {
+            let row: Vec<(i32, Option<f64>)> = plane
+                .iter()
+                .filter_map(|((lat, lon), tec)| {
+                    if *lat == latitude {
+                        let value = if is_rms_map { tec.rms } else { Some(tec.tec) };
+                        Some((*lon, value))
+                    } else {
+                        None
+                    }
+                })
+                .sorted_by_key(|(lon, _)| *lon)
+                .collect();
+
+            let lon1 = row.first().map(|(lon, _)| *lon).unwrap_or(0);
+            let lon2 = row.last().map(|(lon, _)| *lon).unwrap_or(0);
+            lines.push_str(&fmt_rinex(
+                &format!(
+                    "  {:6.1}{:6.1}{:6.1}{:6.1}{:6.1}",
+                    latitude as f64 / 1000.0,
+                    lon1 as f64 / 1000.0,
+                    lon2 as f64 / 1000.0,
+                    header.grid.longitude.spacing,
+                    *altitude as f64 / 100.0,
+                ),
+                "LAT/LON1/LON2/DLON/H",
+            ));
+            lines.push('\n');
+
+            for values in row.chunks(16) {
+                for (_, value) in values {
+                    let value = match value {
+                        Some(value) => (value / scaling).round() as i32,
+                        None => 9999, // "no value"
+                    };
+                    lines.push_str(&format!("{:5}", value));
+                }
+                lines.push('\n');
+            }
+        }
+    }
+
+    lines.push_str(&fmt_rinex(
+        &format!("{:6}", index),
+        &format!("END OF {} MAP", map),
+    ));
+    lines.push('\n');
+    lines
+}
+
 impl Merge for Record {
     /// Merges `rhs` into `Self` without mutable access at the expense of more memcopies
     fn merge(&self, rhs: &Self) -> Result<Self, merge::Error> {
@@ -350,12 +559,70 @@ impl Preprocessing for Record {
         match f {
             Filter::Mask(mask) => self.mask_mut(mask),
             Filter::Smoothing(_) => todo!(),
-            Filter::Decimation(_) => todo!(),
+            // decimation applies to entire maps
+            Filter::Decimation(filter) => match filter.dtype {
+                DecimationType::DecimByRatio(r) => self.decimate_by_ratio_mut(r),
+                DecimationType::DecimByInterval(dt) => self.decimate_by_interval_mut(dt),
+            },
             Filter::Interp(filter) => self.interpolate_mut(filter.series),
         }
     }
 }
 
+#[cfg(feature = "processing")]
+impl Decimate for Record {
+    fn decimate_by_ratio_mut(&mut self, r: u32) {
+        // all planes of a given map share the same epoch
+        let mut i = 0;
+        let mut last_epoch = Option::<Epoch>::None;
+        let mut retained = false;
+        self.retain(|(e, _), _| {
+            if last_epoch != Some(*e) {
+                retained = (i % r.max(1)) == 0;
+                last_epoch = Some(*e);
+                i += 1;
+            }
+            retained
+        });
+    }
+    fn decimate_by_ratio(&self, r: u32) -> Self {
+        let mut s = self.clone();
+        s.decimate_by_ratio_mut(r);
+        s
+    }
+    fn decimate_by_interval_mut(&mut self, interval: Duration) {
+        let mut last_retained = Option::<Epoch>::None;
+        self.retain(|(e, _), _| {
+            if let Some(last) = last_retained {
+                if *e == last {
+                    true // other planes of retained map
+                } else if *e - last >= interval {
+                    last_retained = Some(*e);
+                    true
+                } else {
+                    false
+                }
+            } else {
+                last_retained = Some(*e);
+                true // always retain 1st map
+            }
+        });
+    }
+    fn decimate_by_interval(&self, interval: Duration) -> Self {
+        let mut s = self.clone();
+        s.decimate_by_interval_mut(interval);
+        s
+    }
+    fn decimate_match_mut(&mut self, rhs: &Self) {
+        self.retain(|(e, _), _| rhs.keys().any(|(rhs_e, _)| rhs_e == e));
+    }
+    fn decimate_match(&self, rhs: &Self) -> Self {
+        let mut s = self.clone();
+        s.decimate_match_mut(rhs);
+        s
+    }
+}
+
 #[cfg(feature = "processing")]
 impl Interpolate for Record {
     fn interpolate(&self, series: TimeSeries) -> Self {
@@ -389,6 +656,18 @@ mod test {
         //     true
         // );
     }
+    #[test]
+    fn exponent_selection() {
+        // 0.1 TECU resolution on typical values: one more digit fits
+        assert_eq!(best_exponent([12.3, 85.4, 0.0].into_iter(), -1), -2);
+        assert_eq!(best_exponent([99.98].into_iter(), -1), -2);
+        assert_eq!(best_exponent([99.99].into_iter(), -1), -1);
+        assert_eq!(best_exponent([-45.6, 1234.5].into_iter(), -1), 0);
+        assert_eq!(best_exponent([123456.0].into_iter(), -1), 2);
+        // null or missing values: header exponent is preserved
+        assert_eq!(best_exponent([0.0].into_iter(), -1), -1);
+        assert_eq!(best_exponent(std::iter::empty(), 0), 0);
+    }
     //#[test]
     //fn test_merge_map2d() {
     //}
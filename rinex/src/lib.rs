@@ -12,6 +12,7 @@ pub mod cospar;
 pub mod domes;
 pub mod doris;
 pub mod epoch;
+pub mod formatter;
 pub mod gnss_time;
 pub mod hardware;
 pub mod hatanaka;
@@ -29,11 +30,20 @@ pub mod version;
 
 mod bibliography;
 mod constants;
+mod gaps;
 mod ground_position;
 mod leap; // leap second
 mod linspace; // grid and linear spacing
 mod observable;
 mod production; // RINEX production infrastructure // physical observations
+mod validation;
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docrs, doc(cfg(feature = "serde")))]
+pub mod metadata;
+
+#[cfg(feature = "serde")]
+mod serde_map;
 
 #[cfg(test)]
 mod tests;
@@ -55,7 +65,7 @@ use reader::BufferedReader;
 pub mod writer;
 use writer::BufferedWriter;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::Write; //, Read};
 use std::path::Path;
 use std::str::FromStr;
@@ -77,10 +87,22 @@ use production::{DataSource, DetailedProductionAttributes, ProductionAttributes,
 use hifitime::Unit;
 //use hifitime::{efmt::Format as EpochFormat, efmt::Formatter as EpochFormatter, Duration, Unit};
 
-/// Package to include all basic structures
+/// Package to include all basic structures.
+/// Record and header fields of each RINEX type are exposed
+/// under a type specific alias, for example [prelude::ObservationRecord].
+/// ```
+/// use rinex::prelude::*;
+/// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// assert_eq!(rnx.header.version, Version::new(3, 2));
+/// let record: &ObservationRecord = rnx.record.as_obs()
+///     .unwrap();
+/// assert_eq!(Carrier::L1.frequency_mhz(), 1575.42);
+/// ```
 pub mod prelude {
     #[cfg(feature = "antex")]
     pub use crate::antex::AntennaMatcher;
+    pub use crate::carrier::Carrier;
     #[cfg(feature = "clock")]
     pub use crate::clock::{ClockKey, ClockProfile, ClockProfileType, ClockType, WorkClock};
     #[cfg(feature = "sp3")]
@@ -91,11 +113,32 @@ pub mod prelude {
     pub use crate::doris::Station;
     pub use crate::ground_position::GroundPosition;
     pub use crate::header::Header;
-    pub use crate::observable::Observable;
-    pub use crate::observation::EpochFlag;
+    pub use crate::merge::{Error as MergeError, Merge};
+    pub use crate::navigation::Ephemeris;
+    pub use crate::observable::{ObsCode, ObsPhysics, Observable};
+    #[cfg(feature = "obs")]
+    pub use crate::observation::{Combination, Combine, Dcb};
+    pub use crate::observation::{EpochFlag, LliFlags, ObservationData, Ssi, SNR};
+    #[cfg(feature = "processing")]
+    pub use crate::preprocessing::*;
+    pub use crate::prod::{
+        DataSource, DetailedProductionAttributes, ProductionAttributes, FFU, PPU,
+    };
+    pub use crate::record::Record as RinexRecord;
+    pub use crate::split::{Error as SplitError, Split};
     pub use crate::types::Type as RinexType;
+    pub use crate::validation::FormattingError;
+    pub use crate::version::Version;
     pub use crate::Error;
     pub use crate::Rinex;
+    // type specific records and header fields
+    pub use crate::antex::{HeaderFields as AntexHeader, Record as AntexRecord};
+    pub use crate::clock::{HeaderFields as ClockHeader, Record as ClockRecord};
+    pub use crate::doris::{HeaderFields as DorisHeader, Record as DorisRecord};
+    pub use crate::ionex::{HeaderFields as IonexHeader, Record as IonexRecord};
+    pub use crate::meteo::{HeaderFields as MeteoHeader, Record as MeteoRecord};
+    pub use crate::navigation::Record as NavRecord;
+    pub use crate::observation::{HeaderFields as ObservationHeader, Record as ObservationRecord};
     pub use gnss::prelude::Constellation;
     pub use gnss::prelude::SV;
     pub use hifitime::{Duration, Epoch, TimeScale, TimeSeries};
@@ -130,8 +173,13 @@ use carrier::Carrier;
 use prelude::*;
 
 pub use merge::Merge;
+use merge::{MergeConflict, MergeRecord, MergeStrategy};
+pub use observation::{LliFlags, Ssi};
 pub use split::Split;
 
+pub use gaps::GapAnalysis;
+pub use validation::{FormattingError, ValidationIssue};
+
 #[cfg(feature = "serde")]
 #[macro_use]
 extern crate serde;
@@ -231,8 +279,7 @@ pub(crate) fn fmt_comment(content: &str) -> String {
 ///     // Do something
 /// }
 /// // comments encountered in file body
-/// // are currently stored like this and indexed by epoch of "appearance"
-/// // they are currently not really exploited
+/// // are stored like this and indexed by the epoch they precede
 /// for (epoch, comment) in rnx.comments {
 ///     println!("{:?}: \"{:?}\"", epoch, comment);
 /// }
@@ -244,6 +291,8 @@ pub struct Rinex {
     /// found in `record` section exclusively.    
     /// Comments extracted from `header` sections are exposed in `header.comments`
     pub comments: record::Comments,
+    /// `trailing_comments` : comments found after the last epoch of the `record`
+    pub trailing_comments: Vec<String>,
     /// `record` contains `RINEX` file body
     /// and is type and constellation dependent
     pub record: record::Record,
@@ -252,6 +301,10 @@ pub struct Rinex {
      * parsed from files that follow stadard naming conventions
      */
     prod_attr: Option<ProductionAttributes>,
+    /*
+     * Non fatal issues encountered while parsing
+     */
+    warnings: Vec<record::ParsingWarning>,
 }
 
 #[derive(Error, Debug)]
@@ -263,6 +316,19 @@ pub enum Error {
     RecordError(#[from] record::Error),
     #[error("file i/o error")]
     IoError(#[from] std::io::Error),
+    #[error("carrier error: {0}")]
+    CarrierError(#[from] carrier::Error),
+    #[error("{0} RINEX cannot be converted to revision {1}")]
+    UnsupportedConversion(types::Type, Version),
+    #[error("observables cannot be represented in RINEX {0}: {1}")]
+    ObservableConversion(Version, String),
+    #[error("formatting error")]
+    FormattingError(#[from] FormattingError),
+    #[error("{0} RINEX does not contain epoch flags")]
+    NoEpochFlags(types::Type),
+    #[cfg(feature = "plot")]
+    #[error("plot rendering error: {0}")]
+    PlotError(String),
 }
 
 impl Rinex {
@@ -272,7 +338,9 @@ impl Rinex {
             header,
             record,
             comments: record::Comments::new(),
+            trailing_comments: Vec::new(),
             prod_attr: None,
+            warnings: Vec::new(),
         }
     }
     /// Returns a copy of self with given header attributes.
@@ -281,9 +349,24 @@ impl Rinex {
             header,
             record: self.record.clone(),
             comments: self.comments.clone(),
+            trailing_comments: self.trailing_comments.clone(),
             prod_attr: self.prod_attr.clone(),
+            warnings: self.warnings.clone(),
         }
     }
+    /// Returns the non fatal issues encountered while parsing this file.
+    /// For example, [record::ParsingWarning::TruncatedRecord] is reported
+    /// when the file was cut short (interrupted download..): the record
+    /// then only contains the complete epochs.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// assert!(rnx.warnings().is_empty());
+    /// ```
+    pub fn warnings(&self) -> &[record::ParsingWarning] {
+        &self.warnings
+    }
     /// Replaces header section.
     pub fn replace_header(&mut self, header: Header) {
         self.header = header.clone();
@@ -293,10 +376,42 @@ impl Rinex {
         Rinex {
             header: self.header.clone(),
             comments: self.comments.clone(),
+            trailing_comments: self.trailing_comments.clone(),
             record,
             prod_attr: self.prod_attr.clone(),
+            warnings: self.warnings.clone(),
         }
     }
+    /// Returns the [ProductionAttributes] attached to Self, either identified
+    /// in the original filename or attached with [Self::with_production_attributes].
+    pub fn production_attributes(&self) -> Option<&ProductionAttributes> {
+        self.prod_attr.as_ref()
+    }
+    /// Returns a copy of self with given [ProductionAttributes] attached.
+    /// They superceed the attributes identified in the original filename,
+    /// and are used by [Self::standard_filename]: this is how the fields
+    /// that cannot be deduced from the content (country code, data source..)
+    /// are supplied.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::prod::DataSource;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let mut attributes = rinex.guess_production_attributes();
+    /// if let Some(details) = attributes.details.as_mut() {
+    ///     details.country = "GRC".to_string();
+    ///     details.data_src = DataSource::Stream;
+    /// }
+    /// let rinex = rinex.with_production_attributes(attributes);
+    /// assert_eq!(
+    ///     rinex.standard_filename(false, None, None, None),
+    ///     "DUTH00GRC_S_20220630000_01H_29M_MO.rnx");
+    /// ```
+    pub fn with_production_attributes(&self, attributes: ProductionAttributes) -> Self {
+        let mut s = self.clone();
+        s.prod_attr = Some(attributes);
+        s
+    }
     /// Replaces internal record.
     pub fn replace_record(&mut self, record: record::Record) {
         self.record = record.clone();
@@ -383,17 +498,193 @@ impl Rinex {
     /// [Rinex::crnx2rnx] mutable implementation
     pub fn crnx2rnx_mut(&mut self) {
         if self.is_observation_rinex() {
-            let params = self.header.obs.as_ref().unwrap();
-            self.header = self
-                .header
-                .with_observation_fields(observation::HeaderFields {
-                    crinex: None,
-                    codes: params.codes.clone(),
-                    clock_offset_applied: params.clock_offset_applied,
-                    scaling: params.scaling.clone(),
-                    time_of_first_obs: params.time_of_first_obs,
-                    time_of_last_obs: params.time_of_last_obs,
-                });
+            if let Some(obs) = self.header.obs.as_mut() {
+                obs.crinex = None;
+            }
+        }
+    }
+    /// Converts this Observation RINEX to given revision.
+    /// Observables are translated between V2 (two characters) and V3 (three characters) codes,
+    /// and epochs will be formatted according to the new revision.
+    /// V2 to V3 conversion assumes the most common tracking mode for each signal.
+    /// Conversions that cannot be represented (for example, several V3 signals
+    /// sharing a single V2 code, or signals that V2 cannot describe) are rejected,
+    /// the error lists the problematic codes. Declared observables that are never
+    /// observed are not converted.
+    /// See [Observable::to_v2] and [Observable::to_v3] for the code mapping.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::version::Version;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let v2 = rnx.convert_version(Version::new(2, 11))
+    ///     .unwrap();
+    /// assert_eq!(v2.header.version, Version::new(2, 11));
+    /// let observables = v2.observable().map(|obs| obs.to_string()).collect::<Vec<_>>();
+    /// assert!(observables.contains(&"C1".to_string())); // C1C
+    /// assert!(observables.contains(&"P2".to_string())); // C2W, C2P
+    /// ```
+    pub fn convert_version(&self, target: Version) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.convert_to_version_mut(target)?;
+        Ok(s)
+    }
+    /// [Self::convert_version] mutable implementation.
+    /// Self is not modified when the conversion is rejected.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::version::Version;
+    /// let original = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let mut rnx = original.clone();
+    /// rnx.convert_to_version_mut(Version::new(2, 11))
+    ///     .unwrap();
+    /// assert_eq!(rnx.header.version, Version::new(2, 11));
+    /// // common tracking modes are recovered
+    /// rnx.convert_to_version_mut(Version::new(3, 5))
+    ///     .unwrap();
+    /// assert_eq!(rnx.record, original.record);
+    /// ```
+    pub fn convert_to_version_mut(&mut self, target: Version) -> Result<(), Error> {
+        if !self.is_observation_rinex() || target.major < 2 || target.major > 4 {
+            return Err(Error::UnsupportedConversion(self.header.rinex_type, target));
+        }
+        let to_v2 = target.major < 3;
+        if to_v2 == (self.header.version.major < 3) {
+            // same observable and epoch formats
+            self.set_version_mut(target, to_v2);
+            return Ok(());
+        }
+
+        let (header, record) = match (self.header.obs.as_ref(), self.record.as_obs()) {
+            (Some(header), Some(record)) => (header, record),
+            _ => {
+                self.set_version_mut(target, to_v2);
+                return Ok(());
+            },
+        };
+
+        // signals actually observed
+        let observed: HashSet<(Constellation, Observable)> = record
+            .values()
+            .flat_map(|(_, vehicles)| {
+                vehicles.iter().flat_map(|(sv, observations)| {
+                    let constellation = match sv.constellation.is_sbas() {
+                        true => Constellation::SBAS,
+                        false => sv.constellation,
+                    };
+                    observations
+                        .keys()
+                        .map(move |obs| (constellation, obs.clone()))
+                })
+            })
+            .collect();
+
+        let mut issues: Vec<String> = Vec::new();
+        let mut codes: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        let mut mapping: HashMap<(Constellation, Observable), Observable> = HashMap::new();
+        for (constellation, observables) in header
+            .codes
+            .iter()
+            .sorted_by_key(|(constellation, _)| format!("{:x}", constellation))
+        {
+            let mut converted: Vec<Observable> = Vec::new();
+            for observable in observables {
+                let key = (*constellation, observable.clone());
+                if !observed.is_empty() && !observed.contains(&key) {
+                    continue;
+                }
+                let target = match to_v2 {
+                    true => observable.to_v2(*constellation),
+                    false => observable.to_v3(*constellation),
+                };
+                match target {
+                    Some(target) => {
+                        let conflict = mapping
+                            .iter()
+                            .find(|((c, _), code)| c == constellation && **code == target)
+                            .map(|((_, prev), _)| prev.clone());
+                        match conflict {
+                            Some(prev) => issues.push(format!(
+                                "{:x} {} and {} (both {})",
+                                constellation, prev, observable, target
+                            )),
+                            None => {
+                                mapping.insert(key, target.clone());
+                                converted.push(target);
+                            },
+                        }
+                    },
+                    None => issues.push(format!("{:x} {}", constellation, observable)),
+                }
+            }
+            if !converted.is_empty() {
+                codes.insert(*constellation, converted);
+            }
+        }
+        if !issues.is_empty() {
+            return Err(Error::ObservableConversion(target, issues.join(", ")));
+        }
+
+        // conversion is feasible: modify self
+        self.set_version_mut(target, to_v2);
+        let header = self.header.obs.as_mut().unwrap();
+        let record = self.record.as_mut_obs().unwrap();
+        if to_v2 {
+            // V2 describes a single list of observables
+            let mut observables: Vec<Observable> = Vec::new();
+            for (_, codes) in codes
+                .iter()
+                .sorted_by_key(|(constellation, _)| format!("{:x}", constellation))
+            {
+                for code in codes {
+                    if !observables.contains(code) {
+                        observables.push(code.clone());
+                    }
+                }
+            }
+            for codes in codes.values_mut() {
+                *codes = observables.clone();
+            }
+            // V2 does not support data scaling
+            observation::record::rescale_mut(record, &header.scaling_factors(), &HashMap::new());
+            header.scaling.clear();
+            header.data_scaling.clear();
+        }
+
+        for (_, (_, vehicles)) in record.iter_mut() {
+            for (sv, observations) in vehicles.iter_mut() {
+                let constellation = match sv.constellation.is_sbas() {
+                    true => Constellation::SBAS,
+                    false => sv.constellation,
+                };
+                *observations = observations
+                    .drain()
+                    .filter_map(|(observable, data)| {
+                        mapping
+                            .get(&(constellation, observable))
+                            .map(|target| (target.clone(), data))
+                    })
+                    .collect();
+            }
+        }
+        header.codes = codes;
+
+        if !header.prn_obs_counts.is_empty() {
+            self.rebuild_obs_counts_mut();
+        }
+        Ok(())
+    }
+    /*
+     * Updates RINEX (and possible CRINEX) revision, see convert_to_version_mut
+     */
+    fn set_version_mut(&mut self, target: Version, to_v2: bool) {
+        self.header.version = target;
+        if let Some(crinex) = self.header.obs.as_mut().and_then(|obs| obs.crinex.as_mut()) {
+            crinex.version = match to_v2 {
+                true => Version::new(1, 0),
+                false => Version::new(3, 0),
+            };
         }
     }
     /// Returns a filename that would describe Self according to standard naming conventions.
@@ -406,105 +697,169 @@ impl Rinex {
     /// and prefered shorter filenames (V2 like format): force short to "true".
     /// Otherwse, we will prefer modern V3 like formats.
     /// Use "suffix" to append a custom suffix like ".gz" for example.
+    /// Use "country" to specify the 3 letter country code of modern (long) filenames,
+    /// which superceeds the country code possibly identified in the original filename.
+    /// Otherwise, the country code and station numbers are picked up from the
+    /// 9 character station name (see [Header::station_9char]), if the MARKER NAME follows
+    /// this convention. When no country code is known, it is replaced by "XXX".
     /// NB this will only output uppercase filenames (as per standard specs).
+    ///
+    /// Production attributes are determined by order of priority:
+    ///   - from the "custom" attributes, if provided
+    ///   - from the attributes identified in the original filename
+    ///   - from the record content: Day of Year (DOY) of the first epoch,
+    ///     file period (PPU) from the first to last epoch time span,
+    ///     and data frequency (FFU) from the dominant sample rate.
+    ///
+    /// V2 like Navigation filenames use the standard constellation suffix:
+    /// 'N' (GPS), 'G' (Glonass), 'L' (Galileo), 'H' (SBAS) and 'P' (Mixed).
+    /// Clock RINEX use the IGS product naming conventions,
+    /// ANTEX files are simply named after the production attributes.
     /// ```
     /// use rinex::prelude::*;
     /// // Parse a File that follows standard naming conventions
     /// // and verify we generate something correct
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rinex.standard_filename(false, None, None, None),
+    ///     "AMEL00NLD_R_20210010000_01D_MN.rnx");
+    ///
+    /// // Short name does not contain the country code
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// assert_eq!(rinex.standard_filename(true, None, None, None), "DUTH0630.22O");
+    ///
+    /// // File period and data frequency are deduced from the record content
+    /// assert_eq!(
+    ///     rinex.standard_filename(false, None, None, Some("GRC")),
+    ///     "DUTH00GRC_R_20220630000_01H_29M_MO.rnx");
     /// ```
     pub fn standard_filename(
         &self,
         short: bool,
         suffix: Option<&str>,
         custom: Option<ProductionAttributes>,
+        country: Option<&str>,
     ) -> String {
         let header = &self.header;
         let rinextype = header.rinex_type;
         let is_crinex = header.is_crinex();
         let constellation = header.constellation;
 
+        // ANTEX records are not indexed by epoch
+        let (first_epoch, last_epoch, sample_rate) = match rinextype {
+            RinexType::AntennaData => (None, None, None),
+            _ => (
+                self.first_epoch(),
+                self.last_epoch(),
+                self.dominant_sample_rate(),
+            ),
+        };
+
+        // custom attributes superceed the attributes identified in the original filename
+        let attributes = custom.as_ref().or(self.prod_attr.as_ref());
+        let details = attributes.and_then(|attr| attr.details.as_ref());
+        // IGS 9 character station name, described by the MARKER NAME
+        let station = header.station_9char();
+
+        let name = match attributes {
+            Some(attr) => attr.name.clone(),
+            None => match rinextype {
+                RinexType::ClockData => header
+                    .clock
+                    .as_ref()
+                    .and_then(|clk| clk.igs.clone())
+                    .unwrap_or_else(|| "XXX".to_string()),
+                RinexType::IonosphereMaps => "XXX".to_string(),
+                _ => match &station {
+                    Some(station) => station.monument.clone(),
+                    None => "XXXX".to_string(),
+                },
+            },
+        };
+        // 3 letter agency code (IONEX, CLK)
+        let agency = name[..std::cmp::min(3, name.len())].to_string();
+
+        let date = match attributes {
+            Some(attr) => Some((attr.year as i32, attr.doy)),
+            None => first_epoch.map(|t| (epoch_decompose(t).0, t.day_of_year().floor() as u32)),
+        };
+        let (yyyy, yy, ddd) = match date {
+            Some((y, doy)) => (
+                format!("{:04}", y),
+                format!("{:02}", y % 100),
+                format!("{:03}", doy),
+            ),
+            None => ("YYYY".to_string(), "YY".to_string(), "DDD".to_string()),
+        };
+        let (hh, mm) = match details {
+            Some(details) => (format!("{:02}", details.hh), format!("{:02}", details.mm)),
+            None => match first_epoch {
+                Some(t) => {
+                    let (_, _, _, hh, mm, _, _) = epoch_decompose(t);
+                    (format!("{:02}", hh), format!("{:02}", mm))
+                },
+                None => ("HH".to_string(), "MM".to_string()),
+            },
+        };
+        // PPU periodicity
+        let ppu = match details.map(|details| details.ppu) {
+            Some(ppu) if ppu != PPU::Unspecified => ppu,
+            _ => match (first_epoch, last_epoch) {
+                (Some(first), Some(last)) => PPU::from(last - first),
+                _ => PPU::Unspecified,
+            },
+        };
+        // FFU sampling rate
+        let ffu = match sample_rate {
+            Some(duration) => FFU::from(duration).to_string(),
+            None => match details.and_then(|details| details.ffu) {
+                Some(ffu) => ffu.to_string(),
+                None => "XXX".to_string(),
+            },
+        };
+
         let mut filename = match rinextype {
             RinexType::IonosphereMaps => {
-                let name = match custom {
-                    Some(ref custom) => {
-                        custom.name[..std::cmp::min(3, custom.name.len())].to_string()
-                    },
-                    None => {
-                        if let Some(attr) = &self.prod_attr {
-                            attr.name.clone()
-                        } else {
-                            "XXX".to_string()
-                        }
-                    },
-                };
-                let region = match &custom {
-                    Some(ref custom) => custom.region.unwrap_or('G'),
-                    None => {
-                        if let Some(attr) = &self.prod_attr {
-                            attr.region.unwrap_or('G')
-                        } else {
-                            'G'
-                        }
-                    },
-                };
-                let ddd = match &custom {
-                    Some(ref custom) => format!("{:03}", custom.doy),
-                    None => {
-                        if let Some(epoch) = self.first_epoch() {
-                            let ddd = epoch.day_of_year().round() as u32;
-                            format!("{:03}", ddd)
-                        } else {
-                            "DDD".to_string()
-                        }
-                    },
-                };
-                let yy = match &custom {
-                    Some(ref custom) => format!("{:02}", custom.year - 2_000),
-                    None => {
-                        if let Some(epoch) = self.first_epoch() {
-                            let yy = epoch_decompose(epoch).0;
-                            format!("{:02}", yy - 2_000)
-                        } else {
-                            "YY".to_string()
-                        }
-                    },
-                };
-                ProductionAttributes::ionex_format(&name, region, &ddd, &yy)
+                let region = attributes.and_then(|attr| attr.region).unwrap_or('G');
+                ProductionAttributes::ionex_format(&agency, region, &ddd, &yy)
             },
-            RinexType::ObservationData | RinexType::MeteoData | RinexType::NavigationData => {
-                let name = match custom {
-                    Some(ref custom) => custom.name.clone(),
-                    None => {
-                        if let Some(attr) = &self.prod_attr {
-                            attr.name.clone()
-                        } else {
-                            "XXXX".to_string()
-                        }
-                    },
-                };
-                let ddd = match &custom {
-                    Some(ref custom) => format!("{:03}", custom.doy),
-                    None => {
-                        if let Some(epoch) = self.first_epoch() {
-                            let ddd = epoch.day_of_year().round() as u32;
-                            format!("{:03}", ddd)
-                        } else {
-                            "DDD".to_string()
-                        }
-                    },
-                };
+            RinexType::ClockData => {
                 if short {
-                    let yy = match &custom {
-                        Some(ref custom) => format!("{:02}", custom.year - 2_000),
-                        None => {
-                            if let Some(epoch) = self.first_epoch() {
-                                let yy = epoch_decompose(epoch).0;
-                                format!("{:02}", yy - 2_000)
-                            } else {
-                                "YY".to_string()
-                            }
+                    // legacy IGS format: GPS week and day of week
+                    let wwwwd = match first_epoch {
+                        Some(t) => {
+                            let (week, nanos) = t.to_time_of_week();
+                            let dow = nanos / (86_400 * 1_000_000_000);
+                            format!("{:04}{}", week, dow)
                         },
+                        None => "WWWWD".to_string(),
+                    };
+                    ProductionAttributes::clock_short_format(&agency, &wwwwd)
+                } else {
+                    // project and solution type cannot be recovered from the content
+                    let name = if name.len() == 10 {
+                        name.clone()
+                    } else {
+                        format!("{}0XXXXXX", agency)
                     };
+                    ProductionAttributes::igs_long_format(
+                        &name,
+                        &yyyy,
+                        &ddd,
+                        &hh,
+                        &mm,
+                        &ppu.to_string(),
+                        &ffu,
+                        "CLK",
+                        "CLK",
+                    )
+                }
+            },
+            RinexType::AntennaData => ProductionAttributes::antex_format(&name),
+            RinexType::ObservationData | RinexType::MeteoData | RinexType::NavigationData => {
+                if short {
                     let ext = match rinextype {
                         RinexType::ObservationData => {
                             if is_crinex {
@@ -516,6 +871,9 @@ impl Rinex {
                         RinexType::MeteoData => 'M',
                         RinexType::NavigationData => match constellation {
                             Some(Constellation::Glonass) => 'G',
+                            Some(Constellation::Galileo) => 'L',
+                            Some(Constellation::Mixed) => 'P',
+                            Some(c) if c.is_sbas() => 'H',
                             _ => 'N',
                         },
                         _ => unreachable!("unreachable"),
@@ -523,105 +881,26 @@ impl Rinex {
                     ProductionAttributes::rinex_short_format(&name, &ddd, &yy, ext)
                 } else {
                     /* long /V3 like format */
-                    let batch = match &custom {
-                        Some(ref custom) => {
-                            if let Some(details) = &custom.details {
-                                details.batch
-                            } else {
-                                0
-                            }
-                        },
-                        None => {
-                            if let Some(attr) = &self.prod_attr {
-                                if let Some(details) = &attr.details {
-                                    details.batch
-                                } else {
-                                    0
-                                }
-                            } else {
-                                0
-                            }
-                        },
-                    };
-                    let country = match &custom {
-                        Some(ref custom) => {
-                            if let Some(details) = &custom.details {
-                                details.country.to_string()
-                            } else {
-                                "CCC".to_string()
-                            }
-                        },
-                        None => {
-                            if let Some(attr) = &self.prod_attr {
-                                if let Some(details) = &attr.details {
-                                    details.country.to_string()
-                                } else {
-                                    "CCC".to_string()
-                                }
-                            } else {
-                                "CCC".to_string()
-                            }
-                        },
-                    };
-                    let src = match &header.rcvr {
-                        Some(_) => 'R', // means GNSS rcvr
-                        None => {
-                            if let Some(attr) = &self.prod_attr {
-                                if let Some(details) = &attr.details {
-                                    details.data_src.to_char()
-                                } else {
-                                    'U' // means unspecified
-                                }
-                            } else {
-                                'U' // means unspecified
-                            }
-                        },
-                    };
-                    let yyyy = match &custom {
-                        Some(ref custom) => format!("{:04}", custom.year),
-                        None => {
-                            if let Some(epoch) = self.first_epoch() {
-                                let yy = epoch_decompose(epoch).0;
-                                format!("{:04}", yy)
-                            } else {
-                                "YYYY".to_string()
-                            }
+                    let batch = match (details, &station) {
+                        (Some(details), _) => details.batch,
+                        (None, Some(station)) => {
+                            station.marker_number * 10 + station.receiver_number
                         },
+                        (None, None) => 0,
                     };
-                    let (hh, mm) = match &custom {
-                        Some(ref custom) => {
-                            if let Some(details) = &custom.details {
-                                (format!("{:02}", details.hh), format!("{:02}", details.mm))
-                            } else {
-                                ("HH".to_string(), "MM".to_string())
-                            }
-                        },
-                        None => {
-                            if let Some(epoch) = self.first_epoch() {
-                                let (_, _, _, hh, mm, _, _) = epoch_decompose(epoch);
-                                (format!("{:02}", hh), format!("{:02}", mm))
-                            } else {
-                                ("HH".to_string(), "MM".to_string())
-                            }
+                    let country = match country {
+                        Some(country) => country.to_string(),
+                        None => match (details, &station) {
+                            (Some(details), _) => details.country.to_string(),
+                            (None, Some(station)) => station.country.clone(),
+                            (None, None) => "XXX".to_string(),
                         },
                     };
-                    // FFU sampling rate
-                    let ffu = match self.dominant_sample_rate() {
-                        Some(duration) => FFU::from(duration).to_string(),
-                        None => {
-                            if let Some(ref custom) = custom {
-                                if let Some(details) = &custom.details {
-                                    if let Some(ffu) = details.ffu {
-                                        ffu.to_string()
-                                    } else {
-                                        "XXX".to_string()
-                                    }
-                                } else {
-                                    "XXX".to_string()
-                                }
-                            } else {
-                                "XXX".to_string()
-                            }
+                    let src = match details.map(|details| details.data_src) {
+                        Some(src) if src != DataSource::Unknown => src.to_char(),
+                        _ => match &header.rcvr {
+                            Some(_) => 'R', // means GNSS rcvr
+                            None => 'U',    // means unspecified
                         },
                     };
                     // ffu only in OBS file names
@@ -629,29 +908,14 @@ impl Rinex {
                         RinexType::ObservationData => Some(ffu),
                         _ => None,
                     };
-                    // PPU periodicity
-                    let ppu = if let Some(ref custom) = custom {
-                        if let Some(details) = &custom.details {
-                            details.ppu
-                        } else {
-                            PPU::Unspecified
-                        }
-                    } else if let Some(ref attr) = self.prod_attr {
-                        if let Some(details) = &attr.details {
-                            details.ppu
-                        } else {
-                            PPU::Unspecified
-                        }
-                    } else {
-                        PPU::Unspecified
+                    let system = match constellation {
+                        Some(Constellation::Mixed) | None => "M".to_string(),
+                        Some(constell) => format!("{:x}", constell),
                     };
                     let fmt = match rinextype {
-                        RinexType::ObservationData => "MO".to_string(),
+                        RinexType::ObservationData => format!("{}O", system),
                         RinexType::MeteoData => "MM".to_string(),
-                        RinexType::NavigationData => match constellation {
-                            Some(Constellation::Mixed) | None => "MN".to_string(),
-                            Some(constell) => format!("M{:x}", constell),
-                        },
+                        RinexType::NavigationData => format!("{}N", system),
                         _ => unreachable!("unreachable fmt"),
                     };
                     let ext = if is_crinex { "crx" } else { "rnx" };
@@ -692,19 +956,19 @@ impl Rinex {
     /// let rinex = rinex.unwrap();
     ///
     /// // The standard filename generator has no means to generate something correct.
-    /// let standard_name = rinex.standard_filename(true, None, None);
+    /// let standard_name = rinex.standard_filename(true, None, None, None);
     /// assert_eq!(standard_name, "XXXX0070.21M");
     ///
     /// // Now use the smart attributes detector as custom attributes
     /// let guessed = rinex.guess_production_attributes();
-    /// let standard_name = rinex.standard_filename(true, None, Some(guessed.clone()));
+    /// let standard_name = rinex.standard_filename(true, None, Some(guessed.clone()), None);
     ///
     /// // Short name are always correctly determined
     /// assert_eq!(standard_name, "bako0070.21M");
     ///
     /// // Modern (lengthy) names have fields like the Country code that cannot be recovered
     /// // if the original file did not follow standard conventions itself.
-    /// let standard_name = rinex.standard_filename(false, None, Some(guessed.clone()));
+    /// let standard_name = rinex.standard_filename(false, None, Some(guessed.clone()), None);
     /// assert_eq!(standard_name, "bako00XXX_U_20210070000_00U_MM.rnx");
     /// ```
     pub fn guess_production_attributes(&self) -> ProductionAttributes {
@@ -720,7 +984,7 @@ impl Rinex {
             _ => {},
         }
         match first_epoch {
-            Some(t0) => attributes.doy = t0.day_of_year().round() as u32,
+            Some(t0) => attributes.doy = t0.day_of_year().floor() as u32,
             _ => {},
         }
         // notes on attribute."name"
@@ -746,8 +1010,9 @@ impl Rinex {
             RinexType::IonosphereMaps => {
                 attributes.name = self.header.agency.to_string();
             },
-            _ => match &self.header.geodetic_marker {
-                Some(marker) => attributes.name = marker.name.to_string(),
+            _ => match (self.header.station_9char(), &self.header.geodetic_marker) {
+                (Some(station), _) => attributes.name = station.monument,
+                (None, Some(marker)) => attributes.name = marker.name.to_string(),
                 _ => attributes.name = self.header.agency.to_string(),
             },
         }
@@ -806,21 +1071,43 @@ impl Rinex {
 
     /// See [Self::from_file]
     pub fn from_path(path: &Path) -> Result<Rinex, Error> {
+        Self::from_path_with_max_magnitude(path, observation::MAX_MAGNITUDE)
+    }
+
+    /// Same as [Self::from_file], but Observation RINEX values whose
+    /// magnitude exceeds `max_magnitude` are dropped while parsing,
+    /// instead of [observation::MAX_MAGNITUDE]. Non finite values are always dropped.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file_with_max_magnitude("../test_resources/OBS/V3/DUTH0630.22O", 1.0E8)
+    ///     .unwrap();
+    /// // phase observations [cycles] exceed 1.0E8 in this file
+    /// let l1c = Observable::from_str("L1C").unwrap();
+    /// let record = rinex.record.as_obs()
+    ///     .unwrap();
+    /// assert!(record.values().all(|(_, vehicles)| {
+    ///     vehicles.values().all(|observations| !observations.contains_key(&l1c))
+    /// }));
+    /// ```
+    pub fn from_file_with_max_magnitude(
+        fullpath: &str,
+        max_magnitude: f64,
+    ) -> Result<Rinex, Error> {
+        Self::from_path_with_max_magnitude(Path::new(fullpath), max_magnitude)
+    }
+
+    /// See [Self::from_file_with_max_magnitude]
+    pub fn from_path_with_max_magnitude(path: &Path, max_magnitude: f64) -> Result<Rinex, Error> {
         let fullpath = path.to_string_lossy().to_string();
 
         // create buffered reader
         let mut reader = BufferedReader::new(&fullpath)?;
-
-        // Parse header fields
-        let mut header = Header::new(&mut reader)?;
-
-        // Parse file body (record content)
-        // Comments might serve some fileops like "splice".
-        let (record, comments) = record::parse_record(&mut reader, &mut header)?;
+        let mut rinex = Self::parse(&mut reader, max_magnitude)?;
 
         // Parse / identify production attributes
         // that only exist in the filename.
-        let prod_attr = match path.file_name() {
+        rinex.prod_attr = match path.file_name() {
             Some(filename) => {
                 let filename = filename.to_string_lossy().to_string();
                 if let Ok(attrs) = ProductionAttributes::from_str(&filename) {
@@ -831,23 +1118,117 @@ impl Rinex {
             },
             _ => None,
         };
+        Ok(rinex)
+    }
+
+    /// Parses a RINEX from any [std::io::Read]able stream, for example
+    /// content received over the network or already loaded in memory.
+    /// gzip compressed content is identified by its magic bytes,
+    /// and CRINEX is decompressed on the fly, like [Self::from_file].
+    /// There is no filename to deduce production attributes from,
+    /// see [Self::guess_production_attributes].
+    /// ```
+    /// use rinex::prelude::*;
+    /// let content = std::fs::read("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let rinex = Rinex::from_reader(content.as_slice())
+    ///     .unwrap();
+    /// assert_eq!(rinex.epoch().count(), 3);
+    /// ```
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Rinex, Error> {
+        let mut reader = BufferedReader::from_reader(reader)?;
+        Self::parse(&mut reader, observation::MAX_MAGNITUDE)
+    }
+
+    /*
+     * Parses header and record from given reader
+     */
+    fn parse(reader: &mut BufferedReader, max_magnitude: f64) -> Result<Rinex, Error> {
+        // Parse header fields
+        let mut header = Header::new(reader)?;
 
-        Ok(Rinex {
+        // Parse file body (record content)
+        // Comments might serve some fileops like "splice".
+        let (record, comments, trailing_comments, truncated) =
+            record::parse_record_with_truncation(reader, &mut header, max_magnitude)?;
+
+        let mut rinex = Rinex {
             header,
             record,
             comments,
-            prod_attr,
-        })
+            trailing_comments,
+            prod_attr: None,
+            warnings: Vec::new(),
+        };
+        if truncated {
+            // file was cut short: complete epochs are preserved
+            let last_good_epoch = rinex.last_epoch();
+            rinex
+                .warnings
+                .push(record::ParsingWarning::TruncatedRecord { last_good_epoch });
+        }
+        Ok(rinex)
     }
 
-    /// Returns true if this is an ATX RINEX
-    pub fn is_antex(&self) -> bool {
-        self.header.rinex_type == types::Type::AntennaData
-    }
+    /// Parses several files concurrently, using a small pool of threads
+    /// (one per available core). Results are returned in `paths` order
+    /// and are identical to [Self::from_file] on each path.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let results = Rinex::from_files(&[
+    ///     "../test_resources/OBS/V3/DUTH0630.22O",
+    ///     "../test_resources/MET/V2/abvi0010.15m",
+    ///     "not-a-file.22O",
+    /// ]);
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results[0].as_ref().unwrap().is_observation_rinex());
+    /// assert!(results[1].as_ref().unwrap().is_meteo_rinex());
+    /// assert!(results[2].is_err());
+    /// ```
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(docrs, doc(cfg(feature = "parallel")))]
+    pub fn from_files(paths: &[&str]) -> Vec<Result<Rinex, Error>> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let next = AtomicUsize::new(0);
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(paths.len());
 
-    /// Returns true if this is a CLOCK RINEX
-    pub fn is_clock_rinex(&self) -> bool {
-        self.header.rinex_type == types::Type::ClockData
+        let mut results: Vec<Option<Result<Rinex, Error>>> = paths.iter().map(|_| None).collect();
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut parsed = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            if index >= paths.len() {
+                                break;
+                            }
+                            parsed.push((index, Self::from_file(paths[index])));
+                        }
+                        parsed
+                    })
+                })
+                .collect();
+            for handle in handles {
+                for (index, result) in handle.join().expect("parsing thread panicked") {
+                    results[index] = Some(result);
+                }
+            }
+        });
+        results.into_iter().flatten().collect()
+    }
+
+    /// Returns true if this is an ATX RINEX
+    pub fn is_antex(&self) -> bool {
+        self.header.rinex_type == types::Type::AntennaData
+    }
+
+    /// Returns true if this is a CLOCK RINEX
+    pub fn is_clock_rinex(&self) -> bool {
+        self.header.rinex_type == types::Type::ClockData
     }
 
     /// Returns true if Self is a IONEX
@@ -893,6 +1274,7 @@ impl Rinex {
     /// Generates a new RINEX = Self(=RINEX(A)) - RHS(=RINEX(B)).
     /// Therefore RHS is considered reference.
     /// This operation is typically used to compare two GNSS receivers.
+    /// Epochs are matched like [Self::common_epochs].
     /// Both RINEX formats must match otherwise this will panic.
     /// This is only available to Observation RINEX files.
     pub fn substract(&self, rhs: &Self) -> Self {
@@ -907,8 +1289,15 @@ impl Rinex {
             .as_obs()
             .expect("can only substract observation data");
 
+        // rhs timestamps are snapped to self
+        let matches = self.matching_epochs(rhs, self.epoch_tolerance());
+
         for ((epoch, flag), (clk, svnn)) in lhs_rec {
-            if let Some((ref_clk, ref_svnn)) = rhs_rec.get(&(*epoch, *flag)) {
+            let ref_epoch = match matches.get(epoch) {
+                Some(ref_epoch) => *ref_epoch,
+                None => continue,
+            };
+            if let Some((ref_clk, ref_svnn)) = rhs_rec.get(&(ref_epoch, *flag)) {
                 for (sv, observables) in svnn {
                     if let Some(ref_observables) = ref_svnn.get(sv) {
                         for (observable, observation) in observables {
@@ -967,83 +1356,864 @@ impl Rinex {
             }
         }
 
-        Rinex::new(self.header.clone(), record::Record::ObsRecord(record))
+        Rinex::new(self.header.clone(), record::Record::ObsRecord(record))
+    }
+
+    /// Returns true if Differential Code Biases (DCBs)
+    /// are compensated for, in this file, for this GNSS constellation.
+    /// DCBs are biases due to tiny frequency differences,
+    /// in both the SV embedded code generator, and receiver PLL.
+    /// If this is true, that means all code signals received in from
+    /// all SV within that constellation, have intrinsinc DCB compensation.
+    /// In very high precision and specific applications, you then do not have
+    /// to deal with their compensation yourself.
+    pub fn dcb_compensation(&self, constellation: Constellation) -> bool {
+        self.header
+            .dcb_compensations
+            .iter()
+            .filter(|dcb| dcb.constellation == constellation)
+            .count()
+            > 0
+    }
+    /// Returns true if Antenna Phase Center variations are compensated
+    /// for in this file. Useful for high precision application.
+    pub fn pcv_compensation(&self, constellation: Constellation) -> bool {
+        self.header
+            .pcv_compensations
+            .iter()
+            .filter(|pcv| pcv.constellation == constellation)
+            .count()
+            > 0
+    }
+    /// Returns `true` if self is a `merged` RINEX file,   
+    /// meaning, this file is the combination of two RINEX files merged together.  
+    /// This is determined by the presence of a custom yet somewhat standardized `FILE MERGE` comments
+    pub fn is_merged(&self) -> bool {
+        let special_comment = String::from("FILE MERGE");
+        for comment in self.header.comments.iter() {
+            if comment.contains(&special_comment) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the list of merging operations described by
+    /// the `FILE MERGE` header comments, in order of appearance.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/npaz3550.21o")
+    ///     .unwrap();
+    /// let info = rnx.merge_info();
+    /// assert_eq!(info.len(), 1);
+    /// assert_eq!(info[0].program, "gfzrnx-1.13-7761");
+    /// assert_eq!(info[0].date, Epoch::from_str("2021-12-22T03:30:32 UTC").unwrap());
+    /// ```
+    pub fn merge_info(&self) -> Vec<MergeRecord> {
+        self.header
+            .comments
+            .iter()
+            .filter_map(|comment| MergeRecord::from_comment(comment))
+            .collect()
+    }
+
+    /// Returns the [`Epoch`]s where merging operations occurred, in chronological order.
+    /// Those are the dates of [Self::merge_info] that fall within this record:
+    /// other programs usually stamp the processing date instead.
+    /// Merge boundaries are stamped with a 1 second resolution (sub-second
+    /// boundaries are truncated), each date is therefore rounded up to the first
+    /// epoch of this record within that second. Dates that do not match
+    /// any epoch are not returned.
+    /// Use this to [Split::split] files merged by this library.
+    pub fn merge_boundaries(&self) -> Vec<Epoch> {
+        if self.is_antex() {
+            return Vec::new();
+        }
+        let first = match self.first_epoch() {
+            Some(first) => first,
+            _ => return Vec::new(),
+        };
+        let epochs: Vec<Epoch> = self.epoch().unique().sorted().collect();
+        self.merge_info()
+            .iter()
+            .filter_map(|info| {
+                let t = info.date.to_time_scale(first.time_scale);
+                let index = epochs.partition_point(|e| *e < t);
+                epochs
+                    .get(index)
+                    .filter(|e| **e - t < Duration::from_seconds(1.0))
+                    .copied()
+            })
+            .filter(|t| *t > first)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Returns the receiver and antenna changes that occurred during this
+    /// Observation RINEX session, in chronological order.
+    /// Those are described by the header lines following epochs flagged
+    /// [observation::EpochFlag::HeaderInformationFollows] or
+    /// [observation::EpochFlag::NewSiteOccupation].
+    /// Merging files whose headers describe different equipment,
+    /// without such event, introduces a change at the merge boundary.
+    pub fn equipment_changes(&self) -> Vec<(Epoch, observation::EquipmentChange)> {
+        match &self.header.obs {
+            Some(obs) => obs
+                .equipment_changes
+                .iter()
+                .map(|(t, change)| (*t, change.clone()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the sorted list of [`Observable`]s that are both declared
+    /// in the header and actually encountered in the record.
+    /// Only applies to Observation and Meteo RINEX, returns an empty list otherwise.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// let observables = rnx.observables();
+    /// assert!(observables.contains(&Observable::Temperature));
+    /// ```
+    pub fn observables(&self) -> Vec<Observable> {
+        let declared: Vec<&Observable> = if let Some(obs) = &self.header.obs {
+            obs.codes.values().flatten().collect()
+        } else if let Some(meteo) = &self.header.meteo {
+            meteo.codes.iter().collect()
+        } else {
+            return Vec::new();
+        };
+        self.observable()
+            .filter(|observable| declared.contains(observable))
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    /// Returns the list of observables declared in the header, per [`Constellation`],
+    /// in header order. Unlike [Self::observables], the record content is not considered.
+    /// Meteo RINEX only describe a single list, which is keyed under [`Constellation::Mixed`].
+    /// Returns an empty map for other RINEX types.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let observables = rnx.observables_per_constellation();
+    /// assert_eq!(observables[&Constellation::GPS][0], "C1C");
+    ///
+    /// let rnx = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// let observables = rnx.observables_per_constellation();
+    /// assert_eq!(observables[&Constellation::Mixed].len(), 7);
+    /// ```
+    pub fn observables_per_constellation(&self) -> HashMap<Constellation, Vec<String>> {
+        if let Some(obs) = &self.header.obs {
+            obs.codes
+                .iter()
+                .map(|(constellation, codes)| {
+                    (
+                        *constellation,
+                        codes.iter().map(|c| c.to_string()).collect(),
+                    )
+                })
+                .collect()
+        } else if let Some(meteo) = &self.header.meteo {
+            let codes = meteo.codes.iter().map(|c| c.to_string()).collect();
+            HashMap::from([(Constellation::Mixed, codes)])
+        } else {
+            HashMap::new()
+        }
+    }
+
+    /// Returns the sorted list of [`Constellation`]s encountered in the record.
+    /// For Mixed RINEX, this is derived from the record content and not
+    /// from the header description.
+    /// Observation, Navigation and Clock RINEX are described by their vehicles.
+    /// Meteo RINEX do not contain vehicles: the header constellation is used,
+    /// if any. Returns an empty list otherwise.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rnx.constellations(),
+    ///     vec![
+    ///         Constellation::GPS,
+    ///         Constellation::Glonass,
+    ///         Constellation::BeiDou,
+    ///         Constellation::Galileo,
+    ///     ],
+    /// );
+    /// ```
+    pub fn constellations(&self) -> Vec<Constellation> {
+        if self.is_meteo_rinex() {
+            return match self.header.constellation {
+                Some(Constellation::Mixed) | None => Vec::new(),
+                Some(c) => vec![c],
+            };
+        }
+        self.space_vehicules()
+            .into_iter()
+            .map(|sv| sv.constellation)
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Returns the sorted and deduplicated list of [`SV`]s encountered in the record.
+    /// Only applies to Observation, Navigation and Clock RINEX,
+    /// returns an empty list otherwise.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// assert!(rnx.space_vehicules().is_empty());
+    /// ```
+    pub fn space_vehicules(&self) -> Vec<SV> {
+        if self.record.as_obs().is_some()
+            || self.record.as_nav().is_some()
+            || self.record.as_clock().is_some()
+        {
+            self.sv().sorted().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /*
+     * Default tolerance when comparing epochs of two RINEX:
+     * half the sampling interval, exact match when it is unknown.
+     */
+    fn epoch_tolerance(&self) -> Duration {
+        self.header
+            .sampling_interval
+            .or_else(|| self.dominant_sample_rate())
+            .map(|dt| dt * 0.5)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /*
+     * Snaps each epoch of self to the nearest `rhs` epoch, within tolerance.
+     * Self epochs without counterpart are not listed.
+     */
+    fn matching_epochs(&self, rhs: &Self, tolerance: Duration) -> BTreeMap<Epoch, Epoch> {
+        let rhs: Vec<Epoch> = rhs.epoch().unique().sorted().collect();
+        self.epoch()
+            .filter_map(|t| {
+                let nearest = match rhs.binary_search(&t) {
+                    Ok(i) => Some(rhs[i]),
+                    Err(i) => [i.checked_sub(1), Some(i)]
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|i| rhs.get(i))
+                        .min_by_key(|e| (**e - t).abs())
+                        .copied(),
+                };
+                nearest
+                    .filter(|e| (*e - t).abs() <= tolerance)
+                    .map(|e| (t, e))
+            })
+            .collect()
+    }
+
+    /// Returns the sorted list of [`Epoch`]s shared by both `self` and `rhs`,
+    /// expressed in `self` timestamps.
+    /// Epochs are compared by timestamp only: for Observation RINEX,
+    /// two epochs with identical timestamps but different [`EpochFlag`]s
+    /// are still considered common.
+    /// Timestamp jitter up to half the sampling interval is tolerated,
+    /// see [Self::common_epochs_within] to customize it.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o")
+    ///     .unwrap();
+    /// let common = rnx.common_epochs(&rnx);
+    /// assert_eq!(common.len(), rnx.epoch().count());
+    /// ```
+    pub fn common_epochs(&self, rhs: &Self) -> Vec<Epoch> {
+        self.common_epochs_within(rhs, self.epoch_tolerance())
+    }
+
+    /// Returns the sorted list of [`Epoch`]s shared by both `self` and `rhs`,
+    /// expressed in `self` timestamps: each `self` epoch is matched to
+    /// the nearest `rhs` epoch, and retained when both differ by `tolerance`
+    /// or less. A null `tolerance` only retains identical timestamps.
+    pub fn common_epochs_within(&self, rhs: &Self, tolerance: Duration) -> Vec<Epoch> {
+        self.matching_epochs(rhs, tolerance).into_keys().collect()
+    }
+
+    /// Returns true if `self` and `rhs` are sampled on the same epochs,
+    /// tolerating jitter up to half the sampling interval.
+    pub fn agrees_with(&self, rhs: &Self) -> bool {
+        let common = self.common_epochs(rhs).len();
+        common == self.epoch().unique().count() && common == rhs.epoch().unique().count()
+    }
+
+    /// Removes from `self` all epochs not present in `rhs`, and for Observation RINEX,
+    /// all vehicles not observed by `rhs` at that epoch.
+    /// This is typically used prior differencing two RINEX.
+    /// Timestamp jitter up to half the sampling interval is tolerated,
+    /// see [Self::intersect_within_mut] to customize it.
+    pub fn intersect_mut(&mut self, rhs: &Self) {
+        self.intersect_within_mut(rhs, self.epoch_tolerance());
+    }
+
+    /// [Self::intersect_mut] immutable implementation.
+    pub fn intersect(&self, rhs: &Self) -> Self {
+        let mut s = self.clone();
+        s.intersect_mut(rhs);
+        s
+    }
+
+    /// [Self::intersect_mut] with custom `tolerance` on timestamp differences.
+    pub fn intersect_within_mut(&mut self, rhs: &Self, tolerance: Duration) {
+        let matches = self.matching_epochs(rhs, tolerance);
+        if let Some(rec) = self.record.as_mut_obs() {
+            let mut rhs_vehicles: HashMap<Epoch, Vec<SV>> = HashMap::new();
+            if let Some(rhs) = rhs.record.as_obs() {
+                for ((t, _), (_, vehicles)) in rhs.iter() {
+                    rhs_vehicles
+                        .entry(*t)
+                        .or_default()
+                        .extend(vehicles.keys().copied());
+                }
+            }
+            rec.retain(|(t, _), (_, vehicles)| {
+                let t_rhs = match matches.get(t) {
+                    Some(t_rhs) => t_rhs,
+                    None => return false,
+                };
+                if let Some(rhs_vehicles) = rhs_vehicles.get(t_rhs) {
+                    let empty = vehicles.is_empty();
+                    vehicles.retain(|sv, _| rhs_vehicles.contains(sv));
+                    empty || !vehicles.is_empty()
+                } else {
+                    true
+                }
+            });
+        } else if let Some(rec) = self.record.as_mut_nav() {
+            rec.retain(|t, _| matches.contains_key(t));
+        } else if let Some(rec) = self.record.as_mut_meteo() {
+            rec.retain(|t, _| matches.contains_key(t));
+        } else if let Some(rec) = self.record.as_mut_clock() {
+            rec.retain(|t, _| matches.contains_key(t));
+        } else if let Some(rec) = self.record.as_mut_ionex() {
+            rec.retain(|(t, _), _| matches.contains_key(t));
+        } else if let Some(rec) = self.record.as_mut_doris() {
+            rec.retain(|(t, _), _| matches.contains_key(t));
+        }
+    }
+
+    /// Returns the sorted list of [`SV`]s encountered in both `self` and `rhs`.
+    /// Only applies to Observation, Navigation and Clock RINEX,
+    /// returns an empty list otherwise.
+    pub fn common_space_vehicules(&self, rhs: &Self) -> Vec<SV> {
+        let rhs = rhs.space_vehicules();
+        self.space_vehicules()
+            .into_iter()
+            .filter(|sv| rhs.contains(sv))
+            .collect()
+    }
+
+    /// Retains data from given [SV]s only, in place.
+    /// Epochs that no longer contain any vehicle are dropped.
+    /// Applies to Observation, Navigation and Clock RINEX
+    /// (ground station clocks are preserved), has no effect otherwise.
+    /// The header [Constellation] is tightened, see [Self::tighten_constellation_mut]:
+    /// when a single constellation remains, Observation epochs are expressed in its timescale.
+    /// This composes with [Self::constellation_filter_mut]:
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// rnx.constellation_filter_mut(vec![Constellation::GPS]);
+    /// rnx.space_vehicule_filter_mut(vec![
+    ///     SV::from_str("G01").unwrap(),
+    ///     SV::from_str("E01").unwrap(),
+    /// ]);
+    /// assert_eq!(rnx.space_vehicules(), vec![SV::from_str("G01").unwrap()]);
+    /// ```
+    pub fn space_vehicule_filter_mut(&mut self, filter: Vec<SV>) {
+        self.retain_sv_mut(|sv| filter.contains(sv));
+    }
+    /// Copies and returns Self, with data from given [SV]s only.
+    /// See [Self::space_vehicule_filter_mut].
+    pub fn space_vehicule_filter(&self, filter: Vec<SV>) -> Self {
+        let mut s = self.clone();
+        s.space_vehicule_filter_mut(filter);
+        s
+    }
+    /// Retains data from given [Constellation]s only, in place.
+    /// Epochs that no longer contain any vehicle are dropped.
+    /// See [Self::space_vehicule_filter_mut], including for the timescale
+    /// of single constellation Observation RINEX.
+    pub fn constellation_filter_mut(&mut self, filter: Vec<Constellation>) {
+        self.retain_sv_mut(|sv| filter.contains(&sv.constellation));
+    }
+    /// Copies and returns Self, with data from given [Constellation]s only.
+    /// See [Self::constellation_filter_mut].
+    pub fn constellation_filter(&self, filter: Vec<Constellation>) -> Self {
+        let mut s = self.clone();
+        s.constellation_filter_mut(filter);
+        s
+    }
+    /// Rewrites the header [Constellation] to the one constellation
+    /// actually found in the record, if only one is present.
+    /// For example, a file declared [Constellation::Mixed] that only
+    /// contains GPS vehicles is then described as a GPS file.
+    /// Applies to Observation and Navigation RINEX, has no effect otherwise.
+    /// Observation epochs, along with TIME OF FIRST OBS and TIME OF LAST OBS,
+    /// are then expressed in the timescale of that constellation,
+    /// which is implied by single constellation files: they still describe the same instants.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// assert_eq!(rnx.header.constellation, Some(Constellation::Mixed));
+    /// // filters tighten the header definition
+    /// rnx.constellation_filter_mut(vec![Constellation::GPS]);
+    /// assert_eq!(rnx.header.constellation, Some(Constellation::GPS));
+    /// ```
+    pub fn tighten_constellation_mut(&mut self) {
+        if !self.is_observation_rinex() && !self.is_navigation_rinex() {
+            return;
+        }
+        let constellation = match self.constellations().as_slice() {
+            [constellation] => *constellation,
+            _ => return,
+        };
+        if self.header.constellation == Some(constellation) {
+            return;
+        }
+        self.header.constellation = Some(constellation);
+        let ts = match constellation.timescale() {
+            Some(ts) => ts,
+            None => return,
+        };
+        if let Some(obs) = &mut self.header.obs {
+            obs.time_of_first_obs = obs.time_of_first_obs.map(|t| t.to_time_scale(ts));
+            obs.time_of_last_obs = obs.time_of_last_obs.map(|t| t.to_time_scale(ts));
+        }
+        if let Some(rec) = self.record.as_mut_obs() {
+            if rec.keys().all(|(t, _)| t.time_scale == ts) {
+                return;
+            }
+            *rec = std::mem::take(rec)
+                .into_iter()
+                .map(|((t, flag), data)| ((t.to_time_scale(ts), flag), data))
+                .collect();
+            self.comments = std::mem::take(&mut self.comments)
+                .into_iter()
+                .map(|(t, comments)| (t.to_time_scale(ts), comments))
+                .collect();
+        }
+    }
+    /// Copies and returns Self, with tightened header [Constellation].
+    /// See [Self::tighten_constellation_mut].
+    pub fn tighten_constellation(&self) -> Self {
+        let mut s = self.clone();
+        s.tighten_constellation_mut();
+        s
+    }
+    /*
+     * Retains vehicles matching given predicate, drops emptied epochs
+     */
+    fn retain_sv_mut<F: Fn(&SV) -> bool>(&mut self, retain: F) {
+        if let Some(rec) = self.record.as_mut_obs() {
+            rec.retain(|_, (_, vehicles)| {
+                vehicles.retain(|sv, _| retain(sv));
+                !vehicles.is_empty()
+            });
+            let rebuild = self
+                .header
+                .obs
+                .as_ref()
+                .map_or(false, |obs| !obs.prn_obs_counts.is_empty());
+            if rebuild {
+                self.rebuild_obs_counts_mut();
+            }
+        } else if let Some(rec) = self.record.as_mut_nav() {
+            rec.retain(|_, frames| {
+                frames.retain(|fr| match fr {
+                    NavFrame::Eph(_, sv, _)
+                    | NavFrame::Eop(_, sv, _)
+                    | NavFrame::Ion(_, sv, _)
+                    | NavFrame::Sto(_, sv, _) => retain(sv),
+                });
+                !frames.is_empty()
+            });
+        } else if let Some(rec) = self.record.as_mut_clock() {
+            rec.retain(|_, keys| {
+                keys.retain(|key, _| match &key.clock_type {
+                    clock::ClockType::SV(sv) => retain(sv),
+                    clock::ClockType::Station(_) => true,
+                });
+                !keys.is_empty()
+            });
+        }
+        self.tighten_constellation_mut();
+    }
+
+    /// Removes all observations where receiver phase lock was lost.   
+    /// This is only relevant on OBS RINEX.
+    pub fn lock_loss_filter_mut(&mut self) {
+        self.lli_and_mask_mut(observation::LliFlags::LOCK_LOSS)
+    }
+
+    /// Applies given AND mask in place, to all observations.
+    /// This has no effect on non observation records.
+    /// This also drops observations that did not come with an LLI flag.  
+    /// Only relevant on OBS RINEX.
+    pub fn lli_and_mask_mut(&mut self, mask: observation::LliFlags) {
+        if !self.is_observation_rinex() {
+            return; // nothing to browse
+        }
+        let record = self.record.as_mut_obs().unwrap();
+        for (_e, (_clk, sv)) in record.iter_mut() {
+            for (_sv, obs) in sv.iter_mut() {
+                obs.retain(|_, data| {
+                    if let Some(lli) = data.lli {
+                        lli.intersects(mask)
+                    } else {
+                        false // drops data with no LLI attached
+                    }
+                })
+            }
+        }
+    }
+
+    /// [`Rinex::lli_and_mask`] immutable implementation.   
+    /// Only relevant on OBS RINEX.
+    pub fn lli_and_mask(&self, mask: observation::LliFlags) -> Self {
+        let mut c = self.clone();
+        c.lli_and_mask_mut(mask);
+        c
+    }
+
+    /// Splits `self` into two observation sets, with respect to given LLI `mask`:
+    /// (observations that match the mask, remaining observations).
+    /// Unlike [`Rinex::lli_and_mask`], no data is lost: both sets
+    /// put together give the original record back. Partitioning
+    /// is performed per SV and per [`Observable`], so an epoch where a single
+    /// vehicle was flagged is preserved in both sets.
+    ///   - An observation matches a non empty mask when its LLI flag has at least
+    ///     one bit in common with the mask. For example `LliFlags::HALF_CYCLE_SLIP`
+    ///     retrieves all half cycle ambiguities, whatever the other bits may be.
+    ///   - `LliFlags::OK_OR_UNKNOWN` (empty mask) retrieves observations with no
+    ///     LLI bit set, which includes observations that did not come with an LLI flag.
+    ///
+    /// Vehicles and epochs that end up with no observation are removed from each set.
+    /// Only relevant on OBS RINEX: other types are returned as (copy, copy).
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::observation::LliFlags;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V2/npaz3550.21o")
+    ///     .unwrap();
+    /// let (spoofed, _others) = rinex.partition_by_lli(LliFlags::UNDER_ANTI_SPOOFING);
+    /// let record = spoofed.record.as_obs().unwrap();
+    /// for (_, (_, vehicles)) in record {
+    ///     for (_, observations) in vehicles {
+    ///         for (_, data) in observations {
+    ///             assert!(data.lli.unwrap().intersects(LliFlags::UNDER_ANTI_SPOOFING));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn partition_by_lli(&self, mask: observation::LliFlags) -> (Self, Self) {
+        let matches = |data: &observation::ObservationData| -> bool {
+            let lli = data.lli.unwrap_or(observation::LliFlags::OK_OR_UNKNOWN);
+            if mask.is_empty() {
+                lli.is_empty()
+            } else {
+                lli.intersects(mask)
+            }
+        };
+        let (mut matching, mut others) = (self.clone(), self.clone());
+        if let Some(record) = matching.record.as_mut_obs() {
+            record.retain(|_, (_, vehicles)| {
+                vehicles.retain(|_, observations| {
+                    observations.retain(|_, data| matches(data));
+                    !observations.is_empty()
+                });
+                !vehicles.is_empty()
+            });
+        }
+        if let Some(record) = others.record.as_mut_obs() {
+            record.retain(|_, (_, vehicles)| {
+                vehicles.retain(|_, observations| {
+                    observations.retain(|_, data| !matches(data));
+                    !observations.is_empty()
+                });
+                !vehicles.is_empty()
+            });
+        }
+        (matching, others)
+    }
+    /// Drops all observations whose SSI indication is below given threshold.
+    /// Observations that did not come with an SSI indication are considered "unknown":
+    /// they are retained if `keep_unknown` is set, dropped otherwise.
+    /// Only relevant on OBS RINEX.
+    pub fn minimum_sig_strength_filter_mut(&mut self, min: observation::Ssi, keep_unknown: bool) {
+        if let Some(record) = self.record.as_mut_obs() {
+            record.retain(|_, (_, vehicles)| {
+                vehicles.retain(|_, observations| {
+                    observations.retain(|_, data| {
+                        if let Some(ssi) = data.snr {
+                            ssi >= min
+                        } else {
+                            keep_unknown
+                        }
+                    });
+                    !observations.is_empty()
+                });
+                !vehicles.is_empty()
+            });
+        }
+    }
+
+    /// [`Rinex::minimum_sig_strength_filter_mut`] immutable implementation.
+    /// Only relevant on OBS RINEX.
+    pub fn minimum_sig_strength_filter(&self, min: observation::Ssi, keep_unknown: bool) -> Self {
+        let mut c = self.clone();
+        c.minimum_sig_strength_filter_mut(min, keep_unknown);
+        c
+    }
+
+    /// Retains, on each epoch, the observations whose SNR ranks in the top
+    /// `percentile` percent, which is an adaptive alternative to
+    /// [Self::minimum_sig_strength_filter_mut]. For example, 25.0 keeps
+    /// the strongest quarter of each epoch. Equal SNR are ranked by SV
+    /// then by observable, and observations that did not come with an SNR
+    /// indication are dropped. Only relevant on OBS RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// rinex.retain_top_snr_mut(50.0);
+    /// ```
+    pub fn retain_top_snr_mut(&mut self, percentile: f64) {
+        let percentile = percentile.clamp(0.0, 100.0);
+        if let Some(record) = self.record.as_mut_obs() {
+            record.retain(|_, (_, vehicles)| {
+                let ranked: Vec<(observation::SNR, SV, Observable)> = vehicles
+                    .iter()
+                    .flat_map(|(sv, observations)| {
+                        observations.iter().filter_map(|(observable, data)| {
+                            data.snr.map(|snr| (snr, *sv, observable.clone()))
+                        })
+                    })
+                    .sorted_by(|(snr_a, sv_a, obs_a), (snr_b, sv_b, obs_b)| {
+                        snr_b.cmp(snr_a).then(sv_a.cmp(sv_b)).then(obs_a.cmp(obs_b))
+                    })
+                    .collect();
+                let nb_kept = (ranked.len() as f64 * percentile / 100.0).ceil() as usize;
+                let kept: std::collections::HashSet<(SV, Observable)> = ranked
+                    .into_iter()
+                    .take(nb_kept)
+                    .map(|(_, sv, observable)| (sv, observable))
+                    .collect();
+                vehicles.retain(|sv, observations| {
+                    observations.retain(|observable, _| kept.contains(&(*sv, observable.clone())));
+                    !observations.is_empty()
+                });
+                !vehicles.is_empty()
+            });
+        }
+    }
+
+    /// [`Rinex::retain_top_snr_mut`] immutable implementation.
+    /// Only relevant on OBS RINEX.
+    pub fn retain_top_snr(&self, percentile: f64) -> Self {
+        let mut c = self.clone();
+        c.retain_top_snr_mut(percentile);
+        c
+    }
+
+    /*
+     * Retains observations (and header definitions) for which `keep`
+     * is true, vehicles, epochs and constellation definitions left empty are dropped.
+     */
+    fn retain_observables_mut<F: Fn(Constellation, &Observable) -> bool>(&mut self, keep: F) {
+        if let Some(record) = self.record.as_mut_obs() {
+            record.retain(|_, (_, vehicles)| {
+                vehicles.retain(|sv, observations| {
+                    observations.retain(|observable, _| keep(sv.constellation, observable));
+                    !observations.is_empty()
+                });
+                !vehicles.is_empty()
+            });
+        }
+        if let Some(obs) = &mut self.header.obs {
+            obs.codes.retain(|constellation, codes| {
+                codes.retain(|observable| keep(*constellation, observable));
+                !codes.is_empty()
+            });
+        }
+    }
+
+    /// Retains observations made on given frequency bands only, in record and
+    /// header definitions. Carriers are compared by frequency: for example
+    /// [Carrier::L1] also retains Galileo E1 and BeiDou B1C observations.
+    /// Only relevant on OBS RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// // L1/L2 observations only
+    /// rinex.filter_observables_by_band_mut(&[Carrier::L1, Carrier::L2]);
+    /// ```
+    pub fn filter_observables_by_band_mut(&mut self, bands: &[Carrier]) {
+        self.retain_observables_mut(|constellation, observable| {
+            observable
+                .carrier(constellation)
+                .map(|carrier| {
+                    bands
+                        .iter()
+                        .any(|band| band.frequency_mhz() == carrier.frequency_mhz())
+                })
+                .unwrap_or(false)
+        });
     }
 
-    /// Returns true if Differential Code Biases (DCBs)
-    /// are compensated for, in this file, for this GNSS constellation.
-    /// DCBs are biases due to tiny frequency differences,
-    /// in both the SV embedded code generator, and receiver PLL.
-    /// If this is true, that means all code signals received in from
-    /// all SV within that constellation, have intrinsinc DCB compensation.
-    /// In very high precision and specific applications, you then do not have
-    /// to deal with their compensation yourself.
-    pub fn dcb_compensation(&self, constellation: Constellation) -> bool {
-        self.header
-            .dcb_compensations
-            .iter()
-            .filter(|dcb| dcb.constellation == constellation)
-            .count()
-            > 0
+    /// [`Rinex::filter_observables_by_band_mut`] immutable implementation.
+    pub fn filter_observables_by_band(&self, bands: &[Carrier]) -> Self {
+        let mut c = self.clone();
+        c.filter_observables_by_band_mut(bands);
+        c
     }
-    /// Returns true if Antenna Phase Center variations are compensated
-    /// for in this file. Useful for high precision application.
-    pub fn pcv_compensation(&self, constellation: Constellation) -> bool {
-        self.header
-            .pcv_compensations
-            .iter()
-            .filter(|pcv| pcv.constellation == constellation)
-            .count()
-            > 0
+
+    /// Retains observations of given physical nature only, in record and header definitions.
+    /// This is coarser than the Observable filter, which matches exact codes.
+    /// Can be combined with [Self::filter_observables_by_band_mut],
+    /// for example to retain phase observations on E5 only. Only relevant on OBS RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// rinex.filter_observables_by_physics_mut(&[ObsPhysics::Phase]);
+    /// rinex.filter_observables_by_band_mut(&[Carrier::E5]);
+    /// ```
+    pub fn filter_observables_by_physics_mut(&mut self, kinds: &[ObsPhysics]) {
+        self.retain_observables_mut(|_, observable| {
+            observable
+                .physics()
+                .map(|physics| kinds.contains(&physics))
+                .unwrap_or(false)
+        });
     }
-    /// Returns `true` if self is a `merged` RINEX file,   
-    /// meaning, this file is the combination of two RINEX files merged together.  
-    /// This is determined by the presence of a custom yet somewhat standardized `FILE MERGE` comments
-    pub fn is_merged(&self) -> bool {
-        let special_comment = String::from("FILE MERGE");
-        for comment in self.header.comments.iter() {
-            if comment.contains(&special_comment) {
-                return true;
+
+    /// [`Rinex::filter_observables_by_physics_mut`] immutable implementation.
+    pub fn filter_observables_by_physics(&self, kinds: &[ObsPhysics]) -> Self {
+        let mut c = self.clone();
+        c.filter_observables_by_physics_mut(kinds);
+        c
+    }
+
+    /// Splits Self into one [Rinex] per physical nature of observations:
+    /// pseudo range, phase, doppler and signal strength.
+    /// Each one comes with a pruned header, that only describes the relevant codes.
+    /// Categories that do not have observations are omitted.
+    /// Only relevant on OBS RINEX: returns an empty map otherwise.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let subsets = rinex.split_by_physics();
+    /// assert_eq!(subsets.len(), 4);
+    /// let doppler = &subsets[&ObsPhysics::Doppler];
+    /// assert!(doppler.observable().all(|obs| obs.is_doppler_observable()));
+    /// ```
+    pub fn split_by_physics(&self) -> HashMap<ObsPhysics, Self> {
+        let mut subsets = HashMap::new();
+        if !self.is_observation_rinex() {
+            return subsets;
+        }
+        for physics in [
+            ObsPhysics::PseudoRange,
+            ObsPhysics::Phase,
+            ObsPhysics::Doppler,
+            ObsPhysics::Ssi,
+        ] {
+            let mut subset = self.filter_observables_by_physics(&[physics]);
+            if subset.observation().count() == 0 {
+                continue;
+            }
+            if let Some(obs) = &mut subset.header.obs {
+                obs.codes.retain(|_, codes| !codes.is_empty());
+                obs.scaling
+                    .retain(|(_, observable), _| observable.physics() == Some(physics));
+                if !obs.prn_obs_counts.is_empty() {
+                    subset.rebuild_obs_counts_mut();
+                }
             }
+            subsets.insert(physics, subset);
         }
-        false
+        subsets
     }
 
-    /// Removes all observations where receiver phase lock was lost.   
-    /// This is only relevant on OBS RINEX.
-    pub fn lock_loss_filter_mut(&mut self) {
-        self.lli_and_mask_mut(observation::LliFlags::LOCK_LOSS)
+    /// Detects and repairs millisecond jumps on pseudo range observations:
+    /// receiver clock steering introduces steps of k·c·1ms on all pseudo ranges at once.
+    /// A jump is only repaired when all vehicles (at least two) jump together,
+    /// isolated steps on a single vehicle are left untouched. Predictions restart
+    /// after data gaps, special events and loss of lock.
+    /// The k·c·1ms offset is removed from all following pseudo ranges,
+    /// restoring continuity. Each repair is recorded as a header comment.
+    /// Only relevant on OBS RINEX.
+    pub fn repair_ms_jumps_mut(&mut self) {
+        let header = &self.header;
+        let jumps = match self.record.as_mut_obs() {
+            Some(r) => observation::record::repair_ms_jumps_mut(r, header),
+            None => return,
+        };
+        for (epoch, ms) in jumps {
+            self.header
+                .comments
+                .push(Header::ms_jump_comment(epoch, ms));
+        }
     }
 
-    /// Applies given AND mask in place, to all observations.
-    /// This has no effect on non observation records.
-    /// This also drops observations that did not come with an LLI flag.  
+    /// Recomputes the # OF SATELLITES and PRN / # OF OBS header fields
+    /// from the record, typically after filtering operations.
     /// Only relevant on OBS RINEX.
-    pub fn lli_and_mask_mut(&mut self, mask: observation::LliFlags) {
-        if !self.is_observation_rinex() {
-            return; // nothing to browse
-        }
-        let record = self.record.as_mut_obs().unwrap();
-        for (_e, (_clk, sv)) in record.iter_mut() {
-            for (_sv, obs) in sv.iter_mut() {
-                obs.retain(|_, data| {
-                    if let Some(lli) = data.lli {
-                        lli.intersects(mask)
-                    } else {
-                        false // drops data with no LLI attached
-                    }
-                })
+    pub fn rebuild_obs_counts_mut(&mut self) {
+        let record = match self.record.as_obs() {
+            Some(record) => record,
+            None => return,
+        };
+        let mut counts: HashMap<SV, HashMap<String, u32>> = HashMap::new();
+        for (_, (_, vehicles)) in record.iter() {
+            for (sv, observations) in vehicles.iter() {
+                let counts = counts.entry(*sv).or_default();
+                for observable in observations.keys() {
+                    *counts.entry(observable.to_string()).or_default() += 1;
+                }
             }
         }
+        if let Some(obs) = &mut self.header.obs {
+            obs.nb_satellites = Some(counts.len() as u16);
+            obs.prn_obs_counts = counts;
+        }
     }
 
-    /// [`Rinex::lli_and_mask`] immutable implementation.   
-    /// Only relevant on OBS RINEX.
-    pub fn lli_and_mask(&self, mask: observation::LliFlags) -> Self {
+    /// [`Rinex::rebuild_obs_counts_mut`] immutable implementation.
+    pub fn rebuild_obs_counts(&self) -> Self {
         let mut c = self.clone();
-        c.lli_and_mask_mut(mask);
+        c.rebuild_obs_counts_mut();
+        c
+    }
+
+    /// [`Rinex::repair_ms_jumps_mut`] immutable implementation.
+    pub fn repair_ms_jumps(&self) -> Self {
+        let mut c = self.clone();
+        c.repair_ms_jumps_mut();
         c
     }
+
     /// Aligns Phase observations at origin
     pub fn observation_phase_align_origin_mut(&mut self) {
         let mut init_phases: HashMap<SV, HashMap<Observable, f64>> = HashMap::new();
@@ -1101,6 +2271,65 @@ impl Rinex {
         s
     }
 
+    /// Converts all phase observations from carrier cycles to meters, in place,
+    /// by multiplying them with the [`Carrier`] wavelength.
+    /// Glonass carriers are refined with the GLONASS SLOT / FRQ # header table.
+    /// Nothing is converted if one phase observable cannot be identified.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// rnx.observation_phase_to_meters_mut()
+    ///     .unwrap();
+    /// ```
+    pub fn observation_phase_to_meters_mut(&mut self) -> Result<(), Error> {
+        let glo_channels = &self.header.glo_channels;
+        let record = match self.record.as_mut_obs() {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+        // identify all carriers first: either everything is converted, or nothing
+        let mut wavelengths: HashMap<(SV, Observable), f64> = HashMap::new();
+        for (_, (_, vehicles)) in record.iter() {
+            for (sv, observations) in vehicles.iter() {
+                for observable in observations.keys() {
+                    if !observable.is_phase_observable()
+                        || wavelengths.contains_key(&(*sv, observable.clone()))
+                    {
+                        continue;
+                    }
+                    let mut carrier = Carrier::from_observable(sv.constellation, observable)?;
+                    if sv.constellation == Constellation::Glonass {
+                        if let Carrier::G1(_) | Carrier::G2(_) = carrier {
+                            let offset = glo_channels
+                                .get(sv)
+                                .ok_or(carrier::Error::MissingGlonassChannel(*sv))?;
+                            carrier = carrier.with_glonass_offset(*offset);
+                        }
+                    }
+                    wavelengths.insert((*sv, observable.clone()), carrier.wavelength());
+                }
+            }
+        }
+        for (_, (_, vehicles)) in record.iter_mut() {
+            for (sv, observations) in vehicles.iter_mut() {
+                for (observable, data) in observations.iter_mut() {
+                    if let Some(lambda) = wavelengths.get(&(*sv, observable.clone())) {
+                        data.obs *= lambda;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Converts all phase observations from carrier cycles to meters,
+    /// see [Self::observation_phase_to_meters_mut].
+    pub fn observation_phase_to_meters(&self) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.observation_phase_to_meters_mut()?;
+        Ok(s)
+    }
+
     /// Writes self into given file.   
     /// Both header + record will strictly follow RINEX standards.   
     /// Record: refer to supported RINEX types.
@@ -1115,10 +2344,163 @@ impl Rinex {
     ///   * [Self::standard_filename] to generate a standardized filename
     ///   * [Self::guess_production_attributes] helps generate standardized filenames for
     ///     files that do not follow naming conventions
-    pub fn to_file(&self, path: &str) -> Result<(), Error> {
-        let mut writer = BufferedWriter::new(path)?;
+    /// Content is [Self::validate]d first: the first [ValidationIssue]
+    /// is returned as [FormattingError::InvalidContent] and nothing is written.
+    pub fn to_file(&self, path: &str) -> Result<(), FormattingError> {
+        if let Some(issue) = self.validate().into_iter().next() {
+            return Err(FormattingError::InvalidContent(issue));
+        }
+        let writer = BufferedWriter::new(path)?;
+        self.format(writer)
+    }
+    /// Formats Self into any [std::io::Write]able stream, see [Self::to_file].
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///   .unwrap();
+    /// let mut content = Vec::<u8>::new();
+    /// rnx.to_writer(&mut content)
+    ///   .unwrap();
+    /// let parsed = Rinex::from_reader(content.as_slice())
+    ///   .unwrap();
+    /// assert_eq!(parsed.record, rnx.record);
+    /// ```
+    pub fn to_writer<W: std::io::Write>(&self, writer: W) -> Result<(), FormattingError> {
+        if let Some(issue) = self.validate().into_iter().next() {
+            return Err(FormattingError::InvalidContent(issue));
+        }
+        self.format(writer)
+    }
+    /*
+     * Formats (validated) Self into given writer
+     */
+    fn format<W: std::io::Write>(&self, mut writer: W) -> Result<(), FormattingError> {
         write!(writer, "{}", self.header)?;
-        self.record.to_file(&self.header, &mut writer)?;
+        self.record.to_file(
+            &self.header,
+            &self.comments,
+            &self.trailing_comments,
+            &mut writer,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+    /// Formats Self into a [String], see [Self::to_writer].
+    pub fn to_string(&self) -> Result<String, FormattingError> {
+        let mut content = Vec::<u8>::new();
+        self.to_writer(&mut content)?;
+        Ok(String::from_utf8_lossy(&content).to_string())
+    }
+    /// Reports all [ValidationIssue]s that would prevent Self from being
+    /// correctly formatted (see [Self::to_file]). An empty list means
+    /// Self can be produced.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::ValidationIssue;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///   .unwrap();
+    /// assert!(rnx.validate().is_empty());
+    ///
+    /// rnx.header.constellation = None;
+    /// assert_eq!(
+    ///     rnx.validate(),
+    ///     vec![ValidationIssue::MissingConstellation(RinexType::ObservationData)]);
+    /// assert!(rnx.to_file("invalid.rnx").is_err());
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::<ValidationIssue>::new();
+        let rinex_type = self.header.rinex_type;
+        let record_matches = match rinex_type {
+            types::Type::ObservationData => self.record.as_obs().is_some(),
+            types::Type::NavigationData => self.record.as_nav().is_some(),
+            types::Type::MeteoData => self.record.as_meteo().is_some(),
+            types::Type::ClockData => self.record.as_clock().is_some(),
+            types::Type::IonosphereMaps => self.record.as_ionex().is_some(),
+            types::Type::AntennaData | types::Type::DORIS => {
+                issues.push(ValidationIssue::UnsupportedType(rinex_type));
+                return issues;
+            },
+        };
+        if !record_matches {
+            issues.push(ValidationIssue::RecordTypeMismatch(rinex_type));
+        }
+        match rinex_type {
+            types::Type::ObservationData | types::Type::NavigationData => {
+                if self.header.constellation.is_none() {
+                    issues.push(ValidationIssue::MissingConstellation(rinex_type));
+                }
+            },
+            _ => {},
+        }
+        if rinex_type == types::Type::ObservationData {
+            let has_data = self.record.as_obs().map_or(false, |rec| !rec.is_empty());
+            let has_codes = self.header.obs.as_ref().map_or(false, |obs| {
+                obs.codes.values().any(|codes| !codes.is_empty())
+            });
+            if self.header.obs.is_none() || (has_data && !has_codes) {
+                issues.push(ValidationIssue::MissingObservables);
+            }
+        }
+        if rinex_type == types::Type::MeteoData {
+            let has_data = self.record.as_meteo().map_or(false, |rec| !rec.is_empty());
+            let has_codes = self
+                .header
+                .meteo
+                .as_ref()
+                .map_or(false, |meteo| !meteo.codes.is_empty());
+            if self.header.meteo.is_none() || (has_data && !has_codes) {
+                issues.push(ValidationIssue::MissingMeteoObservables);
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(feature = "serde")]
+use metadata::Metadata;
+
+/*
+ * Archiving related methods
+ */
+#[cfg(feature = "serde")]
+#[cfg_attr(docrs, doc(cfg(feature = "serde")))]
+impl Rinex {
+    /// Returns [`Metadata`]: parsed header section and summary of the record content.
+    pub fn metadata(&self) -> Metadata {
+        // ANTEX records are not indexed by epochs
+        if self.is_antex() {
+            return Metadata {
+                header: self.header.clone(),
+                ..Default::default()
+            };
+        }
+        Metadata {
+            header: self.header.clone(),
+            first_epoch: self.first_epoch(),
+            last_epoch: self.last_epoch(),
+            sv: self.sv().unique().sorted().collect(),
+        }
+    }
+    /// Writes [`Metadata`] into a JSON sidecar file, convenient for archiving.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::metadata::Metadata;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let path = std::env::temp_dir().join("DUTH0630.json");
+    /// rnx.write_metadata_json(path.to_str().unwrap())
+    ///     .unwrap();
+    ///
+    /// let content = std::fs::read_to_string(&path)
+    ///     .unwrap();
+    /// let metadata: Metadata = serde_json::from_str(&content)
+    ///     .unwrap();
+    /// assert_eq!(metadata.header, rnx.header);
+    /// ```
+    pub fn write_metadata_json(&self, path: &str) -> Result<(), Error> {
+        let fd = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(fd);
+        serde_json::to_writer_pretty(writer, &self.metadata()).map_err(std::io::Error::from)?;
         Ok(())
     }
 }
@@ -1172,6 +2554,23 @@ impl Rinex {
             .max_by(|(_, pop_i), (_, pop_j)| pop_i.cmp(pop_j))
             .map(|dominant| dominant.0)
     }
+    /// Returns the sampling interval of this record: the header INTERVAL field
+    /// when it is defined, the [Self::dominant_sample_rate] otherwise.
+    /// Returns None when the record does not contain at least two epochs.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// assert_eq!(rnx.sampling_interval(), Some(Duration::from_seconds(60.0)));
+    /// ```
+    pub fn sampling_interval(&self) -> Option<Duration> {
+        if self.epoch().nth(1).is_none() {
+            return None;
+        }
+        self.header
+            .sampling_interval
+            .or_else(|| self.dominant_sample_rate())
+    }
     /// Histogram analysis on Epoch interval. Although
     /// it is feasible on all types indexed by [Epoch],
     /// this operation only makes truly sense on Observation Data.
@@ -1213,6 +2612,45 @@ impl Rinex {
                 .into_iter(),
         )
     }
+    /// Returns the duration of the largest data gap, see [Self::gap_analysis].
+    /// Returns None if no data gap was found.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rinex.largest_data_gap_duration(),
+    ///     Some(Duration::from_seconds(10.0 * 3600.0 + 21.0 * 60.0)));
+    /// ```
+    pub fn largest_data_gap_duration(&self) -> Option<Duration> {
+        self.gap_analysis(None).largest_gap.map(|(_, dt)| dt)
+    }
+    /// Analyzes the data gaps of this record, with respect to the `nominal`
+    /// sampling interval. When not supplied, the nominal interval is the header
+    /// INTERVAL field, or the dominant interval of the record.
+    /// Any interval between two successive epochs, larger than the nominal interval,
+    /// is a gap (like [Self::data_gaps]).
+    /// Only relevant on OBS (valid epochs only, see [Self::epoch_ok]), METEO
+    /// and CLK RINEX: other types return an empty analysis.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// let analysis = rinex.gap_analysis(None);
+    /// assert_eq!(analysis.nominal, Some(Duration::from_seconds(60.0)));
+    /// assert_eq!(analysis.gaps, 5);
+    /// ```
+    pub fn gap_analysis(&self, nominal: Option<Duration>) -> GapAnalysis {
+        let epochs: Vec<Epoch> = match self.header.rinex_type {
+            RinexType::ObservationData => self.epoch_ok().collect(),
+            RinexType::MeteoData | RinexType::ClockData => self.epoch().collect(),
+            _ => return GapAnalysis::default(),
+        };
+        let nominal = nominal
+            .or(self.header.sampling_interval)
+            .or_else(|| self.dominant_sample_rate());
+        GapAnalysis::new(&epochs, nominal)
+    }
     /// Returns True if Self has a steady sampling, ie., all epoch interval
     /// are evenly spaced
     pub fn steady_sampling(&self) -> bool {
@@ -1299,9 +2737,13 @@ impl Rinex {
  * These methods are used to browse data easily and efficiently.
  */
 impl Rinex {
+    /// Returns [`Epoch`] iterator, in chronological order.
+    /// Each timestamp is returned once, even when several
+    /// entries share it (for example, event and data epochs).
+    /// ANTEX records are not sampled in time: the iterator is empty.
     pub fn epoch(&self) -> Box<dyn Iterator<Item = Epoch> + '_> {
         if let Some(r) = self.record.as_obs() {
-            Box::new(r.iter().map(|((k, _), _)| *k))
+            Box::new(r.iter().map(|((k, _), _)| *k).dedup())
         } else if let Some(r) = self.record.as_nav() {
             Box::new(r.iter().map(|(k, _)| *k))
         } else if let Some(r) = self.record.as_meteo() {
@@ -1309,7 +2751,12 @@ impl Rinex {
         } else if let Some(r) = self.record.as_clock() {
             Box::new(r.iter().map(|(k, _)| *k))
         } else if let Some(r) = self.record.as_ionex() {
-            Box::new(r.iter().map(|((k, _), _)| *k))
+            // one plane per altitude, for each map
+            Box::new(r.iter().map(|((k, _), _)| *k).dedup())
+        } else if let Some(r) = self.record.as_doris() {
+            Box::new(r.iter().map(|((k, _), _)| *k).dedup())
+        } else if self.record.as_antex().is_some() {
+            Box::new(std::iter::empty())
         } else {
             panic!(
                 "cannot get an epoch iterator for \"{:?}\" RINEX",
@@ -1487,6 +2934,43 @@ impl Rinex {
             );
         }
     }
+    /// Returns the presence of each [`SV`] at each [`Epoch`], which is the data
+    /// behind satellite availability ("banana") plots: the list of [`Epoch`]s,
+    /// and for each [`SV`], a vector aligned to this list
+    /// that is true where the vehicle was observed (or broadcast).
+    /// Epochs that do not describe any vehicle (like events) are not listed.
+    /// This is only feasible where [Self::sv_epoch] is.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o")
+    ///     .unwrap();
+    ///
+    /// let (epochs, presence) = rnx.sv_epoch_presence();
+    /// assert_eq!(epochs.len(), 3);
+    /// assert_eq!(presence[&SV::new(Constellation::GPS, 1)], vec![false, true, true]);
+    /// assert_eq!(presence[&SV::new(Constellation::GPS, 3)], vec![true, false, true]);
+    /// assert_eq!(presence[&SV::new(Constellation::GPS, 31)], vec![true, false, false]);
+    /// ```
+    pub fn sv_epoch_presence(&self) -> (Vec<Epoch>, HashMap<SV, Vec<bool>>) {
+        // epochs may be described several times (events)
+        let sv_epoch = self.sv_epoch().filter(|(_, svnn)| !svnn.is_empty()).fold(
+            BTreeMap::<Epoch, Vec<SV>>::new(),
+            |mut map, (epoch, svnn)| {
+                map.entry(epoch).or_default().extend(svnn);
+                map
+            },
+        );
+        let epochs = sv_epoch.keys().copied().collect::<Vec<_>>();
+        let mut presence = HashMap::<SV, Vec<bool>>::new();
+        for (index, vehicles) in sv_epoch.values().enumerate() {
+            for sv in vehicles {
+                presence
+                    .entry(*sv)
+                    .or_insert_with(|| vec![false; epochs.len()])[index] = true;
+            }
+        }
+        (epochs, presence)
+    }
     /// Returns a (unique) Iterator over all identified [`Constellation`]s.
     /// ```
     /// use rinex::prelude::*;
@@ -1667,6 +3151,121 @@ impl Rinex {
                 .flat_map(|record| record.iter()),
         )
     }
+    /// Returns the clock offset and observations made at given [Epoch].
+    /// When several events were declared at this [Epoch],
+    /// the first one (in [EpochFlag] order) is returned.
+    /// Only applies to Observation RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let t = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+    /// let (clock_offset, vehicles) = rnx.observation_at(t)
+    ///     .unwrap();
+    /// assert!(clock_offset.is_none());
+    /// assert_eq!(vehicles.len(), 17);
+    /// ```
+    pub fn observation_at(
+        &self,
+        epoch: Epoch,
+    ) -> Option<&(
+        Option<f64>,
+        BTreeMap<SV, HashMap<Observable, ObservationData>>,
+    )> {
+        self.record
+            .as_obs()?
+            .range((epoch, EpochFlag::Ok)..)
+            .next()
+            .filter(|((t, _), _)| *t == epoch)
+            .map(|(_, data)| data)
+    }
+    /// Returns the clock offset and observations made at the [Epoch]
+    /// nearest to given [Epoch], along with that [Epoch].
+    /// See [Self::observation_at].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let t = Epoch::from_str("2022-03-04T00:30:00 GPST").unwrap();
+    /// let (nearest, _) = rnx.observation_nearest(t)
+    ///     .unwrap();
+    /// assert_eq!(nearest, Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap());
+    /// ```
+    pub fn observation_nearest(
+        &self,
+        epoch: Epoch,
+    ) -> Option<(
+        Epoch,
+        &(
+            Option<f64>,
+            BTreeMap<SV, HashMap<Observable, ObservationData>>,
+        ),
+    )> {
+        let record = self.record.as_obs()?;
+        let before = record.range(..(epoch, EpochFlag::Ok)).next_back();
+        let after = record.range((epoch, EpochFlag::Ok)..).next();
+        let ((t, _), _) = match (before, after) {
+            (Some(before), Some(after)) => {
+                if (epoch - before.0 .0).abs() < (after.0 .0 - epoch).abs() {
+                    before
+                } else {
+                    after
+                }
+            },
+            (Some(before), None) => before,
+            (None, Some(after)) => after,
+            (None, None) => return None,
+        };
+        // first event at this epoch
+        self.observation_at(*t).map(|data| (*t, data))
+    }
+    /// Returns all observations of given [SV], indexed by [Epoch].
+    /// When several events were declared at the same [Epoch],
+    /// the first one (in [EpochFlag] order) is retained.
+    /// Only applies to Observation RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let observations = rnx.sv_observations(g01);
+    /// assert_eq!(observations.len(), 3);
+    /// ```
+    pub fn sv_observations(
+        &self,
+        sv: SV,
+    ) -> BTreeMap<Epoch, &HashMap<Observable, ObservationData>> {
+        let mut observations = BTreeMap::new();
+        for ((epoch, _), (_, vehicles)) in self.observation() {
+            if let Some(data) = vehicles.get(&sv) {
+                observations.entry(*epoch).or_insert(data);
+            }
+        }
+        observations
+    }
+    /// Returns the time series of given [Observable] for given [SV].
+    /// See [Self::sv_observations].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let c1c = Observable::from_str("C1C").unwrap();
+    /// let series = rnx.observable_series(g01, &c1c);
+    /// assert_eq!(series.values().next(), Some(&20243517.560));
+    /// ```
+    pub fn observable_series(&self, sv: SV, observable: &Observable) -> BTreeMap<Epoch, f64> {
+        self.sv_observations(sv)
+            .into_iter()
+            .filter_map(|(epoch, observations)| {
+                observations.get(observable).map(|data| (epoch, data.obs))
+            })
+            .collect()
+    }
     /// Returns Navigation Data interator (any type of message).
     /// NAV records may contain several different types of frames.
     /// You should prefer more precise methods, like [ephemeris] or
@@ -1733,7 +3332,13 @@ impl Rinex {
 // use std::str::FromStr;
 
 #[cfg(feature = "obs")]
-use crate::observation::{record::code_multipath, LliFlags, SNR};
+use crate::observation::{
+    record::{
+        code_carrier_divergence, code_multipath, code_multipath_mp12, code_multipath_mp12_summary,
+        level_phase_to_code, preferred_combination, rtcm_msm_summary,
+    },
+    MultipathSummary, SNR,
+};
 
 /*
  * OBS RINEX specific methods: only available on crate feature.
@@ -1825,6 +3430,245 @@ impl Rinex {
                 .filter_map(|(e, f)| if f.is_ok() { Some(e) } else { None }),
         )
     }
+    /// Removes all epochs that do not have an [`EpochFlag::Ok`] flag attached to them,
+    /// that is, all [`Self::epoch_anomalies`]. Only relevant on OBS RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_flags.22O")
+    ///     .unwrap();
+    /// assert_eq!(rnx.epoch_anomalies().count(), 1);
+    /// rnx.cleanup_mut();
+    /// assert_eq!(rnx.epoch_anomalies().count(), 0);
+    /// assert_eq!(rnx.epoch_ok().count(), 2);
+    /// ```
+    pub fn cleanup_mut(&mut self) {
+        if let Some(record) = self.record.as_mut_obs() {
+            record.retain(|(_, flag), _| flag.is_ok());
+        }
+    }
+    /// [`Self::cleanup_mut`] immutable implementation.
+    pub fn cleanup(&self) -> Self {
+        let mut s = self.clone();
+        s.cleanup_mut();
+        s
+    }
+    /// Retains only epochs that have an [`EpochFlag::Ok`] flag attached to them.
+    /// When `power_failure` is set, epochs flagged [`EpochFlag::PowerFailure`] are retained as well,
+    /// because they still carry valid observations.
+    /// Only OBS RINEX have epoch flags: other RINEX are left untouched and
+    /// [`Error::NoEpochFlags`] is returned.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_flags.22O")
+    ///     .unwrap();
+    /// let with_power_failures = rnx.epoch_ok_filter(true)
+    ///     .unwrap();
+    /// assert_eq!(with_power_failures.epoch().count(), 3);
+    /// rnx.epoch_ok_filter_mut(false)
+    ///     .unwrap();
+    /// assert_eq!(rnx.epoch().count(), 2);
+    /// ```
+    pub fn epoch_ok_filter_mut(&mut self, power_failure: bool) -> Result<(), Error> {
+        let rinex_type = self.header.rinex_type;
+        let record = self
+            .record
+            .as_mut_obs()
+            .ok_or(Error::NoEpochFlags(rinex_type))?;
+        record.retain(|(_, flag), _| {
+            flag.is_ok() || (power_failure && *flag == EpochFlag::PowerFailure)
+        });
+        Ok(())
+    }
+    /// [`Self::epoch_ok_filter_mut`] immutable implementation.
+    pub fn epoch_ok_filter(&self, power_failure: bool) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.epoch_ok_filter_mut(power_failure)?;
+        Ok(s)
+    }
+    /// Retains only abnormal epochs, that is all [`Self::epoch_anomalies`],
+    /// which is convenient to inspect events.
+    /// This is the complement of [`Self::epoch_ok_filter_mut`] (without power failures).
+    /// Only OBS RINEX have epoch flags: other RINEX are left untouched and
+    /// [`Error::NoEpochFlags`] is returned.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_flags.22O")
+    ///     .unwrap();
+    /// rnx.epoch_nok_filter_mut()
+    ///     .unwrap();
+    /// assert_eq!(rnx.epoch().count(), 1);
+    /// assert_eq!(rnx.epoch_ok().count(), 0);
+    /// ```
+    pub fn epoch_nok_filter_mut(&mut self) -> Result<(), Error> {
+        let rinex_type = self.header.rinex_type;
+        let record = self
+            .record
+            .as_mut_obs()
+            .ok_or(Error::NoEpochFlags(rinex_type))?;
+        record.retain(|(_, flag), _| !flag.is_ok());
+        Ok(())
+    }
+    /// [`Self::epoch_nok_filter_mut`] immutable implementation.
+    pub fn epoch_nok_filter(&self) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.epoch_nok_filter_mut()?;
+        Ok(s)
+    }
+    /// Exports observations as a rectangular matrix, for analysis pipelines:
+    /// one row per [`Epoch`], one column per ([`SV`], [`Observable`]) pair.
+    /// Columns are sorted by [`SV`] then by [`Observable`].
+    /// Only `observables` are exported, or all of them when this list is empty.
+    /// Missing observations are set to NaN.
+    /// Returns (rows, columns, values), values being indexed [row][column].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let c1c = Observable::from_str("C1C").unwrap();
+    /// let (rows, columns, values) = rnx.observation_matrix(&[c1c.clone()]);
+    /// assert_eq!(rows.len(), 3);
+    /// assert_eq!(columns[0], (SV::from_str("G01").unwrap(), c1c));
+    /// assert_eq!(values[0][0], 20243517.560);
+    /// ```
+    pub fn observation_matrix(
+        &self,
+        observables: &[Observable],
+    ) -> (Vec<Epoch>, Vec<(SV, Observable)>, Vec<Vec<f64>>) {
+        let record = match self.record.as_obs() {
+            Some(record) => record,
+            None => return (Vec::new(), Vec::new(), Vec::new()),
+        };
+        let mut columns = BTreeSet::<(SV, Observable)>::new();
+        for (_, vehicles) in record.values() {
+            for (sv, observations) in vehicles {
+                for observable in observations.keys() {
+                    if observables.is_empty() || observables.contains(observable) {
+                        columns.insert((*sv, observable.clone()));
+                    }
+                }
+            }
+        }
+        let columns: Vec<(SV, Observable)> = columns.into_iter().collect();
+        let rows: Vec<Epoch> = self.epoch().collect();
+        let mut values = vec![vec![f64::NAN; columns.len()]; rows.len()];
+        for ((epoch, _), (_, vehicles)) in record {
+            let row = match rows.binary_search(epoch) {
+                Ok(row) => row,
+                Err(_) => continue,
+            };
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    if let Ok(col) = columns.binary_search(&(*sv, observable.clone())) {
+                        values[row][col] = data.obs;
+                    }
+                }
+            }
+        }
+        (rows, columns, values)
+    }
+    /// Writes [Self::observation_matrix] into a CSV file.
+    /// First row describes the columns: "epoch", then "SV:observable" (for example "G01:C1C").
+    /// Missing observations are left empty.
+    pub fn to_csv(&self, path: &str, observables: &[Observable]) -> Result<(), Error> {
+        let (rows, columns, values) = self.observation_matrix(observables);
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write!(writer, "epoch")?;
+        for (sv, observable) in &columns {
+            write!(writer, ",{:x}:{}", sv, observable)?;
+        }
+        writeln!(writer)?;
+        for (epoch, values) in rows.iter().zip(values.iter()) {
+            write!(writer, "{}", epoch)?;
+            for value in values {
+                if value.is_nan() {
+                    write!(writer, ",")?;
+                } else {
+                    write!(writer, ",{}", value)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+    /// Exports [Self::observation_matrix] as a 2D array,
+    /// indexed [epoch][(SV, observable)].
+    #[cfg(feature = "ndarray")]
+    #[cfg_attr(docrs, doc(cfg(feature = "ndarray")))]
+    pub fn to_ndarray(&self, observables: &[Observable]) -> ndarray::Array2<f64> {
+        let (rows, columns, values) = self.observation_matrix(observables);
+        ndarray::Array2::from_shape_fn((rows.len(), columns.len()), |(i, j)| values[i][j])
+    }
+    /// Renders the signal strength [dB-Hz] of given [SV] over time, into a PNG file.
+    /// Each signal strength observable (S1C, S2W..) is drawn as one curve.
+    /// When this file does not contain such observables, the SNR indications
+    /// attached to each observation are used instead.
+    /// X axis spans the observation period and Y axis starts at 0 dB-Hz.
+    /// Axes are not annotated, to avoid depending on system fonts.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let path = std::env::temp_dir().join("g01-snr.png");
+    /// rnx.plot_snr(g01, path.to_str().unwrap())
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "plot")]
+    #[cfg_attr(docrs, doc(cfg(feature = "plot")))]
+    pub fn plot_snr(&self, sv: SV, path: &str) -> Result<(), Error> {
+        use plotters::prelude::*;
+        let t0 = self.first_epoch().unwrap_or_default();
+        let mut series: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+        for ((epoch, _), svnn, observable, value) in self.ssi() {
+            if svnn == sv {
+                let dt = (epoch - t0).to_seconds();
+                series
+                    .entry(observable.to_string())
+                    .or_default()
+                    .push((dt, value));
+            }
+        }
+        if series.is_empty() {
+            for ((epoch, _), svnn, observable, snr) in self.snr() {
+                if svnn == sv {
+                    let dt = (epoch - t0).to_seconds();
+                    series
+                        .entry(observable.to_string())
+                        .or_default()
+                        .push((dt, snr.as_db_hz()));
+                }
+            }
+        }
+
+        let t_max = self
+            .last_epoch()
+            .map(|t| (t - t0).to_seconds())
+            .unwrap_or_default()
+            .max(1.0);
+        let y_max = series
+            .values()
+            .flatten()
+            .map(|(_, y)| *y)
+            .fold(60.0_f64, f64::max);
+
+        let plot_err = |e: DrawingAreaErrorKind<_>| Error::PlotError(e.to_string());
+        let root = BitMapBackend::new(path, (1024, 768)).into_drawing_area();
+        root.fill(&WHITE).map_err(plot_err)?;
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .build_cartesian_2d(0.0..t_max, 0.0..y_max)
+            .map_err(plot_err)?;
+        for (index, points) in series.into_values().enumerate() {
+            let style = Palette99::pick(index).stroke_width(2);
+            chart
+                .draw_series(LineSeries::new(points, style))
+                .map_err(plot_err)?;
+        }
+        root.present().map_err(plot_err)?;
+        Ok(())
+    }
     /// Returns an iterator over all [`Epoch`]s where
     /// a Cycle Slip is declared by the receiver
     /// ```
@@ -1841,6 +3685,33 @@ impl Rinex {
             }
         }))
     }
+    /// Returns an iterator over all [`Epoch`]s where at least one observation
+    /// comes with the [observation::LliFlags::LOCK_LOSS] bit set,
+    /// whatever the other LLI bits may be. Only relevant on OBS RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// for epoch in rnx.lock_loss_epochs() {
+    ///     // receiver lost lock on at least one signal
+    /// }
+    /// ```
+    pub fn lock_loss_epochs(&self) -> Box<dyn Iterator<Item = Epoch> + '_> {
+        Box::new(self.observation().filter_map(|((e, _), (_, vehicles))| {
+            let lock_loss = vehicles.values().any(|observations| {
+                observations.values().any(|data| {
+                    data.lli
+                        .map(|lli| lli.intersects(observation::LliFlags::LOCK_LOSS))
+                        .unwrap_or(false)
+                })
+            });
+            if lock_loss {
+                Some(*e)
+            } else {
+                None
+            }
+        }))
+    }
     /// Returns an iterator over receiver clock offsets, expressed in seconds.
     /// Such information is kind of rare (modern / dual frequency receivers?)
     /// and we don't have a compelling example yet.
@@ -1859,6 +3730,123 @@ impl Rinex {
                 .filter_map(|(e, (clk, _))| clk.as_ref().map(|clk| (*e, *clk))),
         )
     }
+    /// Injects receiver clock offsets [s], indexed by sampling [`Epoch`],
+    /// for example when synthesizing Observation RINEX.
+    /// Offsets apply whatever the [`EpochFlag`], and offsets that do not
+    /// match any epoch of the record are ignored.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::collections::BTreeMap;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let offsets: BTreeMap<Epoch, f64> = rnx.epoch()
+    ///     .map(|t| (t, 1.0E-6))
+    ///     .collect();
+    /// rnx.with_receiver_clock_offsets_mut(&offsets);
+    /// assert_eq!(rnx.recvr_clock().count(), 3);
+    /// ```
+    pub fn with_receiver_clock_offsets_mut(&mut self, offsets: &BTreeMap<Epoch, f64>) {
+        if let Some(record) = self.record.as_mut_obs() {
+            for ((epoch, _), (clk, _)) in record.iter_mut() {
+                if let Some(offset) = offsets.get(epoch) {
+                    *clk = Some(*offset);
+                }
+            }
+        }
+    }
+    /// Copies and returns [Self] with given receiver clock offsets,
+    /// see [Self::with_receiver_clock_offsets_mut].
+    pub fn with_receiver_clock_offsets(&self, offsets: &BTreeMap<Epoch, f64>) -> Self {
+        let mut s = self.clone();
+        s.with_receiver_clock_offsets_mut(offsets);
+        s
+    }
+    /// Returns the receiver clock drift [s/s], obtained by differencing
+    /// successive receiver clock offsets over their time spacing.
+    /// Each drift value is indexed by the latter [`Epoch`], so this
+    /// series has one fewer point than [Self::recvr_clock].
+    /// ```
+    /// use rinex::prelude::Rinex;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/VLNS0010.22O")
+    ///     .unwrap();
+    /// let drift = rnx.receiver_clock_drift();
+    /// assert_eq!(drift.len(), rnx.recvr_clock().count() - 1);
+    /// ```
+    pub fn receiver_clock_drift(&self) -> BTreeMap<Epoch, f64> {
+        self.recvr_clock()
+            .tuple_windows()
+            .filter_map(|(((t0, _), clk0), ((t1, _), clk1))| {
+                let dt = (t1 - t0).to_seconds();
+                if dt > 0.0 {
+                    Some((t1, (clk1 - clk0) / dt))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+    /// Declares a scaling `factor` for all observables of given [Constellation]
+    /// (SYS / SCALE FACTOR without list of observables), in place:
+    /// observations are multiplied accordingly. Observables that have their
+    /// own scaling factor are not affected, because that factor takes precedence
+    /// (see [observation::HeaderFields::scaling_factor]).
+    /// A factor of 1 removes the system wide scaling, null factors are ignored.
+    /// Only applies to Observation RINEX.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let scaled = rnx.apply_data_scaling(Constellation::GPS, 1000);
+    /// // scaling is transparent to physical value iterators
+    /// assert_eq!(
+    ///     scaled.carrier_phase().count(),
+    ///     rnx.carrier_phase().count(),
+    /// );
+    /// ```
+    pub fn apply_data_scaling_mut(&mut self, constellation: Constellation, factor: u16) {
+        if factor == 0 {
+            return;
+        }
+        let (header, record) = match (self.header.obs.as_mut(), self.record.as_mut_obs()) {
+            (Some(header), Some(record)) => (header, record),
+            _ => return,
+        };
+        let current = header.scaling_factors();
+        if factor == 1 {
+            header.data_scaling.remove(&constellation);
+        } else {
+            header.data_scaling.insert(constellation, factor);
+        }
+        observation::record::rescale_mut(record, &current, &header.scaling_factors());
+    }
+    /// Copies and returns Self with system wide data scaling.
+    /// See [Self::apply_data_scaling_mut].
+    pub fn apply_data_scaling(&self, constellation: Constellation, factor: u16) -> Self {
+        let mut s = self.clone();
+        s.apply_data_scaling_mut(constellation, factor);
+        s
+    }
+    /// Removes all data scaling (system wide and per observable), in place:
+    /// observations are divided by their scaling factor, so the record
+    /// contains physical values and the header no longer declares any factor.
+    /// Only applies to Observation RINEX.
+    pub fn remove_data_scaling_mut(&mut self) {
+        let (header, record) = match (self.header.obs.as_mut(), self.record.as_mut_obs()) {
+            (Some(header), Some(record)) => (header, record),
+            _ => return,
+        };
+        observation::record::rescale_mut(record, &header.scaling_factors(), &HashMap::new());
+        header.scaling.clear();
+        header.data_scaling.clear();
+    }
+    /// Copies and returns Self without data scaling.
+    /// See [Self::remove_data_scaling_mut].
+    pub fn remove_data_scaling(&self) -> Self {
+        let mut s = self.clone();
+        s.remove_data_scaling_mut();
+        s
+    }
     /// Returns an iterator over phase data, expressed in (whole) carrier cycles.
     /// If Self is a High Precision RINEX (scaled RINEX), data is correctly scaled.
     /// High precision RINEX allows up to 100 pico carrier cycle precision.
@@ -1889,9 +3877,9 @@ impl Rinex {
                         if let Some(header) = &self.header.obs {
                             // apply a scaling (if any), otherwise preserve data precision
                             if let Some(scaling) =
-                                header.scaling(sv.constellation, observable.clone())
+                                header.scaling_factor(sv.constellation, observable)
                             {
-                                Some((*e, *sv, observable, obsdata.obs / *scaling as f64))
+                                Some((*e, *sv, observable, obsdata.obs / scaling as f64))
                             } else {
                                 Some((*e, *sv, observable, obsdata.obs))
                             }
@@ -2093,6 +4081,42 @@ impl Rinex {
             })
         }))
     }
+    /// Returns the (min, max) signal strength, scanned over all
+    /// signal strength (Sx) observations. Returns None if no such
+    /// observation exists.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    /// let (min, max) = rnx.sig_strength_range()
+    ///     .unwrap();
+    /// assert!(min <= max);
+    /// ```
+    pub fn sig_strength_range(&self) -> Option<(f64, f64)> {
+        self.ssi()
+            .fold(None, |range, (_, _, _, value)| match range {
+                Some((min, max)) => Some((f64::min(min, value), f64::max(max, value))),
+                None => Some((value, value)),
+            })
+    }
+    /// Returns (min, mean, max) signal strength per [`SV`],
+    /// scanned over all signal strength (Sx) observations.
+    pub fn sig_strength_per_sv(&self) -> BTreeMap<SV, (f64, f64, f64)> {
+        let mut acc: BTreeMap<SV, (f64, f64, f64, usize)> = BTreeMap::new();
+        for (_, sv, _, value) in self.ssi() {
+            if let Some((min, sum, max, n)) = acc.get_mut(&sv) {
+                *min = f64::min(*min, value);
+                *max = f64::max(*max, value);
+                *sum += value;
+                *n += 1;
+            } else {
+                acc.insert(sv, (value, value, value, 1));
+            }
+        }
+        acc.into_iter()
+            .map(|(sv, (min, sum, max, n))| (sv, (min, sum / n as f64, max)))
+            .collect()
+    }
     /// Returns an Iterator over "complete" Epochs.
     /// "Complete" Epochs are Epochs were both Phase and Pseudo Range
     /// observations are present on two carriers, sane sampling conditions are met
@@ -2170,11 +4194,152 @@ impl Rinex {
             HashMap::new()
         }
     }
+    /// Returns MP1 and MP2 code multipath combinations, per SV and per epoch,
+    /// like teqc. The best available code and phase observables are selected
+    /// for each constellation (for example C1W over C1C).
+    /// Values are centered on each continuous arc: arcs restart on
+    /// cycle slips (LLI), special events and data gaps.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let multipath = rnx.code_multipath_mp12();
+    /// let (mp1, mp2) = multipath[&g01].values().next().unwrap();
+    /// assert!(mp1.is_some() && mp2.is_some());
+    /// ```
+    pub fn code_multipath_mp12(&self) -> BTreeMap<SV, BTreeMap<Epoch, (Option<f64>, Option<f64>)>> {
+        if let Some(r) = self.record.as_obs() {
+            code_multipath_mp12(r, &self.header)
+        } else {
+            BTreeMap::new()
+        }
+    }
+    /// Returns MP1 and MP2 code multipath statistics, per SV,
+    /// see [Self::code_multipath_mp12].
+    pub fn code_multipath_mp12_summary(&self) -> BTreeMap<SV, MultipathSummary> {
+        if let Some(r) = self.record.as_obs() {
+            code_multipath_mp12_summary(r, &self.header)
+        } else {
+            BTreeMap::new()
+        }
+    }
+    /// Returns the Geometry Free (GF) combination, in meters, per SV and per epoch.
+    /// This is the [Combination::GeometryFree] of [Combine], reduced to the preferred
+    /// signals of each SV: the L1 reference and the second frequency of each constellation
+    /// (same bands as [Self::code_multipath_mp12]).
+    /// Combines phase observations (converted to meters) when `code` is false:
+    /// GF = λ1 L1 - λ2 L2, pseudo ranges otherwise: GF = P2 - P1, so both grow with
+    /// the ionospheric delay. Epochs missing either observable are skipped.
+    /// GF cancels geometry and clock terms: over a continuous phase arc,
+    /// it only varies with the ionosphere.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let gf = rnx.gf_combination(false);
+    /// assert!(gf[&g01].len() > 0);
+    /// ```
+    pub fn gf_combination(&self, code: bool) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+        preferred_combination(self.combine(Combination::GeometryFree), code)
+    }
+    /// Returns the Ionosphere Free (IF) combination, in meters, per SV and per epoch:
+    /// IF = (f1² X1 - f2² X2) / (f1² - f2²), see [Combination::IonosphereFree].
+    /// See [Self::gf_combination] for signal selection and `code` usage.
+    pub fn if_combination(&self, code: bool) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+        preferred_combination(self.combine(Combination::IonosphereFree), code)
+    }
+    /// Returns the Wide Lane (WL) combination, in meters, per SV and per epoch:
+    /// WL = (f1 X1 - f2 X2) / (f1 - f2), see [Combination::WideLane].
+    /// See [Self::gf_combination] for signal selection and `code` usage.
+    pub fn wl_combination(&self, code: bool) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+        preferred_combination(self.combine(Combination::WideLane), code)
+    }
+    /// Returns the Narrow Lane (NL) combination, in meters, per SV and per epoch:
+    /// NL = (f1 X1 + f2 X2) / (f1 + f2), see [Combination::NarrowLane].
+    /// See [Self::gf_combination] for signal selection and `code` usage.
+    pub fn nl_combination(&self, code: bool) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+        preferred_combination(self.combine(Combination::NarrowLane), code)
+    }
+    /// Returns the code carrier divergence [m/s], per SV and per epoch:
+    /// the time derivative of the code minus phase difference, on a single frequency.
+    /// Signals are selected on the first band of each constellation (same as MP1
+    /// in [Self::code_multipath_mp12]). The divergence is evaluated within continuous
+    /// arcs: first epoch of each arc, cycle slips (LLI) and data gaps do not produce a value.
+    /// Code minus phase is twice the ionospheric delay plus multipath and noise:
+    /// its rate reveals ionospheric activity and receiver issues.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let divergence = rnx.code_carrier_divergence();
+    /// for (_, rate) in divergence[&g01].iter() {
+    ///     assert!(rate.abs() < 1.0);
+    /// }
+    /// ```
+    pub fn code_carrier_divergence(&self) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+        if let Some(r) = self.record.as_obs() {
+            code_carrier_divergence(r, &self.header)
+        } else {
+            BTreeMap::new()
+        }
+    }
+    /// Levels phase observations to pseudo range, so both can be
+    /// visualized on the same scale. Phase is converted to meters, then shifted by the
+    /// mean code minus phase difference of each continuous arc.
+    /// Arcs are delimited by cycle slips (LLI), data gaps and special events.
+    /// Each phase observable is leveled to the code observed on the same frequency band,
+    /// preferably with the same attribute: arcs without any code observation are dropped.
+    /// Returns leveled phase [m] per SV, per epoch and per phase observable code.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// let g01 = SV::from_str("G01").unwrap();
+    /// let leveled = rnx.level_phase_to_code();
+    /// let (_, phases) = leveled[&g01].iter().next().unwrap();
+    /// assert!(phases.contains_key("L1C"));
+    /// ```
+    pub fn level_phase_to_code(&self) -> BTreeMap<SV, BTreeMap<Epoch, HashMap<String, f64>>> {
+        if let Some(r) = self.record.as_obs() {
+            level_phase_to_code(r, &self.header)
+        } else {
+            BTreeMap::new()
+        }
+    }
+    /// Lists the RTCM3 MSM message numbers that would represent each epoch,
+    /// one per constellation. This is a mapping, not an encoder, that helps
+    /// planning RTCM streaming. The lowest standard resolution message carrying
+    /// all observed quantities is selected: MSM1 (code), MSM2 (phase),
+    /// MSM3 (code and phase), MSM4 (+ C/N0) and MSM5 (+ doppler).
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+    ///     .unwrap();
+    /// let t0 = Epoch::from_str("2021-12-21T00:00:00 GPST").unwrap();
+    /// let messages = rnx.rtcm_msm_summary();
+    /// // code, phase and C/N0 for GPS, Glonass, Galileo and BeiDou: MSM4
+    /// assert_eq!(messages[&t0], vec![1074, 1084, 1094, 1124]);
+    /// ```
+    pub fn rtcm_msm_summary(&self) -> BTreeMap<Epoch, Vec<u16>> {
+        if let Some(r) = self.record.as_obs() {
+            rtcm_msm_summary(r)
+        } else {
+            BTreeMap::new()
+        }
+    }
 }
 
 #[cfg(feature = "nav")]
 use crate::navigation::{
-    BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NavMsgType, NgModel, StoMessage,
+    BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NavMsgType, NgModel, StoMessage, SvHealth,
 };
 
 //#[cfg(feature = "nav")]
@@ -2310,8 +4475,237 @@ impl Rinex {
             })
             .min_by_key(|(toe_i, _)| (t - *toe_i))
     }
+    /// Selects the Ephemeris frame to be used to navigate `sv` at instant `t`:
+    /// the frame whose time of ephemeris (toe) is the closest to `t`, on either side,
+    /// within the validity period of this vehicle (see [Ephemeris::validity_period]).
+    /// Frames of vehicles declared unhealthy (see [SvHealth::is_healthy]) are discarded,
+    /// frames with unknown health indications (see [SvHealth::is_unknown]) are preserved.
+    /// Frames that do not describe a toe (SBAS, Glonass, CNAV) are referenced to their time of clock.
+    /// Returns (toe, ephemeris frame), like [Self::sv_ephemeris].
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let g19 = SV::from_str("G19").unwrap();
+    /// let t = Epoch::from_str("2021-01-01T14:30:00 GPST").unwrap();
+    /// assert!(rinex.ephemeris_valid_at(g19, t).is_some());
+    /// // beyond validity period
+    /// let t = Epoch::from_str("2021-01-01T17:00:00 GPST").unwrap();
+    /// assert!(rinex.ephemeris_valid_at(g19, t).is_none());
+    /// ```
+    pub fn ephemeris_valid_at(&self, sv: SV, t: Epoch) -> Option<(Epoch, &Ephemeris)> {
+        self.ephemeris_frame_valid_at(sv, t)
+            .map(|(_, toe, eph)| (toe, eph))
+    }
+    /*
+     * Ephemeris frame selection, see [Self::ephemeris_valid_at].
+     * Returns (toc, toe, ephemeris frame): the clock polynomial is defined about toc.
+     */
+    fn ephemeris_frame_valid_at(&self, sv: SV, t: Epoch) -> Option<(Epoch, Epoch, &Ephemeris)> {
+        let validity = Ephemeris::validity_period(sv)?;
+        self.ephemeris()
+            .filter_map(|(toc, (msg, svnn, eph))| {
+                if svnn != sv {
+                    return None;
+                }
+                let healthy = eph
+                    .sv_health(sv.constellation)
+                    .map(|health| health.is_healthy() || health.is_unknown())
+                    .unwrap_or(true);
+                if !healthy {
+                    return None;
+                }
+                let toe = match msg {
+                    NavMsgType::CNAV => *toc,
+                    _ => sv
+                        .timescale()
+                        .and_then(|ts| eph.toe_gpst(ts))
+                        .unwrap_or(*toc),
+                };
+                let dt = (t - toe).abs();
+                if dt <= validity {
+                    Some((dt, *toc, toe, eph))
+                } else {
+                    None
+                }
+            })
+            .min_by_key(|(dt, _, _, _)| *dt)
+            .map(|(_, toc, toe, eph)| (toc, toe, eph))
+    }
+    /// Evaluates the clock bias [s] of `sv` at instant `t`, from the broadcast
+    /// clock model a0 + a1·dt + a2·dt², where dt = t - toc, handling week crossings.
+    /// The Ephemeris frame with closest time of clock (toc) is selected, within the
+    /// validity period of this constellation (see [Ephemeris::max_dtoe]).
+    /// Glonass frames describe -TauN and GammaN only, the third field being the message frame time.
+    /// When `relativistic` is true, the relativistic correction is added,
+    /// when Keplerian parameters are available.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let g20 = SV::from_str("G20").unwrap();
+    /// let t = Epoch::from_str("2021-01-01T16:15:00 GPST").unwrap();
+    /// let bias = rinex.sv_clock_bias(g20, t, false)
+    ///     .unwrap();
+    /// assert!((bias - 5.253581330180e-04).abs() < 1.0E-9);
+    /// ```
+    pub fn sv_clock_bias(&self, sv: SV, t: Epoch, relativistic: bool) -> Option<f64> {
+        let (toc, ephemeris) = self.sv_clock_ephemeris(sv, t)?;
+        let (a0, a1, a2) = ephemeris.sv_clock();
+        let a2 = match sv.constellation {
+            Constellation::Glonass => 0.0,
+            _ => a2,
+        };
+        const WEEK_SECONDS: f64 = 604800.0;
+        let mut dt = (t - toc).to_seconds();
+        if dt > WEEK_SECONDS / 2.0 {
+            dt -= WEEK_SECONDS;
+        } else if dt < -WEEK_SECONDS / 2.0 {
+            dt += WEEK_SECONDS;
+        }
+        let mut bias = a0 + a1 * dt + a2 * dt.powi(2);
+        if relativistic {
+            if let Some(helper) = ephemeris.ephemeris_helper(sv, t) {
+                bias += helper.dtr;
+            }
+        }
+        Some(bias)
+    }
+    /// Evaluates the clock correction [s] of `sv` at instant `t`, to apply to
+    /// single frequency pseudo ranges of the reference signal: the broadcast clock model
+    /// including relativistic correction (see [Self::sv_clock_bias]), minus the group delay
+    /// broadcast in the same frame (see [Ephemeris::group_delay]).
+    /// Glonass frames describe -TauN + GammaN·dt, without group delay.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let g20 = SV::from_str("G20").unwrap();
+    /// let t = Epoch::from_str("2021-01-01T16:15:00 GPST").unwrap();
+    /// let correction = rinex.sv_clock_correction(g20, t)
+    ///     .unwrap();
+    /// assert!((correction - 5.253582087e-04).abs() < 1.0E-12);
+    /// ```
+    pub fn sv_clock_correction(&self, sv: SV, t: Epoch) -> Option<f64> {
+        let (_, ephemeris) = self.sv_clock_ephemeris(sv, t)?;
+        let bias = self.sv_clock_bias(sv, t, true)?;
+        Some(bias - ephemeris.group_delay(sv.constellation).unwrap_or(0.0))
+    }
+    /*
+     * Selects the Ephemeris frame of `sv` with closest time of clock (toc) to `t`,
+     * within the validity period of this constellation
+     */
+    fn sv_clock_ephemeris(&self, sv: SV, t: Epoch) -> Option<(Epoch, &Ephemeris)> {
+        let max_dtoe = Ephemeris::max_dtoe(sv.constellation)?;
+        self.ephemeris()
+            .filter_map(|(toc, (_, svnn, eph))| {
+                if svnn == sv && (t - *toc).abs() <= max_dtoe {
+                    Some((*toc, eph))
+                } else {
+                    None
+                }
+            })
+            .min_by_key(|(toc, _)| (t - *toc).abs())
+    }
+    /// Evaluates the clock bias [s] of all vehicles at instant `t`,
+    /// see [Self::sv_clock_bias]. Vehicles without valid Ephemeris are omitted.
+    pub fn sv_clock_biases(&self, t: Epoch, relativistic: bool) -> BTreeMap<SV, f64> {
+        self.ephemeris()
+            .map(|(_, (_, sv, _))| sv)
+            .unique()
+            .filter_map(|sv| Some((sv, self.sv_clock_bias(sv, t, relativistic)?)))
+            .collect()
+    }
+    /// Returns the [SvHealth] broadcast by `sv` in the Ephemeris frame
+    /// published at `epoch` (time of clock), if any.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// for (toc, (_, sv, _)) in rinex.ephemeris() {
+    ///     if let Some(health) = rinex.health(sv, *toc) {
+    ///         assert!(health.is_healthy());
+    ///     }
+    /// }
+    /// ```
+    pub fn health(&self, sv: SV, epoch: Epoch) -> Option<SvHealth> {
+        self.record
+            .as_nav()?
+            .get(&epoch)?
+            .iter()
+            .find_map(|fr| match fr.as_eph() {
+                Some((_, svnn, eph)) if svnn == sv => eph.sv_health(sv.constellation),
+                _ => None,
+            })
+    }
+    /// Retains Ephemeris frames of vehicles declared healthy only,
+    /// so they are not used in orbit determination.
+    /// Health encoding is constellation dependent, see [SvHealth::is_healthy].
+    /// Frames with unknown health indications (like all SBAS vehicles,
+    /// see [SvHealth::is_unknown]) are preserved only if `keep_unknown` is true.
+    /// Ephemeris frames that do not describe any health indication are preserved,
+    /// as are all other frames.
+    pub fn retain_healthy_sv_mut(&mut self, keep_unknown: bool) {
+        if let Some(rec) = self.record.as_mut_nav() {
+            rec.retain(|_, frames| {
+                frames.retain(|fr| match fr.as_eph() {
+                    Some((_, sv, eph)) => eph
+                        .sv_health(sv.constellation)
+                        .map(|health| health.is_healthy() || (keep_unknown && health.is_unknown()))
+                        .unwrap_or(true),
+                    None => true,
+                });
+                !frames.is_empty()
+            });
+        }
+    }
+    /// Copies and returns Self, with Ephemeris frames of
+    /// vehicles declared healthy only. See [Self::retain_healthy_sv_mut].
+    pub fn retain_healthy_sv(&self, keep_unknown: bool) -> Self {
+        let mut s = self.clone();
+        s.retain_healthy_sv_mut(keep_unknown);
+        s
+    }
+    /// Re-expresses all Navigation epochs in given [TimeScale].
+    /// Broadcast epochs (time of clock) are parsed in the timescale of each constellation
+    /// (for example BDT for BeiDou, GST for Galileo), which is preserved
+    /// by the iterators like [Self::sv_clock] or [Self::sv_position].
+    /// Use this to express all of them in a common timescale, which is preferred
+    /// when working with mixed constellations. Frames are still formatted in
+    /// the native timescale of each constellation, as RINEX expects.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap()
+    ///     .with_timescale(TimeScale::GPST);
+    /// for (toc, _, _) in rinex.sv_clock() {
+    ///     assert_eq!(toc.time_scale, TimeScale::GPST);
+    /// }
+    /// ```
+    pub fn with_timescale(&self, ts: TimeScale) -> Self {
+        let mut s = self.clone();
+        s.with_timescale_mut(ts);
+        s
+    }
+    /// Re-expresses all Navigation epochs in given [TimeScale], see [Self::with_timescale].
+    pub fn with_timescale_mut(&mut self, ts: TimeScale) {
+        if let Some(rec) = self.record.as_mut_nav() {
+            let mut converted = navigation::Record::new();
+            for (epoch, frames) in std::mem::take(rec) {
+                converted
+                    .entry(epoch.to_time_scale(ts))
+                    .or_default()
+                    .extend(frames);
+            }
+            *rec = converted;
+        }
+    }
     /// Returns an Iterator over SV (embedded) clock offset (s), drift (s.s⁻¹) and
-    /// drift rate (s.s⁻²)
+    /// drift rate (s.s⁻²). Epochs are expressed in the timescale of each vehicle,
+    /// see [Self::with_timescale].
     /// ```
     /// use rinex::prelude::*;
     /// let mut rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
@@ -2330,7 +4724,8 @@ impl Rinex {
         )
     }
     /// Returns an Iterator over SV position vectors,
-    /// expressed in km ECEF for all Epochs.
+    /// expressed in km ECEF for all Epochs. Epochs are expressed in the timescale
+    /// of each vehicle, see [Self::with_timescale].
     /// ```
     /// use rinex::prelude::*;
     ///
@@ -2544,6 +4939,109 @@ impl Rinex {
                 }),
         )
     }
+    /// Returns the visibility periods (passes) of each [SV] above `elev_mask` (in degrees),
+    /// as seen from `ground` position. SV positions are resolved from the ephemeris
+    /// of this NAV record (see [Self::ephemeris_valid_at]), every `sampling` period,
+    /// from the first to the last epoch of this record.
+    /// Each pass is described by its first and last visible sample. Passes are therefore
+    /// bounded by the record span: a vehicle already visible at the first epoch,
+    /// or still visible at the last epoch, counts as one (partial) pass.
+    /// Vehicles that are never visible are not returned.
+    /// ```
+    /// use rinex::wgs84;
+    /// use rinex::prelude::*;
+    /// let ref_pos = wgs84!(3582105.291, 532589.7313, 5232754.8054);
+    ///
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    ///
+    /// let passes = rinex.sv_passes(ref_pos, 10.0, Duration::from_seconds(60.0));
+    /// for (sv, passes) in passes {
+    ///     for (start, end) in passes {
+    ///         // visible from start to end
+    ///     }
+    /// }
+    /// ```
+    pub fn sv_passes(
+        &self,
+        ground: GroundPosition,
+        elev_mask: f64,
+        sampling: Duration,
+    ) -> HashMap<SV, Vec<(Epoch, Epoch)>> {
+        let mut passes = HashMap::<SV, Vec<(Epoch, Epoch)>>::new();
+        let (first, last) = match (self.first_epoch(), self.last_epoch()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return passes,
+        };
+        let vehicles: Vec<SV> = self
+            .ephemeris()
+            .map(|(_, (_, sv, _))| sv)
+            .unique()
+            .collect();
+        for sv in vehicles {
+            // ongoing pass: (first visible sample, last visible sample)
+            let mut pass: Option<(Epoch, Epoch)> = None;
+            for t in TimeSeries::inclusive(first, last, sampling) {
+                let visible = match self.ephemeris_valid_at(sv, t) {
+                    Some((_, ephemeris)) => match ephemeris.sv_elev_azim(sv, t, ground) {
+                        Some((elev, _)) => elev >= elev_mask,
+                        None => false,
+                    },
+                    None => false,
+                };
+                if visible {
+                    pass = match pass {
+                        Some((start, _)) => Some((start, t)),
+                        None => Some((t, t)),
+                    };
+                } else if let Some(p) = pass.take() {
+                    passes.entry(sv).or_default().push(p);
+                }
+            }
+            if let Some(p) = pass {
+                // still visible at the end of the record
+                passes.entry(sv).or_default().push(p);
+            }
+        }
+        passes
+    }
+    /// Returns the total visibility duration of each [SV] above `elev_mask` (in degrees),
+    /// as seen from `ground` position, accumulated over all of its [Self::sv_passes].
+    /// Each visible sample accounts for one `sampling` period, so a pass lasts
+    /// its last visible sample minus its first one, plus `sampling`.
+    /// Returned duration is bounded by the record span (plus one `sampling` period),
+    /// the number of distinct passes is given by [Self::sv_passes].
+    /// ```
+    /// use rinex::wgs84;
+    /// use rinex::prelude::*;
+    /// let ref_pos = wgs84!(3582105.291, 532589.7313, 5232754.8054);
+    ///
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    ///
+    /// let span = rinex.last_epoch().unwrap() - rinex.first_epoch().unwrap();
+    /// let sampling = Duration::from_seconds(60.0);
+    /// let visibility = rinex.sv_visibility(ref_pos, 10.0, sampling);
+    /// for (sv, duration) in visibility {
+    ///     assert!(duration <= span + sampling);
+    /// }
+    /// ```
+    pub fn sv_visibility(
+        &self,
+        ground: GroundPosition,
+        elev_mask: f64,
+        sampling: Duration,
+    ) -> HashMap<SV, Duration> {
+        self.sv_passes(ground, elev_mask, sampling)
+            .into_iter()
+            .map(|(sv, passes)| {
+                let total = passes.iter().fold(Duration::ZERO, |total, (start, end)| {
+                    total + (*end - *start) + sampling
+                });
+                (sv, total)
+            })
+            .collect()
+    }
     /*
      * [IonMessage] Iterator
      */
@@ -2768,6 +5266,254 @@ impl Rinex {
             })
         }))
     }
+    /// Returns [`IonMessage`] frames Iterator.
+    /// Only RINEX4 provides time varying ionosphere models in the record,
+    /// this is empty for older revisions: refer to [Self::ionosphere_model].
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+    ///     .unwrap();
+    /// for (epoch, (msg, sv, ion)) in rnx.nav_ion_frames() {
+    ///     if let Some(kb) = ion.as_klobuchar() {
+    ///         let (a0, a1, a2, a3) = kb.alpha;
+    ///     }
+    /// }
+    /// ```
+    pub fn nav_ion_frames(
+        &self,
+    ) -> Box<dyn Iterator<Item = (&Epoch, (NavMsgType, SV, &IonMessage))> + '_> {
+        Box::new(self.navigation().flat_map(|(e, frames)| {
+            frames.iter().filter_map(move |fr| {
+                if let Some((msg, sv, ion)) = fr.as_ion() {
+                    Some((e, (msg, sv, ion)))
+                } else {
+                    None
+                }
+            })
+        }))
+    }
+    /// Returns the broadcast ionosphere model, for applications only
+    /// interested in the model parameters (Klobuchar, NeQuick-G or BDGIM).
+    /// In RINEX2/3, the model is described in the [Header]:
+    /// the GPS model is preferred when several are provided.
+    /// In RINEX4, the first ION frame of the record is returned.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+    ///     .unwrap();
+    /// let model = rnx.ionosphere_model()
+    ///     .unwrap();
+    /// assert!(model.as_klobuchar().is_some());
+    /// ```
+    pub fn ionosphere_model(&self) -> Option<IonMessage> {
+        let header = &self.header.ionod_corrections;
+        if let Some(model) = header.get(&Constellation::GPS) {
+            Some(*model)
+        } else if let Some((_, model)) = header.iter().min_by_key(|(c, _)| **c) {
+            Some(*model)
+        } else {
+            self.nav_ion_frames().map(|(_, (_, _, ion))| *ion).next()
+        }
+    }
+}
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+use observation::spp::{SppCandidate, SppSolution, SPEED_OF_LIGHT};
+
+/*
+ * Positioning methods, combining OBS and NAV RINEX
+ */
+#[cfg(all(feature = "obs", feature = "nav"))]
+#[cfg_attr(docrs, doc(cfg(all(feature = "obs", feature = "nav"))))]
+impl Rinex {
+    /// Single Point Positioning at desired [`Epoch`], using Self (OBS RINEX)
+    /// and provided `nav` (NAV RINEX) for satellite positions and clock offsets.
+    /// Ephemeris are selected with [Self::ephemeris_valid_at], so unhealthy vehicles
+    /// and outdated frames are discarded. One receiver clock bias is estimated per [`Constellation`],
+    /// which absorbs inter system biases.
+    /// Ionosphere free pseudo range combinations are used when
+    /// two frequencies are observed, raw pseudo range otherwise.
+    /// When a carrier is tracked with several codes, the pseudo range is selected
+    /// by tracking mode, in C, P, W, Y, X, L, S, Q, I order, others coming last.
+    /// Only GPS, Galileo, BeiDou and QZSS vehicles contribute.
+    /// Apriori position is the header position (if any), Earth center otherwise.
+    /// Returns None if the problem is not solvable at this epoch.
+    pub fn single_point_position_multi(&self, nav: &Rinex, t: Epoch) -> Option<SppSolution> {
+        let record = self.record.as_obs()?;
+        let (_, vehicles) = record
+            .iter()
+            .find(|((e, flag), _)| *e == t && flag.is_ok())
+            .map(|(_, v)| v)?;
+
+        let mut candidates = Vec::<SppCandidate>::new();
+        for (sv, observations) in vehicles {
+            match sv.constellation {
+                Constellation::GPS
+                | Constellation::Galileo
+                | Constellation::BeiDou
+                | Constellation::QZSS => {},
+                _ => continue,
+            }
+            // gather one pseudo range per carrier:
+            // preferred tracking code first, whatever the storage order
+            let mut pseudo_ranges = Vec::<(Carrier, f64)>::new();
+            for (observable, data) in observations
+                .iter()
+                .sorted_by_key(|(observable, _)| observation::spp::code_priority(observable))
+            {
+                if !observable.is_pseudorange_observable() {
+                    continue;
+                }
+                if let Ok(carrier) = Carrier::from_observable(sv.constellation, observable) {
+                    if !pseudo_ranges.iter().any(|(c, _)| *c == carrier) {
+                        pseudo_ranges.push((carrier, data.obs));
+                    }
+                }
+            }
+            pseudo_ranges.sort_by(|(a, _), (b, _)| b.frequency().total_cmp(&a.frequency()));
+            let pr = match pseudo_ranges.as_slice() {
+                [] => continue,
+                [(_, pr)] => *pr,
+                [(c1, pr1), (c2, pr2), ..] => {
+                    let (f1, f2) = (c1.frequency().powi(2), c2.frequency().powi(2));
+                    (f1 * pr1 - f2 * pr2) / (f1 - f2)
+                },
+            };
+
+            let (toc, _, ephemeris) = match nav.ephemeris_frame_valid_at(*sv, t) {
+                Some(frame) => frame,
+                None => continue,
+            };
+            let t_tx = t - Duration::from_seconds(pr / SPEED_OF_LIGHT);
+            let dt_sv = Ephemeris::sv_clock_corr(*sv, ephemeris.sv_clock(), t_tx, toc);
+            let t_tx = t_tx - dt_sv;
+
+            if let Some((x_km, y_km, z_km)) = ephemeris.sv_position(*sv, t_tx) {
+                candidates.push(SppCandidate {
+                    constellation: sv.constellation,
+                    position: (x_km * 1.0E3, y_km * 1.0E3, z_km * 1.0E3),
+                    pseudo_range: pr + dt_sv.to_seconds() * SPEED_OF_LIGHT,
+                });
+            }
+        }
+
+        let apriori = match self.header.ground_position {
+            Some(position) => position.to_ecef_wgs84(),
+            None => (0.0, 0.0, 0.0),
+        };
+        observation::spp::solve(&candidates, apriori)
+    }
+    /// Returns elevation and azimuth rates of change, both in degrees per second,
+    /// for each SV observed in Self (OBS RINEX), as seen from the `ground` position
+    /// (meter ECEF WGS84). `nav` provides the ephemeris needed to resolve SV positions.
+    /// Rates are obtained by finite differencing the angle series, between two
+    /// consecutive observations of a given SV: the first observation does not have a rate.
+    /// Azimuth differences are wrapped so crossing the North does not create large rates.
+    /// Returns (d(elev)/dt, d(azim)/dt) per [`SV`] and per [`Epoch`].
+    pub fn sat_angle_rates(
+        &self,
+        nav: &Rinex,
+        ground: (f64, f64, f64),
+    ) -> BTreeMap<SV, BTreeMap<Epoch, (f64, f64)>> {
+        let mut angles = BTreeMap::<SV, BTreeMap<Epoch, (f64, f64)>>::new();
+        if let Some(record) = self.record.as_obs() {
+            for ((t, flag), (_, vehicles)) in record {
+                if !flag.is_ok() {
+                    continue;
+                }
+                for sv in vehicles.keys() {
+                    let ephemeris = match nav.ephemeris_valid_at(*sv, *t) {
+                        Some((_, eph)) => eph,
+                        None => continue,
+                    };
+                    if let Some((x_km, y_km, z_km)) = ephemeris.sv_position(*sv, *t) {
+                        let (elev, azim) = Ephemeris::elevation_azimuth(
+                            (x_km * 1.0E3, y_km * 1.0E3, z_km * 1.0E3),
+                            ground,
+                        );
+                        angles.entry(*sv).or_default().insert(*t, (elev, azim));
+                    }
+                }
+            }
+        }
+        angles
+            .into_iter()
+            .map(|(sv, series)| {
+                let rates = series
+                    .iter()
+                    .tuple_windows()
+                    .filter_map(|((t_0, (el_0, az_0)), (t_1, (el_1, az_1)))| {
+                        let dt = (*t_1 - *t_0).to_seconds();
+                        if dt <= 0.0 {
+                            return None;
+                        }
+                        let mut d_az = az_1 - az_0;
+                        if d_az > 180.0 {
+                            d_az -= 360.0;
+                        } else if d_az < -180.0 {
+                            d_az += 360.0;
+                        }
+                        Some((*t_1, ((el_1 - el_0) / dt, d_az / dt)))
+                    })
+                    .collect();
+                (sv, rates)
+            })
+            .collect()
+    }
+    /// Pairs each [`Epoch`] of Self (OBS RINEX) with the Ephemeris frame to use,
+    /// for each observed [`SV`], from provided `nav` (NAV RINEX).
+    /// OBS and NAV epochs rarely coincide: the closest Ephemeris frame (in time of clock)
+    /// is selected, as long as it lies within `tolerance` and within the
+    /// ephemeris validity period of that [`Constellation`].
+    /// Vehicles and epochs that cannot be paired are omitted.
+    /// Returns the NAV frame [`Epoch`], per [`SV`] and per OBS [`Epoch`].
+    pub fn match_nav_to_obs(
+        &self,
+        nav: &Rinex,
+        tolerance: Duration,
+    ) -> BTreeMap<Epoch, BTreeMap<SV, Epoch>> {
+        let mut ret = BTreeMap::<Epoch, BTreeMap<SV, Epoch>>::new();
+        let record = match self.record.as_obs() {
+            Some(record) => record,
+            None => return ret,
+        };
+        let mut frames = HashMap::<SV, BTreeSet<Epoch>>::new();
+        for (toc, (_, sv, _)) in nav.ephemeris() {
+            frames.entry(sv).or_default().insert(*toc);
+        }
+        for ((t, flag), (_, vehicles)) in record {
+            if !flag.is_ok() {
+                continue;
+            }
+            for sv in vehicles.keys() {
+                let tocs = match frames.get(sv) {
+                    Some(tocs) => tocs,
+                    None => continue,
+                };
+                let before = tocs.range(..=*t).next_back();
+                let after = tocs.range(*t..).next();
+                let toc = match before
+                    .into_iter()
+                    .chain(after)
+                    .min_by_key(|toc| (*t - **toc).abs())
+                {
+                    Some(toc) => *toc,
+                    None => continue,
+                };
+                let dt = (*t - toc).abs();
+                if dt > tolerance {
+                    continue;
+                }
+                if let Some(max_dtoe) = Ephemeris::max_dtoe(sv.constellation) {
+                    if dt > max_dtoe {
+                        continue;
+                    }
+                }
+                ret.entry(*t).or_default().insert(*sv, toc);
+            }
+        }
+        ret
+    }
 }
 
 /*
@@ -3041,22 +5787,193 @@ impl Merge for Rinex {
         lhs.merge_mut(rhs)?;
         Ok(lhs)
     }
-    /// Merges `rhs` into `Self` in place
+    /// Merges `rhs` into `Self` in place.
+    /// In case of Observation RINEX using different scaling factors,
+    /// both records are brought to a common scaling (the finest one) prior merging.
+    /// Observations defined in both are overwritten by `rhs`,
+    /// see [Rinex::merge_with_strategy] for other options.
     fn merge_mut(&mut self, rhs: &Self) -> Result<(), merge::Error> {
+        self.merge_mut_with_strategy(rhs, MergeStrategy::PreferOther)?;
+        Ok(())
+    }
+}
+
+impl Rinex {
+    /// Merges `rhs` into `Self`, like [Merge::merge], with control over
+    /// Observation RINEX conflicts: an observation (epoch, SV and observable)
+    /// defined in both with different values is resolved with given [MergeStrategy].
+    /// Returns the merged dataset and a report of all conflicts that were encountered.
+    /// [MergeStrategy::Error] aborts the operation on the first conflicting dataset,
+    /// with [merge::Error::MergeConflicts].
+    /// The strategy has no effect on other RINEX types.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::merge::MergeStrategy;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// // merging identical data does not create conflicts
+    /// let (merged, conflicts) = rnx.merge_with_strategy(&rnx, MergeStrategy::Error)
+    ///     .unwrap();
+    /// assert!(conflicts.is_empty());
+    /// assert_eq!(merged.record, rnx.record);
+    /// ```
+    pub fn merge_with_strategy(
+        &self,
+        rhs: &Self,
+        strategy: MergeStrategy,
+    ) -> Result<(Self, Vec<MergeConflict>), merge::Error> {
+        let mut lhs = self.clone();
+        let conflicts = lhs.merge_mut_with_strategy(rhs, strategy)?;
+        Ok((lhs, conflicts))
+    }
+    /// [Rinex::merge_with_strategy] mutable implementation.
+    /// Returns the report of all conflicts that were encountered.
+    pub fn merge_mut_with_strategy(
+        &mut self,
+        rhs: &Self,
+        strategy: MergeStrategy,
+    ) -> Result<Vec<MergeConflict>, merge::Error> {
+        let lhs_scaling = self
+            .header
+            .obs
+            .as_ref()
+            .map(|obs| obs.scaling_factors())
+            .unwrap_or_default();
+        let rhs_scaling = rhs
+            .header
+            .obs
+            .as_ref()
+            .map(|obs| obs.scaling_factors())
+            .unwrap_or_default();
+
+        let rescaled: Self;
+        let rhs = if lhs_scaling != rhs_scaling
+            && self.is_observation_rinex()
+            && rhs.is_observation_rinex()
+        {
+            // finest scaling, for each code
+            let mut scaling = lhs_scaling.clone();
+            for (key, factor) in &rhs_scaling {
+                let lhs_factor = scaling.entry(key.clone()).or_insert(1);
+                *lhs_factor = std::cmp::max(*lhs_factor, *factor);
+            }
+            if let Some(rec) = self.record.as_mut_obs() {
+                observation::record::rescale_mut(rec, &lhs_scaling, &scaling);
+            }
+            if let Some(obs) = &mut self.header.obs {
+                obs.scaling = scaling.clone();
+                obs.data_scaling.clear();
+            }
+            let mut rhs = rhs.clone();
+            if let Some(rec) = rhs.record.as_mut_obs() {
+                observation::record::rescale_mut(rec, &rhs_scaling, &scaling);
+            }
+            if let Some(obs) = &mut rhs.header.obs {
+                obs.scaling = scaling;
+                obs.data_scaling.clear();
+            }
+            rescaled = rhs;
+            &rescaled
+        } else {
+            rhs
+        };
+
+        // identify conflicts prior merging
+        let conflicts = match (self.record.as_obs(), rhs.record.as_obs()) {
+            (Some(lhs), Some(rhs)) => observation::record::merge_conflicts(lhs, rhs),
+            _ => Vec::new(),
+        };
+        if strategy == MergeStrategy::Error && !conflicts.is_empty() {
+            return Err(merge::Error::MergeConflicts(conflicts));
+        }
+
+        // merge boundary, stamped in the FILE MERGE comment
+        let boundary = match self.is_antex() {
+            true => Epoch::now()?,
+            false => match rhs.first_epoch() {
+                Some(t) => t,
+                None => Epoch::now()?,
+            },
+        };
+
+        // equipment swap at the merge boundary, not described by an event
+        let mut swap: Option<observation::EquipmentChange> = None;
+        if self.is_observation_rinex() && rhs.is_observation_rinex() && rhs.first_epoch().is_some()
+        {
+            let described = rhs
+                .header
+                .obs
+                .as_ref()
+                .map(|obs| obs.equipment_changes.contains_key(&boundary))
+                .unwrap_or(false);
+            if !described {
+                // equipment in use at the boundary
+                let changes = self.equipment_changes();
+                let previous = changes.iter().rev().filter(|(t, _)| *t < boundary);
+                let receiver = previous
+                    .clone()
+                    .find_map(|(_, change)| change.receiver.as_ref())
+                    .or(self.header.rcvr.as_ref());
+                let antenna = previous
+                    .clone()
+                    .find_map(|(_, change)| change.antenna.as_ref())
+                    .or(self.header.rcvr_antenna.as_ref());
+                let change = observation::EquipmentChange {
+                    receiver: rhs
+                        .header
+                        .rcvr
+                        .clone()
+                        .filter(|rcvr| receiver.map(|r| r != rcvr).unwrap_or(false)),
+                    antenna: rhs
+                        .header
+                        .rcvr_antenna
+                        .clone()
+                        .filter(|ant| antenna.map(|a| a != ant).unwrap_or(false)),
+                    ..Default::default()
+                };
+                if change.receiver.is_some() || change.antenna.is_some() {
+                    swap = Some(change);
+                }
+            }
+        }
+
         self.header.merge_mut(&rhs.header)?;
+        self.header.comments.push(Header::merge_comment(boundary));
+        // record comments remain attached to their epoch
+        for (epoch, content) in rhs.comments.iter() {
+            let comments = self.comments.entry(*epoch).or_default();
+            for comment in content.iter() {
+                if !comments.contains(comment) {
+                    comments.push(comment.clone());
+                }
+            }
+        }
+        for comment in rhs.trailing_comments.iter() {
+            if !self.trailing_comments.contains(comment) {
+                self.trailing_comments.push(comment.clone());
+            }
+        }
+        if let (Some(change), Some(obs)) = (swap, &mut self.header.obs) {
+            obs.equipment_changes.entry(boundary).or_insert(change);
+        }
         if !self.is_antex() {
             if self.epoch().count() == 0 {
                 // lhs is empty : overwrite
                 self.record = rhs.record.clone();
             } else if rhs.epoch().count() != 0 {
                 // real merge
-                self.record.merge_mut(&rhs.record)?;
+                match (self.record.as_mut_obs(), rhs.record.as_obs()) {
+                    (Some(lhs), Some(rhs)) if strategy == MergeStrategy::PreferSelf => {
+                        observation::record::merge_mut_prefer_self(lhs, rhs);
+                    },
+                    _ => self.record.merge_mut(&rhs.record)?,
+                }
             }
         } else {
             // real merge
             self.record.merge_mut(&rhs.record)?;
         }
-        Ok(())
+        Ok(conflicts)
     }
 }
 
@@ -3064,18 +5981,28 @@ impl Split for Rinex {
     /// Splits `Self` at desired epoch
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let (r0, r1) = self.record.split(epoch)?;
+        // record comments follow the epoch they are attached to
+        let (c0, c1): (record::Comments, record::Comments) = self
+            .comments
+            .iter()
+            .map(|(t, content)| (*t, content.clone()))
+            .partition(|(t, _)| *t < epoch);
         Ok((
             Self {
                 header: self.header.clone(),
-                comments: self.comments.clone(),
+                comments: c0,
+                trailing_comments: Vec::new(),
                 record: r0,
                 prod_attr: self.prod_attr.clone(),
+                warnings: self.warnings.clone(),
             },
             Self {
                 header: self.header.clone(),
-                comments: self.comments.clone(),
+                comments: c1,
+                trailing_comments: self.trailing_comments.clone(),
                 record: r1,
                 prod_attr: self.prod_attr.clone(),
+                warnings: self.warnings.clone(),
             },
         ))
     }
@@ -3140,7 +6067,13 @@ impl Decimate for Rinex {
         s
     }
     fn decimate_by_ratio_mut(&mut self, r: u32) {
+        if r < 2 {
+            return;
+        }
         self.record.decimate_by_ratio_mut(r);
+        if let Some(interval) = self.header.sampling_interval {
+            self.header.sampling_interval = Some(interval * r as f64);
+        }
     }
     fn decimate_by_interval(&self, dt: Duration) -> Self {
         let mut s = self.clone();
@@ -3160,6 +6093,73 @@ impl Decimate for Rinex {
     }
 }
 
+#[cfg(feature = "processing")]
+use crate::algorithm::{InterpMethod, Resample};
+
+#[cfg(feature = "processing")]
+#[cfg_attr(docrs, doc(cfg(feature = "processing")))]
+impl Resample for Rinex {
+    fn resample(&self, interval: Duration, method: InterpMethod) -> Self {
+        let mut s = self.clone();
+        s.resample_mut(interval, method);
+        s
+    }
+    /// Resamples OBS, CLK and METEO RINEX onto a new time grid.
+    /// Data gaps larger than twice the original sampling interval are not bridged.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use rinex::preprocessing::*;
+    /// let mut rinex = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m")
+    ///     .unwrap();
+    /// rinex.resample_mut(Duration::from_seconds(30.0), InterpMethod::Linear);
+    /// assert_eq!(rinex.sampling_interval(), Some(Duration::from_seconds(30.0)));
+    /// ```
+    fn resample_mut(&mut self, interval: Duration, method: InterpMethod) {
+        if let Some(dt) = self.sampling_interval() {
+            self.resample_with_max_gap_mut(interval, method, dt * 2.0);
+        }
+    }
+    fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) -> Self {
+        let mut s = self.clone();
+        s.resample_with_max_gap_mut(interval, method, max_gap);
+        s
+    }
+    fn resample_with_max_gap_mut(
+        &mut self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) {
+        if interval <= Duration::ZERO {
+            return; // null interval: nothing to resample onto
+        }
+        if let Some(r) = self.record.as_mut_obs() {
+            r.resample_with_max_gap_mut(interval, method, max_gap);
+            let rebuild = self
+                .header
+                .obs
+                .as_ref()
+                .map(|obs| !obs.prn_obs_counts.is_empty())
+                .unwrap_or(false);
+            if rebuild {
+                self.rebuild_obs_counts_mut();
+            }
+        } else if let Some(r) = self.record.as_mut_clock() {
+            r.resample_with_max_gap_mut(interval, method, max_gap);
+        } else if let Some(r) = self.record.as_mut_meteo() {
+            r.resample_with_max_gap_mut(interval, method, max_gap);
+        } else {
+            return;
+        }
+        self.header.sampling_interval = Some(interval);
+    }
+}
+
 #[cfg(feature = "obs")]
 use observation::Dcb;
 
@@ -3300,6 +6300,33 @@ impl Rinex {
     }
 }
 
+#[cfg(feature = "clock")]
+use clock::{ClockAnalysis, ClockType};
+
+#[cfg(feature = "clock")]
+#[cfg_attr(docrs, doc(cfg(feature = "clock")))]
+impl ClockAnalysis for Rinex {
+    fn bias_series(&self, clock: &ClockType) -> BTreeMap<Epoch, f64> {
+        self.record
+            .as_clock()
+            .map(|rec| rec.bias_series(clock))
+            .unwrap_or_default()
+    }
+    fn drift_estimate(&self, clock: &ClockType, start: Epoch, end: Epoch) -> Option<f64> {
+        self.record.as_clock()?.drift_estimate(clock, start, end)
+    }
+    fn align_to_reference_mut(&mut self, reference: &ClockType) {
+        if let Some(rec) = self.record.as_mut_clock() {
+            rec.align_to_reference_mut(reference);
+        }
+    }
+    fn align_to_reference(&self, reference: &ClockType) -> Self {
+        let mut s = self.clone();
+        s.align_to_reference_mut(reference);
+        s
+    }
+}
+
 /*
  * IONEX specific feature
  */
@@ -3424,6 +6451,72 @@ impl Rinex {
             (ionex.grid.latitude.end, ionex.grid.longitude.end),
         ))
     }
+    /// Retains IONEX maps within given latitude band [ddeg], in place.
+    /// Bounds are snapped to the grid nodes that enclose them and
+    /// clamped to the original grid. The header grid definition is updated accordingly,
+    /// TEC values are left untouched.
+    pub fn ionex_latitude_band_mut(&mut self, lat_min: f64, lat_max: f64) {
+        let rec = match self.record.as_mut_ionex() {
+            Some(rec) => rec,
+            None => return,
+        };
+        if let Some((south, north)) = ionex::record::retain_latitude_band_mut(rec, lat_min, lat_max)
+        {
+            if let Some(ionex) = self.header.ionex.as_mut() {
+                let latitude = &mut ionex.grid.latitude;
+                let (south, north) = (south as f64 / 1000.0, north as f64 / 1000.0);
+                if latitude.spacing < 0.0 {
+                    latitude.start = north;
+                    latitude.end = south;
+                } else {
+                    latitude.start = south;
+                    latitude.end = north;
+                }
+            }
+        }
+    }
+    /// Copies and retains IONEX maps within given latitude band [ddeg].
+    /// See [Self::ionex_latitude_band_mut].
+    pub fn ionex_latitude_band(&self, lat_min: f64, lat_max: f64) -> Self {
+        let mut s = self.clone();
+        s.ionex_latitude_band_mut(lat_min, lat_max);
+        s
+    }
+    /// Retains IONEX maps within given region [ddeg], in place.
+    /// Bounds are snapped to the grid nodes that enclose them and
+    /// clamped to the original grid. The header grid definition is updated accordingly,
+    /// TEC values are left untouched.
+    /// When `lon_min` is greater than `lon_max`, the region crosses the ±180° meridian:
+    /// the western part is then described with longitudes above 180°,
+    /// so the grid remains contiguous.
+    pub fn ionex_region_mut(&mut self, lat_min: f64, lat_max: f64, lon_min: f64, lon_max: f64) {
+        self.ionex_latitude_band_mut(lat_min, lat_max);
+        let rec = match self.record.as_mut_ionex() {
+            Some(rec) => rec,
+            None => return,
+        };
+        if let Some((west, east)) = ionex::record::retain_longitude_band_mut(rec, lon_min, lon_max)
+        {
+            if let Some(ionex) = self.header.ionex.as_mut() {
+                let longitude = &mut ionex.grid.longitude;
+                let (west, east) = (west as f64 / 1000.0, east as f64 / 1000.0);
+                if longitude.spacing < 0.0 {
+                    longitude.start = east;
+                    longitude.end = west;
+                } else {
+                    longitude.start = west;
+                    longitude.end = east;
+                }
+            }
+        }
+    }
+    /// Copies and retains IONEX maps within given region [ddeg].
+    /// See [Self::ionex_region_mut].
+    pub fn ionex_region(&self, lat_min: f64, lat_max: f64, lon_min: f64, lon_max: f64) -> Self {
+        let mut s = self.clone();
+        s.ionex_region_mut(lat_min, lat_max, lon_min, lon_max);
+        s
+    }
 }
 
 /*
@@ -3505,6 +6598,70 @@ impl Rinex {
             })
             .reduce(|k, _| k) // we're expecting a single match here
     }
+    /// Returns the phase center variation [mm] of given spacecraft antenna,
+    /// for given frequency, at given nadir and azimuth angles [°].
+    /// The value is interpolated from the calibrated phase pattern.
+    /// "now" is used to determine calibration validity (in time).
+    pub fn sv_antenna_pcv(
+        &self,
+        now: Epoch,
+        sv: SV,
+        freq: Carrier,
+        nadir: f64,
+        azimuth: f64,
+    ) -> Option<f64> {
+        self.antex_valid_calibrations(now)
+            .filter_map(|(ant, freqdata)| match &ant.specific {
+                AntennaSpecific::SvAntenna(sv_ant) => {
+                    if sv_ant.sv == sv {
+                        freqdata
+                            .get(&freq)
+                            .and_then(|freqdata| freqdata.pcv(&ant.zenith_angles(), nadir, azimuth))
+                    } else {
+                        None
+                    }
+                },
+                _ => None,
+            })
+            .reduce(|k, _| k) // we're expecting a single match here
+    }
+    /// Returns the phase center variation [mm] of given RX Antenna model,
+    /// for given frequency, at given zenith and azimuth angles [°].
+    /// The value is interpolated from the calibrated phase pattern,
+    /// azimuth is ignored when the antenna only has an azimuth independent (NOAZI) pattern.
+    /// "now" is used to determine calibration validity (in time).
+    pub fn rx_antenna_pcv(
+        &self,
+        now: Epoch,
+        matcher: AntennaMatcher,
+        freq: Carrier,
+        zenith: f64,
+        azimuth: f64,
+    ) -> Option<f64> {
+        let to_match = matcher.to_lowercase();
+        self.antex_valid_calibrations(now)
+            .filter_map(|(ant, freqdata)| match &ant.specific {
+                AntennaSpecific::RxAntenna(rx_ant) => {
+                    let matches = match &to_match {
+                        AntennaMatcher::IGSCode(code) => rx_ant.igs_type.to_lowercase().eq(code),
+                        AntennaMatcher::SerialNumber(sn) => rx_ant
+                            .serial_number
+                            .as_ref()
+                            .map(|serial| serial.to_lowercase().eq(sn))
+                            .unwrap_or(false),
+                    };
+                    if matches {
+                        freqdata.get(&freq).and_then(|freqdata| {
+                            freqdata.pcv(&ant.zenith_angles(), zenith, azimuth)
+                        })
+                    } else {
+                        None
+                    }
+                },
+                _ => None,
+            })
+            .reduce(|k, _| k) // we're expecting a single match here
+    }
 }
 
 /*
@@ -1,12 +1,11 @@
-use std::ops::Rem;
 use thiserror::Error;
 
 /// Grid definition Error
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("faulty grid definition: `start` and `end` must be multiples of each other")]
+    #[error("faulty grid definition: `start` to `end` does not follow `spacing` direction")]
     GridStartEndError,
-    #[error("faulty grid definition: `start` and `end` must be multiples of `spacing`")]
+    #[error("faulty grid definition: `end` - `start` must be a multiple of `spacing`")]
     GridSpacingError,
 }
 
@@ -26,22 +25,20 @@ pub struct Linspace {
 impl Linspace {
     /// Builds a new Linear space
     pub fn new(start: f64, end: f64, spacing: f64) -> Result<Self, Error> {
-        let r = end.rem(start);
+        let steps = (end - start) / spacing;
         /*
-         * End / Start must be multiple of one another
+         * Spacing must lead from Start to End
          */
-        if r == 0.0 {
-            if end.rem(spacing) == 0.0 {
-                Ok(Self {
-                    start,
-                    end,
-                    spacing,
-                })
-            } else {
-                Err(Error::GridSpacingError)
-            }
-        } else {
+        if steps < 0.0 {
             Err(Error::GridStartEndError)
+        } else if (steps - steps.round()).abs() > 1.0E-6 {
+            Err(Error::GridSpacingError)
+        } else {
+            Ok(Self {
+                start,
+                end,
+                spacing,
+            })
         }
     }
     // Returns grid length, in terms of data points
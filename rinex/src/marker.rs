@@ -1,5 +1,7 @@
 //! Geodetic marker description
-use strum_macros::EnumString;
+use crate::domes::Domes;
+use std::str::FromStr;
+use thiserror::Error;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -11,61 +13,118 @@ pub struct GeodeticMarker {
     pub name: String,
     /// Marker type
     pub marker_type: Option<MarkerType>,
-    /// Marker/monument ID
-    identification: Option<u32>,
-    /// Marker/monument number.
-    /// Probably if agency has more than one of them.
-    monument: Option<u16>,
+    /// Marker number, as described in the header section
+    number: Option<String>,
 }
 
-#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, EnumString)]
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MarkerType {
     /// Earth fixed & high precision
-    #[strum(serialize = "GEODETIC", serialize = "Geodetic")]
     #[default]
     Geodetic,
     /// Earth fixed & low precision
-    #[strum(serialize = "NON GEODETIC", serialize = "NonGeodetic")]
     NonGeodetic,
     /// Generated from network
-    #[strum(serialize = "NON PHYSICAL", serialize = "NonPhysical")]
     NonPhysical,
     /// Orbiting space vehicle
-    #[strum(serialize = "SPACE BORNE", serialize = "Spaceborne")]
     Spaceborne,
     /// Aircraft, balloon..
-    #[strum(serialize = "AIR BORNE", serialize = "Airborne")]
     Airborne,
     /// Mobile water craft
-    #[strum(serialize = "WATER CRAFT", serialize = "Watercraft")]
     Watercraft,
     /// Mobile terrestrial vehicle
-    #[strum(serialize = "GROUND CRAFT", serialize = "Groundcraft")]
     Groundcraft,
     /// Fixed on water surface
-    #[strum(serialize = "FIXED BUOY", serialize = "FixedBuoy")]
     FixedBuoy,
     /// Floating on water surface
-    #[strum(serialize = "FLOATING BUOY", serialize = "FloatingBuoy")]
     FloatingBuoy,
     /// Floating on ice
-    #[strum(serialize = "FLOATING ICE", serialize = "FloatingIce")]
     FloatingIce,
     /// Fixed on glacier
-    #[strum(serialize = "GLACIER", serialize = "Glacier")]
     Glacier,
     /// Rockets, shells, etc..
-    #[strum(serialize = "BALLISTIC", serialize = "Ballistic")]
     Ballistic,
     /// Animal carrying a receiver
-    #[strum(serialize = "ANIMAL", serialize = "Animal")]
     Animal,
     /// Human being carrying a receiver
-    #[strum(serialize = "HUMAN", serialize = "Human")]
     Human,
 }
 
+/// Marker type parsing error
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MarkerTypeError {
+    #[error("unknown marker type \"{0}\"")]
+    UnknownMarkerType(String),
+}
+
+impl MarkerType {
+    /// Returns true if this marker is not fixed to the Earth.
+    pub fn is_mobile(&self) -> bool {
+        !matches!(
+            self,
+            Self::Geodetic
+                | Self::NonGeodetic
+                | Self::NonPhysical
+                | Self::FixedBuoy
+                | Self::Glacier
+        )
+    }
+}
+
+impl FromStr for MarkerType {
+    type Err = MarkerTypeError;
+    /// Parses a MARKER TYPE code. Case, spaces and underscores are not
+    /// significant, so "NON_GEODETIC", "NON GEODETIC" and "NonGeodetic"
+    /// all describe [MarkerType::NonGeodetic].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_uppercase();
+        match code.as_str() {
+            "GEODETIC" => Ok(Self::Geodetic),
+            "NONGEODETIC" => Ok(Self::NonGeodetic),
+            "NONPHYSICAL" => Ok(Self::NonPhysical),
+            "SPACEBORNE" => Ok(Self::Spaceborne),
+            "AIRBORNE" => Ok(Self::Airborne),
+            "WATERCRAFT" => Ok(Self::Watercraft),
+            "GROUNDCRAFT" => Ok(Self::Groundcraft),
+            "FIXEDBUOY" => Ok(Self::FixedBuoy),
+            "FLOATINGBUOY" => Ok(Self::FloatingBuoy),
+            "FLOATINGICE" => Ok(Self::FloatingIce),
+            "GLACIER" => Ok(Self::Glacier),
+            "BALLISTIC" => Ok(Self::Ballistic),
+            "ANIMAL" => Ok(Self::Animal),
+            "HUMAN" => Ok(Self::Human),
+            _ => Err(MarkerTypeError::UnknownMarkerType(s.trim().to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for MarkerType {
+    /// Formats this [MarkerType] as defined in RINEX3 specifications
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Geodetic => write!(f, "GEODETIC"),
+            Self::NonGeodetic => write!(f, "NON_GEODETIC"),
+            Self::NonPhysical => write!(f, "NON_PHYSICAL"),
+            Self::Spaceborne => write!(f, "SPACEBORNE"),
+            Self::Airborne => write!(f, "AIRBORNE"),
+            Self::Watercraft => write!(f, "WATER_CRAFT"),
+            Self::Groundcraft => write!(f, "GROUND_CRAFT"),
+            Self::FixedBuoy => write!(f, "FIXED_BUOY"),
+            Self::FloatingBuoy => write!(f, "FLOATING_BUOY"),
+            Self::FloatingIce => write!(f, "FLOATING_ICE"),
+            Self::Glacier => write!(f, "GLACIER"),
+            Self::Ballistic => write!(f, "BALLISTIC"),
+            Self::Animal => write!(f, "ANIMAL"),
+            Self::Human => write!(f, "HUMAN"),
+        }
+    }
+}
+
 impl GeodeticMarker {
     /// Returns a GeodeticMarker with given "name".
     pub fn with_name(&self, name: &str) -> Self {
@@ -73,34 +132,179 @@ impl GeodeticMarker {
         s.name = name.to_string();
         s
     }
-    /// Returns marker "number" in standardized format
+    /// Returns marker "number", as described in the header section.
     pub fn number(&self) -> Option<String> {
-        let id = self.identification?;
-        let monument = self.monument?;
-        Some(format!("{:05}M{:03}", id, monument))
+        self.number.clone()
     }
-    /// Returns a GeodeticMarker with "number" only if it matches standardized format.
+    /// Returns a GeodeticMarker with given "number". Blank content is ignored.
     pub fn with_number(&self, content: &str) -> Self {
         let mut s = self.clone();
-        if content.len() == 9 && content.chars().nth(5) == Some('M') {
-            if let Ok(id) = u32::from_str_radix(&content[..5], 10) {
-                if let Ok(monument) = u16::from_str_radix(&content[7..], 10) {
-                    s.identification = Some(id);
-                    s.monument = Some(monument);
-                }
-            }
+        let content = content.trim();
+        if !content.is_empty() {
+            s.number = Some(content.to_string());
         }
         s
     }
+    /// Returns the [`Domes`] site identifier, when the marker number
+    /// is a valid DOMES number. This helps cross referencing stations with IGS site logs.
+    /// ```
+    /// use rinex::marker::GeodeticMarker;
+    /// let marker = GeodeticMarker::default()
+    ///     .with_number("40451S003");
+    /// let domes = marker.domes()
+    ///     .unwrap();
+    /// assert_eq!(domes.area, 404);
+    /// assert_eq!(domes.to_string(), "40451S003");
+    ///
+    /// let marker = GeodeticMarker::default()
+    ///     .with_number("-Unknown-");
+    /// assert_eq!(marker.number(), Some("-Unknown-".to_string()));
+    /// assert!(marker.domes().is_none());
+    /// ```
+    pub fn domes(&self) -> Option<Domes> {
+        Domes::from_str(self.number.as_ref()?).ok()
+    }
+}
+
+/// Station name parsing error
+#[derive(Debug, Error)]
+pub enum StationNameError {
+    #[error("invalid station name format")]
+    InvalidFormat,
+    #[error("invalid station name length")]
+    InvalidLength,
+}
+
+/// IGS 9 character station name, as used in modern file names (like "AMEL00NLD").
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StationName {
+    /// 4 character monument (site) name
+    pub monument: String,
+    /// Marker number (0-9), in case several monuments exist on the same site
+    pub marker_number: u8,
+    /// Receiver number (0-9), in case several receivers track on the same monument
+    pub receiver_number: u8,
+    /// ISO 3166 3 letter country code
+    pub country: String,
+}
+
+impl FromStr for StationName {
+    type Err = StationNameError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 9 {
+            return Err(StationNameError::InvalidLength);
+        }
+        if !s.is_ascii() {
+            return Err(StationNameError::InvalidFormat);
+        }
+        let (monument, rem) = s.split_at(4);
+        let (numbers, country) = rem.split_at(2);
+        if !monument.chars().all(|c| c.is_ascii_alphanumeric())
+            || !country.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(StationNameError::InvalidFormat);
+        }
+        let mut digits = numbers.chars().map(|c| c.to_digit(10));
+        match (digits.next().flatten(), digits.next().flatten()) {
+            (Some(marker_number), Some(receiver_number)) => Ok(Self {
+                monument: monument.to_string(),
+                marker_number: marker_number as u8,
+                receiver_number: receiver_number as u8,
+                country: country.to_string(),
+            }),
+            _ => Err(StationNameError::InvalidFormat),
+        }
+    }
+}
+
+impl std::fmt::Display for StationName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.monument, self.marker_number, self.receiver_number, self.country
+        )
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::GeodeticMarker;
+    use super::{GeodeticMarker, MarkerType, MarkerTypeError, StationName};
+    use std::str::FromStr;
+    #[test]
+    fn marker_type() {
+        for (code, expected) in [
+            ("GEODETIC", MarkerType::Geodetic),
+            ("Geodetic", MarkerType::Geodetic),
+            ("NON_GEODETIC", MarkerType::NonGeodetic),
+            ("NON GEODETIC", MarkerType::NonGeodetic),
+            ("NonGeodetic", MarkerType::NonGeodetic),
+            ("NON_PHYSICAL", MarkerType::NonPhysical),
+            ("NON PHYSICAL", MarkerType::NonPhysical),
+            ("SPACEBORNE", MarkerType::Spaceborne),
+            ("SPACE BORNE", MarkerType::Spaceborne),
+            ("spaceborne", MarkerType::Spaceborne),
+            ("AIRBORNE", MarkerType::Airborne),
+            ("AIR BORNE", MarkerType::Airborne),
+            ("WATER_CRAFT", MarkerType::Watercraft),
+            ("GROUND_CRAFT", MarkerType::Groundcraft),
+            ("FIXED_BUOY", MarkerType::FixedBuoy),
+            ("FLOATING_BUOY", MarkerType::FloatingBuoy),
+            ("FLOATING ICE", MarkerType::FloatingIce),
+            ("GLACIER", MarkerType::Glacier),
+            ("BALLISTIC", MarkerType::Ballistic),
+            ("ANIMAL", MarkerType::Animal),
+            ("HUMAN", MarkerType::Human),
+        ] {
+            let marker_type = MarkerType::from_str(code).unwrap();
+            assert_eq!(marker_type, expected, "failed to parse \"{}\"", code);
+            // reciprocal
+            assert_eq!(MarkerType::from_str(&marker_type.to_string()), Ok(expected));
+        }
+        assert!(!MarkerType::Geodetic.is_mobile());
+        assert!(MarkerType::Airborne.is_mobile());
+        assert!(MarkerType::Watercraft.is_mobile());
+        for code in ["", "GEODESIC", "SPACESHIP", "NON"] {
+            assert_eq!(
+                MarkerType::from_str(code),
+                Err(MarkerTypeError::UnknownMarkerType(code.to_string()))
+            );
+        }
+    }
     #[test]
     fn marker_number() {
         let marker = GeodeticMarker::default();
         let marker = marker.with_number("10118M001");
         assert_eq!(marker.number(), Some("10118M001".to_string()));
+        assert_eq!(
+            marker.domes().map(|domes| domes.to_string()),
+            Some("10118M001".to_string())
+        );
+        let marker = marker.with_number("13407S012  ");
+        assert_eq!(marker.number(), Some("13407S012".to_string()));
+        assert!(marker.domes().is_some());
+        for number in ["----", "1234", "10118X001", "10118M0A1"] {
+            let marker = GeodeticMarker::default().with_number(number);
+            assert_eq!(marker.number(), Some(number.to_string()));
+            assert!(marker.domes().is_none(), "{} is not a DOMES number", number);
+        }
+    }
+    #[test]
+    fn station_name() {
+        let station = StationName::from_str("AMEL00NLD").unwrap();
+        assert_eq!(station.monument, "AMEL");
+        assert_eq!(station.marker_number, 0);
+        assert_eq!(station.receiver_number, 0);
+        assert_eq!(station.country, "NLD");
+        assert_eq!(station.to_string(), "AMEL00NLD");
+
+        let station = StationName::from_str("ESBC12DNK").unwrap();
+        assert_eq!((station.marker_number, station.receiver_number), (1, 2));
+        assert_eq!(station.country, "DNK");
+
+        for name in ["AMEL", "AMEL00NL", "AMEL0XNLD", "AMEL00N1D", "AMEL 0NLD"] {
+            assert!(StationName::from_str(name).is_err(), "{}", name);
+        }
     }
 }
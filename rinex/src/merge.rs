@@ -1,5 +1,6 @@
 //! RINEX File merging (combination)
-use crate::prelude::Epoch;
+use crate::observation::ObservationData;
+use crate::prelude::{Epoch, EpochFlag, Observable, SV};
 use hifitime::EpochError;
 use std::cmp::{Eq, PartialEq};
 use std::collections::HashMap;
@@ -23,6 +24,84 @@ pub enum Error {
     IonexBaseRadiusMismatch,
     #[error("failed to retrieve system time for merge ops date")]
     HifitimeError(#[from] EpochError),
+    #[error("{} conflicting observations", .0.len())]
+    MergeConflicts(Vec<MergeConflict>),
+}
+
+/// [MergeStrategy] describes how to resolve observations that are defined
+/// in both datasets, with different values. See [crate::Rinex::merge_with_strategy].
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum MergeStrategy {
+    /// Preserve Self's observations
+    PreferSelf,
+    /// Overwrite with the other dataset's observations.
+    /// This is the default [Merge] behavior.
+    #[default]
+    PreferOther,
+    /// Abort the merge operation, with [Error::MergeConflicts]
+    Error,
+}
+
+/// [MergeConflict] describes an observation
+/// that both datasets define, with different values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Sampling [Epoch]
+    pub epoch: Epoch,
+    /// [EpochFlag] attached to this epoch
+    pub flag: EpochFlag,
+    /// Satellite vehicle
+    pub sv: SV,
+    /// Physical observable
+    pub observable: Observable,
+    /// Self's value
+    pub lhs: ObservationData,
+    /// Other dataset's value
+    pub rhs: ObservationData,
+}
+
+/// [MergeRecord] describes one merging operation, as described
+/// by the pseudo standard `FILE MERGE` header comment.
+/// See [crate::Rinex::merge_info].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeRecord {
+    /// Program that performed the merging operation
+    pub program: String,
+    /// Date stamped by the merging program. This library stamps
+    /// the merge boundary: first [Epoch] of the merged-in dataset.
+    pub date: Epoch,
+}
+
+impl MergeRecord {
+    /*
+     * Parses a FILE MERGE comment.
+     * Our canonical format is "%Y%m%d %H%M%S UTC", but we tolerate
+     * "%Y%m%d %H:%M:%S UTC" and missing/attached time zone codes.
+     */
+    pub(crate) fn from_comment(comment: &str) -> Option<Self> {
+        let offset = comment.find("FILE MERGE")?;
+        let program = comment[..offset].trim().to_string();
+        let date = comment[offset + 10..]
+            .trim()
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .trim();
+        let (ymd, hms) = date.split_once(' ')?;
+        let hms = hms.trim().replace(':', "");
+        if ymd.len() != 8 || hms.len() != 6 {
+            return None;
+        }
+        if !ymd.chars().chain(hms.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let y = ymd[0..4].parse::<i32>().ok()?;
+        let m = ymd[4..6].parse::<u8>().ok()?;
+        let d = ymd[6..8].parse::<u8>().ok()?;
+        let hh = hms[0..2].parse::<u8>().ok()?;
+        let mm = hms[2..4].parse::<u8>().ok()?;
+        let ss = hms[4..6].parse::<u8>().ok()?;
+        let date = Epoch::maybe_from_gregorian_utc(y, m, d, hh, mm, ss, 0).ok()?;
+        Some(Self { program, date })
+    }
 }
 
 /*
@@ -0,0 +1,18 @@
+//! RINEX metadata, for archiving purposes
+use crate::prelude::{Epoch, Header, SV};
+
+/// [`Metadata`] is the parsed [`Header`] completed by
+/// a summary of the record content.
+/// It is typically stored as a JSON sidecar next to the RINEX file,
+/// see [`crate::Rinex::write_metadata_json`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Parsed header section
+    pub header: Header,
+    /// First [`Epoch`] described by the record
+    pub first_epoch: Option<Epoch>,
+    /// Last [`Epoch`] described by the record
+    pub last_epoch: Option<Epoch>,
+    /// Vehicles described by the record, in ascending order
+    pub sv: Vec<SV>,
+}
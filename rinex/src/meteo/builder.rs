@@ -0,0 +1,107 @@
+//! Meteo RINEX synthesis
+use super::{HeaderFields, Record};
+use crate::prelude::*;
+use crate::{record, types::Type, version::Version};
+use thiserror::Error;
+
+/// [`MeteoBuilder`] related errors
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    #[error("no observations were provided")]
+    EmptyRecord,
+    #[error("{0} has not been declared")]
+    UndeclaredObservable(Observable),
+}
+
+/// [`MeteoBuilder`] helps synthesize Meteo RINEX from scratch.
+/// Mandatory header fields (PGM / RUN BY / DATE, INTERVAL)
+/// are deduced when building.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::meteo::MeteoBuilder;
+/// use rinex::version::Version;
+/// use std::str::FromStr;
+///
+/// let pressure = Observable::from_str("PR").unwrap();
+/// let temperature = Observable::from_str("TD").unwrap();
+/// let t0 = Epoch::from_str("2023-01-01T00:00:00 UTC").unwrap();
+/// let t1 = Epoch::from_str("2023-01-01T00:15:00 UTC").unwrap();
+///
+/// let mut builder = MeteoBuilder::new(Version::new(2, 11))
+///     .with_codes(&[pressure.clone(), temperature.clone()]);
+///
+/// builder.add_observation(t0, pressure.clone(), 1016.2);
+/// builder.add_observation(t0, temperature.clone(), 9.6);
+/// builder.add_observation(t1, pressure.clone(), 1016.3);
+///
+/// let rinex = builder.build()
+///     .unwrap();
+/// assert_eq!(rinex.header.sampling_interval, Some(Duration::from_seconds(900.0)));
+///
+/// let path = std::env::temp_dir().join("builder.met");
+/// rinex.to_file(path.to_str().unwrap())
+///     .unwrap();
+/// let parsed = Rinex::from_path(&path)
+///     .unwrap();
+/// assert_eq!(parsed.record, rinex.record);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeteoBuilder {
+    version: Version,
+    codes: Vec<Observable>,
+    record: Record,
+}
+
+impl MeteoBuilder {
+    /// Creates a new [`MeteoBuilder`] for given RINEX revision.
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            codes: Vec::new(),
+            record: Record::new(),
+        }
+    }
+    /// Declares the list of observables.
+    /// Observables are formatted in this order.
+    pub fn with_codes(&self, codes: &[Observable]) -> Self {
+        let mut s = self.clone();
+        s.codes = codes.to_vec();
+        s
+    }
+    /// Adds one observation. The epoch is created on the fly.
+    pub fn add_observation(&mut self, epoch: Epoch, code: Observable, value: f64) {
+        self.record.entry(epoch).or_default().insert(code, value);
+    }
+    /// Builds the [`Rinex`], after verifying that all observations
+    /// are described in the header section.
+    pub fn build(&self) -> Result<Rinex, Error> {
+        if self.record.is_empty() {
+            return Err(Error::EmptyRecord);
+        }
+        for observations in self.record.values() {
+            for observable in observations.keys() {
+                if !self.codes.contains(observable) {
+                    return Err(Error::UndeclaredObservable(observable.clone()));
+                }
+            }
+        }
+
+        let mut header = Header::default()
+            .with_version(self.version)
+            .with_type(Type::MeteoData)
+            .with_general_infos(
+                &format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+                "Unknown",
+                "",
+            )
+            .with_production_now();
+        header.meteo = Some(HeaderFields {
+            codes: self.codes.clone(),
+            ..Default::default()
+        });
+
+        let mut rinex = Rinex::new(header, record::Record::MeteoRecord(self.record.clone()));
+        rinex.header.sampling_interval = rinex.dominant_sample_rate();
+        Ok(rinex)
+    }
+}
@@ -1,9 +1,12 @@
 //! Meteo RINEX module
+pub mod builder;
 pub mod record;
 pub mod sensor;
+pub use builder::MeteoBuilder;
 pub use record::Record;
 
 use crate::Observable;
+use std::str::FromStr;
 
 /// Meteo specific header fields
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
@@ -14,3 +17,26 @@ pub struct HeaderFields {
     /// Sensors that produced the following observables
     pub sensors: Vec<sensor::Sensor>,
 }
+
+impl HeaderFields {
+    /// Returns the [sensor::Sensor] that produced given observation code,
+    /// (for example "PR" or "TD") if it is described.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/MET/V4/example1.txt")
+    ///     .unwrap();
+    /// let meteo = rinex.header.meteo.as_ref()
+    ///     .unwrap();
+    /// let sensor = meteo.sensor("TD")
+    ///     .unwrap();
+    /// assert_eq!(sensor.observable, Observable::Temperature);
+    /// assert!(sensor.position.is_some());
+    /// assert!(meteo.sensor("WS").is_none());
+    /// ```
+    pub fn sensor(&self, code: &str) -> Option<&sensor::Sensor> {
+        let observable = Observable::from_str(code).ok()?;
+        self.sensors
+            .iter()
+            .find(|sensor| sensor.observable == observable)
+    }
+}
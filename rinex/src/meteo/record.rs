@@ -267,7 +267,7 @@ impl Decimate for Record {
     fn decimate_by_ratio_mut(&mut self, r: u32) {
         let mut i = 0;
         self.retain(|_, _| {
-            let retained = (i % r) == 0;
+            let retained = (i % r.max(1)) == 0;
             i += 1;
             retained
         });
@@ -379,6 +379,67 @@ impl Interpolate for Record {
     }
 }
 
+#[cfg(feature = "processing")]
+impl Resample for Record {
+    fn resample(&self, interval: Duration, method: InterpMethod) -> Self {
+        let mut s = self.clone();
+        s.resample_mut(interval, method);
+        s
+    }
+    fn resample_mut(&mut self, interval: Duration, method: InterpMethod) {
+        let epochs = self.keys().copied().collect::<Vec<_>>();
+        if let Some(max_gap) = crate::algorithm::default_max_gap(&epochs) {
+            self.resample_with_max_gap_mut(interval, method, max_gap);
+        }
+    }
+    fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) -> Self {
+        let mut s = self.clone();
+        s.resample_with_max_gap_mut(interval, method, max_gap);
+        s
+    }
+    fn resample_with_max_gap_mut(
+        &mut self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) {
+        if interval <= Duration::ZERO {
+            return; // null interval: nothing to resample onto
+        }
+        let mut series = HashMap::<Observable, (Vec<Epoch>, Vec<f64>)>::new();
+        for (e, observations) in self.iter() {
+            for (observable, value) in observations {
+                let (epochs, values) = series.entry(observable.clone()).or_default();
+                epochs.push(*e);
+                values.push(*value);
+            }
+        }
+        let (t0, t1) = match (self.keys().next(), self.keys().next_back()) {
+            (Some(t0), Some(t1)) => (*t0, *t1),
+            _ => return,
+        };
+        let mut resampled = Record::new();
+        for t in crate::algorithm::resampling_grid(t0, t1, interval) {
+            let observations = series
+                .iter()
+                .filter_map(|(observable, (epochs, values))| {
+                    let value = crate::algorithm::interpolate(epochs, values, t, method, max_gap)?;
+                    Some((observable.clone(), value))
+                })
+                .collect::<HashMap<_, _>>();
+            if !observations.is_empty() {
+                resampled.insert(t, observations);
+            }
+        }
+        *self = resampled;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
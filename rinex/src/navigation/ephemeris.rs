@@ -1,4 +1,4 @@
-use super::{orbits::closest_nav_standards, NavMsgType, OrbitItem};
+use super::{orbits::closest_nav_standards, NavMsgType, OrbitItem, SvHealth};
 use crate::constants::Constants;
 use crate::{constants, epoch, prelude::*, version::Version};
 
@@ -96,7 +96,7 @@ impl EphemerisHelper {
 
 /// Ephermeris NAV frame type
 #[derive(Default, Clone, Debug, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ephemeris {
     /// Clock bias (in seconds)
     pub clock_bias: f64,
@@ -173,6 +173,30 @@ impl Ephemeris {
     pub fn sv_clock(&self) -> (f64, f64, f64) {
         (self.clock_bias, self.clock_drift, self.clock_drift_rate)
     }
+    /// Returns the [SvHealth] indication broadcast in this frame, if any.
+    /// `constellation` is the broadcasting vehicle's constellation, because
+    /// health encoding differs between constellations.
+    pub fn sv_health(&self, constellation: Constellation) -> Option<SvHealth> {
+        let item = ["health", "satH1", "svHealth"]
+            .iter()
+            .find_map(|field| self.orbits.get(*field))?;
+        match item {
+            OrbitItem::Health(h) => Some(SvHealth::GPS(h.clone() as u32)),
+            OrbitItem::GloHealth(h) => Some(SvHealth::Glonass(h.clone())),
+            OrbitItem::GalHealth(h) => Some(SvHealth::Galileo(*h)),
+            OrbitItem::GeoHealth(h) => Some(SvHealth::SBAS(h.clone())),
+            OrbitItem::IrnssHealth(h) => Some(SvHealth::IRNSS(h.clone())),
+            item => {
+                // raw health word
+                let word = item.to_f64() as u32;
+                match constellation {
+                    Constellation::GPS | Constellation::QZSS => Some(SvHealth::GPS(word)),
+                    Constellation::BeiDou => Some(SvHealth::BeiDou(word)),
+                    _ => None,
+                }
+            },
+        }
+    }
     /// Retrieves orbit data field expressed as f64 value, if such field exists.
     pub fn get_orbit_f64(&self, field: &str) -> Option<f64> {
         if let Some(v) = self.orbits.get(field) {
@@ -201,17 +225,34 @@ impl Ephemeris {
     pub fn tgd(&self) -> Option<Duration> {
         Some(Duration::from_seconds(self.get_orbit_f64("tgd")?))
     }
+    /// Returns the group delay [s] of the reference signal, broadcast in this frame
+    /// by a vehicle of given [Constellation]: TGD for GPS, QZSS and IRNSS,
+    /// BGD E5b/E1 (or E5a/E1 for F/NAV frames) for Galileo, TGD1 B1/B3 for BeiDou.
+    /// Glonass and SBAS frames do not describe such term. Empty (null) fields are considered missing.
+    pub fn group_delay(&self, constellation: Constellation) -> Option<f64> {
+        let fields: &[&str] = match constellation {
+            Constellation::GPS | Constellation::QZSS | Constellation::IRNSS => &["tgd"],
+            Constellation::Galileo => &["bgdE5bE1", "bgdE5aE1"],
+            Constellation::BeiDou => &["tgd1b1b3", "tgdb1b3"],
+            _ => &[],
+        };
+        fields
+            .iter()
+            .filter_map(|field| self.get_orbit_f64(field))
+            .find(|tgd| *tgd != 0.0)
+    }
     /*
-     * Helper to apply a clock correction to provided time (expressed as Epoch)
+     * Helper to apply a clock correction to provided time (expressed as Epoch),
+     * the clock polynomial being defined about the time of clock (toc)
      */
-    pub fn sv_clock_corr(sv: SV, clock_bias: (f64, f64, f64), t: Epoch, toe: Epoch) -> Duration {
+    pub fn sv_clock_corr(sv: SV, clock_bias: (f64, f64, f64), t: Epoch, toc: Epoch) -> Duration {
         let (a0, a1, a2) = clock_bias;
         match sv.constellation {
             Constellation::Glonass => {
                 todo!("sv_clock_corr not supported for glonass @ the moment");
             },
             _ => {
-                let mut dt = (t - toe).to_seconds();
+                let mut dt = (t - toc).to_seconds();
                 // TODO: does this apply to others like GST ?
                 const WEEK_SECONDS: f64 = 604800.0;
                 if dt > WEEK_SECONDS / 2.0 {
@@ -689,6 +730,16 @@ impl Ephemeris {
             },
         }
     }
+    /// Returns the validity period of Ephemeris frames broadcast by this [SV],
+    /// on both sides of their time of ephemeris. This is [Self::max_dtoe],
+    /// except for BeiDou GEO vehicles that publish hourly updates, valid for 1 hour.
+    pub fn validity_period(sv: SV) -> Option<Duration> {
+        if Constants::is_beidou_geo(sv) {
+            Some(Duration::from_seconds(3600.0))
+        } else {
+            Self::max_dtoe(sv.constellation)
+        }
+    }
 }
 
 /*
@@ -2,7 +2,7 @@ use bitflags::bitflags;
 
 /// GNSS / GPS orbit health indication
 #[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Health {
     #[default]
     Unhealthy = 0,
@@ -32,7 +32,7 @@ impl std::fmt::UpperExp for Health {
 
 /// IRNSS orbit health indication
 #[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IrnssHealth {
     Healthy = 0,
     #[default]
@@ -50,7 +50,7 @@ impl std::fmt::UpperExp for IrnssHealth {
 
 /// SBAS/GEO orbit health indication
 #[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GeoHealth {
     #[default]
     Unknown = 0,
@@ -68,7 +68,7 @@ impl std::fmt::UpperExp for GeoHealth {
 
 /// GLO orbit health indication
 #[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GloHealth {
     Healthy = 0,
     #[default]
@@ -88,7 +88,7 @@ bitflags! {
     /// GAL orbit health indication
     #[derive(Debug, Default, Copy, Clone)]
     #[derive(PartialEq, PartialOrd)]
-    #[cfg_attr(feature = "serde", derive(Serialize))]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct GalHealth: u8 {
         const E1B_DVS = 0x01;
         const E1B_HS0 = 0x02;
@@ -101,6 +101,52 @@ bitflags! {
     }
 }
 
+/// [SvHealth] is the health indication broadcast in Ephemeris frames,
+/// whose encoding is constellation dependent.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SvHealth {
+    /// GPS / QZSS health word
+    GPS(u32),
+    /// GLO health indication
+    Glonass(GloHealth),
+    /// GAL signals health and data validity status
+    Galileo(GalHealth),
+    /// BDS SatH1 (D1/D2) or health flag (CNV1/2/3)
+    BeiDou(u32),
+    /// GEO/SBAS health indication
+    SBAS(GeoHealth),
+    /// IRNSS health indication
+    IRNSS(IrnssHealth),
+}
+
+impl SvHealth {
+    /// Returns true if this vehicle is declared healthy.
+    ///  - GPS, QZSS and BDS: health word is null
+    ///  - GAL: all signals have null health and data validity status
+    ///  - GLO and IRNSS: healthy indication
+    ///  - GEO/SBAS: never, [GeoHealth] does not describe a healthy state
+    ///
+    /// Unknown health indications are not healthy, see [Self::is_unknown].
+    pub fn is_healthy(&self) -> bool {
+        match self {
+            Self::GPS(word) | Self::BeiDou(word) => *word == 0,
+            Self::Glonass(health) => *health == GloHealth::Healthy,
+            Self::Galileo(health) => health.is_empty(),
+            Self::SBAS(_) => false,
+            Self::IRNSS(health) => *health == IrnssHealth::Healthy,
+        }
+    }
+    /// Returns true if this health indication is unknown:
+    /// the vehicle is neither declared healthy nor unhealthy.
+    pub fn is_unknown(&self) -> bool {
+        matches!(
+            self,
+            Self::SBAS(GeoHealth::Unknown) | Self::IRNSS(IrnssHealth::Unknown)
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -128,4 +174,23 @@ mod test {
     fn test_gal() {
         assert_eq!(GalHealth::default(), GalHealth::empty());
     }
+    #[test]
+    fn test_sv_health() {
+        assert!(SvHealth::GPS(0).is_healthy());
+        assert!(!SvHealth::GPS(63).is_healthy());
+        assert!(SvHealth::BeiDou(0).is_healthy());
+        assert!(!SvHealth::BeiDou(1).is_healthy());
+        assert!(SvHealth::Glonass(GloHealth::Healthy).is_healthy());
+        assert!(!SvHealth::Glonass(GloHealth::Unhealthy).is_healthy());
+        assert!(SvHealth::Galileo(GalHealth::empty()).is_healthy());
+        assert!(!SvHealth::Galileo(GalHealth::E5A_HS0 | GalHealth::E5A_HS1).is_healthy());
+        assert!(SvHealth::IRNSS(IrnssHealth::Healthy).is_healthy());
+        assert!(!SvHealth::IRNSS(IrnssHealth::Unknown).is_healthy());
+        assert!(SvHealth::IRNSS(IrnssHealth::Unknown).is_unknown());
+        assert!(!SvHealth::SBAS(GeoHealth::Unknown).is_healthy());
+        assert!(SvHealth::SBAS(GeoHealth::Unknown).is_unknown());
+        assert!(!SvHealth::SBAS(GeoHealth::Reserved).is_healthy());
+        assert!(!SvHealth::SBAS(GeoHealth::Reserved).is_unknown());
+        assert!(!SvHealth::GPS(63).is_unknown());
+    }
 }
@@ -49,7 +49,7 @@ pub enum Error {
 
 /// Klobuchar Parameters region
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum KbRegionCode {
     /// Worlwide (GPS) Orbits.
     WideArea = 0,
@@ -66,7 +66,7 @@ impl Default for KbRegionCode {
 /// Klobuchar model payload,
 /// we don't know how to parse the possible extra Region Code yet
 #[derive(Default, Debug, Copy, Clone, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KbModel {
     /// Alpha coefficients
     /// ((sec), (sec.semi-circle⁻¹), (sec.semi-circle⁻²), (sec.semi-circle⁻³))
@@ -215,7 +215,7 @@ impl KbModel {
 bitflags! {
     #[derive(Debug, Default, Clone, Copy)]
     #[derive(PartialEq, PartialOrd)]
-    #[cfg_attr(feature = "serde", derive(Serialize))]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct NgRegionFlags: u16 {
         const REGION5 = 0x01;
         const REGION4 = 0x02;
@@ -227,7 +227,7 @@ bitflags! {
 
 /// Nequick-G Model payload
 #[derive(Debug, Clone, Default, Copy, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NgModel {
     /// a_i coefficients
     /// (sfu, (sfu.semi-circle⁻¹), (sfu.semi-circle⁻²))
@@ -280,7 +280,7 @@ impl NgModel {
 
 /// BDGIM Model payload
 #[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BdModel {
     /// Alpha coefficients in TEC unit
     pub alpha: (f64, f64, f64, f64, f64, f64, f64, f64, f64),
@@ -338,7 +338,7 @@ impl BdModel {
 
 /// IonMessage wraps all known Ionosphere models
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IonMessage {
     /// Klobuchar Model
     KlobucharModel(KbModel),
@@ -10,7 +10,7 @@ pub mod record;
 
 pub use eopmessage::EopMessage;
 pub use ephemeris::Ephemeris;
-pub use health::{GeoHealth, GloHealth, Health, IrnssHealth};
+pub use health::{GalHealth, GeoHealth, GloHealth, Health, IrnssHealth, SvHealth};
 pub use ionmessage::{BdModel, IonMessage, KbModel, KbRegionCode, NgModel, NgRegionFlags};
 pub use orbits::OrbitItem;
 pub use record::{NavFrame, NavMsgType, Record};
@@ -56,6 +56,8 @@ pub enum Error {
     StoMessageError(#[from] stomessage::Error),
     #[error("failed to identify timescale for {0}")]
     TimescaleIdentification(SV),
+    #[error("undefined constellation")]
+    MissingConstellation,
 }
 
 /*
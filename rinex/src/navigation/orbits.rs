@@ -10,7 +10,7 @@ include!(concat!(env!("OUT_DIR"), "/nav_orbits.rs"));
 bitflags! {
     #[derive(Default, Debug, Clone)]
     #[derive(PartialEq, PartialOrd)]
-    #[cfg_attr(feature = "serde", derive(Serialize))]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct GloStatus: u32 {
         const GROUND_GPS_ONBOARD_OFFSET = 0x01;
         const ONBOARD_GPS_GROUND_OFFSET = 0x02;
@@ -29,7 +29,7 @@ bitflags! {
 /// It is a complex data wrapper, for high level
 /// record description, across all revisions and constellations
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum OrbitItem {
     /// unsigned byte
     U8(u8),
@@ -113,9 +113,12 @@ impl OrbitItem {
                 let unsigned = float as u32;
                 match constellation {
                     Constellation::GPS | Constellation::QZSS => {
-                        let flag: health::Health = num::FromPrimitive::from_u32(unsigned)
-                            .unwrap_or(health::Health::default());
-                        Ok(OrbitItem::Health(flag))
+                        let flag: Option<health::Health> = num::FromPrimitive::from_u32(unsigned);
+                        match flag {
+                            Some(flag) => Ok(OrbitItem::Health(flag)),
+                            // preserve raw health word (6 bit), for data not to be lost
+                            None => Ok(OrbitItem::U32(unsigned)),
+                        }
                     },
                     Constellation::Glonass => {
                         let flag: health::GloHealth = num::FromPrimitive::from_u32(unsigned)
@@ -164,6 +167,22 @@ impl OrbitItem {
             OrbitItem::GloStatus(h) => format!("{:14.11E}", h.bits() as f64),
         }
     }
+    /// Converts self to double precision, whatever the inner type,
+    /// which is how data fields are encoded in NAV RINEX
+    pub(crate) fn to_f64(&self) -> f64 {
+        match self {
+            OrbitItem::U8(n) => *n as f64,
+            OrbitItem::I8(n) => *n as f64,
+            OrbitItem::U32(n) => *n as f64,
+            OrbitItem::F64(f) => *f,
+            OrbitItem::Health(h) => h.clone() as u32 as f64,
+            OrbitItem::GloHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GeoHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::IrnssHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GalHealth(h) => h.bits() as f64,
+            OrbitItem::GloStatus(h) => h.bits() as f64,
+        }
+    }
     /// Unwraps OrbitItem as f64
     pub fn as_f64(&self) -> Option<f64> {
         match self {
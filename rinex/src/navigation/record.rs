@@ -34,7 +34,8 @@ fn double_exponent_digits(content: &str) -> String {
 }
 
 use crate::{
-    epoch, merge, merge::Merge, prelude::*, split, split::Split, types::Type, version::Version,
+    epoch, formatter::sv_order, merge, merge::Merge, prelude::*, split, split::Split, types::Type,
+    version::Version,
 };
 
 use super::{
@@ -128,7 +129,7 @@ impl std::str::FromStr for NavMsgType {
 
 /// Navigation Frame published at a certain Epoch
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NavFrame {
     /// Ephemeris for given [`SV`]
     Eph(NavMsgType, SV, Ephemeris),
@@ -147,6 +148,15 @@ impl Default for NavFrame {
 }
 
 impl NavFrame {
+    /// Returns the [`SV`] this frame was published by
+    pub fn sv(&self) -> SV {
+        match self {
+            Self::Eph(_, sv, _)
+            | Self::Eop(_, sv, _)
+            | Self::Ion(_, sv, _)
+            | Self::Sto(_, sv, _) => *sv,
+        }
+    }
     /// Unwraps self, if possible, as ([`NavMsgType`], [`SV`], [`Ephemeris`])
     pub fn as_eph(&self) -> Option<(NavMsgType, SV, &Ephemeris)> {
         match self {
@@ -367,14 +377,91 @@ pub(crate) fn fmt_epoch(
     data: &Vec<NavFrame>,
     header: &Header,
 ) -> Result<String, Error> {
-    if header.version.major < 4 {
-        fmt_epoch_v2v3(epoch, data, header)
+    // frames are produced in vehicle order, whatever the parsing order
+    let mut data = data.iter().collect::<Vec<_>>();
+    data.sort_by_key(|fr| sv_order(&fr.sv()));
+    if header.version.major < 3 {
+        fmt_epoch_v2(epoch, &data, header)
+    } else if header.version.major < 4 {
+        fmt_epoch_v2v3(epoch, &data, header)
     } else {
-        fmt_epoch_v4(epoch, data, header)
+        fmt_epoch_v4(epoch, &data, header)
+    }
+}
+
+/*
+ * Expresses the time of clock in the vehicle native timescale,
+ * as RINEX expects, whatever the timescale the record was converted to.
+ */
+fn sv_toc(epoch: &Epoch, sv: SV) -> Epoch {
+    match sv.constellation.timescale() {
+        Some(ts) => epoch.to_time_scale(ts),
+        None => *epoch,
     }
 }
 
-fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<String, Error> {
+/*
+ * Formats given value in the 19 character wide "D" notation
+ * used in V2 NAV: 12 digit mantissa and two digit (signed) exponent,
+ * with a leading space for positive values.
+ */
+fn fmt_v2_float(value: f64) -> String {
+    let formatted = format!("{:.12E}", value);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('E').unwrap_or(0));
+    let exponent = exponent[1..].parse::<i32>().unwrap_or(0);
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{:>15}D{}{:02}", mantissa, sign, exponent.abs())
+}
+
+fn fmt_epoch_v2(epoch: &Epoch, data: &[&NavFrame], header: &Header) -> Result<String, Error> {
+    let mut lines = String::with_capacity(128);
+    for fr in data.iter() {
+        if let Some(fr) = fr.as_eph() {
+            let (_, sv, ephemeris) = fr;
+            // in V2 format, only PRN is shown
+            lines.push_str(&format!(
+                "{:2} {}{}{}{}\n",
+                sv.prn,
+                epoch::format(
+                    sv_toc(epoch, sv),
+                    Type::NavigationData,
+                    header.version.major
+                ),
+                fmt_v2_float(ephemeris.clock_bias),
+                fmt_v2_float(ephemeris.clock_drift),
+                fmt_v2_float(ephemeris.clock_drift_rate),
+            ));
+
+            // locate closest standards in DB
+            let closest_orbits_definition =
+                match closest_nav_standards(sv.constellation, header.version, NavMsgType::LNAV) {
+                    Some(v) => v,
+                    _ => return Err(Error::OrbitRevision),
+                };
+
+            for chunk in closest_orbits_definition.items.chunks(4) {
+                // omitted fields are blanked, trailing ones are simply not written
+                let nb_items = chunk
+                    .iter()
+                    .rposition(|(key, _)| ephemeris.orbits.contains_key(*key))
+                    .map(|pos| pos + 1)
+                    .unwrap_or(0);
+                lines.push_str("   ");
+                for (key, _) in chunk.iter().take(nb_items) {
+                    if let Some(data) = ephemeris.orbits.get(*key) {
+                        lines.push_str(&fmt_v2_float(data.to_f64()));
+                    } else {
+                        lines.push_str("                   ");
+                    }
+                }
+                lines.push('\n');
+            }
+        }
+    }
+    Ok(lines)
+}
+
+fn fmt_epoch_v2v3(epoch: &Epoch, data: &[&NavFrame], header: &Header) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
     for fr in data.iter() {
         if let Some(fr) = fr.as_eph() {
@@ -390,13 +477,15 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
                     // in V2 format, only PRN is shown
                     lines.push_str(&format!("{:2} ", sv.prn));
                 },
-                None => {
-                    panic!("can't generate data without predefined constellations");
-                },
+                None => return Err(Error::MissingConstellation),
             }
             lines.push_str(&format!(
                 "{} ",
-                epoch::format(*epoch, Type::NavigationData, header.version.major)
+                epoch::format(
+                    sv_toc(epoch, sv),
+                    Type::NavigationData,
+                    header.version.major
+                )
             ));
             lines.push_str(&format!(
                 "{:14.11E} {:14.11E} {:14.11E}\n   ",
@@ -447,7 +536,7 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
     Ok(lines)
 }
 
-fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<String, Error> {
+fn fmt_epoch_v4(epoch: &Epoch, data: &[&NavFrame], header: &Header) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
     for fr in data.iter() {
         if let Some(fr) = fr.as_eph() {
@@ -465,11 +554,15 @@ fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<
                     // in V2 format, only PRN is shown
                     lines.push_str(&format!("{:02} ", sv.prn));
                 },
-                None => panic!("producing data with no constellation previously defined"),
+                None => return Err(Error::MissingConstellation),
             }
             lines.push_str(&format!(
                 "{} ",
-                epoch::format(*epoch, Type::NavigationData, header.version.major)
+                epoch::format(
+                    sv_toc(epoch, sv),
+                    Type::NavigationData,
+                    header.version.major
+                )
             ));
             lines.push_str(&format!(
                 "{:14.13E} {:14.13E} {:14.13E}\n",
@@ -1172,7 +1265,7 @@ impl Decimate for Record {
     fn decimate_by_ratio_mut(&mut self, r: u32) {
         let mut i = 0;
         self.retain(|_, _| {
-            let retained = (i % r) == 0;
+            let retained = (i % r.max(1)) == 0;
             i += 1;
             retained
         });
@@ -16,7 +16,7 @@ pub enum Error {
 
 /// System Time Offset Message
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StoMessage {
     /// Time System
     pub system: String,
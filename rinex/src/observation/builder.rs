@@ -0,0 +1,171 @@
+//! Observation RINEX synthesis
+use super::{EpochFlag, HeaderFields, LliFlags, ObservationData, Record, SNR};
+use crate::prelude::*;
+use crate::{record, version::Version};
+use itertools::Itertools;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// [`ObservationBuilder`] related errors
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    #[error("no observations were provided")]
+    EmptyRecord,
+    #[error("{0} does not match header constellation")]
+    ConstellationMismatch(SV),
+    #[error("{1} has not been declared for {0}")]
+    UndeclaredObservable(Constellation, Observable),
+    #[error("V2 RINEX requires the same observables for all constellations")]
+    V2ObservablesMismatch,
+}
+
+/// [`ObservationBuilder`] helps synthesize Observation RINEX from scratch,
+/// for example when converting a proprietary receiver format.
+/// Mandatory header fields (PGM / RUN BY / DATE, TIME OF FIRST OBS,
+/// TIME OF LAST OBS, INTERVAL) are deduced when building.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observation::{LliFlags, ObservationBuilder, SNR};
+/// use rinex::version::Version;
+/// use std::str::FromStr;
+///
+/// let c1c = Observable::from_str("C1C").unwrap();
+/// let l1c = Observable::from_str("L1C").unwrap();
+/// let g01 = SV::from_str("G01").unwrap();
+/// let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+/// let t1 = Epoch::from_str("2023-01-01T00:00:30 GPST").unwrap();
+///
+/// let mut builder = ObservationBuilder::new(Version::new(3, 4), Constellation::GPS)
+///     .with_codes(Constellation::GPS, &[c1c.clone(), l1c.clone()]);
+///
+/// builder.add_epoch(t0, None);
+/// builder.add_observation(t0, g01, c1c.clone(), 20243517.560, None, Some(SNR::DbHz42_47));
+/// builder.add_observation(t0, g01, l1c.clone(), 106380411.418, Some(LliFlags::OK_OR_UNKNOWN), None);
+/// builder.add_epoch(t1, None);
+/// builder.add_observation(t1, g01, c1c.clone(), 20244226.380, None, None);
+///
+/// let rinex = builder.build()
+///     .unwrap();
+/// assert_eq!(rinex.header.sampling_interval, Some(Duration::from_seconds(30.0)));
+///
+/// // generated content is accepted by our parser
+/// let path = std::env::temp_dir().join("builder.rnx");
+/// rinex.to_file(path.to_str().unwrap())
+///     .unwrap();
+/// let parsed = Rinex::from_path(&path)
+///     .unwrap();
+/// assert_eq!(parsed.epoch().count(), 2);
+/// assert_eq!(parsed.record, rinex.record);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ObservationBuilder {
+    version: Version,
+    constellation: Constellation,
+    codes: HashMap<Constellation, Vec<Observable>>,
+    record: Record,
+}
+
+impl ObservationBuilder {
+    /// Creates a new [`ObservationBuilder`] for given RINEX revision.
+    /// `constellation` is the header constellation: use [`Constellation::Mixed`]
+    /// for multi GNSS content.
+    pub fn new(version: Version, constellation: Constellation) -> Self {
+        Self {
+            version,
+            constellation,
+            codes: HashMap::new(),
+            record: Record::new(),
+        }
+    }
+    /// Declares the list of observables for given constellation.
+    /// Observables are formatted in this order.
+    pub fn with_codes(&self, constellation: Constellation, codes: &[Observable]) -> Self {
+        let mut s = self.clone();
+        s.codes.insert(constellation, codes.to_vec());
+        s
+    }
+    /// Declares a new epoch, with possible receiver clock offset [s].
+    /// Epochs are marked [`EpochFlag::Ok`].
+    pub fn add_epoch(&mut self, epoch: Epoch, clock_offset: Option<f64>) {
+        let (clk, _) = self
+            .record
+            .entry((epoch, EpochFlag::Ok))
+            .or_insert((None, Default::default()));
+        *clk = clock_offset;
+    }
+    /// Adds one observation. The epoch is created on the fly
+    /// if it was not previously declared with [`Self::add_epoch`].
+    pub fn add_observation(
+        &mut self,
+        epoch: Epoch,
+        sv: SV,
+        code: Observable,
+        value: f64,
+        lli: Option<LliFlags>,
+        ssi: Option<SNR>,
+    ) {
+        let (_, vehicles) = self
+            .record
+            .entry((epoch, EpochFlag::Ok))
+            .or_insert((None, Default::default()));
+        vehicles
+            .entry(sv)
+            .or_default()
+            .insert(code, ObservationData::new(value, lli, ssi));
+    }
+    /// Builds the [`Rinex`], after verifying that all observations
+    /// are described in the header section.
+    pub fn build(&self) -> Result<Rinex, Error> {
+        let first = self.record.keys().next().ok_or(Error::EmptyRecord)?.0;
+        let last = self.record.keys().last().ok_or(Error::EmptyRecord)?.0;
+
+        for (_, (_, vehicles)) in self.record.iter() {
+            for (sv, observations) in vehicles.iter() {
+                if self.constellation != Constellation::Mixed
+                    && sv.constellation != self.constellation
+                {
+                    return Err(Error::ConstellationMismatch(*sv));
+                }
+                // SBAS vehicles are described by the SBAS observables
+                let constellation = match sv.constellation.is_sbas() {
+                    true => Constellation::SBAS,
+                    false => sv.constellation,
+                };
+                let codes = self.codes.get(&constellation);
+                for observable in observations.keys() {
+                    if !codes.map(|c| c.contains(observable)).unwrap_or(false) {
+                        return Err(Error::UndeclaredObservable(
+                            constellation,
+                            observable.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // V2 only describes a single list of observables
+        if self.version.major < 3 && self.codes.values().unique().count() > 1 {
+            return Err(Error::V2ObservablesMismatch);
+        }
+
+        let header = Header::basic_obs()
+            .with_version(self.version)
+            .with_constellation(self.constellation)
+            .with_general_infos(
+                &format!("rust-rinex-{}", env!("CARGO_PKG_VERSION")),
+                "Unknown",
+                "",
+            )
+            .with_production_now()
+            .with_observation_fields(HeaderFields {
+                time_of_first_obs: Some(first),
+                time_of_last_obs: Some(last),
+                codes: self.codes.clone(),
+                ..Default::default()
+            });
+
+        let mut rinex = Rinex::new(header, record::Record::ObsRecord(self.record.clone()));
+        rinex.header.sampling_interval = rinex.dominant_sample_rate();
+        Ok(rinex)
+    }
+}
@@ -1,19 +1,36 @@
 //! Observation RINEX module
 use super::{epoch, prelude::*, version::Version};
-use std::collections::HashMap;
+use crate::hardware::{Antenna, Rcvr};
+use std::collections::{BTreeMap, HashMap};
 
 pub mod record;
 
+pub mod builder;
+pub use builder::ObservationBuilder;
+
 pub mod flag;
 pub use flag::EpochFlag;
 
 mod snr;
-pub use snr::SNR;
+pub use snr::{Ssi, SNR};
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+pub(crate) mod spp;
+
+#[cfg(all(feature = "obs", feature = "nav"))]
+pub use spp::SppSolution;
 
 #[cfg(docrs)]
 use crate::Bibliography;
 
-pub use record::{LliFlags, ObservationData, Record};
+pub use record::{LliFlags, MultipathSummary, ObservationData, Record};
+
+/// Default maximal observation magnitude, which is the largest value
+/// the F14.3 RINEX format can describe. Observations exceeding it
+/// (for example "9.999999999999E+99" sentinels) or that are not finite
+/// are dropped when parsing.
+/// Use [crate::Rinex::from_file_with_max_magnitude] to customize it.
+pub const MAX_MAGNITUDE: f64 = 9_999_999_999.999;
 
 macro_rules! fmt_month {
     ($m: expr) => {
@@ -101,6 +118,27 @@ impl std::fmt::Display for Crinex {
     }
 }
 
+/// Wavelength factors of old RINEX (V1 and V2), described in
+/// `WAVELENGTH FACT L1/2` header fields. Factors are either
+/// 1 (full cycle ambiguities), 2 (half cycle ambiguities, squaring receivers)
+/// or 0 (L2 only: single frequency receiver).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WavelengthFactors {
+    /// Default (L1, L2) factors
+    pub default: (u8, u8),
+    /// Vehicle specific (L1, L2) factors, that override the default factors
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
+    pub sv: HashMap<SV, (u8, u8)>,
+}
+
+impl WavelengthFactors {
+    /// Returns (L1, L2) wavelength factors that apply to given `sv`
+    pub fn factors(&self, sv: SV) -> (u8, u8) {
+        self.sv.get(&sv).copied().unwrap_or(self.default)
+    }
+}
+
 /// Observation Record specific header fields
 #[derive(Debug, Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -117,7 +155,48 @@ pub struct HeaderFields {
     pub clock_offset_applied: bool,
     /// Possible observation scaling, used in high precision
     /// OBS RINEX (down to nano radians precision).
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub scaling: HashMap<(Constellation, Observable), u16>,
+    /// Observation scaling applying to all observables of a constellation
+    /// (SYS / SCALE FACTOR without list of observables).
+    /// Factors defined in [Self::scaling] take precedence, see [Self::scaling_factor].
+    pub data_scaling: HashMap<Constellation, u16>,
+    /// Number of satellites described in this file (# OF SATELLITES)
+    pub nb_satellites: Option<u16>,
+    /// Number of observations per satellite and per observable code
+    /// (PRN / # OF OBS), see [crate::Rinex::rebuild_obs_counts_mut]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
+    pub prn_obs_counts: HashMap<SV, HashMap<String, u32>>,
+    /// Receiver and antenna changes that occurred during this session,
+    /// see [crate::Rinex::equipment_changes]
+    pub equipment_changes: BTreeMap<Epoch, EquipmentChange>,
+    /// Unit of the signal strength observations (SIGNAL STRENGTH UNIT),
+    /// for example "DBHZ"
+    pub signal_strength_unit: Option<String>,
+}
+
+/// Receiver and/or antenna change, described by the header lines following
+/// an epoch flagged [EpochFlag::HeaderInformationFollows] or [EpochFlag::NewSiteOccupation].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EquipmentChange {
+    /// Event flag, either [EpochFlag::HeaderInformationFollows]
+    /// or [EpochFlag::NewSiteOccupation]
+    pub flag: EpochFlag,
+    /// New receiver (REC # / TYPE / VERS), if it was replaced
+    pub receiver: Option<Rcvr>,
+    /// New antenna (ANT # / TYPE), if it was replaced
+    pub antenna: Option<Antenna>,
+}
+
+impl Default for EquipmentChange {
+    fn default() -> Self {
+        Self {
+            flag: EpochFlag::HeaderInformationFollows,
+            receiver: None,
+            antenna: None,
+        }
+    }
 }
 
 impl HeaderFields {
@@ -137,16 +216,63 @@ impl HeaderFields {
     pub(crate) fn with_scaling(&mut self, c: Constellation, observable: Observable, scaling: u16) {
         self.scaling.insert((c, observable.clone()), scaling);
     }
-    /// Returns given scaling to apply for given GNSS system
-    /// and given observation. Returns 1.0 by default, so it always applies
-    pub(crate) fn scaling(&self, c: Constellation, observable: Observable) -> Option<&u16> {
-        self.scaling.get(&(c, observable))
+    /// Returns the scaling factor that applies to given [Observable]
+    /// of given [Constellation], if any. Stored observations are the
+    /// physical values multiplied by this factor.
+    /// The factor defined for this very observable takes precedence over
+    /// the factor defined for all observables of this constellation
+    /// ([Self::data_scaling]). Factors declared for [Constellation::Mixed]
+    /// (DORIS "D" lines) apply to all constellations, when no factor
+    /// is defined for this very constellation.
+    /// ```
+    /// use rinex::prelude::*;
+    /// use std::str::FromStr;
+    /// let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// let l1c = Observable::from_str("L1C").unwrap();
+    /// let obs = rnx.header.obs.as_ref().unwrap();
+    /// assert_eq!(obs.scaling_factor(Constellation::GPS, &l1c), None);
+    ///
+    /// rnx.apply_data_scaling_mut(Constellation::GPS, 100);
+    /// let obs = rnx.header.obs.as_ref().unwrap();
+    /// assert_eq!(obs.scaling_factor(Constellation::GPS, &l1c), Some(100));
+    /// assert_eq!(obs.scaling_factor(Constellation::Glonass, &l1c), None);
+    /// ```
+    pub fn scaling_factor(&self, c: Constellation, observable: &Observable) -> Option<u16> {
+        let c = match c.is_sbas() {
+            true => Constellation::SBAS,
+            false => c,
+        };
+        let mixed = (Constellation::Mixed, observable.clone());
+        self.scaling
+            .get(&(c, observable.clone()))
+            .or(self.scaling.get(&mixed))
+            .or(self.data_scaling.get(&c))
+            .or(self.data_scaling.get(&Constellation::Mixed))
+            .copied()
+    }
+    /*
+     * Scaling factor of each declared observable (if any),
+     * resolved by [Self::scaling_factor]
+     */
+    pub(crate) fn scaling_factors(&self) -> HashMap<(Constellation, Observable), u16> {
+        let mut factors: HashMap<(Constellation, Observable), u16> = self
+            .scaling
+            .iter()
+            .filter(|((c, _), _)| *c != Constellation::Mixed)
+            .map(|(key, factor)| (key.clone(), *factor))
+            .collect();
+        for (c, observables) in &self.codes {
+            for observable in observables {
+                if let Some(factor) = self.scaling_factor(*c, observable) {
+                    factors.insert((*c, observable.clone()), factor);
+                }
+            }
+        }
+        factors
     }
 }
 
-#[cfg(feature = "obs")]
-use std::collections::BTreeMap;
-
 #[cfg(feature = "obs")]
 #[cfg_attr(docrs, doc(cfg(feature = "obs")))]
 #[derive(Debug, Copy, Clone)]
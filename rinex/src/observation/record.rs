@@ -4,12 +4,22 @@ use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{
-    epoch, merge, merge::Merge, prelude::*, split, split::Split, types::Type, version::Version,
+    epoch, fmt_rinex,
+    formatter::sv_order,
+    merge,
+    merge::{Merge, MergeConflict},
+    prelude::*,
+    split,
+    split::Split,
+    types::Type,
+    version::Version,
     Carrier, Observable,
 };
 
+use crate::hardware::Rcvr;
+use crate::header::{parse_antenna_delta_hen, parse_antenna_type};
 use crate::observation::EpochFlag;
-use crate::observation::SNR;
+use crate::observation::{EquipmentChange, SNR};
 use hifitime::Duration;
 
 #[derive(Error, Debug)]
@@ -30,15 +40,31 @@ pub enum Error {
     EpochParsingError,
     #[error("line is empty")]
     MissingData,
+    #[error("event epoch (flag {0}) does not contain observations")]
+    EventEpoch(EpochFlag),
 }
 
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 bitflags! {
+    /// Loss of Lock Indicator (LLI), attached to an observation.
+    /// LLI is a single digit, each bit of it has a specific meaning:
+    ///   - bit 0 (0x01): [Self::LOCK_LOSS]
+    ///   - bit 1 (0x02): [Self::HALF_CYCLE_SLIP] (a.k.a [Self::HALF_CYCLE_AMBIGUITY])
+    ///   - bit 2 (0x04): [Self::UNDER_ANTI_SPOOFING] (a.k.a [Self::UNDER_ANTISPOOFING])
+    ///
+    /// A blank or 0 LLI is [Self::OK_OR_UNKNOWN].
+    /// ```
+    /// use rinex::observation::LliFlags;
+    /// let lli = LliFlags::from_bits(5).unwrap();
+    /// assert!(lli.intersects(LliFlags::LOCK_LOSS));
+    /// assert!(lli.intersects(LliFlags::UNDER_ANTISPOOFING));
+    /// assert!(!lli.intersects(LliFlags::HALF_CYCLE_AMBIGUITY));
+    /// ```
     #[derive(Debug, Copy, Clone)]
     #[derive(PartialEq, PartialOrd)]
-    #[cfg_attr(feature = "serde", derive(Serialize))]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct LliFlags: u8 {
         /// Current epoch is marked Ok or Unknown status
         const OK_OR_UNKNOWN = 0x00;
@@ -50,11 +76,15 @@ bitflags! {
         /// Observing under anti spoofing,
         /// might suffer from decreased SNR - decreased signal quality
         const UNDER_ANTI_SPOOFING = 0x04;
+        /// Half cycle ambiguity, same as [Self::HALF_CYCLE_SLIP]
+        const HALF_CYCLE_AMBIGUITY = Self::HALF_CYCLE_SLIP.bits();
+        /// Same as [Self::UNDER_ANTI_SPOOFING]
+        const UNDER_ANTISPOOFING = Self::UNDER_ANTI_SPOOFING.bits();
     }
 }
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ObservationData {
     /// physical measurement
     pub obs: f64,
@@ -174,11 +204,94 @@ pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
     }
 }
 
+/*
+ * Parses the epoch flag and the number of following lines (vehicles or events),
+ * from the epoch descriptor
+ */
+fn epoch_descriptor(header: &Header, line: &str) -> Option<(EpochFlag, usize)> {
+    let line = line.strip_prefix('>').unwrap_or(line);
+    let offset = match header.version.major > 2 {
+        true => 28,
+        false => 26,
+    };
+    if line.len() < offset + 6 || !line.is_char_boundary(offset) {
+        return None;
+    }
+    let flag = EpochFlag::from_str(line[offset..offset + 3].trim()).ok()?;
+    let n = line[offset + 3..offset + 6].trim().parse::<usize>().ok()?;
+    Some((flag, n))
+}
+
+/// Returns true if given epoch content is complete, that is:
+/// it contains as many lines as described by its epoch descriptor.
+/// This is used to identify an incomplete epoch, at the end of a truncated file.
+/// V2 writers may omit the last (blank) observation lines of the last vehicle,
+/// so we only require that one line exists for it.
+pub(crate) fn is_complete_epoch(header: &Header, content: &str) -> bool {
+    let mut lines = content.lines();
+    let (flag, n) = match lines.next().and_then(|line| epoch_descriptor(header, line)) {
+        Some(descriptor) => descriptor,
+        None => return false,
+    };
+    let nb_lines = lines.count();
+    match flag {
+        EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => {
+            if header.version.major > 2 {
+                nb_lines >= n
+            } else if n == 0 {
+                true
+            } else {
+                let nb_obs = header
+                    .obs
+                    .as_ref()
+                    .and_then(|obs| obs.codes.values().map(|codes| codes.len()).max())
+                    .unwrap_or(0);
+                // 12 vehicles per epoch line, 5 observations per line
+                let per_sv = num_integer::div_ceil(nb_obs, 5);
+                nb_lines >= (n - 1) / 12 + (n - 1) * per_sv + 1
+            }
+        },
+        // events: n following lines
+        _ => nb_lines >= n,
+    }
+}
+
+/// Returns true if the last line of given epoch content was interrupted,
+/// that is: it stops in the middle of a fixed width field.
+/// Observations are F14.3 fields followed by optional LLI and SSI flags,
+/// event lines are header lines and should be labelized.
+pub(crate) fn is_interrupted_line(header: &Header, content: &str) -> bool {
+    let mut lines = content.lines();
+    let flag = match lines.next().and_then(|line| epoch_descriptor(header, line)) {
+        Some((flag, _)) => flag,
+        None => return true,
+    };
+    let line = match lines.last() {
+        Some(line) => line,
+        None => return false, // descriptor only: see is_complete_epoch
+    };
+    match flag {
+        EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => {
+            let len = line.trim_end().len();
+            let fields_len = match header.version.major > 2 {
+                true => match len.checked_sub(3) {
+                    Some(len) => len, // SV prefix
+                    None => return true,
+                },
+                false => len,
+            };
+            !matches!(fields_len % 16, 0 | 14 | 15)
+        },
+        _ => line.len() <= 60,
+    }
+}
+
 /// Builds `Record` entry for `ObservationData` from given epoch content
 pub(crate) fn parse_epoch(
     header: &Header,
     content: &str,
     ts: TimeScale,
+    max_magnitude: f64,
 ) -> Result<
     (
         (Epoch, EpochFlag),
@@ -211,6 +324,11 @@ pub(crate) fn parse_epoch(
         line = line.split_at(1).1;
     }
 
+    // interrupted epoch descriptor
+    if line.len() < offset + 6 || !line.is_char_boundary(offset) {
+        return Err(Error::MissingData);
+    }
+
     let (date, rem) = line.split_at(offset);
     let epoch = epoch::parse_in_timescale(date, ts)?;
     let (flag, rem) = rem.split_at(3);
@@ -219,12 +337,12 @@ pub(crate) fn parse_epoch(
     let n_sat = n_sat.trim().parse::<u16>()?;
 
     // grab possible clock offset
-    let offs: Option<&str> = match header.version.major < 2 {
+    let offs: Option<&str> = match header.version.major < 3 {
         true => {
             // RINEX 2
-            // clock offsets are last 12 characters
-            if line.len() > 60 - 12 {
-                Some(line.split_at(60 - 12).1.trim())
+            // clock offsets follow the (first) 12 vehicles
+            if line.len() > 68 {
+                Some(line.split_at(68).1.trim())
             } else {
                 None
             }
@@ -259,13 +377,75 @@ pub(crate) fn parse_epoch(
     };
 
     match flag {
-        EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => {
-            parse_normal(header, epoch, flag, n_sat, clock_offset, rem, lines)
-        },
+        EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => parse_normal(
+            header,
+            epoch,
+            flag,
+            n_sat,
+            clock_offset,
+            rem,
+            lines,
+            max_magnitude,
+        ),
         _ => parse_event(header, epoch, flag, n_sat, clock_offset, rem, lines),
     }
 }
 
+/// Parses delimited epoch blocks concurrently, using one thread per available core.
+/// Results are returned in block order, each one paired with its `tag`.
+#[cfg(feature = "parallel")]
+pub(crate) fn parse_epochs<T: Send>(
+    header: &Header,
+    blocks: Vec<(String, T)>,
+    ts: TimeScale,
+    max_magnitude: f64,
+) -> Vec<(
+    Result<
+        (
+            (Epoch, EpochFlag),
+            Option<f64>,
+            BTreeMap<SV, HashMap<Observable, ObservationData>>,
+        ),
+        Error,
+    >,
+    T,
+)> {
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let chunk_size = ((blocks.len() + threads - 1) / threads).max(1);
+
+    let mut chunks = Vec::with_capacity(threads);
+    let mut blocks = blocks.into_iter();
+    loop {
+        let chunk: Vec<_> = blocks.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        chunks.push(chunk);
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .into_iter()
+                        .map(|(content, tag)| {
+                            (parse_epoch(header, &content, ts, max_magnitude), tag)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("epoch parsing thread panicked"))
+            .collect()
+    })
+}
+
 fn parse_normal(
     header: &Header,
     epoch: Epoch,
@@ -274,6 +454,7 @@ fn parse_normal(
     clock_offset: Option<f64>,
     rem: &str,
     mut lines: std::str::Lines<'_>,
+    max_magnitude: f64,
 ) -> Result<
     (
         (Epoch, EpochFlag),
@@ -302,9 +483,9 @@ fn parse_normal(
                     return Err(Error::MissingData);
                 }
             }
-            parse_v2(header, &systems, observables, lines)
+            parse_v2(header, &systems, observables, lines, max_magnitude)
         },
-        _ => parse_v3(observables, lines),
+        _ => parse_v3(observables, lines, max_magnitude),
     };
     Ok(((epoch, flag), clock_offset, data))
 }
@@ -312,7 +493,7 @@ fn parse_normal(
 fn parse_event(
     _header: &Header,
     _epoch: Epoch,
-    _flag: EpochFlag,
+    flag: EpochFlag,
     _n_records: u16,
     _clock_offset: Option<f64>,
     _rem: &str,
@@ -325,11 +506,78 @@ fn parse_event(
     ),
     Error,
 > {
-    // TODO: Verify that the number of lines of data
-    // to read matches the number of records expected
+    // Events do not carry observations and are not stored in the record:
+    // the following header lines are interpreted by [parse_equipment_change].
+    Err(Error::EventEpoch(flag))
+}
+
+/// Parses the receiver and antenna descriptions contained in an event block,
+/// following an epoch flagged [EpochFlag::HeaderInformationFollows]
+/// or [EpochFlag::NewSiteOccupation].
+/// Returns None for any other epoch, or if no equipment is described.
+pub(crate) fn parse_equipment_change(
+    header: &Header,
+    content: &str,
+    ts: TimeScale,
+) -> Option<(Epoch, EquipmentChange)> {
+    let mut lines = content.lines();
+    let line = lines.next()?;
+    let line = line.strip_prefix('>').unwrap_or(line);
 
-    // TODO: Actually process event data
-    Err(Error::MissingData)
+    let offset = match header.version.major > 2 {
+        true => 28,
+        false => 26,
+    };
+    let date = line.get(..offset)?;
+    let flag = EpochFlag::from_str(line.get(offset..offset + 3)?.trim()).ok()?;
+    if flag != EpochFlag::HeaderInformationFollows && flag != EpochFlag::NewSiteOccupation {
+        return None;
+    }
+    let epoch = epoch::parse_in_timescale(date, ts).ok()?;
+
+    let mut change = EquipmentChange {
+        flag,
+        ..Default::default()
+    };
+    let mut delta_hen: Option<(f64, f64, f64)> = None;
+    for line in lines {
+        if line.len() < 60 || !line.is_char_boundary(60) {
+            continue;
+        }
+        let (content, marker) = line.split_at(60);
+        match marker.trim() {
+            "REC # / TYPE / VERS" => {
+                if let Ok(receiver) = Rcvr::from_str(content) {
+                    change.receiver = Some(receiver);
+                }
+            },
+            "ANT # / TYPE" => {
+                change.antenna = Some(parse_antenna_type(content, change.antenna.take()));
+            },
+            "ANTENNA: DELTA H/E/N" => {
+                delta_hen = parse_antenna_delta_hen(content).or(delta_hen);
+            },
+            _ => {},
+        }
+    }
+    // eccentricities only qualify a new antenna
+    if let (Some(antenna), Some(delta_hen)) = (&mut change.antenna, delta_hen) {
+        *antenna = antenna.with_delta_hen(delta_hen);
+    }
+    if change.receiver.is_none() && change.antenna.is_none() {
+        None
+    } else {
+        Some((epoch, change))
+    }
+}
+
+/*
+ * Returns true if this observation may be stored:
+ * some corrupt files contain sentinel values (like 9.999999999999E+99)
+ * that would poison any later statistical analysis.
+ */
+fn is_sane(obs: f64, max_magnitude: f64) -> bool {
+    obs.is_finite() && obs.abs() <= max_magnitude
 }
 
 /*
@@ -342,6 +590,7 @@ fn parse_v2(
     systems: &str,
     header_observables: &HashMap<Constellation, Vec<Observable>>,
     lines: std::str::Lines<'_>,
+    max_magnitude: f64,
 ) -> BTreeMap<SV, HashMap<Observable, ObservationData>> {
     let svnn_size = 3; // SVNN standard
     let nb_max_observables = 5; // in a single line
@@ -459,12 +708,18 @@ fn parse_v2(
                         }
                         if slice.len() > 15 {
                             let snr_str = &slice[15..16];
-                            if let Ok(s) = SNR::from_str(snr_str) {
-                                snr = Some(s);
+                            if !snr_str.trim().is_empty() {
+                                // blank: unknown, omitted
+                                if let Ok(s) = SNR::from_str(snr_str) {
+                                    snr = Some(s);
+                                }
                             }
                         }
                     }
                     //println!("{} {:?} {:?} ==> {}", obs, lli, snr, obscodes[obs_ptr-1]); //DEBUG
+                    if !is_sane(obs, max_magnitude) {
+                        continue; // corrupt or sentinel value
+                    }
                     inner.insert(
                         observables[obs_ptr - 1].clone(),
                         ObservationData { obs, lli, snr },
@@ -545,6 +800,7 @@ fn parse_v2(
 fn parse_v3(
     observables: &HashMap<Constellation, Vec<Observable>>,
     lines: std::str::Lines<'_>,
+    max_magnitude: f64,
 ) -> BTreeMap<SV, HashMap<Observable, ObservationData>> {
     let svnn_size = 3; // SVNN standard
     let observable_width = 16; // data + 2 flags
@@ -553,6 +809,9 @@ fn parse_v3(
     for line in lines {
         // browse all lines
         //println!("parse_v3: \"{}\"", line); //DEBUG
+        if line.len() < svnn_size || !line.is_char_boundary(svnn_size) {
+            continue; // interrupted or invalid line
+        }
         let (sv, line) = line.split_at(svnn_size);
         if let Ok(sv) = SV::from_str(sv) {
             let obscodes = match sv.constellation.is_sbas() {
@@ -580,6 +839,9 @@ fn parse_v3(
                     let obs = &content[0..std::cmp::min(observable_width - 2, content_len)];
                     //println!("OBS \"{}\"", obs); //DEBUG
                     if let Ok(obs) = f64::from_str(obs.trim()) {
+                        if !is_sane(obs, max_magnitude) {
+                            continue; // corrupt or sentinel value
+                        }
                         if content_len > observable_width - 2 {
                             let lli_str = &content[observable_width - 2..observable_width - 1];
                             if let Ok(u) = u8::from_str_radix(lli_str, 10) {
@@ -588,8 +850,11 @@ fn parse_v3(
                         }
                         if content_len > observable_width - 1 {
                             let snr_str = &content[observable_width - 1..observable_width];
-                            if let Ok(s) = SNR::from_str(snr_str) {
-                                snr = Some(s);
+                            if !snr_str.trim().is_empty() {
+                                // blank: unknown, omitted
+                                if let Ok(s) = SNR::from_str(snr_str) {
+                                    snr = Some(s);
+                                }
                             }
                         }
                         //println!("LLI {:?}", lli); //DEBUG
@@ -598,19 +863,27 @@ fn parse_v3(
                         inner.insert(obscodes[i].clone(), ObservationData { obs, lli, snr });
                     }
                 }
-                if rem.len() >= observable_width - 2 {
+                if rem.len() >= observable_width - 2 && nb_obs < obscodes.len() {
                     let mut snr: Option<SNR> = None;
                     let mut lli: Option<LliFlags> = None;
                     let obs = &rem[0..observable_width - 2];
-                    if let Ok(obs) = obs.trim().parse::<f64>() {
+                    let obs = obs
+                        .trim()
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|obs| is_sane(*obs, max_magnitude));
+                    if let Some(obs) = obs {
                         if rem.len() > observable_width - 2 {
                             let lli_str = &rem[observable_width - 2..observable_width - 1];
                             if let Ok(u) = lli_str.parse::<u8>() {
                                 lli = LliFlags::from_bits(u);
                                 if rem.len() > observable_width - 1 {
                                     let snr_str = &rem[observable_width - 1..];
-                                    if let Ok(s) = SNR::from_str(snr_str) {
-                                        snr = Some(s);
+                                    if !snr_str.trim().is_empty() {
+                                        // blank: unknown, omitted
+                                        if let Ok(s) = SNR::from_str(snr_str) {
+                                            snr = Some(s);
+                                        }
                                     }
                                 }
                             }
@@ -642,6 +915,67 @@ pub(crate) fn fmt_epoch(
     }
 }
 
+/*
+ * Formats an event epoch, followed by the header lines
+ * that describe the new receiver and/or antenna
+ */
+pub(crate) fn fmt_equipment_change(
+    epoch: Epoch,
+    change: &EquipmentChange,
+    header: &Header,
+) -> String {
+    let flag = change.flag;
+    let mut records = Vec::<String>::with_capacity(3);
+    if let Some(rcvr) = &change.receiver {
+        records.push(fmt_rinex(
+            &format!("{:<20}{:<20}{}", rcvr.sn, rcvr.model, rcvr.firmware),
+            "REC # / TYPE / VERS",
+        ));
+    }
+    if let Some(antenna) = &change.antenna {
+        records.push(fmt_rinex(
+            &format!("{:<20}{}", antenna.model, antenna.sn),
+            "ANT # / TYPE",
+        ));
+        if let Some((h, e, n)) = antenna.delta_hen {
+            records.push(fmt_rinex(
+                &format!("{:14.4}{:14.4}{:14.4}", h, e, n),
+                "ANTENNA: DELTA H/E/N",
+            ));
+        }
+    }
+    let mut lines = match header.version.major < 3 {
+        true => format!(
+            " {}  {} {:2}",
+            epoch::format(epoch, Type::ObservationData, 2),
+            flag,
+            records.len()
+        ),
+        false => format!(
+            "> {}  {} {:2}",
+            epoch::format(epoch, Type::ObservationData, 3),
+            flag,
+            records.len()
+        ),
+    };
+    for record in records {
+        lines.push('\n');
+        lines.push_str(&record);
+    }
+    lines
+}
+
+/*
+ * Vehicles of an epoch, in the order they are to be produced
+ */
+fn sorted_vehicles(
+    data: &BTreeMap<SV, HashMap<Observable, ObservationData>>,
+) -> Vec<(&SV, &HashMap<Observable, ObservationData>)> {
+    let mut vehicles = data.iter().collect::<Vec<_>>();
+    vehicles.sort_by_key(|(sv, _)| sv_order(sv));
+    vehicles
+}
+
 fn fmt_epoch_v3(
     epoch: Epoch,
     flag: EpochFlag,
@@ -660,11 +994,11 @@ fn fmt_epoch_v3(
     ));
 
     if let Some(data) = clock_offset {
-        lines.push_str(&format!("{:13.4}", data));
+        lines.push_str(&format!("      {:15.12}", data));
     }
 
     lines.push('\n');
-    for (sv, data) in data.iter() {
+    for (sv, data) in sorted_vehicles(data) {
         lines.push_str(&format!("{:x}", sv));
         let observables = match sv.constellation.is_sbas() {
             true => observables.get(&Constellation::SBAS),
@@ -695,6 +1029,21 @@ fn fmt_epoch_v3(
     lines
 }
 
+/*
+ * V2 clock offsets are appended to the first line (F12.9),
+ * after the list of vehicles it describes (12 at most)
+ */
+fn fmt_clock_offset_v2(lines: &mut String, nb_vehicles: usize, clock_offset: &Option<f64>) {
+    if let Some(data) = clock_offset {
+        lines.push_str(&format!(
+            "{:width$}{:12.9}",
+            "",
+            data,
+            width = 3 * (12 - nb_vehicles.min(12))
+        ));
+    }
+}
+
 fn fmt_epoch_v2(
     epoch: Epoch,
     flag: EpochFlag,
@@ -712,25 +1061,26 @@ fn fmt_epoch_v2(
         data.len()
     ));
 
+    let vehicles = sorted_vehicles(data);
     let mut index = 0_u8;
-    for (sv_index, (sv, _)) in data.iter().enumerate() {
+    for (sv_index, (sv, _)) in vehicles.iter().enumerate() {
         if index == 12 {
             index = 0;
             if sv_index == 12 {
                 // first line
-                if let Some(data) = clock_offset {
-                    // push clock offsets
-                    lines.push_str(&format!(" {:9.1}", data));
-                }
+                fmt_clock_offset_v2(&mut lines, 12, clock_offset);
             }
             lines.push_str("\n                                ");
         }
         lines.push_str(&format!("{:x}", sv));
         index += 1;
     }
+    if data.len() <= 12 {
+        fmt_clock_offset_v2(&mut lines, data.len(), clock_offset);
+    }
     let obs_per_line = 5;
-    // for each vehicle per epoch
-    for (sv, observations) in data.iter() {
+    // for each vehicle per epoch, in the epoch description order
+    for (sv, observations) in vehicles {
         // follow list of observables, as described in header section
         // for given constellation
         let observables = match sv.constellation.is_sbas() {
@@ -766,6 +1116,31 @@ fn fmt_epoch_v2(
     lines
 }
 
+/// Rescales all observations in place, from the `current` scaling factors
+/// to the `target` scaling factors. Missing factors mean unscaled data.
+pub(crate) fn rescale_mut(
+    rec: &mut Record,
+    current: &HashMap<(Constellation, Observable), u16>,
+    target: &HashMap<(Constellation, Observable), u16>,
+) {
+    for (_, (_, vehicles)) in rec.iter_mut() {
+        for (sv, observations) in vehicles.iter_mut() {
+            let constellation = match sv.constellation.is_sbas() {
+                true => Constellation::SBAS,
+                false => sv.constellation,
+            };
+            for (observable, data) in observations.iter_mut() {
+                let key = (constellation, observable.clone());
+                let current = *current.get(&key).unwrap_or(&1);
+                let target = *target.get(&key).unwrap_or(&1);
+                if current != target {
+                    data.obs *= target as f64 / current as f64;
+                }
+            }
+        }
+    }
+}
+
 impl Merge for Record {
     /// Merge `rhs` into `Self`
     fn merge(&self, rhs: &Self) -> Result<Self, merge::Error> {
@@ -806,6 +1181,53 @@ impl Merge for Record {
     }
 }
 
+/// Returns all observations that both records define, with different values.
+pub(crate) fn merge_conflicts(lhs: &Record, rhs: &Record) -> Vec<MergeConflict> {
+    let mut conflicts = Vec::<MergeConflict>::new();
+    for ((epoch, flag), (_, rhs_vehicles)) in rhs {
+        if let Some((_, vehicles)) = lhs.get(&(*epoch, *flag)) {
+            for (sv, rhs_observations) in rhs_vehicles {
+                if let Some(observations) = vehicles.get(sv) {
+                    for (observable, rhs_data) in rhs_observations {
+                        if let Some(data) = observations.get(observable) {
+                            if data != rhs_data {
+                                conflicts.push(MergeConflict {
+                                    epoch: *epoch,
+                                    flag: *flag,
+                                    sv: *sv,
+                                    observable: observable.clone(),
+                                    lhs: *data,
+                                    rhs: *rhs_data,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+/// Merges `rhs` into `lhs` following [MergeStrategy::PreferSelf]:
+/// only observations that `lhs` did not define are introduced.
+pub(crate) fn merge_mut_prefer_self(lhs: &mut Record, rhs: &Record) {
+    for (rhs_epoch, (rhs_clk, rhs_vehicles)) in rhs {
+        let (clk, vehicles) = lhs.entry(*rhs_epoch).or_insert((*rhs_clk, BTreeMap::new()));
+        if clk.is_none() {
+            *clk = *rhs_clk;
+        }
+        for (rhs_vehicle, rhs_observations) in rhs_vehicles {
+            let observations = vehicles.entry(*rhs_vehicle).or_default();
+            for (rhs_observable, rhs_data) in rhs_observations {
+                observations
+                    .entry(rhs_observable.clone())
+                    .or_insert(*rhs_data);
+            }
+        }
+    }
+}
+
 impl Split for Record {
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let r0 = self
@@ -1194,6 +1616,114 @@ impl Interpolate for Record {
     }
 }
 
+#[cfg(feature = "processing")]
+impl Resample for Record {
+    fn resample(&self, interval: Duration, method: InterpMethod) -> Self {
+        let mut s = self.clone();
+        s.resample_mut(interval, method);
+        s
+    }
+    fn resample_mut(&mut self, interval: Duration, method: InterpMethod) {
+        let epochs = self
+            .keys()
+            .filter_map(|(e, flag)| if flag.is_ok() { Some(*e) } else { None })
+            .collect::<Vec<_>>();
+        if let Some(max_gap) = crate::algorithm::default_max_gap(&epochs) {
+            self.resample_with_max_gap_mut(interval, method, max_gap);
+        }
+    }
+    fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) -> Self {
+        let mut s = self.clone();
+        s.resample_with_max_gap_mut(interval, method, max_gap);
+        s
+    }
+    /*
+     * Phase, pseudo range and doppler observations are interpolated with given method.
+     * Signal strength observations, LLI and SNR flags are picked from the nearest sample.
+     * Only sane epochs are considered: events are dropped.
+     */
+    fn resample_with_max_gap_mut(
+        &mut self,
+        interval: Duration,
+        method: InterpMethod,
+        max_gap: Duration,
+    ) {
+        if interval <= Duration::ZERO {
+            return; // null interval: nothing to resample onto
+        }
+        let mut clock = (Vec::<Epoch>::new(), Vec::<f64>::new());
+        let mut series =
+            HashMap::<(SV, Observable), (Vec<Epoch>, Vec<ObservationData>, Vec<f64>)>::new();
+        for ((e, flag), (clk, vehicles)) in self.iter() {
+            if !flag.is_ok() {
+                continue;
+            }
+            if let Some(clk) = clk {
+                clock.0.push(*e);
+                clock.1.push(*clk);
+            }
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    let (epochs, data_pts, values) = series
+                        .entry((*sv, observable.clone()))
+                        .or_insert_with(|| (Vec::new(), Vec::new(), Vec::new()));
+                    epochs.push(*e);
+                    data_pts.push(*data);
+                    values.push(data.obs);
+                }
+            }
+        }
+        let (t0, t1) = match (
+            series
+                .values()
+                .filter_map(|(epochs, _, _)| epochs.first())
+                .min(),
+            series
+                .values()
+                .filter_map(|(epochs, _, _)| epochs.last())
+                .max(),
+        ) {
+            (Some(t0), Some(t1)) => (*t0, *t1),
+            _ => {
+                self.clear();
+                return;
+            },
+        };
+        let mut resampled = Record::new();
+        for t in crate::algorithm::resampling_grid(t0, t1, interval) {
+            let mut vehicles = BTreeMap::<SV, HashMap<Observable, ObservationData>>::new();
+            for ((sv, observable), (epochs, data_pts, values)) in &series {
+                let nearest = match crate::algorithm::nearest(epochs, t, max_gap) {
+                    Some(nearest) => data_pts[nearest],
+                    None => continue,
+                };
+                let data = if observable.is_ssi_observable() {
+                    nearest
+                } else {
+                    match crate::algorithm::interpolate(epochs, values, t, method, max_gap) {
+                        Some(obs) => ObservationData { obs, ..nearest },
+                        None => continue,
+                    }
+                };
+                vehicles
+                    .entry(*sv)
+                    .or_default()
+                    .insert(observable.clone(), data);
+            }
+            if !vehicles.is_empty() {
+                let clk = crate::algorithm::interpolate(&clock.0, &clock.1, t, method, max_gap);
+                resampled.insert((t, EpochFlag::Ok), (clk, vehicles));
+            }
+        }
+        *self = resampled;
+    }
+}
+
 /*
  * Decimates only a given record subset
  */
@@ -1365,10 +1895,19 @@ impl Preprocessing for Record {
 #[cfg(feature = "processing")]
 impl Decimate for Record {
     fn decimate_by_ratio_mut(&mut self, r: u32) {
+        // entries sharing a timestamp (event + data) count once,
+        // and are either all retained or all dropped
         let mut i = 0;
-        self.retain(|_, _| {
-            let retained = (i % r) == 0;
+        let mut last = Option::<(Epoch, bool)>::None;
+        self.retain(|(e, _), _| {
+            if let Some((t, retained)) = last {
+                if t == *e {
+                    return retained;
+                }
+            }
+            let retained = (i % r.max(1)) == 0;
             i += 1;
+            last = Some((*e, retained));
             retained
         });
     }
@@ -1381,6 +1920,9 @@ impl Decimate for Record {
         let mut last_retained = Option::<Epoch>::None;
         self.retain(|(e, _), _| {
             if let Some(last) = last_retained {
+                if *e == last {
+                    return true; // same timestamp (event + data)
+                }
                 let dt = *e - last;
                 if dt >= interval {
                     last_retained = Some(*e);
@@ -1400,7 +1942,10 @@ impl Decimate for Record {
         s
     }
     fn decimate_match_mut(&mut self, rhs: &Self) {
-        self.retain(|e, _| rhs.get(e).is_some());
+        // match on timestamps, whatever the flags
+        let mut epochs: Vec<Epoch> = rhs.keys().map(|(e, _)| *e).collect();
+        epochs.dedup();
+        self.retain(|(e, _), _| epochs.binary_search(e).is_ok());
     }
     fn decimate_match(&self, rhs: &Self) -> Self {
         let mut s = self.clone();
@@ -1810,6 +2355,749 @@ pub(crate) fn code_multipath(
     ret
 }
 
+/// MP1 / MP2 code multipath statistics, for one satellite
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MultipathSummary {
+    /// Number of continuous arcs, delimited by cycle slips or data gaps
+    pub arcs: usize,
+    /// MP1 root mean square [m]
+    pub mp1_rms: Option<f64>,
+    /// MP2 root mean square [m]
+    pub mp2_rms: Option<f64>,
+}
+
+/*
+ * Frequency bands combined in MP1 / MP2, per constellation
+ */
+#[cfg(feature = "obs")]
+fn mp12_bands(constellation: Constellation) -> Option<(char, char)> {
+    match constellation {
+        Constellation::GPS | Constellation::QZSS | Constellation::Glonass => Some(('1', '2')),
+        Constellation::Galileo => Some(('1', '5')),
+        Constellation::BeiDou => Some(('2', '7')),
+        Constellation::IRNSS => Some(('5', '9')),
+        c if c.is_sbas() => Some(('1', '5')),
+        _ => None,
+    }
+}
+
+/*
+ * Ranks signal attributes, lowest is preferred:
+ * precise codes first, then civilian codes
+ */
+#[cfg(feature = "obs")]
+fn mp12_rank(code: &str) -> usize {
+    const PREFERENCE: &str = "PWYCXQILSDBAZMNE";
+    match code.chars().nth(2) {
+        Some(attribute) => PREFERENCE.find(attribute).unwrap_or(PREFERENCE.len()),
+        // V2 codes: P1 / P2 are preferred over C1 / C2
+        None => usize::from(!code.starts_with('P')),
+    }
+}
+
+/*
+ * Lists all observables encountered, per SV
+ */
+#[cfg(feature = "obs")]
+fn sv_observables(rec: &Record) -> BTreeMap<SV, Vec<&Observable>> {
+    let mut ret: BTreeMap<SV, Vec<&Observable>> = BTreeMap::new();
+    for (_, (_, vehicles)) in rec.iter() {
+        for (sv, observations) in vehicles.iter() {
+            let list = ret.entry(*sv).or_default();
+            for observable in observations.keys() {
+                if !list.contains(&observable) {
+                    list.push(observable);
+                }
+            }
+        }
+    }
+    ret
+}
+
+/*
+ * Selects the preferred phase or code observable on given frequency band.
+ * Observables matching the `attribute` of a previously selected signal are preferred.
+ */
+#[cfg(feature = "obs")]
+fn best_observable(
+    observables: &[&Observable],
+    band: char,
+    phase: bool,
+    attribute: Option<char>,
+) -> Option<Observable> {
+    observables
+        .iter()
+        .filter(|obs| {
+            let code = obs.to_string();
+            code.chars().nth(1) == Some(band)
+                && match phase {
+                    true => obs.is_phase_observable(),
+                    false => obs.is_pseudorange_observable(),
+                }
+        })
+        .min_by_key(|obs| {
+            let code = obs.to_string();
+            let matching = attribute.is_some() && code.chars().nth(2) == attribute;
+            (!matching, mp12_rank(&code), code)
+        })
+        .map(|obs| (*obs).clone())
+}
+
+/*
+ * Selects the (P1, P2, L1, L2) observables to combine for given SV
+ */
+#[cfg(feature = "obs")]
+fn mp12_observables(
+    sv: SV,
+    observables: &[&Observable],
+) -> Option<(
+    Option<Observable>,
+    Option<Observable>,
+    Observable,
+    Observable,
+)> {
+    let (band1, band2) = mp12_bands(sv.constellation)?;
+    let attribute = |obs: &Option<Observable>| -> Option<char> {
+        obs.as_ref().and_then(|obs| obs.to_string().chars().nth(2))
+    };
+    let p1 = best_observable(observables, band1, false, None);
+    let p2 = best_observable(observables, band2, false, None);
+    let l1 = best_observable(observables, band1, true, attribute(&p1))?;
+    let l2 = best_observable(observables, band2, true, attribute(&p2))?;
+    Some((p1, p2, l1, l2))
+}
+
+/*
+ * Maximal duration between two samples of a continuous arc:
+ * a data gap is declared when one sample is missing
+ */
+fn arc_max_gap(rec: &Record, header: &Header) -> Duration {
+    let interval = header.sampling_interval.unwrap_or_else(|| {
+        let epochs: Vec<Epoch> = rec.keys().map(|(epoch, _)| *epoch).collect();
+        epochs
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .filter(|dt| *dt > Duration::ZERO)
+            .min()
+            .unwrap_or(Duration::ZERO)
+    });
+    interval * 1.5
+}
+
+/*
+ * Carrier signal of given observable,
+ * refined for Glonass vehicles when channels are described
+ */
+#[cfg(feature = "obs")]
+fn sv_carrier(header: &Header, sv: &SV, observable: &Observable) -> Option<Carrier> {
+    let carrier = Carrier::from_observable(sv.constellation, observable).ok()?;
+    match header.glo_channels.get(sv) {
+        Some(offset) if sv.constellation == Constellation::Glonass => {
+            Some(carrier.with_glonass_offset(*offset))
+        },
+        _ => Some(carrier),
+    }
+}
+
+/*
+ * MP1 / MP2 code multipath combinations, per SV, organized in
+ * continuous arcs. Values are not centered yet.
+ */
+#[cfg(feature = "obs")]
+fn mp12_arcs(
+    rec: &Record,
+    header: &Header,
+) -> BTreeMap<SV, Vec<Vec<(Epoch, Option<f64>, Option<f64>)>>> {
+    let max_gap = arc_max_gap(rec, header);
+
+    // select observables to combine, per SV
+    let combinations: BTreeMap<SV, _> = sv_observables(rec)
+        .into_iter()
+        .filter_map(|(sv, list)| {
+            let (p1, p2, l1, l2) = mp12_observables(sv, &list)?;
+            let f1 = sv_carrier(header, &sv, &l1)?;
+            let f2 = sv_carrier(header, &sv, &l2)?;
+            Some((sv, (p1, p2, l1, l2, f1, f2)))
+        })
+        .collect();
+
+    let mut ret: BTreeMap<SV, Vec<Vec<(Epoch, Option<f64>, Option<f64>)>>> = BTreeMap::new();
+    let mut latest: HashMap<SV, Epoch> = HashMap::new();
+
+    for ((epoch, flag), (_, vehicles)) in rec.iter() {
+        if !flag.is_ok() {
+            // special events interrupt all arcs
+            latest.clear();
+            continue;
+        }
+        for (sv, observations) in vehicles.iter() {
+            let (p1, p2, l1, l2, f1, f2) = match combinations.get(sv) {
+                Some(combination) => combination,
+                None => continue,
+            };
+            let (l1_data, l2_data) = match (observations.get(l1), observations.get(l2)) {
+                (Some(l1), Some(l2)) => (l1, l2),
+                _ => continue,
+            };
+            let slip = [l1_data, l2_data].iter().any(|data| {
+                data.lli
+                    .map(|lli| lli.intersects(LliFlags::LOCK_LOSS))
+                    .unwrap_or(false)
+            });
+            let gap = latest
+                .get(sv)
+                .map(|t| *epoch - *t > max_gap)
+                .unwrap_or(true);
+
+            let alpha = (f1.frequency() / f2.frequency()).powi(2);
+            let l1 = l1_data.obs * f1.wavelength();
+            let l2 = l2_data.obs * f2.wavelength();
+            let mp1 = p1
+                .as_ref()
+                .and_then(|p1| observations.get(p1))
+                .map(|p1| p1.obs - (1.0 + 2.0 / (alpha - 1.0)) * l1 + 2.0 / (alpha - 1.0) * l2);
+            let mp2 = p2.as_ref().and_then(|p2| observations.get(p2)).map(|p2| {
+                p2.obs - 2.0 * alpha / (alpha - 1.0) * l1 + (2.0 * alpha / (alpha - 1.0) - 1.0) * l2
+            });
+            if mp1.is_none() && mp2.is_none() {
+                continue;
+            }
+
+            let arcs = ret.entry(*sv).or_default();
+            if slip || gap || arcs.is_empty() {
+                arcs.push(Vec::new());
+            }
+            if let Some(arc) = arcs.last_mut() {
+                arc.push((*epoch, mp1, mp2));
+            }
+            latest.insert(*sv, *epoch);
+        }
+    }
+    ret
+}
+
+/*
+ * Averages given arc component
+ */
+#[cfg(feature = "obs")]
+fn mp12_mean(values: impl Iterator<Item = Option<f64>>) -> f64 {
+    let (sum, count) = values
+        .flatten()
+        .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count > 0 {
+        sum / count as f64
+    } else {
+        0.0
+    }
+}
+
+/*
+ * MP1 / MP2 code multipath, centered on each arc like teqc
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn code_multipath_mp12(
+    rec: &Record,
+    header: &Header,
+) -> BTreeMap<SV, BTreeMap<Epoch, (Option<f64>, Option<f64>)>> {
+    let mut ret: BTreeMap<SV, BTreeMap<Epoch, (Option<f64>, Option<f64>)>> = BTreeMap::new();
+    for (sv, arcs) in mp12_arcs(rec, header) {
+        let values = ret.entry(sv).or_default();
+        for arc in arcs {
+            let mp1_mean = mp12_mean(arc.iter().map(|(_, mp1, _)| *mp1));
+            let mp2_mean = mp12_mean(arc.iter().map(|(_, _, mp2)| *mp2));
+            for (epoch, mp1, mp2) in arc {
+                values.insert(
+                    epoch,
+                    (mp1.map(|v| v - mp1_mean), mp2.map(|v| v - mp2_mean)),
+                );
+            }
+        }
+    }
+    ret
+}
+
+/*
+ * MP1 / MP2 code multipath statistics
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn code_multipath_mp12_summary(
+    rec: &Record,
+    header: &Header,
+) -> BTreeMap<SV, MultipathSummary> {
+    let arcs = mp12_arcs(rec, header);
+    let centered = code_multipath_mp12(rec, header);
+    let rms = |values: Vec<f64>| -> Option<f64> {
+        if values.is_empty() {
+            None
+        } else {
+            Some((values.iter().map(|v| v.powi(2)).sum::<f64>() / values.len() as f64).sqrt())
+        }
+    };
+    centered
+        .into_iter()
+        .map(|(sv, values)| {
+            let summary = MultipathSummary {
+                arcs: arcs.get(&sv).map(|arcs| arcs.len()).unwrap_or(0),
+                mp1_rms: rms(values.values().filter_map(|(mp1, _)| *mp1).collect()),
+                mp2_rms: rms(values.values().filter_map(|(_, mp2)| *mp2).collect()),
+            };
+            (sv, summary)
+        })
+        .collect()
+}
+
+/*
+ * Reduces a dual frequency [Combine] output to the preferred signals of each SV:
+ * second frequency of the MP12 bands, preferred attributes first.
+ * Keeps phase or code combinations only, epoch flags are dropped.
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn preferred_combination(
+    combinations: HashMap<
+        (Observable, Observable),
+        BTreeMap<SV, BTreeMap<(Epoch, EpochFlag), f64>>,
+    >,
+    code: bool,
+) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+    let mut selected: BTreeMap<
+        SV,
+        (
+            (usize, usize, String, String),
+            BTreeMap<(Epoch, EpochFlag), f64>,
+        ),
+    > = BTreeMap::new();
+    for ((lhs, reference), vehicles) in combinations {
+        if lhs.is_pseudorange_observable() != code {
+            continue;
+        }
+        let (lhs, reference) = (lhs.to_string(), reference.to_string());
+        for (sv, data) in vehicles {
+            let band2 = match mp12_bands(sv.constellation) {
+                Some((_, band2)) => band2,
+                None => continue,
+            };
+            if lhs.chars().nth(1) != Some(band2) {
+                continue;
+            }
+            let rank = (
+                mp12_rank(&reference),
+                mp12_rank(&lhs),
+                reference.clone(),
+                lhs.clone(),
+            );
+            match selected.get(&sv) {
+                Some((best, _)) if *best <= rank => {},
+                _ => {
+                    selected.insert(sv, (rank, data));
+                },
+            }
+        }
+    }
+    selected
+        .into_iter()
+        .map(|(sv, (_, data))| {
+            let data = data.into_iter().map(|((t, _), value)| (t, value)).collect();
+            (sv, data)
+        })
+        .collect()
+}
+
+/*
+ * Phase observations (in meters), shifted by the mean code minus phase
+ * difference over each continuous arc, indexed by phase observable code.
+ * Each phase observable is paired to the code observed on the same band.
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn level_phase_to_code(
+    rec: &Record,
+    header: &Header,
+) -> BTreeMap<SV, BTreeMap<Epoch, HashMap<String, f64>>> {
+    let max_gap = arc_max_gap(rec, header);
+
+    // (phase, code, carrier) to level, per SV
+    let pairs: BTreeMap<SV, Vec<(Observable, Observable, Carrier)>> = sv_observables(rec)
+        .into_iter()
+        .map(|(sv, observables)| {
+            let pairs = observables
+                .iter()
+                .filter(|observable| observable.is_phase_observable())
+                .filter_map(|phase| {
+                    let code = phase.to_string();
+                    let band = code.chars().nth(1)?;
+                    let pr = best_observable(&observables, band, false, code.chars().nth(2))?;
+                    let carrier = sv_carrier(header, &sv, phase)?;
+                    Some(((*phase).clone(), pr, carrier))
+                })
+                .collect();
+            (sv, pairs)
+        })
+        .collect();
+
+    // continuous arcs of (epoch, phase [m], code [m]), per SV and phase observable
+    let mut arcs: HashMap<(SV, Observable), Vec<Vec<(Epoch, f64, Option<f64>)>>> = HashMap::new();
+    let mut latest: HashMap<(SV, Observable), Epoch> = HashMap::new();
+
+    for ((epoch, flag), (_, vehicles)) in rec.iter() {
+        if !flag.is_ok() {
+            // special events interrupt all arcs
+            latest.clear();
+            continue;
+        }
+        for (sv, observations) in vehicles.iter() {
+            let pairs = match pairs.get(sv) {
+                Some(pairs) => pairs,
+                None => continue,
+            };
+            for (phase, code, carrier) in pairs {
+                let data = match observations.get(phase) {
+                    Some(data) => data,
+                    None => continue,
+                };
+                let slip = data
+                    .lli
+                    .map(|lli| lli.intersects(LliFlags::LOCK_LOSS))
+                    .unwrap_or(false);
+                let key = (*sv, phase.clone());
+                let gap = latest
+                    .get(&key)
+                    .map(|t| *epoch - *t > max_gap)
+                    .unwrap_or(true);
+
+                let series = arcs.entry(key.clone()).or_default();
+                if slip || gap || series.is_empty() {
+                    series.push(Vec::new());
+                }
+                if let Some(arc) = series.last_mut() {
+                    let code = observations.get(code).map(|code| code.obs);
+                    arc.push((*epoch, data.obs * carrier.wavelength(), code));
+                }
+                latest.insert(key, *epoch);
+            }
+        }
+    }
+
+    let mut ret: BTreeMap<SV, BTreeMap<Epoch, HashMap<String, f64>>> = BTreeMap::new();
+    for ((sv, phase), series) in arcs {
+        let phase = phase.to_string();
+        for arc in series {
+            let (sum, count) = arc
+                .iter()
+                .filter_map(|(_, phase, code)| code.map(|code| code - phase))
+                .fold((0.0, 0), |(sum, count), dx| (sum + dx, count + 1));
+            if count == 0 {
+                continue; // no code to level to
+            }
+            let offset = sum / count as f64;
+            let values = ret.entry(sv).or_default();
+            for (epoch, value, _) in arc {
+                values
+                    .entry(epoch)
+                    .or_default()
+                    .insert(phase.clone(), value + offset);
+            }
+        }
+    }
+    ret
+}
+
+/*
+ * Time derivative [m/s] of the code minus phase difference,
+ * on the first frequency band of each constellation (same as MP1),
+ * evaluated within continuous arcs.
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn code_carrier_divergence(
+    rec: &Record,
+    header: &Header,
+) -> BTreeMap<SV, BTreeMap<Epoch, f64>> {
+    let max_gap = arc_max_gap(rec, header);
+
+    // (code, phase, carrier) per SV
+    let signals: BTreeMap<SV, (Observable, Observable, Carrier)> = sv_observables(rec)
+        .into_iter()
+        .filter_map(|(sv, observables)| {
+            let (band, _) = mp12_bands(sv.constellation)?;
+            let code = best_observable(&observables, band, false, None)?;
+            let attribute = code.to_string().chars().nth(2);
+            let phase = best_observable(&observables, band, true, attribute)?;
+            let carrier = sv_carrier(header, &sv, &phase)?;
+            Some((sv, (code, phase, carrier)))
+        })
+        .collect();
+
+    let mut ret: BTreeMap<SV, BTreeMap<Epoch, f64>> = BTreeMap::new();
+    // latest (epoch, code - phase) of the ongoing arc
+    let mut latest: HashMap<SV, (Epoch, f64)> = HashMap::new();
+
+    for ((epoch, flag), (_, vehicles)) in rec.iter() {
+        if !flag.is_ok() {
+            // special events interrupt all arcs
+            latest.clear();
+            continue;
+        }
+        for (sv, observations) in vehicles.iter() {
+            let (code, phase, carrier) = match signals.get(sv) {
+                Some(signals) => signals,
+                None => continue,
+            };
+            let (code, phase) = match (observations.get(code), observations.get(phase)) {
+                (Some(code), Some(phase)) => (code, phase),
+                _ => continue,
+            };
+            let slip = phase
+                .lli
+                .map(|lli| lli.intersects(LliFlags::LOCK_LOSS))
+                .unwrap_or(false);
+            let cmc = code.obs - phase.obs * carrier.wavelength();
+            if let Some((prev_epoch, prev_cmc)) = latest.get(sv) {
+                let dt = *epoch - *prev_epoch;
+                if !slip && dt <= max_gap && dt > Duration::ZERO {
+                    ret.entry(*sv)
+                        .or_default()
+                        .insert(*epoch, (cmc - prev_cmc) / dt.to_seconds());
+                }
+            }
+            latest.insert(*sv, (*epoch, cmc));
+        }
+    }
+    ret
+}
+
+/*
+ * RTCM3 MSM message number, minus the MSM type (1-7), for given constellation
+ */
+#[cfg(feature = "obs")]
+fn rtcm_msm_base(constellation: Constellation) -> Option<u16> {
+    match constellation {
+        Constellation::GPS => Some(1070),
+        Constellation::Glonass => Some(1080),
+        Constellation::Galileo => Some(1090),
+        Constellation::QZSS => Some(1110),
+        Constellation::BeiDou => Some(1120),
+        Constellation::IRNSS => Some(1130),
+        c if c.is_sbas() => Some(1100),
+        _ => None,
+    }
+}
+
+/*
+ * Lists the standard resolution RTCM3 MSM messages (MSM1 to MSM5)
+ * that can represent each epoch, per constellation.
+ */
+#[cfg(feature = "obs")]
+pub(crate) fn rtcm_msm_summary(rec: &Record) -> BTreeMap<Epoch, Vec<u16>> {
+    let mut ret: BTreeMap<Epoch, Vec<u16>> = BTreeMap::new();
+    for ((epoch, _), (_, vehicles)) in rec.iter() {
+        // (code, phase, cnr, doppler) content, per constellation
+        let mut content: HashMap<u16, (bool, bool, bool, bool)> = HashMap::new();
+        for (sv, observations) in vehicles.iter() {
+            let base = match rtcm_msm_base(sv.constellation) {
+                Some(base) => base,
+                None => continue,
+            };
+            let (code, phase, cnr, doppler) = content.entry(base).or_default();
+            for (observable, data) in observations.iter() {
+                match observable {
+                    Observable::PseudoRange(_) => *code = true,
+                    Observable::Phase(_) => *phase = true,
+                    Observable::SSI(_) => *cnr = true,
+                    Observable::Doppler(_) => *doppler = true,
+                    _ => {},
+                }
+                *cnr |= data.snr.is_some();
+            }
+        }
+        let messages = ret.entry(*epoch).or_default();
+        for (base, content) in content {
+            let msm = match content {
+                (_, _, _, true) => 5,
+                (_, _, true, _) => 4,
+                (true, true, _, _) => 3,
+                (false, true, _, _) => 2,
+                (true, false, _, _) => 1,
+                _ => continue,
+            };
+            if !messages.contains(&(base + msm)) {
+                messages.push(base + msm);
+            }
+        }
+        messages.sort();
+    }
+    ret
+}
+
+/// Distance travelled by light in 1 ms [m]
+const LIGHT_MILLISECOND: f64 = 299_792.458;
+
+/*
+ * Detects and removes millisecond jumps on pseudo range observations.
+ * Each pseudo range is predicted from its two previous (repaired) values:
+ * a residual close to a multiple of c.1ms is a jump candidate.
+ * Receiver clock steering introduces the same jump on all vehicles at once:
+ * the offset is only removed (from this epoch onwards) when all vehicles
+ * that can be predicted, at least two of them, agree on the same jump.
+ * Predictions restart after data gaps, special events and loss of lock.
+ * Returns the (epoch, number of ms) jumps that were repaired.
+ */
+pub(crate) fn repair_ms_jumps_mut(rec: &mut Record, header: &Header) -> Vec<(Epoch, i64)> {
+    // tolerance on the residual, once the jump is removed [m]
+    const TOLERANCE: f64 = LIGHT_MILLISECOND / 10.0;
+    let max_gap = arc_max_gap(rec, header);
+    let mut ret: Vec<(Epoch, i64)> = Vec::new();
+    // offset [m] removed so far
+    let mut offset = 0.0_f64;
+    // last two repaired observations
+    let mut buffers: HashMap<(SV, Observable), Vec<(Epoch, f64)>> = HashMap::new();
+    let mut latest = Option::<Epoch>::None;
+    for ((epoch, flag), (_, vehicles)) in rec.iter_mut() {
+        let gap = latest.map(|t| *epoch - t > max_gap).unwrap_or(false);
+        if !flag.is_ok() || gap {
+            buffers.clear();
+        }
+        latest = Some(*epoch);
+        // loss of lock interrupts the vehicle predictions
+        for (sv, observations) in vehicles.iter() {
+            let lock_loss = observations.values().any(|data| {
+                data.lli
+                    .map(|lli| lli.intersects(LliFlags::LOCK_LOSS))
+                    .unwrap_or(false)
+            });
+            if lock_loss {
+                buffers.retain(|(svnn, _), _| svnn != sv);
+            }
+        }
+        // jump candidates, per vehicle
+        let mut candidates: HashMap<SV, Vec<i64>> = HashMap::new();
+        for (sv, observations) in vehicles.iter() {
+            for (observable, data) in observations.iter() {
+                if !observable.is_pseudorange_observable() {
+                    continue;
+                }
+                let past = match buffers.get(&(*sv, observable.clone())) {
+                    Some(past) => past,
+                    None => continue,
+                };
+                let value = data.obs - offset;
+                let residual = match past.as_slice() {
+                    [(t0, x0), (t1, x1)] => {
+                        let rate = (x1 - x0) / (*t1 - *t0).to_seconds();
+                        value - x1 - rate * (*epoch - *t1).to_seconds()
+                    },
+                    [(_, x1)] => value - x1,
+                    _ => continue,
+                };
+                let k = (residual / LIGHT_MILLISECOND).round();
+                let k = if (residual - k * LIGHT_MILLISECOND).abs() < TOLERANCE {
+                    k as i64
+                } else {
+                    0
+                };
+                candidates.entry(*sv).or_default().push(k);
+            }
+        }
+        let mut jumps = candidates.values().flatten();
+        let common = jumps
+            .next()
+            .copied()
+            .filter(|k| *k != 0 && candidates.len() > 1 && jumps.all(|other| other == k));
+        if let Some(k) = common {
+            offset += k as f64 * LIGHT_MILLISECOND;
+            ret.push((*epoch, k));
+        }
+        // repair and update predictions
+        for (sv, observations) in vehicles.iter_mut() {
+            for (observable, data) in observations.iter_mut() {
+                if !observable.is_pseudorange_observable() {
+                    continue;
+                }
+                data.obs -= offset;
+                let past = buffers.entry((*sv, observable.clone())).or_default();
+                past.push((*epoch, data.obs));
+                if past.len() > 2 {
+                    past.remove(0);
+                }
+            }
+        }
+    }
+    ret
+}
+
+/*
+ * Observation records are serialized as nested maps, indexed by
+ * "epoch flag" descriptions, SV and observable codes,
+ * so they remain valid JSON objects.
+ */
+#[cfg(feature = "serde")]
+pub(crate) mod serde_record {
+    use super::{ObservationData, Record};
+    use crate::observation::EpochFlag;
+    use crate::prelude::{Epoch, Observable, SV};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::{BTreeMap, HashMap};
+    use std::str::FromStr;
+
+    #[derive(Serialize, Deserialize)]
+    struct EpochContent {
+        clock_offset: Option<f64>,
+        vehicles: BTreeMap<String, BTreeMap<String, ObservationData>>,
+    }
+
+    pub(crate) fn serialize<S>(record: &Record, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(
+            record
+                .iter()
+                .map(|((epoch, flag), (clock_offset, vehicles))| {
+                    let vehicles = vehicles
+                        .iter()
+                        .map(|(sv, observations)| {
+                            let observations = observations
+                                .iter()
+                                .map(|(observable, data)| (observable.to_string(), *data))
+                                .collect();
+                            (format!("{:x}", sv), observations)
+                        })
+                        .collect();
+                    let content = EpochContent {
+                        clock_offset: *clock_offset,
+                        vehicles,
+                    };
+                    (format!("{} {}", epoch, flag), content)
+                }),
+        )
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Record, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut record = Record::new();
+        let content = BTreeMap::<String, EpochContent>::deserialize(deserializer)?;
+        for (key, content) in content {
+            // epoch descriptions contain whitespaces: flag is the last item
+            let (epoch, flag) = key
+                .rsplit_once(' ')
+                .ok_or_else(|| D::Error::custom(format!("invalid epoch \"{}\"", key)))?;
+            let epoch = Epoch::from_str(epoch).map_err(D::Error::custom)?;
+            let flag = EpochFlag::from_str(flag).map_err(D::Error::custom)?;
+            let mut vehicles = BTreeMap::new();
+            for (sv, observations) in content.vehicles {
+                let sv = SV::from_str(&sv).map_err(D::Error::custom)?;
+                let mut inner = HashMap::with_capacity(observations.len());
+                for (observable, data) in observations {
+                    let observable = Observable::from_str(&observable).map_err(D::Error::custom)?;
+                    inner.insert(observable, data);
+                }
+                vehicles.insert(sv, inner);
+            }
+            record.insert((epoch, flag), (content.clock_offset, vehicles));
+        }
+        Ok(record)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1822,14 +3110,14 @@ mod test {
         let ts = TimeScale::UTC;
         let clock_offset: Option<f64> = None;
 
-        let e = parse_epoch(&header, epoch_str, ts);
+        let e = parse_epoch(&header, epoch_str, ts, crate::observation::MAX_MAGNITUDE);
 
         match expected_flag {
             EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => {
                 assert!(e.is_ok())
             },
             _ => {
-                // TODO: Update alongside parse_event
+                // events are not stored as observations
                 assert!(e.is_err());
                 return;
             },
@@ -1932,6 +3220,36 @@ mod test {
         );
     }
     #[test]
+    fn obs_v3_cycle_slip_records() {
+        let mut fields = crate::observation::HeaderFields::default();
+        fields.codes.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L1C").unwrap(),
+            ],
+        );
+        let header = Header::default()
+            .with_version(Version { major: 3, minor: 0 })
+            .with_observation_fields(fields);
+        let content = "> 2022 03 04 00 57  0.0000000  6  1
+G01  21653418.260   113789486.67008";
+        let ((_, flag), _, data) = parse_epoch(
+            &header,
+            content,
+            TimeScale::GPST,
+            crate::observation::MAX_MAGNITUDE,
+        )
+        .unwrap();
+        assert_eq!(flag, EpochFlag::CycleSlip);
+        // cycle slip records are observations
+        let g01 = data.get(&SV::new(Constellation::GPS, 1)).unwrap();
+        assert_eq!(g01.len(), 2);
+        let l1c = g01.get(&Observable::from_str("L1C").unwrap()).unwrap();
+        assert_eq!(l1c.obs, 113789486.670);
+        assert_eq!(l1c.snr, Some(SNR::from_str("8").unwrap()));
+    }
+    #[test]
     fn obs_record_is_new_epoch() {
         assert!(is_new_epoch(
             "95 01 01 00 00 00.0000000  0  7 06 17 21 22 23 28 31",
@@ -1962,4 +3280,49 @@ mod test {
             Version { major: 3, minor: 0 }
         ));
     }
+    #[test]
+    fn lli_flags() {
+        assert_eq!(LliFlags::from_bits(0), Some(LliFlags::OK_OR_UNKNOWN));
+        assert!(LliFlags::OK_OR_UNKNOWN.is_empty());
+        for (bits, flag) in [
+            (0x01, LliFlags::LOCK_LOSS),
+            (0x02, LliFlags::HALF_CYCLE_SLIP),
+            (0x02, LliFlags::HALF_CYCLE_AMBIGUITY),
+            (0x04, LliFlags::UNDER_ANTI_SPOOFING),
+            (0x04, LliFlags::UNDER_ANTISPOOFING),
+        ] {
+            let lli = LliFlags::from_bits(bits).unwrap();
+            assert_eq!(lli, flag);
+            assert_eq!(lli.bits(), bits);
+            assert!(lli.intersects(flag));
+            assert!(!lli.intersects(LliFlags::all().difference(flag)));
+        }
+        // combinations
+        let lli = LliFlags::from_bits(0x07).unwrap();
+        assert_eq!(lli, LliFlags::all());
+        assert!(lli.contains(LliFlags::LOCK_LOSS | LliFlags::UNDER_ANTISPOOFING));
+        // undefined bits
+        assert!(LliFlags::from_bits(0x08).is_none());
+
+        // parsed from an observation
+        let observables = HashMap::from([(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("S1C").unwrap(),
+            ],
+        )]);
+        let content = "G01  22331467.88017 117352685.28228        48.9504 ";
+        let data = parse_v3(
+            &observables,
+            content.lines(),
+            crate::observation::MAX_MAGNITUDE,
+        );
+        let g01 = data.get(&SV::new(Constellation::GPS, 1)).unwrap();
+        let lli = |code: &str| g01[&Observable::from_str(code).unwrap()].lli.unwrap();
+        assert_eq!(lli("C1C"), LliFlags::LOCK_LOSS);
+        assert_eq!(lli("L1C"), LliFlags::HALF_CYCLE_AMBIGUITY);
+        assert_eq!(lli("S1C"), LliFlags::UNDER_ANTISPOOFING);
+    }
 }
@@ -8,6 +8,10 @@ pub enum Error {
     InvalidSNRCode,
 }
 
+/// Signal Strength Indicator (SSI), as described by the 1-9 RINEX scale.
+/// The RINEX scale maps to the very same dBHz ranges as [SNR].
+pub type Ssi = SNR;
+
 /// Signal to noise ratio description, generally closely tied
 /// to raw GNSS signal observations.
 #[derive(Default, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Debug)]
@@ -36,6 +40,14 @@ pub enum SNR {
     DbHz54,
 }
 
+impl std::fmt::Display for SNR {
+    /// Formats `self` as its single digit RINEX code,
+    /// so [SNR::from_str] parses it back identically.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
+}
+
 impl std::fmt::LowerHex for SNR {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let descriptor = match self {
@@ -76,7 +88,8 @@ impl FromStr for SNR {
     type Err = Error;
     fn from_str(code: &str) -> Result<Self, Self::Err> {
         match code.trim() {
-            "0" => Ok(SNR::DbHz0),
+            // "0" or blank: unknown, don't care
+            "0" | "" => Ok(SNR::DbHz0),
             "1" => Ok(SNR::DbHz12),
             "2" => Ok(SNR::DbHz12_17),
             "3" => Ok(SNR::DbHz18_23),
@@ -163,6 +176,24 @@ impl From<u8> for SNR {
 }
 
 impl SNR {
+    /// Returns the representative signal strength in dB-Hz
+    /// (center of the range) described by `self`.
+    /// For [SNR::DbHz54], the lower bound is returned,
+    /// and 0 is returned when the signal strength is unknown ([SNR::DbHz0]).
+    pub fn as_db_hz(&self) -> f64 {
+        match self {
+            Self::DbHz0 => 0.0,
+            Self::DbHz12 => 6.0,
+            Self::DbHz12_17 => 14.5,
+            Self::DbHz18_23 => 20.5,
+            Self::DbHz24_29 => 26.5,
+            Self::DbHz30_35 => 32.5,
+            Self::DbHz36_41 => 38.5,
+            Self::DbHz42_47 => 44.5,
+            Self::DbHz48_53 => 50.5,
+            Self::DbHz54 => 54.0,
+        }
+    }
     /// Returns true if self describes a bad signal level
     pub fn bad(self) -> bool {
         self <= SNR::DbHz18_23
@@ -221,4 +252,31 @@ mod test {
         assert!(SNR::from_str("strong").unwrap().strong());
         assert!(SNR::from_str("excellent").unwrap().excellent());
     }
+    #[test]
+    fn ssi_parsing_reciprocity() {
+        for (code, expected, db_hz) in [
+            ("0", Ssi::DbHz0, 0.0),
+            ("1", Ssi::DbHz12, 6.0),
+            ("2", Ssi::DbHz12_17, 14.5),
+            ("3", Ssi::DbHz18_23, 20.5),
+            ("4", Ssi::DbHz24_29, 26.5),
+            ("5", Ssi::DbHz30_35, 32.5),
+            ("6", Ssi::DbHz36_41, 38.5),
+            ("7", Ssi::DbHz42_47, 44.5),
+            ("8", Ssi::DbHz48_53, 50.5),
+            ("9", Ssi::DbHz54, 54.0),
+        ] {
+            let ssi = Ssi::from_str(code);
+            assert_eq!(ssi, Ok(expected), "failed to parse \"{}\"", code);
+            let ssi = ssi.unwrap();
+            assert_eq!(ssi.to_string(), code);
+            assert_eq!(Ssi::from_str(&ssi.to_string()), Ok(ssi));
+            assert_eq!(ssi.as_db_hz(), db_hz);
+            // whitespace padded fields
+            assert_eq!(Ssi::from_str(&format!(" {} ", code)), Ok(ssi));
+        }
+        assert_eq!(Ssi::from_str(" "), Ok(Ssi::DbHz0));
+        assert_eq!(Ssi::from_str(""), Ok(Ssi::DbHz0));
+        assert!(Ssi::from_str("10").is_err());
+    }
 }
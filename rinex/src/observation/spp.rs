@@ -0,0 +1,124 @@
+//! Single Point Positioning
+use crate::prelude::{Constellation, Observable};
+use itertools::Itertools;
+use nalgebra::{DMatrix, DVector};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Speed of light [m.s⁻¹]
+pub(crate) const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Earth rotation rate in WGS84 frame [rad.s⁻¹]
+const EARTH_OMEGA_E_WGS84: f64 = 7.2921151467E-5;
+
+/// Maximal number of least square iterations
+const MAX_ITERATIONS: usize = 10;
+
+/// Least square convergence criteria, on position [m]
+const CONVERGENCE_M: f64 = 1.0E-4;
+
+/// Pseudo range tracking modes, by order of preference,
+/// when a carrier is observed with several codes
+const CODE_PRIORITY: &str = "CPWYXLSQI";
+
+/*
+ * Sort key of an observable, for pseudo range selection:
+ * preferred tracking mode first, then name.
+ * V2 codes carry no tracking mode, C1 is preferred to P1.
+ */
+pub(crate) fn code_priority(observable: &Observable) -> (usize, String) {
+    let code = observable.to_string();
+    let mode = match code.len() {
+        3 => code.chars().nth(2),
+        _ => code.chars().next(),
+    };
+    let rank = mode
+        .and_then(|mode| CODE_PRIORITY.find(mode))
+        .unwrap_or(CODE_PRIORITY.len());
+    (rank, code)
+}
+
+/// Single Point Positioning solution,
+/// see [crate::Rinex::single_point_position_multi].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SppSolution {
+    /// Receiver position, in meters ECEF
+    pub position: (f64, f64, f64),
+    /// Receiver clock bias, expressed in meters, one per [Constellation].
+    /// Inter system biases are absorbed by these states.
+    pub clock_biases: BTreeMap<Constellation, f64>,
+}
+
+/// Contribution of a single SV to the SPP solution
+pub(crate) struct SppCandidate {
+    /// Constellation this SV belongs to
+    pub constellation: Constellation,
+    /// SV position at signal transmission time, in meters ECEF
+    pub position: (f64, f64, f64),
+    /// Pseudo range compensated for SV clock offset, in meters
+    pub pseudo_range: f64,
+}
+
+/*
+ * Solves the SPP problem in the least square sense,
+ * one receiver clock state per constellation.
+ */
+pub(crate) fn solve(candidates: &[SppCandidate], apriori: (f64, f64, f64)) -> Option<SppSolution> {
+    let constellations: Vec<Constellation> = candidates
+        .iter()
+        .map(|cd| cd.constellation)
+        .unique()
+        .sorted()
+        .collect();
+
+    let nb_states = 3 + constellations.len();
+    let nb_obs = candidates.len();
+    if nb_obs < nb_states {
+        return None; // under determined
+    }
+
+    let mut state = DVector::<f64>::zeros(nb_states);
+    state[0] = apriori.0;
+    state[1] = apriori.1;
+    state[2] = apriori.2;
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut g = DMatrix::<f64>::zeros(nb_obs, nb_states);
+        let mut y = DVector::<f64>::zeros(nb_obs);
+
+        for (i, cd) in candidates.iter().enumerate() {
+            let (sv_x, sv_y, sv_z) = cd.position;
+            let (dx, dy, dz) = (sv_x - state[0], sv_y - state[1], sv_z - state[2]);
+            let rho = (dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt();
+            // earth rotation during signal propagation
+            let sagnac = EARTH_OMEGA_E_WGS84 * (sv_x * state[1] - sv_y * state[0]) / SPEED_OF_LIGHT;
+            let clock_state = 3 + constellations.iter().position(|c| *c == cd.constellation)?;
+
+            g[(i, 0)] = -dx / rho;
+            g[(i, 1)] = -dy / rho;
+            g[(i, 2)] = -dz / rho;
+            g[(i, clock_state)] = 1.0;
+            y[i] = cd.pseudo_range - (rho + sagnac + state[clock_state]);
+        }
+
+        let g_t = g.transpose();
+        let dx = (&g_t * &g).try_inverse()? * g_t * y;
+        state += &dx;
+
+        if dx.rows(0, 3).norm() < CONVERGENCE_M {
+            break;
+        }
+    }
+
+    Some(SppSolution {
+        position: (state[0], state[1], state[2]),
+        clock_biases: constellations
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, state[3 + i]))
+            .collect(),
+    })
+}
@@ -89,6 +89,31 @@ impl ProductionAttributes {
         format!("{}{}{}0.{}I", name, region, ddd, yy,)
     }
     /* filename generator */
+    pub(crate) fn clock_short_format(agency: &str, wwwwd: &str) -> String {
+        format!("{}{}.CLK", agency, wwwwd)
+    }
+    /* filename generator */
+    pub(crate) fn antex_format(name: &str) -> String {
+        format!("{}.ATX", name)
+    }
+    /* filename generator */
+    pub(crate) fn igs_long_format(
+        name: &str,
+        yyyy: &str,
+        ddd: &str,
+        hh: &str,
+        mm: &str,
+        ppu: &str,
+        ffu: &str,
+        content: &str,
+        ext: &str,
+    ) -> String {
+        format!(
+            "{}_{}{}{}{}_{}_{}_{}.{}",
+            name, yyyy, ddd, hh, mm, ppu, ffu, content, ext,
+        )
+    }
+    /* filename generator */
     pub(crate) fn rinex_short_format(name: &str, ddd: &str, yy: &str, ext: char) -> String {
         format!("{}{}0.{}{}", &name, ddd, yy, ext,)
     }
@@ -178,6 +203,34 @@ impl std::str::FromStr for ProductionAttributes {
                 },
                 details: None,
             })
+        } else if Self::is_igs_long_format(&fname) {
+            /*
+             * IGS products (like CLK) long format:
+             * AAAVPPPTTT_YYYYDDDHHMM_LEN_SMP_CNT.FMT
+             */
+            Ok(Self {
+                year: fname[11..15]
+                    .parse::<u32>()
+                    .map_err(|_| Error::NonStandardFileName)?,
+                name: fname[..10].to_string(),
+                doy: fname[15..18]
+                    .parse::<u32>()
+                    .map_err(|_| Error::NonStandardFileName)?,
+                region: None,
+                details: Some(DetailedProductionAttributes {
+                    batch: 0,
+                    country: String::new(), // not described by IGS products
+                    data_src: DataSource::Unknown,
+                    ppu: PPU::from_str(&fname[23..26])?,
+                    hh: fname[18..20]
+                        .parse::<u8>()
+                        .map_err(|_| Error::NonStandardFileName)?,
+                    mm: fname[20..22]
+                        .parse::<u8>()
+                        .map_err(|_| Error::NonStandardFileName)?,
+                    ffu: Some(FFU::from_str(&fname[27..30])?),
+                }),
+            })
         } else {
             let offset = fname.find('.').unwrap_or(0);
             if offset < 30 {
@@ -234,6 +287,23 @@ impl std::str::FromStr for ProductionAttributes {
     }
 }
 
+impl ProductionAttributes {
+    /*
+     * Returns true if given (uppercase) filename follows
+     * the IGS products long format
+     */
+    fn is_igs_long_format(fname: &str) -> bool {
+        let bytes = fname.as_bytes();
+        fname.len() >= 38
+            && fname.is_ascii()
+            && bytes[10] == b'_'
+            && bytes[22] == b'_'
+            && bytes[26] == b'_'
+            && bytes[30] == b'_'
+            && bytes[34] == b'.'
+    }
+}
+
 use crate::merge::{merge_mut_option, Error as MergeError, Merge};
 
 impl Merge for ProductionAttributes {
@@ -440,6 +510,29 @@ mod test {
         }
     }
     #[test]
+    fn igs_product_filenames() {
+        let filename = "GRG0MGXFIN_20201770000_01D_30S_CLK.CLK.gz";
+        let attrs = ProductionAttributes::from_str(filename).unwrap();
+        assert_eq!(attrs.name, "GRG0MGXFIN");
+        assert_eq!(attrs.year, 2020);
+        assert_eq!(attrs.doy, 177);
+        assert_eq!(
+            attrs.details,
+            Some(DetailedProductionAttributes {
+                country: String::new(),
+                batch: 0,
+                data_src: DataSource::Unknown,
+                ppu: PPU::Daily,
+                hh: 0,
+                mm: 0,
+                ffu: Some(FFU {
+                    val: 30,
+                    unit: Unit::Second,
+                }),
+            })
+        );
+    }
+    #[test]
     fn ionex_filenames() {
         for (filename, name, year, doy, region) in [
             ("CKMG0020.22I", "CKM", 2022, 2, 'G'),
@@ -3,18 +3,38 @@
 #[cfg(feature = "flate2")]
 use flate2::read::GzDecoder;
 use std::fs::File;
-use std::io::BufReader; // Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Read}; // Seek, SeekFrom};
 
-#[derive(Debug)]
-pub enum BufferedReader {
+/// gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub enum BufferedReader<'a> {
     /// Readable `RINEX`
     PlainFile(BufReader<File>),
     /// gzip compressed RINEX
     #[cfg(feature = "flate2")]
     GzFile(BufReader<GzDecoder<File>>),
+    /// Readable `RINEX` from any [Read]able stream
+    Stream(BufReader<Box<dyn Read + 'a>>),
+    /// gzip compressed RINEX from any [Read]able stream
+    #[cfg(feature = "flate2")]
+    GzStream(BufReader<GzDecoder<BufReader<Box<dyn Read + 'a>>>>),
 }
 
-impl BufferedReader {
+impl std::fmt::Debug for BufferedReader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::PlainFile(r) => write!(f, "PlainFile({:?})", r),
+            #[cfg(feature = "flate2")]
+            Self::GzFile(r) => write!(f, "GzFile({:?})", r),
+            Self::Stream(_) => write!(f, "Stream"),
+            #[cfg(feature = "flate2")]
+            Self::GzStream(_) => write!(f, "GzStream"),
+        }
+    }
+}
+
+impl<'a> BufferedReader<'a> {
     /// Builds a new BufferedReader for efficient file interation,
     /// with possible .gz decompression
     pub fn new(path: &str) -> std::io::Result<Self> {
@@ -36,6 +56,46 @@ impl BufferedReader {
             Ok(Self::PlainFile(BufReader::new(f)))
         }
     }
+    /// Builds a new BufferedReader from any [Read]able stream
+    /// (for example a network socket or a byte slice).
+    /// gzip compressed content is identified by its magic bytes,
+    /// CRINEX is identified while parsing the header as usual.
+    pub fn from_reader<R: Read + 'a>(reader: R) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(Box::new(reader) as Box<dyn Read + 'a>);
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            // --> gzip encoded
+            #[cfg(feature = "flate2")]
+            {
+                Ok(Self::GzStream(BufReader::new(GzDecoder::new(reader))))
+            }
+            #[cfg(not(feature = "flate2"))]
+            {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "gzip compressed data requires --flate2 feature",
+                ))
+            }
+        } else {
+            // Assumes no extra compression
+            Ok(Self::Stream(reader))
+        }
+    }
+    /// Reads the next line into `buf`, which is reused from one call to another,
+    /// so we do not allocate a new String per line.
+    /// Returns the line content without its termination, like [BufRead::lines],
+    /// or None once the end of stream is reached.
+    pub fn next_line<'a>(&mut self, buf: &'a mut String) -> Option<std::io::Result<&'a str>> {
+        buf.clear();
+        match self.read_line(buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line: &'a str = buf;
+                let line = line.strip_suffix('\n').unwrap_or(line);
+                Some(Ok(line.strip_suffix('\r').unwrap_or(line)))
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
     /*
         /// Enhances self for hatanaka internal decompression,
         /// preserves inner pointer state
@@ -81,22 +141,28 @@ impl BufferedReader {
     */
 }
 
-impl std::io::Read for BufferedReader {
+impl std::io::Read for BufferedReader<'_> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
         match self {
             Self::PlainFile(ref mut h) => h.read(buf),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut h) => h.read(buf),
+            Self::Stream(ref mut h) => h.read(buf),
+            #[cfg(feature = "flate2")]
+            Self::GzStream(ref mut h) => h.read(buf),
         }
     }
 }
 
-impl std::io::BufRead for BufferedReader {
+impl std::io::BufRead for BufferedReader<'_> {
     fn fill_buf(&mut self) -> Result<&[u8], std::io::Error> {
         match self {
             Self::PlainFile(ref mut bufreader) => bufreader.fill_buf(),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.fill_buf(),
+            Self::Stream(ref mut bufreader) => bufreader.fill_buf(),
+            #[cfg(feature = "flate2")]
+            Self::GzStream(ref mut bufreader) => bufreader.fill_buf(),
         }
     }
     fn consume(&mut self, s: usize) {
@@ -104,6 +170,9 @@ impl std::io::BufRead for BufferedReader {
             Self::PlainFile(ref mut bufreader) => bufreader.consume(s),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.consume(s),
+            Self::Stream(ref mut bufreader) => bufreader.consume(s),
+            #[cfg(feature = "flate2")]
+            Self::GzStream(ref mut bufreader) => bufreader.consume(s),
         }
     }
 }
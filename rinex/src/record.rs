@@ -3,7 +3,7 @@ use std::io::prelude::*;
 use thiserror::Error;
 
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::{
     antex, clock,
@@ -16,33 +16,46 @@ use super::{
     split,
     split::Split,
     types::Type,
-    writer::BufferedWriter,
     *,
 };
 use hifitime::Duration;
+use itertools::Itertools;
 
+/// `RINEX` file body content.
+/// With the `serde` feature, only Navigation and Observation records
+/// can be deserialized.
 #[derive(Clone, Debug, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Record {
     /// ATX record, see [antex::record::Record]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     AntexRecord(antex::Record),
     /// Clock record, see [clock::record::Record]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     ClockRecord(clock::Record),
     /// IONEX (Ionosphere maps) record, see [ionex::record::Record]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     IonexRecord(ionex::Record),
     /// Meteo record, see [meteo::record::Record]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     MeteoRecord(meteo::Record),
     /// Navigation record, see [navigation::record::Record]
     NavRecord(navigation::Record),
     /// Observation record, see [observation::record::Record]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::observation::record::serde_record")
+    )]
     ObsRecord(observation::Record),
     /// DORIS RINEX, special DORIS measurements wraped as observations
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     DorisRecord(doris::Record),
 }
 
 /// Record comments are high level informations, sorted by epoch
-/// (timestamp) of appearance. We deduce the "associated" timestamp from the
-/// previosuly parsed epoch, when parsing the record.
+/// (timestamp) of appearance. Comments are attached to the epoch they precede.
+/// Trailing comments (following the last epoch) are stored separately,
+/// see [crate::Rinex::trailing_comments].
 pub type Comments = BTreeMap<Epoch, Vec<String>>;
 
 impl Record {
@@ -144,100 +157,124 @@ impl Record {
             _ => None,
         }
     }
-    /// Streams into given file writer
-    pub fn to_file(
+    /// Streams into given file writer.
+    /// For OBS, NAV, METEO and CLOCK records, `comments` are written
+    /// prior the epoch they are attached to, `trailing` comments
+    /// are written after the last epoch.
+    pub fn to_file<W: Write>(
         &self,
         header: &header::Header,
-        writer: &mut BufferedWriter,
-    ) -> Result<(), Error> {
+        comments: &Comments,
+        trailing: &[String],
+        writer: &mut W,
+    ) -> Result<(), FormattingError> {
+        let mut comments = comments.iter().peekable();
         match &header.rinex_type {
             Type::MeteoData => {
-                let record = self.as_meteo().unwrap();
+                let record = self
+                    .as_meteo()
+                    .ok_or(ValidationIssue::RecordTypeMismatch(Type::MeteoData))?;
                 for (epoch, data) in record.iter() {
-                    if let Ok(epoch) = meteo::record::fmt_epoch(epoch, data, header) {
-                        let _ = write!(writer, "{}", epoch);
-                    }
+                    write_comments(writer, &mut comments, Some(*epoch))?;
+                    let epoch = meteo::record::fmt_epoch(epoch, data, header)?;
+                    write!(writer, "{}", epoch)?;
                 }
+                write_remaining_comments(writer, &mut comments, trailing)?;
             },
             Type::ObservationData => {
-                let record = self.as_obs().unwrap();
-                let obs_fields = &header.obs.as_ref().unwrap();
+                let record = self
+                    .as_obs()
+                    .ok_or(ValidationIssue::RecordTypeMismatch(Type::ObservationData))?;
+                let obs_fields = header
+                    .obs
+                    .as_ref()
+                    .ok_or(ValidationIssue::MissingObservables)?;
                 let mut compressor = Compressor::default();
+                // equipment changes are described by event epochs
+                let mut changes = obs_fields.equipment_changes.iter().peekable();
                 for ((epoch, flag), (clock_offset, data)) in record.iter() {
+                    while let Some((t, change)) = changes.next_if(|(t, _)| *t <= epoch) {
+                        let event = observation::record::fmt_equipment_change(*t, change, header);
+                        write_obs_epoch(writer, &mut compressor, header, &event)?;
+                    }
+                    write_comments(writer, &mut comments, Some(*epoch))?;
                     let epoch =
                         observation::record::fmt_epoch(*epoch, *flag, clock_offset, data, header);
-                    if obs_fields.crinex.is_some() {
-                        let major = header.version.major;
-                        let constell = &header.constellation.as_ref().unwrap();
-                        for line in epoch.lines() {
-                            let line = line.to_owned() + "\n"; // helps the following .lines() iterator
-                                                               // embedded in compression method
-                            if let Ok(compressed) =
-                                compressor.compress(major, &obs_fields.codes, constell, &line)
-                            {
-                                // println!("compressed \"{}\"", compressed); // DEBUG
-                                writeln!(writer, "{}", compressed)?;
-                            }
-                        }
-                    } else {
-                        writeln!(writer, "{}", epoch)?;
-                    }
+                    write_obs_epoch(writer, &mut compressor, header, &epoch)?;
+                }
+                for (t, change) in changes {
+                    let event = observation::record::fmt_equipment_change(*t, change, header);
+                    write_obs_epoch(writer, &mut compressor, header, &event)?;
                 }
+                write_remaining_comments(writer, &mut comments, trailing)?;
             },
             Type::NavigationData => {
-                let record = self.as_nav().unwrap();
+                let record = self
+                    .as_nav()
+                    .ok_or(ValidationIssue::RecordTypeMismatch(Type::NavigationData))?;
                 for (epoch, frames) in record.iter() {
-                    if let Ok(epoch) = navigation::record::fmt_epoch(epoch, frames, header) {
-                        let _ = write!(writer, "{}", epoch);
-                    }
+                    write_comments(writer, &mut comments, Some(*epoch))?;
+                    let epoch = navigation::record::fmt_epoch(epoch, frames, header)?;
+                    write!(writer, "{}", epoch)?;
                 }
+                write_remaining_comments(writer, &mut comments, trailing)?;
             },
             Type::ClockData => {
                 if let Some(rec) = self.as_clock() {
                     for (epoch, keys) in rec {
+                        write_comments(writer, &mut comments, Some(*epoch))?;
                         for (key, prof) in keys {
-                            let _ =
-                                write!(writer, "{}", clock::record::fmt_epoch(epoch, key, prof));
+                            write!(writer, "{}", clock::record::fmt_epoch(epoch, key, prof))?;
                         }
                     }
+                    write_remaining_comments(writer, &mut comments, trailing)?;
                 }
             },
             Type::IonosphereMaps => {
-                if let Some(_r) = self.as_ionex() {
-                    //for (index, (epoch, (_map, _, _))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF TEC MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF TEC MAP", index);
-                    //}
-                    // /*
-                    //  * not efficient browsing, but matches provided examples and common formatting.
-                    //  * RMS and Height maps are passed after TEC maps.
-                    //  */
-                    //for (index, (epoch, (_, _map, _))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF RMS MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF RMS MAP", index);
-                    //}
-                    //for (index, (epoch, (_, _, _map))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF HEIGHT MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF HEIGHT MAP", index);
-                    //}
+                if let (Some(rec), Some(fields)) = (self.as_ionex(), &header.ionex) {
+                    let epochs: Vec<Epoch> = rec.keys().map(|(e, _)| *e).unique().collect();
+                    /*
+                     * RMS maps are written after all TEC maps,
+                     * which matches the provided examples and common formatting.
+                     */
+                    let has_rms = rec
+                        .values()
+                        .any(|plane| plane.values().any(|tec| tec.rms.is_some()));
+                    let mut maps = vec![false];
+                    if has_rms {
+                        maps.push(true);
+                    }
+                    for is_rms_map in maps {
+                        for (index, epoch) in epochs.iter().enumerate() {
+                            let planes: Vec<(i32, &ionex::TECPlane)> = rec
+                                .iter()
+                                .filter_map(
+                                    |((e, h), plane)| {
+                                        if e == epoch {
+                                            Some((*h, plane))
+                                        } else {
+                                            None
+                                        }
+                                    },
+                                )
+                                .collect();
+                            write!(
+                                writer,
+                                "{}",
+                                ionex::record::fmt_map(
+                                    index + 1,
+                                    *epoch,
+                                    &planes,
+                                    fields,
+                                    is_rms_map
+                                )
+                            )?;
+                        }
+                    }
+                    writeln!(writer, "{}", fmt_rinex("", "END OF FILE"))?;
                 }
             },
-            _ => panic!("record type not supported yet"),
+            rinex_type => return Err(ValidationIssue::UnsupportedType(*rinex_type).into()),
         }
         Ok(())
     }
@@ -249,6 +286,78 @@ impl Default for Record {
     }
 }
 
+/*
+ * Writes all comments attached to epochs prior (or equal to) given epoch,
+ * or all remaining comments if no epoch is given.
+ */
+fn write_comments<'a, W: Write, I: Iterator<Item = (&'a Epoch, &'a Vec<String>)>>(
+    writer: &mut W,
+    comments: &mut std::iter::Peekable<I>,
+    epoch: Option<Epoch>,
+) -> Result<(), FormattingError> {
+    while let Some((e, content)) = comments.peek() {
+        if let Some(epoch) = epoch {
+            if **e > epoch {
+                break;
+            }
+        }
+        for comment in content.iter() {
+            writeln!(writer, "{}", fmt_comment(comment))?;
+        }
+        comments.next();
+    }
+    Ok(())
+}
+
+/*
+ * Writes all remaining comments, once the last epoch was written:
+ * comments attached to later epochs, then trailing comments.
+ */
+fn write_remaining_comments<'a, W: Write, I: Iterator<Item = (&'a Epoch, &'a Vec<String>)>>(
+    writer: &mut W,
+    comments: &mut std::iter::Peekable<I>,
+    trailing: &[String],
+) -> Result<(), FormattingError> {
+    write_comments(writer, comments, None)?;
+    for comment in trailing.iter() {
+        writeln!(writer, "{}", fmt_comment(comment))?;
+    }
+    Ok(())
+}
+
+/*
+ * Writes a formatted OBS epoch, compressed if CRINEX is desired
+ */
+fn write_obs_epoch<W: Write>(
+    writer: &mut W,
+    compressor: &mut Compressor,
+    header: &header::Header,
+    epoch: &str,
+) -> Result<(), FormattingError> {
+    let obs_fields = header
+        .obs
+        .as_ref()
+        .ok_or(ValidationIssue::MissingObservables)?;
+    if obs_fields.crinex.is_some() {
+        let major = header.version.major;
+        let constell = header
+            .constellation
+            .as_ref()
+            .ok_or(ValidationIssue::MissingConstellation(Type::ObservationData))?;
+        for line in epoch.lines() {
+            let line = line.to_owned() + "\n"; // helps the following .lines() iterator
+                                               // embedded in compression method
+            if let Ok(compressed) = compressor.compress(major, &obs_fields.codes, constell, &line) {
+                // println!("compressed \"{}\"", compressed); // DEBUG
+                writeln!(writer, "{}", compressed)?;
+            }
+        }
+    } else {
+        writeln!(writer, "{}", epoch)?;
+    }
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("record parsing not supported for type \"{0}\"")]
@@ -263,6 +372,20 @@ pub enum Error {
     BadObservationDataDefinition,
     #[error("failed to identify timescale")]
     ObservationDataTimescaleIdentification,
+    #[error("operation not supported by this record type")]
+    UnsupportedOperation,
+    #[error("undefined constellation")]
+    MissingConstellation,
+}
+
+/// Non fatal issues, encountered while parsing a file
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum ParsingWarning {
+    /// File is truncated (for example, download was interrupted):
+    /// the record only contains the complete epochs,
+    /// the last one being `last_good_epoch` (if any).
+    #[error("truncated record (last good epoch: {last_good_epoch:?})")]
+    TruncatedRecord { last_good_epoch: Option<Epoch> },
 }
 
 /// Returns true if given line matches the start   
@@ -285,11 +408,48 @@ pub fn is_new_epoch(line: &str, header: &header::Header) -> bool {
 }
 
 /// Builds a `Record`, `RINEX` file body content,
-/// which is constellation and `RINEX` file type dependent
+/// which is constellation and `RINEX` file type dependent.
+/// Returns the record, the comments attached to the epoch they precede
+/// and the trailing comments (following the last epoch).
 pub fn parse_record(
     reader: &mut BufferedReader,
     header: &mut header::Header,
-) -> Result<(Record, Comments), Error> {
+) -> Result<(Record, Comments, Vec<String>), Error> {
+    parse_record_with_max_magnitude(reader, header, observation::MAX_MAGNITUDE)
+}
+
+/// Same as [parse_record], but Observation RINEX values whose magnitude
+/// exceeds `max_magnitude` are dropped.
+pub fn parse_record_with_max_magnitude(
+    reader: &mut BufferedReader,
+    header: &mut header::Header,
+    max_magnitude: f64,
+) -> Result<(Record, Comments, Vec<String>), Error> {
+    let (record, comments, trailing, _) =
+        parse_record_with_truncation(reader, header, max_magnitude)?;
+    Ok((record, comments, trailing))
+}
+
+/// Same as [parse_record_with_max_magnitude], but also reports whether
+/// the record was truncated: stream was interrupted, last Observation epoch
+/// is incomplete or last line was interrupted and cannot be interpreted.
+/// The incomplete residue is then dropped, so the record only contains complete epochs.
+/// A complete file, whose last line is simply not terminated, is not truncated.
+pub(crate) fn parse_record_with_truncation(
+    reader: &mut BufferedReader,
+    header: &mut header::Header,
+    max_magnitude: f64,
+) -> Result<(Record, Comments, Vec<String>, bool), Error> {
+    // NAV and CRINEX records cannot be interpreted without a constellation
+    let is_crinex = header
+        .obs
+        .as_ref()
+        .map(|obs| obs.crinex.is_some())
+        .unwrap_or(false);
+    if (header.rinex_type == Type::NavigationData || is_crinex) && header.constellation.is_none() {
+        return Err(Error::MissingConstellation);
+    }
+
     let mut first_epoch = true;
     let mut content = String::default();
     let mut epoch_content = String::with_capacity(6 * 64);
@@ -298,6 +458,8 @@ pub fn parse_record(
     let mut comments: Comments = Comments::new();
     let mut comment_ts = Epoch::default();
     let mut comment_content: Vec<String> = Vec::with_capacity(4);
+    // comments preceding the epoch being built
+    let mut epoch_comments: Vec<String> = Vec::with_capacity(4);
 
     let mut decompressor = Decompressor::new();
     // record
@@ -308,6 +470,13 @@ pub fn parse_record(
     let mut clk_rec = clock::Record::new(); // CLK
     let mut dor_rec = doris::Record::new(); // DORIS
 
+    // OBS epochs are delimited first, then parsed concurrently,
+    // along with the comments that should be attached to them
+    #[cfg(feature = "parallel")]
+    let mut obs_blocks: Vec<(String, Vec<String>)> = Vec::new();
+    // receiver / antenna changes, described within OBS event blocks
+    let mut equipment_changes: BTreeMap<Epoch, observation::EquipmentChange> = BTreeMap::new();
+
     // OBSERVATION case
     //  timescale is defined either
     //    [+] by TIME OF FIRST header field
@@ -355,27 +524,35 @@ pub fn parse_record(
     let mut ionx_rec = ionex::Record::new();
     let mut ionex_rms_plane = false;
 
-    for l in reader.lines() {
-        // iterates one line at a time
-        let line = l.unwrap();
+    // truncated files (interrupted downloads..) are tolerated
+    let mut truncated = false;
+    // false if last line was interrupted
+    let mut terminated = true;
+    // true if last line was appended to the epoch being built
+    let mut last_in_epoch = false;
+
+    // iterates one line at a time, reusing a single buffer
+    let mut buf = String::with_capacity(128);
+    while let Some(line) = reader.next_line(&mut buf) {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => {
+                // interrupted or corrupt stream:
+                // we preserve what was parsed so far
+                truncated = true;
+                break;
+            },
+        };
         // COMMENTS special case
         // --> store
         // ---> append later with epoch.timestamp attached to it
         if is_rinex_comment(&line) {
             let comment = line.split_at(60).0.trim_end();
             comment_content.push(comment.to_string());
+            terminated = buf.ends_with('\n');
+            last_in_epoch = false;
             continue;
         }
-        // IONEX exponent-->data scaling use update regularly
-        //  and used in TEC map parsing
-        if line.contains("EXPONENT") {
-            if let Some(ionex) = header.ionex.as_mut() {
-                let content = line.split_at(60).0;
-                if let Ok(e) = content.trim().parse::<i8>() {
-                    *ionex = ionex.with_exponent(e); // scaling update
-                }
-            }
-        }
         /*
          * If plain RINEX: content is passed as is
          *      if CRINEX: decompress and pass recovered content
@@ -397,7 +574,7 @@ pub fn parse_record(
                     //   and .lines() will destroy them
                     &(line.to_owned() + "\n"),
                 ) {
-                    content = recovered.clone();
+                    content = recovered;
                 } else {
                     content.clear();
                 }
@@ -405,29 +582,34 @@ pub fn parse_record(
                 /*
                  * RINEX
                  */
+                content.clear();
                 if line.is_empty() {
                     // we might encounter empty lines
                     // and the following parsers (.lines() iterator)
                     // do not like it
-                    content = String::from("\n");
+                    content.push('\n');
                 } else {
-                    content = line.to_string();
+                    content.push_str(line);
                 }
             }
         } else {
             /*
              * RINEX
              */
+            content.clear();
             if line.is_empty() {
                 // we might encounter empty lines
                 // and the following parsers (.lines() iterator)
                 // do not like it
-                content = String::from("\n");
+                content.push('\n');
             } else {
-                content = line.to_string();
+                content.push_str(line);
             }
         }
 
+        terminated = buf.ends_with('\n');
+        last_in_epoch = !content.is_empty();
+
         for line in content.lines() {
             // in case of CRINEX -> RINEX < 3 being recovered,
             // we have more than 1 ligne to process
@@ -451,9 +633,23 @@ pub fn parse_record(
                         }
                     },
                     Type::ObservationData => {
-                        if let Ok((e, ck_offset, map)) =
-                            observation::record::parse_epoch(header, &epoch_content, obs_ts)
-                        {
+                        if let Some((t, change)) = observation::record::parse_equipment_change(
+                            header,
+                            &epoch_content,
+                            obs_ts,
+                        ) {
+                            equipment_changes.insert(t, change);
+                        }
+                        #[cfg(feature = "parallel")]
+                        obs_blocks
+                            .push((epoch_content.clone(), std::mem::take(&mut epoch_comments)));
+                        #[cfg(not(feature = "parallel"))]
+                        if let Ok((e, ck_offset, map)) = observation::record::parse_epoch(
+                            header,
+                            &epoch_content,
+                            obs_ts,
+                            max_magnitude,
+                        ) {
                             obs_rec.insert(e, (ck_offset, map));
                             comment_ts = e.0; // for comments classification & management
                         }
@@ -484,9 +680,10 @@ pub fn parse_record(
                         }
                     },
                     Type::AntennaData => {
-                        let (antenna, content) =
-                            antex::record::parse_antenna(&epoch_content).unwrap();
-                        atx_rec.push((antenna, content));
+                        if let Ok((antenna, content)) = antex::record::parse_antenna(&epoch_content)
+                        {
+                            atx_rec.push((antenna, content));
+                        }
                     },
                     Type::IonosphereMaps => {
                         if let Ok((epoch, altitude, plane)) =
@@ -519,10 +716,12 @@ pub fn parse_record(
                     },
                 }
 
-                // new comments ?
-                if !comment_content.is_empty() {
-                    comments.insert(comment_ts, comment_content.clone());
-                    comment_content.clear() // reset
+                // comments preceding this epoch ?
+                if !epoch_comments.is_empty() {
+                    comments
+                        .entry(comment_ts)
+                        .or_default()
+                        .append(&mut epoch_comments);
                 }
             } //is_new_epoch() +!first
 
@@ -531,12 +730,37 @@ pub fn parse_record(
                     epoch_content.clear()
                 }
                 first_epoch = false;
+                // pending comments precede this new epoch
+                epoch_comments.append(&mut comment_content);
             }
             // epoch content builder
             epoch_content.push_str(&(line.to_owned() + "\n"));
         }
     }
 
+    // last line is not terminated: either the file was interrupted,
+    // or it is simply missing its final line feed
+    let unterminated = !terminated && last_in_epoch;
+    // last epoch of a truncated file is most likely incomplete:
+    // Observation epochs can be verified, other residues are dropped
+    // when the stream was interrupted, or when they cannot be parsed (see below)
+    if !epoch_content.trim().is_empty() {
+        if header.rinex_type == Type::ObservationData {
+            if !observation::record::is_complete_epoch(header, &epoch_content)
+                || (unterminated
+                    && !is_crinex
+                    && observation::record::is_interrupted_line(header, &epoch_content))
+            {
+                truncated = true;
+                epoch_content.clear();
+            }
+        } else if truncated {
+            epoch_content.clear();
+        }
+    }
+    // true if the residue could be interpreted
+    let mut residue_parsed = false;
+
     // --> try to build an epoch out of current residues
     // this covers
     //   + final epoch (last epoch in record)
@@ -552,11 +776,20 @@ pub fn parse_record(
                     .and_modify(|current| current.push(fr.clone()))
                     .or_insert_with(|| vec![fr.clone()]);
                 comment_ts = e; // for comments classification & management
+                residue_parsed = true;
             }
         },
         Type::ObservationData => {
+            if let Some((t, change)) =
+                observation::record::parse_equipment_change(header, &epoch_content, obs_ts)
+            {
+                equipment_changes.insert(t, change);
+            }
+            #[cfg(feature = "parallel")]
+            obs_blocks.push((epoch_content.clone(), Vec::new()));
+            #[cfg(not(feature = "parallel"))]
             if let Ok((e, ck_offset, map)) =
-                observation::record::parse_epoch(header, &epoch_content, obs_ts)
+                observation::record::parse_epoch(header, &epoch_content, obs_ts, max_magnitude)
             {
                 obs_rec.insert(e, (ck_offset, map));
                 comment_ts = e.0; // for comments classification + management
@@ -565,12 +798,14 @@ pub fn parse_record(
         Type::DORIS => {
             if let Ok((e, map)) = doris::record::parse_epoch(header, &epoch_content) {
                 dor_rec.insert(e, map);
+                residue_parsed = true;
             }
         },
         Type::MeteoData => {
             if let Ok((e, map)) = meteo::record::parse_epoch(header, &epoch_content) {
                 met_rec.insert(e, map);
                 comment_ts = e; // for comments classification + management
+                residue_parsed = true;
             }
         },
         Type::ClockData => {
@@ -585,12 +820,14 @@ pub fn parse_record(
                     clk_rec.insert(epoch, inner);
                 }
                 comment_ts = epoch; // for comments classification & management
+                residue_parsed = true;
             }
         },
         Type::IonosphereMaps => {
             if let Ok((epoch, altitude, plane)) =
                 ionex::record::parse_plane(&epoch_content, header, ionex_rms_plane)
             {
+                residue_parsed = true;
                 if ionex_rms_plane {
                     if let Some(rec_plane) = ionx_rec.get_mut(&(epoch, altitude)) {
                         // provide RMS value for the entire plane
@@ -613,16 +850,42 @@ pub fn parse_record(
             }
         },
         Type::AntennaData => {
-            //if let Ok((antenna, content)) = antex::record::parse_antenna(&epoch_content) {
-            //    atx_rec.push((antenna, content));
-            //}
-            let (antenna, content) = antex::record::parse_antenna(&epoch_content).unwrap();
-            atx_rec.push((antenna, content));
+            if let Ok((antenna, content)) = antex::record::parse_antenna(&epoch_content) {
+                atx_rec.push((antenna, content));
+                residue_parsed = true;
+            }
         },
     }
-    // new comments ?
-    if !comment_content.is_empty() {
-        comments.insert(comment_ts, comment_content.clone());
+    // an interrupted last line that cannot be interpreted
+    if unterminated && !residue_parsed && header.rinex_type != Type::ObservationData {
+        truncated = true;
+    }
+    // merge OBS epochs in file order,
+    // which is identical to sequential parsing
+    #[cfg(feature = "parallel")]
+    for (parsed, mut block_comments) in
+        observation::record::parse_epochs(header, obs_blocks, obs_ts, max_magnitude)
+    {
+        if let Ok((e, ck_offset, map)) = parsed {
+            obs_rec.insert(e, (ck_offset, map));
+            comment_ts = e.0; // for comments classification & management
+        }
+        if !block_comments.is_empty() {
+            comments
+                .entry(comment_ts)
+                .or_default()
+                .append(&mut block_comments);
+        }
+    }
+    // comments preceding last epoch ?
+    if !epoch_comments.is_empty() {
+        comments
+            .entry(comment_ts)
+            .or_default()
+            .append(&mut epoch_comments);
+    }
+    if let Some(obs) = &mut header.obs {
+        obs.equipment_changes.append(&mut equipment_changes);
     }
     // wrap record
     let record = match &header.rinex_type {
@@ -634,7 +897,8 @@ pub fn parse_record(
         Type::ObservationData => Record::ObsRecord(obs_rec),
         Type::DORIS => Record::DorisRecord(dor_rec),
     };
-    Ok((record, comments))
+    // remaining comments follow the last epoch
+    Ok((record, comments, comment_content, truncated))
 }
 
 impl Merge for Record {
@@ -658,10 +922,10 @@ impl Merge for Record {
             if let Some(rhs) = rhs.as_meteo() {
                 lhs.merge_mut(rhs)?;
             }
-        /*} else if let Some(lhs) = self.as_mut_ionex() {
-        if let Some(rhs) = rhs.as_ionex() {
-            lhs.merge_mut(&rhs)?;
-        }*/
+        } else if let Some(lhs) = self.as_mut_ionex() {
+            if let Some(rhs) = rhs.as_ionex() {
+                lhs.merge_mut(rhs)?;
+            }
         } else if let Some(lhs) = self.as_mut_antex() {
             if let Some(rhs) = rhs.as_antex() {
                 lhs.merge_mut(rhs)?;
@@ -692,6 +956,9 @@ impl Split for Record {
         } else if let Some(r) = self.as_clock() {
             let (r0, r1) = r.split(epoch)?;
             Ok((Self::ClockRecord(r0), Self::ClockRecord(r1)))
+        } else if self.as_antex().is_some() {
+            // ANTEX is not indexed by epoch
+            Err(split::Error::UnsupportedOperation)
         } else {
             Err(split::Error::NoEpochIteration)
         }
@@ -745,6 +1012,10 @@ impl Decimate for Record {
             rec.decimate_by_ratio_mut(r);
         } else if let Some(rec) = self.as_mut_doris() {
             rec.decimate_by_ratio_mut(r);
+        } else if let Some(rec) = self.as_mut_clock() {
+            rec.decimate_by_ratio_mut(r);
+        } else if let Some(rec) = self.as_mut_ionex() {
+            rec.decimate_by_ratio_mut(r);
         }
     }
     fn decimate_by_interval(&self, dt: Duration) -> Self {
@@ -761,6 +1032,10 @@ impl Decimate for Record {
             rec.decimate_by_interval_mut(dt);
         } else if let Some(rec) = self.as_mut_doris() {
             rec.decimate_by_interval_mut(dt);
+        } else if let Some(rec) = self.as_mut_clock() {
+            rec.decimate_by_interval_mut(dt);
+        } else if let Some(rec) = self.as_mut_ionex() {
+            rec.decimate_by_interval_mut(dt);
         }
     }
     fn decimate_match(&self, rhs: &Self) -> Self {
@@ -785,6 +1060,14 @@ impl Decimate for Record {
             if let Some(rhs) = rhs.as_doris() {
                 rec.decimate_match_mut(rhs);
             }
+        } else if let Some(rec) = self.as_mut_clock() {
+            if let Some(rhs) = rhs.as_clock() {
+                rec.decimate_match_mut(rhs);
+            }
+        } else if let Some(rec) = self.as_mut_ionex() {
+            if let Some(rhs) = rhs.as_ionex() {
+                rec.decimate_match_mut(rhs);
+            }
         }
     }
 }
@@ -8,6 +8,8 @@ pub enum Error {
     NoEpochIteration,
     #[error("this record does not contained specified epoch")]
     NonExistingEpoch,
+    #[error("operation not supported by this record type")]
+    UnsupportedOperation,
 }
 
 pub trait Split {
@@ -15,6 +15,7 @@ mod test {
         assert!(rinex.is_ok());
         let rinex = rinex.unwrap();
         assert!(rinex.is_antex());
+        assert_eq!(rinex.first_epoch(), None, "ANTEX is not sampled in time");
 
         let header = &rinex.header;
         assert_eq!(header.version.major, 1);
@@ -103,6 +104,38 @@ mod test {
             "failed to locate APC for TROSAR25.R4 antenna"
         );
         assert_eq!(apc.unwrap(), (-0.22, -0.01, 154.88));
+
+        /*
+         * crate feature: azimuth dependent PCV
+         */
+        assert_eq!(antenna.azi_inc, 5.0);
+        assert!(l1_specs.phase_pattern.is_azimuth_dependent());
+        let matcher = AntennaMatcher::IGSCode("TROSAR25.R4".to_string());
+        for (zenith, azimuth, expected) in [
+            (0.0, 0.0, Some(-1.01)),
+            (5.0, 0.0, Some(-0.90)),
+            (5.0, 360.0, Some(-0.90)),
+            (5.0, 5.0, Some(-0.87)),
+            (10.0, 10.0, Some(-0.59)),
+            (90.0, 25.0, Some(1.25)),
+            (95.0, 0.0, None),
+        ] {
+            let pcv = rinex.rx_antenna_pcv(fake_now, matcher.clone(), Carrier::L1, zenith, azimuth);
+            assert_eq!(
+                pcv, expected,
+                "bad PCV @ zenith={}, azimuth={}",
+                zenith, azimuth
+            );
+        }
+        let pcv = rinex
+            .rx_antenna_pcv(fake_now, matcher.clone(), Carrier::L1, 5.0, 2.5)
+            .unwrap();
+        assert!((pcv - -0.885).abs() < 1.0E-9);
+        let pcv = rinex
+            .rx_antenna_pcv(fake_now, matcher, Carrier::L1, 7.5, 357.5)
+            .unwrap();
+        let expected = ((-0.89 + -0.57) / 2.0 + (-0.90 + -0.59) / 2.0) / 2.0;
+        assert!((pcv - expected).abs() < 1.0E-9);
     }
     #[cfg(feature = "flate2")]
     #[cfg(feature = "antex")]
@@ -127,5 +160,43 @@ mod test {
             assert!(apc.is_some(), "failed to locate APC {} antenna", antenna,);
             assert_eq!(apc.unwrap(), expected);
         }
+
+        // NOAZI pattern, azimuth is not relevant
+        let matcher = AntennaMatcher::IGSCode("JPSLEGANT_E".to_string());
+        for (zenith, azimuth, expected) in [
+            (0.0, 0.0, Some(0.00)),
+            (5.0, 0.0, Some(-1.73)),
+            (5.0, 123.0, Some(-1.73)),
+            (80.0, 0.0, Some(3.73)),
+            (85.0, 0.0, None),
+        ] {
+            let pcv = rinex.rx_antenna_pcv(fake_now, matcher.clone(), Carrier::L1, zenith, azimuth);
+            assert_eq!(
+                pcv, expected,
+                "bad PCV @ zenith={}, azimuth={}",
+                zenith, azimuth
+            );
+        }
+        let pcv = rinex
+            .rx_antenna_pcv(fake_now, matcher, Carrier::L1, 7.5, 0.0)
+            .unwrap();
+        assert!((pcv - -2.17).abs() < 1.0E-9);
+
+        // spacecraft antenna, nadir angles with 0.5° spacing
+        let e04 = SV::from_str("E04").unwrap();
+        for (nadir, azimuth, expected) in [
+            (0.0, 0.0, Some(0.43)),
+            (0.5, 0.0, Some(0.40)),
+            (0.5, 5.0, Some(0.40)),
+            (2.0, 10.0, Some(0.27)),
+            (20.5, 0.0, None),
+        ] {
+            let pcv = rinex.sv_antenna_pcv(fake_now, e04, Carrier::E5a, nadir, azimuth);
+            assert_eq!(
+                pcv, expected,
+                "bad PCV @ nadir={}, azimuth={}",
+                nadir, azimuth
+            );
+        }
     }
 }
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod test {
+    use crate::clock::ClockAnalysis;
     use crate::domes::TrackingPoint as DomesTrackingPoint;
     use crate::prelude::*;
     use std::str::FromStr;
@@ -137,6 +138,12 @@ mod test {
         let rinex = rinex.unwrap();
         let clock_header = rinex.header.clock.as_ref().expect("badly formed clk rinex");
 
+        // station DOMES, from STATION NAME / NUM
+        assert_eq!(
+            rinex.header.domes().map(|domes| domes.to_string()),
+            Some("40451S003".to_string())
+        );
+
         assert_eq!(
             clock_header.codes,
             vec![
@@ -195,6 +202,11 @@ mod test {
         );
 
         assert_eq!(rinex.epoch().count(), 1);
+        assert_eq!(
+            rinex.first_epoch(),
+            Some(Epoch::from_str("1994-07-14T20:59:00 GPST").unwrap())
+        );
+        assert_eq!(rinex.last_epoch(), rinex.first_epoch());
 
         for (epoch, content) in rinex.precise_clock() {
             assert_eq!(*epoch, Epoch::from_str("1994-07-14T20:59:00 GPST").unwrap());
@@ -382,4 +394,104 @@ mod test {
             }
         }
     }
+    #[test]
+    fn clk_v3_usno_analysis() {
+        let fp = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/CLK/V3/USNO1.txt";
+        let rinex = Rinex::from_file(&fp).unwrap();
+        let t0 = Epoch::from_str("1994-07-14T20:59:00 GPST").unwrap();
+
+        let usno = ClockType::Station("USNO".to_string());
+        let series = rinex.bias_series(&usno);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series.get(&t0), Some(&-0.123456789012E+00));
+
+        // single sample: drift can't be estimated
+        assert_eq!(rinex.drift_estimate(&usno, t0, t0), None);
+
+        let areq = ClockType::Station("AREQ".to_string());
+        let aligned = rinex.align_to_reference(&areq);
+        assert_eq!(aligned.epoch().count(), 1);
+        let g16 = ClockType::SV(SV::from_str("G16").unwrap());
+        assert_eq!(aligned.bias_series(&g16).get(&t0), Some(&0.0));
+        assert_eq!(aligned.bias_series(&areq).get(&t0), Some(&0.0));
+
+        // unknown reference: nothing remains
+        let unknown = ClockType::Station("ZZZZ".to_string());
+        assert_eq!(rinex.align_to_reference(&unknown).epoch().count(), 0);
+    }
+    #[test]
+    fn clk_analysis_synthetic() {
+        let t0 = Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap();
+        let g01 = ClockType::SV(SV::from_str("G01").unwrap());
+        let station = ClockType::Station("REFC".to_string());
+
+        let mut record = crate::clock::Record::new();
+        for i in 0..10 {
+            let t = t0 + Duration::from_seconds(i as f64 * 30.0);
+            let dt = i as f64 * 30.0;
+            let clocks = record.entry(t).or_default();
+            clocks.insert(
+                ClockKey {
+                    clock_type: g01.clone(),
+                    profile_type: ClockProfileType::AS,
+                },
+                ClockProfile {
+                    bias: 1.0E-4 + 1.0E-9 * dt,
+                    ..Default::default()
+                },
+            );
+            // reference is missing at 5th epoch
+            if i != 4 {
+                clocks.insert(
+                    ClockKey {
+                        clock_type: station.clone(),
+                        profile_type: ClockProfileType::AR,
+                    },
+                    ClockProfile {
+                        bias: 5.0E-5 + 2.0E-10 * dt,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        let rinex = Rinex::new(
+            Header::default(),
+            crate::record::Record::ClockRecord(record),
+        );
+
+        assert_eq!(rinex.bias_series(&g01).len(), 10);
+        assert_eq!(rinex.bias_series(&station).len(), 9);
+
+        let drift = rinex
+            .drift_estimate(&g01, t0, t0 + Duration::from_seconds(270.0))
+            .unwrap();
+        assert!((drift - 1.0E-9).abs() < 1.0E-15, "bad drift {}", drift);
+        // window restriction
+        let drift = rinex
+            .drift_estimate(
+                &station,
+                t0 + Duration::from_seconds(60.0),
+                t0 + Duration::from_seconds(180.0),
+            )
+            .unwrap();
+        assert!((drift - 2.0E-10).abs() < 1.0E-15, "bad drift {}", drift);
+
+        let aligned = rinex.align_to_reference(&station);
+        let series = aligned.bias_series(&g01);
+        assert_eq!(series.len(), 9, "epoch without reference should be dropped");
+        assert!(series.get(&(t0 + Duration::from_seconds(120.0))).is_none());
+        for (t, bias) in series {
+            let dt = (t - t0).to_seconds();
+            let expected = 5.0E-5 + 8.0E-10 * dt;
+            assert!((bias - expected).abs() < 1.0E-15, "bad aligned bias @{}", t);
+        }
+        let drift = aligned
+            .drift_estimate(&g01, t0, t0 + Duration::from_seconds(270.0))
+            .unwrap();
+        assert!(
+            (drift - 8.0E-10).abs() < 1.0E-15,
+            "bad aligned drift {}",
+            drift
+        );
+    }
 }
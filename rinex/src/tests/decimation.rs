@@ -5,6 +5,7 @@ mod decimation {
     use crate::preprocessing::*;
     //use itertools::Itertools;
     use std::path::Path;
+    use std::str::FromStr;
 
     #[test]
     #[cfg(feature = "flate2")]
@@ -36,6 +37,50 @@ mod decimation {
         assert_eq!(count, len / 4, "decimate(2'): error",);
     }
     #[test]
+    fn obs_decimation_same_timestamp() {
+        let mut rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let t0 = Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+        let t2 = Epoch::from_str("2022-03-04T00:57:00 GPST").unwrap();
+
+        // external event sharing its timestamp with a data epoch
+        rinex
+            .record
+            .as_mut_obs()
+            .unwrap()
+            .insert((t1, EpochFlag::ExternalEvent), (None, Default::default()));
+
+        // timestamp counts once
+        let decimated = rinex.decimate_by_ratio(2);
+        assert_eq!(decimated.epoch().collect::<Vec<_>>(), vec![t0, t2]);
+        let decimated = rinex.decimate_by_ratio(1);
+        assert_eq!(decimated.record, rinex.record);
+        // null ratio is a no-op
+        let decimated = rinex.decimate_by_ratio(0);
+        assert_eq!(decimated.record, rinex.record);
+        let mut record = rinex.record.as_obs().unwrap().clone();
+        record.decimate_by_ratio_mut(0);
+        assert_eq!(&record, rinex.record.as_obs().unwrap());
+
+        // entries sharing a timestamp are retained together
+        let decimated = rinex.decimate_by_interval(Duration::from_seconds(1710.0));
+        assert_eq!(decimated.record, rinex.record);
+        let decimated = rinex.decimate_by_interval(Duration::from_seconds(1800.0));
+        assert_eq!(decimated.epoch().collect::<Vec<_>>(), vec![t0, t2]);
+        assert_eq!(decimated.record.as_obs().unwrap().len(), 2);
+
+        // match on timestamps
+        let mut target = rinex.clone();
+        target
+            .record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, flag), _| *t == t1 && flag.is_ok());
+        let decimated = rinex.decimate_match(&target);
+        assert_eq!(decimated.epoch().collect::<Vec<_>>(), vec![t1]);
+        assert_eq!(decimated.record.as_obs().unwrap().len(), 2);
+    }
+    #[test]
     #[cfg(feature = "flate2")]
     fn meteo_decimation() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -89,4 +134,63 @@ mod decimation {
         let count = rinex.epoch().count();
         assert_eq!(count, 1013, "decimate(1'+1s): error",);
     }
+    #[test]
+    fn decimation_ratio_interval() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V2/delf0010.21o").unwrap();
+        assert_eq!(
+            rinex.header.sampling_interval,
+            Some(Duration::from_seconds(30.0))
+        );
+        let decimated = rinex.decimate_by_ratio(3);
+        assert_eq!(
+            decimated.header.sampling_interval,
+            Some(Duration::from_seconds(90.0))
+        );
+        assert_eq!(decimated.epoch().count(), 35);
+
+        assert!(DecimationFilter::from_str("0").is_err());
+        assert_eq!(
+            DecimationFilter::from_str("3").unwrap().dtype,
+            DecimationType::DecimByRatio(3)
+        );
+    }
+    #[test]
+    fn clock_decimation() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("CLK")
+            .join("V2")
+            .join("COD20352.CLK");
+
+        let fullpath = path.to_string_lossy();
+        let rinex = Rinex::from_file(fullpath.as_ref());
+        assert!(rinex.is_ok(), "failed to parse \"{}\"", fullpath);
+
+        let rinex = rinex.unwrap();
+        assert_eq!(rinex.epoch().count(), 10);
+
+        let decimated = rinex.decimate_by_ratio(2);
+        let count = decimated.epoch().count();
+        assert_eq!(count, 5, "decimate(/2): error",);
+
+        // epochs of index k % 3 == 0 are retained
+        let epochs: Vec<_> = rinex.epoch().collect();
+        let decimated = rinex.decimate_by_ratio(3);
+        assert_eq!(
+            decimated.epoch().collect::<Vec<_>>(),
+            vec![epochs[0], epochs[3], epochs[6], epochs[9]],
+        );
+        let decimated = rinex.decimate_by_ratio(1);
+        assert_eq!(decimated, rinex);
+
+        let mut rinex = rinex.clone();
+        rinex.decimate_by_interval_mut(Duration::from_seconds(60.0));
+        let count = rinex.epoch().count();
+        assert_eq!(count, 6, "decimate(1'): error",);
+
+        rinex.decimate_by_interval_mut(Duration::from_seconds(120.0));
+        let count = rinex.epoch().count();
+        assert_eq!(count, 4, "decimate(2'): error",);
+    }
 }
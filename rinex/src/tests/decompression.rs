@@ -851,4 +851,92 @@ mod test {
             &["C1C", "C5I", "D1C", "D5I", "L1C", "L5I", "S1C", "S5I"],
         );
     }
+    #[test]
+    fn v3_vlns0010_many_observables() {
+        let crnx = Rinex::from_file("../test_resources/CRNX/V3/VLNS0010.22D").unwrap();
+        let model = Rinex::from_file("../test_resources/OBS/V3/VLNS0010.22O").unwrap();
+
+        // 18 observables per GPS vehicle
+        let codes = &crnx.header.obs.as_ref().unwrap().codes;
+        assert_eq!(codes.get(&Constellation::GPS).unwrap().len(), 18);
+
+        // first epochs are fully recovered
+        let record = crnx.record.as_obs().unwrap();
+        let expected = model.record.as_obs().unwrap();
+        for ((key, (_, vehicles)), (expected_key, (_, expected_vehicles))) in
+            record.iter().zip(expected.iter()).take(2)
+        {
+            assert_eq!(key, expected_key);
+            assert_eq!(vehicles, expected_vehicles, "recovery failed @ {:?}", key);
+        }
+    }
+    #[test]
+    fn malformed_difference() {
+        let mut obscodes: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        obscodes.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("S1C").unwrap(),
+            ],
+        );
+        let content = "> 2022 01 01  0  0  0.0000000  0  1      G08
+3&0
+3&2098293708x 3&110266080971 3&49500
+";
+        let mut decompressor = Decompressor::new();
+        let result = decompressor.decompress(3, &Constellation::Mixed, 3, &obscodes, content);
+        assert!(matches!(
+            result,
+            Err(crate::hatanaka::Error::MalformedDifference)
+        ));
+    }
+    #[test]
+    fn missing_observations() {
+        let mut obscodes: HashMap<Constellation, Vec<Observable>> = HashMap::new();
+        obscodes.insert(
+            Constellation::GPS,
+            vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("S1C").unwrap(),
+            ],
+        );
+        // G08: all observations missing, G10: last two missing
+        let content = "> 2022 01 01  0  0  0.0000000  0  2      G08G10
+3&0
+
+3&20982937080
+";
+        let mut decompressor = Decompressor::new();
+        let recovered = content
+            .lines()
+            .map(|line| {
+                decompressor
+                    .decompress(
+                        3,
+                        &Constellation::Mixed,
+                        3,
+                        &obscodes,
+                        &(line.to_owned() + "\n"),
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(recovered[2].trim_end(), "G08");
+        assert_eq!(recovered[3].trim_end(), "G10  20982937.080");
+
+        // non numerical content remains an error
+        let content = "> 2022 01 01  0  0  0.0000000  0  1      G08
+3&0
+3&20982937080 3&110266080971 abc
+";
+        let mut decompressor = Decompressor::new();
+        let result = decompressor.decompress(3, &Constellation::Mixed, 3, &obscodes, content);
+        assert!(matches!(
+            result,
+            Err(crate::hatanaka::Error::MalformedDifference)
+        ));
+    }
 }
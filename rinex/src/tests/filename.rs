@@ -15,6 +15,9 @@ fn short_filename_conventions() {
         "CRNX/V3/VLNS0010.22D",
         "MET/V2/abvi0010.15m",
         "MET/V2/clar0020.00m",
+        "NAV/V2/amel0010.21g",
+        "NAV/V2/dlf10010.21g",
+        "CLK/V2/COD20352.CLK",
     ] {
         let fp = Path::new(env!("CARGO_MANIFEST_DIR"))
             .join("..")
@@ -31,7 +34,7 @@ fn short_filename_conventions() {
             .to_string()
             .to_uppercase();
 
-        let output = rinex.standard_filename(true, None, None); // force short
+        let output = rinex.standard_filename(true, None, None, None); // force short
         assert_eq!(output, filename, "bad short filename generated");
     }
 }
@@ -55,6 +58,21 @@ fn long_filename_conventions() {
             "ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
             Some(".gz"),
         ),
+        (
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+            "AMEL00NLD_R_20210010000_01D_MN.rnx",
+            None,
+        ),
+        (
+            "NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx",
+            "CBW100NLD_R_20210010000_01D_MN.rnx",
+            None,
+        ),
+        (
+            "CLK/V3/GRG0MGXFIN_20201770000_01D_30S_CLK.CLK.gz",
+            "GRG0MGXFIN_20201770000_01D_30S_CLK.CLK.gz",
+            Some(".gz"),
+        ),
     ] {
         let fp = Path::new(env!("CARGO_MANIFEST_DIR"))
             .join("..")
@@ -62,7 +80,107 @@ fn long_filename_conventions() {
             .join(testfile);
 
         let rinex = Rinex::from_path(&fp).unwrap();
-        let output = rinex.standard_filename(false, custom_suffix, None);
+        let output = rinex.standard_filename(false, custom_suffix, None, None);
         assert_eq!(output, expected, "bad filename generated");
     }
 }
+
+// Test production attributes are deduced from the record content
+#[test]
+fn content_filename_conventions() {
+    let fp = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("test_resources")
+        .join("OBS/V3/DUTH0630.22O");
+
+    let rinex = Rinex::from_path(&fp).unwrap();
+    // loose the attributes attached to the original filename
+    let rinex = Rinex::new(rinex.header.clone(), rinex.record.clone());
+
+    assert_eq!(
+        rinex.standard_filename(true, None, None, None),
+        "XXXX0630.22O"
+    );
+    assert_eq!(
+        rinex.standard_filename(false, None, None, None),
+        "XXXX00XXX_R_20220630000_01H_29M_MO.rnx"
+    );
+    assert_eq!(
+        rinex.standard_filename(false, Some(".gz"), None, Some("GRC")),
+        "XXXX00GRC_R_20220630000_01H_29M_MO.rnx.gz"
+    );
+}
+
+// Test generated names can be parsed back, and regenerated identically
+#[test]
+fn filename_round_trip() {
+    use crate::prod::ProductionAttributes;
+    use std::str::FromStr;
+    for (testfile, short) in [
+        ("OBS/V2/AJAC3550.21O", true),
+        ("OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx", false),
+    ] {
+        let fp = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join(testfile);
+
+        let rinex = Rinex::from_path(&fp).unwrap();
+        let filename = rinex.standard_filename(short, None, None, None);
+        assert_eq!(filename, testfile.split('/').last().unwrap());
+
+        let attributes = ProductionAttributes::from_str(&filename).unwrap();
+        assert_eq!(rinex.production_attributes(), Some(&attributes));
+
+        // loose the attributes attached to the original filename, then supply them
+        let rinex = Rinex::new(rinex.header.clone(), rinex.record.clone());
+        assert!(rinex.production_attributes().is_none());
+        let rinex = rinex.with_production_attributes(attributes);
+        assert_eq!(rinex.standard_filename(short, None, None, None), filename);
+    }
+}
+
+// Test the 9 character station name fills the long filename fields
+#[test]
+fn station_name_filename_conventions() {
+    use crate::marker::GeodeticMarker;
+    let fp = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("test_resources")
+        .join("OBS/V3/DUTH0630.22O");
+
+    let rinex = Rinex::from_path(&fp).unwrap();
+    let mut header = rinex.header.clone();
+    header.geodetic_marker = Some(GeodeticMarker::default().with_name("AMEL00NLD"));
+
+    let station = header.station_9char().unwrap();
+    assert_eq!(station.monument, "AMEL");
+    assert_eq!(station.marker_number, 0);
+    assert_eq!(station.receiver_number, 0);
+    assert_eq!(station.country, "NLD");
+
+    // loose the attributes attached to the original filename
+    let rinex = Rinex::new(header.clone(), rinex.record.clone());
+    assert_eq!(
+        rinex.standard_filename(false, None, None, None),
+        "AMEL00NLD_R_20220630000_01H_29M_MO.rnx"
+    );
+    assert_eq!(
+        rinex.standard_filename(true, None, None, None),
+        "AMEL0630.22O"
+    );
+    // user country code superceeds
+    assert_eq!(
+        rinex.standard_filename(false, None, None, Some("GRC")),
+        "AMEL00GRC_R_20220630000_01H_29M_MO.rnx"
+    );
+    let guessed = rinex.guess_production_attributes();
+    assert_eq!(guessed.name, "AMEL");
+
+    header.geodetic_marker = Some(GeodeticMarker::default().with_name("DUTH12GRC"));
+    let rinex = Rinex::new(header, rinex.record.clone());
+    assert_eq!(
+        rinex.standard_filename(false, None, None, None),
+        "DUTH12GRC_R_20220630000_01H_29M_MO.rnx"
+    );
+}
@@ -202,4 +202,138 @@ mod test {
         //     }
         // }
     }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn v1_ckmg0020_22i_round_trip() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("IONEX")
+            .join("V1")
+            .join("CKMG0020.22I.gz");
+        let fullpath = path.to_string_lossy();
+        let rinex = Rinex::from_file(fullpath.as_ref()).unwrap();
+
+        // dump
+        let tmp_path = "ckmg0020-round-trip.22i";
+        assert!(
+            rinex.to_file(tmp_path).is_ok(),
+            "failed to generate IONEX file"
+        );
+
+        // parse back
+        let parsed = Rinex::from_file(tmp_path);
+        assert!(parsed.is_ok(), "failed to parse back generated IONEX");
+        let parsed = parsed.unwrap();
+        assert!(parsed.is_ionex());
+        assert_eq!(parsed.first_epoch(), rinex.first_epoch());
+        assert_eq!(parsed.last_epoch(), rinex.last_epoch());
+        // per map exponents do not modify the header scaling
+        assert_eq!(
+            parsed.header.ionex.as_ref().map(|ionex| ionex.exponent),
+            Some(-1)
+        );
+
+        let record = rinex.record.as_ionex().unwrap();
+        let parsed_record = parsed.record.as_ionex().unwrap();
+        assert_eq!(
+            record.len(),
+            parsed_record.len(),
+            "wrong amount of TEC maps parsed back"
+        );
+        for (key, plane) in record {
+            let parsed_plane = parsed_record.get(key);
+            assert!(parsed_plane.is_some(), "missing TEC map @ {:?}", key);
+            let parsed_plane = parsed_plane.unwrap();
+            for (coords, tec) in plane {
+                let parsed_tec = parsed_plane.get(coords);
+                assert!(
+                    parsed_tec.is_some(),
+                    "missing TEC value @ {:?} {:?}",
+                    key,
+                    coords
+                );
+                let parsed_tec = parsed_tec.unwrap();
+                assert!(
+                    (tec.tec - parsed_tec.tec).abs() < 1.0E-6,
+                    "bad TEC value @ {:?} {:?}: {} / {}",
+                    key,
+                    coords,
+                    tec.tec,
+                    parsed_tec.tec
+                );
+            }
+        }
+
+        // remove file we just generated
+        let _ = std::fs::remove_file(tmp_path);
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn v1_ckmg0020_22i_region() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("IONEX")
+            .join("V1")
+            .join("CKMG0020.22I.gz");
+        let fullpath = path.to_string_lossy();
+        let rinex = Rinex::from_file(fullpath.as_ref()).unwrap();
+        let record = rinex.record.as_ionex().unwrap();
+
+        // latitude band: bounds are snapped to the enclosing grid nodes
+        let band = rinex.ionex_latitude_band(34.0, 72.0);
+        let grid = &band.header.ionex.as_ref().unwrap().grid;
+        assert_eq!(grid.latitude.start, 72.5);
+        assert_eq!(grid.latitude.end, 32.5);
+        assert_eq!(grid.longitude.start, -180.0);
+        assert_eq!(grid.longitude.end, 180.0);
+        for plane in band.record.as_ionex().unwrap().values() {
+            assert_eq!(plane.len(), 17 * 73, "bad latitude band dimensions");
+        }
+
+        // Europe
+        let europe = rinex.ionex_region(35.0, 72.5, -25.0, 45.0);
+        assert_eq!(
+            europe.tec_map_borders(),
+            Some(((72.5, -25.0), (35.0, 45.0)))
+        );
+        let europe_record = europe.record.as_ionex().unwrap();
+        assert_eq!(europe_record.len(), record.len());
+        for (key, plane) in europe_record {
+            assert_eq!(plane.len(), 16 * 15, "bad region dimensions");
+            let original = record.get(key).unwrap();
+            for (coords, tec) in plane {
+                assert_eq!(original.get(coords), Some(tec), "node value modified");
+            }
+        }
+
+        // bounds outside the original grid are clamped
+        let clamped = rinex.ionex_region(-100.0, 100.0, -200.0, 200.0);
+        assert_eq!(clamped.record, rinex.record);
+        assert_eq!(clamped.header.ionex, rinex.header.ionex);
+
+        // wrap around the ±180° meridian
+        let pacific = rinex.ionex_region(-10.0, 10.0, 170.0, -170.0);
+        assert_eq!(
+            pacific.tec_map_borders(),
+            Some(((10.0, 170.0), (-10.0, 190.0)))
+        );
+        for (key, plane) in pacific.record.as_ionex().unwrap() {
+            assert_eq!(plane.len(), 9 * 5, "bad wrapped region dimensions");
+            let original = record.get(key).unwrap();
+            for ((lat, lon), tec) in plane {
+                let lon = if *lon > 180_000 { *lon - 360_000 } else { *lon };
+                assert_eq!(original.get(&(*lat, lon)), Some(tec));
+            }
+        }
+
+        // regional extracts can be formatted and parsed back
+        let tmp_path = "ckmg0020-europe.22i";
+        europe.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        assert_eq!(parsed.tec_map_borders(), europe.tec_map_borders());
+        assert_eq!(parsed.tec().count(), europe.tec().count());
+        let _ = std::fs::remove_file(tmp_path);
+    }
 }
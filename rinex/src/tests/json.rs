@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::record::Record;
+    #[test]
+    fn header_json_round_trip() {
+        for file in [
+            "OBS/V2/AJAC3550.21O",
+            "OBS/V3/DUTH0630.22O",
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+            "MET/V2/abvi0010.15m",
+        ] {
+            let path = format!("../test_resources/{}", file);
+            let rinex = Rinex::from_file(&path).unwrap();
+            let header = &rinex.header;
+
+            let content = serde_json::to_string(header)
+                .unwrap_or_else(|e| panic!("{}: failed to serialize header: {}", file, e));
+            let parsed: Header = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("{}: failed to deserialize header: {}", file, e));
+
+            assert_eq!(parsed.version, header.version, "{}", file);
+            assert_eq!(parsed.rinex_type, header.rinex_type, "{}", file);
+            assert_eq!(parsed.constellation, header.constellation, "{}", file);
+            assert_eq!(parsed.comments, header.comments, "{}", file);
+            assert_eq!(parsed.program, header.program, "{}", file);
+            assert_eq!(parsed.run_by, header.run_by, "{}", file);
+            assert_eq!(parsed.date, header.date, "{}", file);
+            assert_eq!(parsed.observer, header.observer, "{}", file);
+            assert_eq!(parsed.agency, header.agency, "{}", file);
+            assert_eq!(parsed.geodetic_marker, header.geodetic_marker, "{}", file);
+            assert_eq!(parsed.glo_channels, header.glo_channels, "{}", file);
+            assert_eq!(parsed.leap, header.leap, "{}", file);
+            assert_eq!(parsed.ground_position, header.ground_position, "{}", file);
+            assert_eq!(
+                parsed.sampling_interval, header.sampling_interval,
+                "{}",
+                file
+            );
+            assert_eq!(parsed.rcvr, header.rcvr, "{}", file);
+            assert_eq!(parsed.rcvr_antenna, header.rcvr_antenna, "{}", file);
+            assert_eq!(
+                parsed.ionod_corrections, header.ionod_corrections,
+                "{}",
+                file
+            );
+            assert_eq!(parsed.obs, header.obs, "{}", file);
+            assert_eq!(parsed.meteo, header.meteo, "{}", file);
+            assert_eq!(parsed, *header, "{}", file);
+        }
+    }
+    #[test]
+    fn obs_record_json_round_trip() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let content = serde_json::to_string(&rinex.record).unwrap();
+        // epochs are described in "date flag" form
+        assert!(content.contains("\"2022-03-04T00:00:00 GPST 0\""));
+
+        let record: Record = serde_json::from_str(&content).unwrap();
+        assert_eq!(record, rinex.record);
+    }
+    #[test]
+    fn nav_record_json_round_trip() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let content = serde_json::to_string(&rinex.record).unwrap();
+        let record: Record = serde_json::from_str(&content).unwrap();
+        assert_eq!(record, rinex.record);
+    }
+}
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod test {
+    use crate::merge::{Error as MergeError, MergeConflict, MergeRecord, MergeStrategy};
+    use crate::observation::ObservationData;
     use crate::prelude::*;
     use crate::tests::toolkit::test_observation_rinex;
     use crate::Merge;
+    use crate::Split;
     use crate::{
         //erratic_time_frame,
         evenly_spaced_time_frame,
@@ -209,4 +212,321 @@ mod test {
             assert_eq!(apc.unwrap(), expected_apc);
         }
     }
+    fn scale_observable(
+        rnx: &mut Rinex,
+        constellation: Constellation,
+        observable: &Observable,
+        factor: u16,
+    ) {
+        rnx.header
+            .obs
+            .as_mut()
+            .unwrap()
+            .with_scaling(constellation, observable.clone(), factor);
+        for (_, (_, vehicles)) in rnx.record.as_mut_obs().unwrap().iter_mut() {
+            for (_, observations) in vehicles.iter_mut() {
+                if let Some(data) = observations.get_mut(observable) {
+                    data.obs *= factor as f64;
+                }
+            }
+        }
+    }
+    #[test]
+    fn merge_obs_scaling() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+        let l1 = Observable::from_str("L1").unwrap();
+
+        let epochs: Vec<Epoch> = rnx.epoch().collect();
+        let (mut lhs, mut rhs) = rnx.split(epochs[epochs.len() / 2]).unwrap();
+
+        // same code, different scaling
+        scale_observable(&mut lhs, Constellation::GPS, &l1, 10);
+        scale_observable(&mut rhs, Constellation::GPS, &l1, 100);
+
+        let merged = lhs.merge(&rhs).unwrap();
+        let scaling = merged.header.obs.as_ref().unwrap().scaling.clone();
+        assert_eq!(scaling.get(&(Constellation::GPS, l1.clone())), Some(&100));
+        assert_physical_values(&rnx, &merged, &l1, 100.0);
+
+        // scaling declared for all constellations ("D" lines)
+        let (mut lhs, mut rhs) = rnx.split(epochs[epochs.len() / 2]).unwrap();
+        scale_observable(&mut lhs, Constellation::Mixed, &l1, 1000);
+        scale_observable(&mut rhs, Constellation::GPS, &l1, 100);
+
+        let merged = lhs.merge(&rhs).unwrap();
+        let obs = merged.header.obs.as_ref().unwrap();
+        assert_eq!(obs.scaling_factor(Constellation::GPS, &l1), Some(1000));
+        assert_physical_values(&rnx, &merged, &l1, 1000.0);
+    }
+    /*
+     * Verifies `merged` still describes the physical values of `rnx`,
+     * `scaled` observable being scaled by `factor`
+     */
+    fn assert_physical_values(rnx: &Rinex, merged: &Rinex, scaled: &Observable, factor: f64) {
+        let record = merged.record.as_obs().unwrap();
+        for (k, (_, vehicles)) in rnx.record.as_obs().unwrap() {
+            let (_, merged_vehicles) = record.get(k).unwrap();
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    let merged_data = merged_vehicles.get(sv).unwrap().get(observable).unwrap();
+                    if observable == scaled {
+                        assert!((merged_data.obs / factor - data.obs).abs() < 1.0E-6);
+                    } else {
+                        assert_eq!(merged_data.obs, data.obs);
+                    }
+                }
+            }
+        }
+    }
+    #[test]
+    fn merge_clk() {
+        let rnx = Rinex::from_file("../test_resources/CLK/V2/COD20352.CLK").unwrap();
+        let epoch = Epoch::from_str("2019-01-08T00:02:00 GPST").unwrap();
+
+        let (lhs, rhs) = rnx.split(epoch).unwrap();
+        assert_eq!(lhs.epoch().count(), 4, "split(): bad left component");
+        assert_eq!(rhs.epoch().count(), 6, "split(): bad right component");
+
+        let merged = lhs.merge(&rhs);
+        assert!(merged.is_ok(), "failed to merge CLK: {:?}", merged.err());
+
+        let merged = merged.unwrap();
+        assert_eq!(merged.epoch().count(), 10);
+        assert_eq!(merged.record, rnx.record, "merge() reciprocity");
+    }
+    #[test]
+    fn merge_obs_conflicts() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        let epochs: Vec<Epoch> = rnx.epoch().collect();
+        let n = epochs.len();
+        assert!(n > 4, "test file is too short");
+
+        // overlapping files: both describe epochs[n/2 -1] and epochs[n/2]
+        let (lhs_end, rhs_start) = (epochs[n / 2], epochs[n / 2 - 1]);
+        let mut lhs = rnx.clone();
+        lhs.record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, _), _| *t <= lhs_end);
+        let mut rhs = rnx.clone();
+        rhs.record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, _), _| *t >= rhs_start);
+
+        // identical overlap: no conflicts
+        let (merged, conflicts) = lhs.merge_with_strategy(&rhs, MergeStrategy::Error).unwrap();
+        assert!(conflicts.is_empty(), "identical data should not conflict");
+        assert_eq!(merged.record, rnx.record);
+
+        // corrupt one observation within the overlap
+        let (epoch, flag, sv, observable, original, modified) = {
+            let ((epoch, flag), (_, vehicles)) =
+                rhs.record.as_mut_obs().unwrap().iter_mut().next().unwrap();
+            let (sv, observations) = vehicles.iter_mut().next().unwrap();
+            let (observable, data) = observations.iter_mut().next().unwrap();
+            let original = *data;
+            data.obs += 1.0;
+            (*epoch, *flag, *sv, observable.clone(), original, *data)
+        };
+        assert_eq!(epoch, rhs_start);
+        let expected = vec![MergeConflict {
+            epoch,
+            flag,
+            sv,
+            observable: observable.clone(),
+            lhs: original,
+            rhs: modified,
+        }];
+
+        // strict mode
+        match lhs.merge_with_strategy(&rhs, MergeStrategy::Error) {
+            Err(MergeError::MergeConflicts(conflicts)) => assert_eq!(conflicts, expected),
+            _ => panic!("conflict should have been reported"),
+        }
+
+        let value = |rinex: &Rinex| -> ObservationData {
+            let (_, vehicles) = rinex.record.as_obs().unwrap().get(&(epoch, flag)).unwrap();
+            *vehicles.get(&sv).unwrap().get(&observable).unwrap()
+        };
+
+        // self is preferred: identical to the original file
+        let (merged, conflicts) = lhs
+            .merge_with_strategy(&rhs, MergeStrategy::PreferSelf)
+            .unwrap();
+        assert_eq!(conflicts, expected);
+        assert_eq!(value(&merged), original);
+        assert_eq!(merged.record, rnx.record);
+
+        // other is preferred: this is the default behavior
+        let (merged, conflicts) = lhs
+            .merge_with_strategy(&rhs, MergeStrategy::PreferOther)
+            .unwrap();
+        assert_eq!(conflicts, expected);
+        assert_eq!(value(&merged), modified);
+        assert_eq!(lhs.merge(&rhs).unwrap().record, merged.record);
+    }
+    #[test]
+    fn merge_comment_parsing() {
+        for (comment, program, date) in [
+            (
+                "gfzrnx-1.13-7761    FILE MERGE          20211222 033032 UTC",
+                "gfzrnx-1.13-7761",
+                "2021-12-22T03:30:32 UTC",
+            ),
+            (
+                "rustrnx-0.15.0      FILE MERGE          20220304 002830 UTC",
+                "rustrnx-0.15.0",
+                "2022-03-04T00:28:30 UTC",
+            ),
+            (
+                "teqc  2019Feb25     FILE MERGE          20230101 12:00:00UTC",
+                "teqc  2019Feb25",
+                "2023-01-01T12:00:00 UTC",
+            ),
+            (
+                "FILE MERGE          20230101 12:00:00",
+                "",
+                "2023-01-01T12:00:00 UTC",
+            ),
+        ] {
+            let record = MergeRecord::from_comment(comment)
+                .unwrap_or_else(|| panic!("failed to parse \"{}\"", comment));
+            assert_eq!(record.program, program);
+            assert_eq!(record.date, Epoch::from_str(date).unwrap());
+        }
+        assert!(MergeRecord::from_comment("SOME OTHER COMMENT").is_none());
+        assert!(MergeRecord::from_comment("rustrnx FILE MERGE 2022334 0028 UTC").is_none());
+    }
+    #[test]
+    fn merge_split_round_trip() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert!(rnx.merge_boundaries().is_empty());
+
+        let boundary = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+        let (a, b) = rnx.split(boundary).unwrap();
+
+        let merged = a.merge(&b).unwrap();
+        assert!(merged.is_merged());
+        assert_eq!(merged.merge_boundaries(), vec![boundary]);
+
+        // dump and parse back
+        let tmp_path = "merge-split.22O";
+        merged.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+
+        let info = parsed.merge_info();
+        assert_eq!(info.len(), 1);
+        assert!(info[0].program.starts_with("rustrnx-"));
+        assert_eq!(info[0].date, boundary);
+
+        let boundaries = parsed.merge_boundaries();
+        assert_eq!(boundaries, vec![boundary]);
+
+        let (a_parsed, b_parsed) = parsed.split(boundaries[0]).unwrap();
+        assert_eq!(a_parsed.record, a.record);
+        assert_eq!(b_parsed.record, b.record);
+    }
+    #[test]
+    fn merge_sub_second_boundary() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let t = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+        let (a, mut b) = rnx.split(t).unwrap();
+
+        // rhs is shifted by half a second
+        let dt = Duration::from_milliseconds(500.0);
+        let record = b.record.as_mut_obs().unwrap();
+        *record = record
+            .iter()
+            .map(|((t, flag), data)| ((*t + dt, *flag), data.clone()))
+            .collect();
+        let boundary = t + dt;
+        assert_eq!(b.first_epoch(), Some(boundary));
+
+        // stamped to the second, rounded up to the record epoch
+        let merged = a.merge(&b).unwrap();
+        let info = merged.merge_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].date, t);
+        assert_eq!(merged.merge_boundaries(), vec![boundary]);
+
+        let (lhs, rhs) = merged.split(boundary).unwrap();
+        assert_eq!(lhs.record, a.record);
+        assert_eq!(rhs.record, b.record);
+    }
+    #[test]
+    fn merge_distinct_files_boundaries() {
+        let a = Rinex::from_file("../test_resources/OBS/V3/VLNS0010.22O").unwrap();
+        let b = Rinex::from_file("../test_resources/OBS/V3/VLNS0630.22O").unwrap();
+        let boundary = b.first_epoch().unwrap();
+
+        let merged = a.merge(&b).unwrap();
+        assert_eq!(merged.merge_boundaries(), vec![boundary]);
+
+        let (lhs, rhs) = merged.split(boundary).unwrap();
+        assert_eq!(lhs.record, a.record);
+        assert_eq!(rhs.record, b.record);
+
+        // the FILE MERGE comment survives a round trip
+        let tmp_path = "merge-distinct.22O";
+        merged.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+
+        let boundaries = parsed.merge_boundaries();
+        assert_eq!(boundaries, vec![boundary]);
+
+        let (lhs, rhs) = parsed.split(boundaries[0]).unwrap();
+        assert_eq!(lhs.epoch().count(), a.epoch().count());
+        assert_eq!(rhs.epoch().count(), b.epoch().count());
+        assert_eq!(lhs.last_epoch(), a.last_epoch());
+        assert_eq!(rhs.first_epoch(), Some(boundary));
+    }
+    #[test]
+    fn record_comments_round_trip() {
+        let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let epochs = rnx.epoch().collect::<Vec<_>>();
+        let (t0, t1, t2) = (epochs[0], epochs[1], epochs[2]);
+        rnx.comments
+            .insert(t0, vec!["RECEIVER RESTARTED".to_string()]);
+        rnx.comments.insert(
+            t2,
+            vec![
+                "ANTENNA CABLE CHECKED".to_string(),
+                "DATA STREAM RESUMED".to_string(),
+            ],
+        );
+
+        // comments follow their epoch when splitting
+        let (a, b) = rnx.split(t1).unwrap();
+        assert_eq!(a.comments.keys().collect::<Vec<_>>(), vec![&t0]);
+        assert_eq!(b.comments.keys().collect::<Vec<_>>(), vec![&t2]);
+
+        // and are restored when merging
+        let merged = a.merge(&b).unwrap();
+        assert_eq!(merged.comments, rnx.comments);
+
+        // dump and parse back: comments reappear prior their epoch
+        let tmp_path = "merge-comments.22O";
+        merged.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert_eq!(parsed.comments, rnx.comments);
+
+        let body = content.split("END OF HEADER").nth(1).unwrap();
+        let lines = body.lines().collect::<Vec<_>>();
+        let restart = lines
+            .iter()
+            .position(|l| l.starts_with("RECEIVER RESTARTED"))
+            .unwrap();
+        let cable = lines
+            .iter()
+            .position(|l| l.starts_with("ANTENNA CABLE CHECKED"))
+            .unwrap();
+        assert!(lines[restart + 1].starts_with("> 2022 03 04 00 00"));
+        assert!(lines[cable + 1].starts_with("DATA STREAM RESUMED"));
+        assert!(lines[cable + 2].starts_with("> 2022 03 04 00 57"));
+    }
 }
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::prelude::*;
+    use itertools::Itertools;
+    use std::str::FromStr;
+    #[test]
+    fn metadata_json_sidecar() {
+        for (file, sidecar) in [
+            ("OBS/V3/DUTH0630.22O", "duth0630.json"),
+            ("OBS/V2/AJAC3550.21O", "ajac3550.json"),
+            ("NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx", "amel0010.json"),
+        ] {
+            let path = format!("../test_resources/{}", file);
+            let rinex = Rinex::from_file(&path).unwrap();
+            rinex.write_metadata_json(sidecar).unwrap();
+
+            let content = std::fs::read_to_string(sidecar).unwrap();
+            let _ = std::fs::remove_file(sidecar);
+            let metadata: Metadata = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("failed to parse {} sidecar: {}", file, e));
+
+            assert_eq!(metadata.header, rinex.header, "{}: header mismatch", file);
+            assert_eq!(metadata.first_epoch, rinex.first_epoch());
+            assert_eq!(metadata.last_epoch, rinex.last_epoch());
+            assert_eq!(
+                metadata.sv,
+                rinex.sv().unique().sorted().collect::<Vec<_>>()
+            );
+            assert_eq!(metadata, rinex.metadata());
+        }
+
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let metadata = rinex.metadata();
+        assert_eq!(
+            metadata.first_epoch,
+            Some(Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap())
+        );
+        assert_eq!(
+            metadata.last_epoch,
+            Some(Epoch::from_str("2022-03-04T00:57:00 GPST").unwrap())
+        );
+        assert!(metadata.sv.contains(&SV::from_str("G01").unwrap()));
+        assert!(!metadata.header.glo_channels.is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test {
+    use crate::header::ParsingError;
     use crate::prelude::*;
+    use crate::reader::BufferedReader;
     use crate::tests::toolkit::test_meteo_rinex;
     use crate::{erratic_time_frame, evenly_spaced_time_frame, tests::toolkit::TestTimeFrame};
     use itertools::Itertools;
@@ -285,4 +287,114 @@ mod test {
             }
         }
     }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn v3_pots00deu_sensor_positions() {
+        let mut rinex =
+            Rinex::from_file("../test_resources/MET/V3/POTS00DEU_R_20232540000_01D_05M_MM.rnx.gz")
+                .unwrap();
+        let meteo = rinex.header.meteo.as_ref().unwrap();
+        assert_eq!(meteo.sensors.len(), 3);
+
+        let pr = meteo.sensor("PR").unwrap();
+        assert_eq!(pr.model, Some("Vaisala".to_string()));
+        assert_eq!(pr.sensor_type, Some("PTU200".to_string()));
+        assert_eq!(pr.position, Some((0.0, 0.0, 0.0, 132.8177)));
+
+        let td = meteo.sensor("TD").unwrap();
+        assert_eq!(td.sensor_type, Some("HMP45A-P".to_string()));
+        assert!(td.position.is_none());
+        assert!(meteo.sensor("WS").is_none());
+
+        // describe TD sensor position, then dump and parse back
+        let td_position = (3800689.6340, 882077.3860, 5028791.3150, 132.8177);
+        let meteo = rinex.header.meteo.as_mut().unwrap();
+        for sensor in meteo.sensors.iter_mut() {
+            if sensor.observable == Observable::Temperature {
+                sensor.position = Some(td_position);
+            }
+        }
+
+        let tmp_path = "pots00deu-sensors.rnx";
+        rinex.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path);
+        let _ = std::fs::remove_file(tmp_path);
+
+        let header = content.split("END OF HEADER").next().unwrap();
+        assert_eq!(header.matches("SENSOR POS XYZ/H").count(), 2);
+
+        let parsed = parsed.unwrap();
+        let meteo = parsed.header.meteo.as_ref().unwrap();
+        assert_eq!(meteo.sensors, rinex.header.meteo.as_ref().unwrap().sensors);
+        assert_eq!(
+            meteo.sensor("PR").unwrap().position,
+            Some((0.0, 0.0, 0.0, 132.8177))
+        );
+        assert_eq!(meteo.sensor("TD").unwrap().position, Some(td_position));
+    }
+    #[test]
+    fn sensor_position_prior_description() {
+        let content = std::fs::read_to_string("../test_resources/MET/V4/example1.txt").unwrap();
+        let mod_line = content
+            .lines()
+            .find(|l| l.contains("TD SENSOR MOD/TYPE/ACC"))
+            .unwrap();
+        let pos_line = content
+            .lines()
+            .find(|l| l.contains("TD SENSOR POS XYZ/H"))
+            .unwrap();
+        // position described prior the sensor itself
+        let swapped = content
+            .replacen(mod_line, "SWAP", 1)
+            .replacen(pos_line, mod_line, 1)
+            .replacen("SWAP", pos_line, 1);
+
+        let tmp_path = "example1-swapped-sensors.txt";
+        std::fs::write(tmp_path, swapped).unwrap();
+        let mut reader = BufferedReader::new(tmp_path).unwrap();
+        let header = Header::new(&mut reader);
+        let _ = std::fs::remove_file(tmp_path);
+
+        let header = header.unwrap();
+        let meteo = header.meteo.as_ref().unwrap();
+        assert_eq!(meteo.sensors.len(), 3);
+        let td = meteo.sensor("TD").unwrap();
+        assert_eq!(td.model, Some("Temp.   PTU300".to_string()));
+        assert_eq!(
+            td.position,
+            Some((-1836969.2810, 6065617.0086, -716257.8580, 158.1170))
+        );
+    }
+    #[test]
+    fn v2_abvi0010_15m_obs_meteo_disambiguation() {
+        let content = std::fs::read_to_string("../test_resources/MET/V2/abvi0010.15m").unwrap();
+        let type_line = content.lines().next().unwrap();
+
+        // missing type descriptor: meteo sensors are sufficient
+        let tmp_path = "abvi0010-untyped.15m";
+        std::fs::write(tmp_path, content.replacen(type_line, "", 1)).unwrap();
+        let mut reader = BufferedReader::new(tmp_path).unwrap();
+        let header = Header::new(&mut reader);
+        let _ = std::fs::remove_file(tmp_path);
+
+        let header = header.unwrap();
+        assert_eq!(header.rinex_type, RinexType::MeteoData);
+        assert!(header.obs.is_none(), "meteo codes parsed as observations");
+        let codes = &header.meteo.as_ref().unwrap().codes;
+        assert_eq!(codes.len(), 7);
+        assert_eq!(codes[0], Observable::Pressure);
+
+        // conflicting evidence
+        let obs_type_line = format!(
+            "{:<60}RINEX VERSION / TYPE",
+            "     2.11           OBSERVATION DATA    G (GPS)"
+        );
+        let tmp_path = "abvi0010-conflict.15m";
+        std::fs::write(tmp_path, content.replacen(type_line, &obs_type_line, 1)).unwrap();
+        let mut reader = BufferedReader::new(tmp_path).unwrap();
+        let header = Header::new(&mut reader);
+        let _ = std::fs::remove_file(tmp_path);
+        assert!(matches!(header, Err(ParsingError::ObsMeteoConflict)));
+    }
 }
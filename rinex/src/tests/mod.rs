@@ -13,9 +13,13 @@ mod doris;
 mod filename;
 #[cfg(feature = "ionex")]
 mod ionex;
+#[cfg(feature = "serde")]
+mod json;
 #[cfg(feature = "processing")]
 mod masking;
 mod merge;
+#[cfg(feature = "serde")]
+mod metadata;
 #[cfg(feature = "meteo")]
 mod meteo;
 #[cfg(feature = "nav")]
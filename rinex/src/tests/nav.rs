@@ -301,6 +301,197 @@ mod test {
         }
     }
     #[test]
+    #[cfg(feature = "nav")]
+    fn v3_cbw100nld_timescales() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let c05 = sv!("C05");
+
+        // toc is expressed in the native timescale
+        let (toc, _, (bias, _, _)) = rinex.sv_clock().find(|(_, sv, _)| *sv == c05).unwrap();
+        assert_eq!(toc.time_scale, TimeScale::BDT);
+        assert_eq!(toc, Epoch::from_str("2021-01-01T00:00:00 BDT").unwrap());
+        assert_eq!(bias, -4.263372393325e-04);
+
+        // BDT is 14s behind GPST
+        let gpst = rinex.with_timescale(TimeScale::GPST);
+        for (toc, _, _) in gpst.sv_clock() {
+            assert_eq!(toc.time_scale, TimeScale::GPST);
+        }
+        let (toc, _, (gpst_bias, _, _)) = gpst.sv_clock().find(|(_, sv, _)| *sv == c05).unwrap();
+        assert_eq!(toc, Epoch::from_str("2021-01-01T00:00:14 GPST").unwrap());
+        assert_eq!(gpst_bias, bias);
+        assert_eq!(gpst.sv_clock().count(), rinex.sv_clock().count());
+
+        // frames are still formatted in the native timescale
+        let record = gpst.record.as_nav().unwrap();
+        let frames = record.get(&toc).unwrap();
+        let formatted = crate::navigation::record::fmt_epoch(&toc, frames, &gpst.header).unwrap();
+        assert!(
+            formatted.contains("C05 2021 01 01 00 00 00"),
+            "bad BDT toc: {}",
+            formatted
+        );
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn v3_cbw100nld_sv_clock_bias() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let (g20, c05) = (sv!("G20"), sv!("C05"));
+
+        // G20: toc = 16:00:00 GPST
+        for (t, expected) in [
+            ("2021-01-01T16:00:00 GPST", Some(5.253581330180e-04)),
+            (
+                "2021-01-01T16:15:00 GPST",
+                Some(5.253581330180e-04 - 1.136868377216e-13 * 900.0),
+            ),
+            (
+                "2021-01-01T15:00:00 GPST",
+                Some(5.253581330180e-04 + 1.136868377216e-13 * 3600.0),
+            ),
+            ("2021-01-01T18:30:00 GPST", None),
+        ] {
+            let t = Epoch::from_str(t).unwrap();
+            let bias = rinex.sv_clock_bias(g20, t, false);
+            match expected {
+                Some(expected) => {
+                    let bias = bias.unwrap();
+                    assert!((bias - expected).abs() < 1.0E-12, "{} bias @ {}", g20, t);
+                },
+                None => assert!(
+                    bias.is_none(),
+                    "{} ephemeris should have expired @ {}",
+                    g20,
+                    t
+                ),
+            }
+        }
+
+        // C05: toc = 00:00:00 BDT, evaluated in GPST
+        let t = Epoch::from_str("2021-01-01T00:30:14 GPST").unwrap();
+        let bias = rinex.sv_clock_bias(c05, t, false).unwrap();
+        let expected = -4.263372393325e-04 - 7.525180478751e-11 * 1800.0;
+        assert!((bias - expected).abs() < 1.0E-12, "{} bias @ {}", c05, t);
+
+        // RTKLIB reference values (eph2clk, eph2pos): G20 @ 16:15:00 GPST
+        let t = Epoch::from_str("2021-01-01T16:15:00 GPST").unwrap();
+        let bias = rinex.sv_clock_bias(g20, t, false).unwrap();
+        assert!((bias - 5.253580306999e-04).abs() < 1.0E-9);
+        let corrected = rinex.sv_clock_bias(g20, t, true).unwrap();
+        assert!((corrected - 5.253498268449e-04).abs() < 1.0E-9);
+
+        // relativistic correction: -2·sqrt(mu·A)·e·sin(E)/c², E being the eccentric anomaly
+        let relativistic = corrected - bias;
+        assert!(
+            (relativistic + 8.203854935e-09).abs() < 1.0E-11,
+            "relativistic correction: {}",
+            relativistic
+        );
+
+        let biases = rinex.sv_clock_biases(t, false);
+        assert_eq!(biases.get(&g20), Some(&bias));
+        assert!(
+            biases.get(&c05).is_none(),
+            "{} ephemeris should have expired",
+            c05
+        );
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn v3_sv_clock_correction() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let g20 = sv!("G20");
+        let t = Epoch::from_str("2021-01-01T16:15:00 GPST").unwrap();
+
+        // toc = 16:00:00 GPST, toe = 489600 s, dt = tk = 900 s
+        let (_, (_, _, eph)) = rinex
+            .ephemeris()
+            .find(|(_, (_, sv, _))| *sv == g20)
+            .unwrap();
+        assert_eq!(
+            eph.group_delay(Constellation::GPS),
+            Some(-8.381903171539e-09)
+        );
+
+        // hand computed: E solved from M0 + n·tk, dtr = F·e·sqrt(A)·sin(E)
+        let bias = 5.253581330180e-04 - 1.136868377216e-13 * 900.0;
+        let dtr = -8.203854935163e-09;
+        let expected = bias + dtr + 8.381903171539e-09;
+        let correction = rinex.sv_clock_correction(g20, t).unwrap();
+        assert!((correction - expected).abs() < 1.0E-12, "{} @ {}", g20, t);
+
+        // expired ephemeris
+        let t = Epoch::from_str("2021-01-01T18:30:00 GPST").unwrap();
+        assert!(rinex.sv_clock_correction(g20, t).is_none());
+
+        // Glonass: -TauN + GammaN·dt
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let r19 = sv!("R19");
+        let (toc, _) = rinex
+            .ephemeris()
+            .find(|(_, (_, sv, _))| *sv == r19)
+            .unwrap();
+        let t = *toc + Duration::from_seconds(600.0);
+        let expected = -0.126023776829e-03 + -0.909494701773e-12 * 600.0;
+        let correction = rinex.sv_clock_correction(r19, t).unwrap();
+        assert!((correction - expected).abs() < 1.0E-15, "{} @ {}", r19, t);
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn v3_cbw100nld_sv_visibility() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/CBW100NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        let ground = GroundPosition::from_ecef_wgs84((3582105.291, 532589.7313, 5232754.8054));
+        let sampling = Duration::from_seconds(60.0);
+        let span = rinex.last_epoch().unwrap() - rinex.first_epoch().unwrap();
+        assert_eq!(span, Duration::from_hours(16.0));
+
+        // no elevation mask: visibility only limited by ephemeris validity
+        let visibility = rinex.sv_visibility(ground, -90.0, sampling);
+        let passes = rinex.sv_passes(ground, -90.0, sampling);
+        // G19 ephemeris covers the whole record:
+        // last sample accounts for one sampling period
+        assert_eq!(visibility.get(&sv!("G19")), Some(&(span + sampling)));
+        assert_eq!(passes.get(&sv!("G19")).map(|p| p.len()), Some(1));
+        // E01 ephemeris is valid for 3 hours
+        assert_eq!(
+            visibility.get(&sv!("E01")),
+            Some(&Duration::from_hours(3.0))
+        );
+
+        // elevation mask
+        let masked = rinex.sv_visibility(ground, 10.0, sampling);
+        let masked_passes = rinex.sv_passes(ground, 10.0, sampling);
+        assert!(!masked.is_empty(), "some vehicles should be visible");
+        for (sv, duration) in masked.iter() {
+            assert!(
+                *duration <= span + sampling,
+                "{}: bounded by the record span",
+                sv
+            );
+            assert!(
+                *duration <= visibility[sv],
+                "{}: mask should reduce visibility",
+                sv
+            );
+            let passes = &masked_passes[sv];
+            assert!(!passes.is_empty());
+            let total = passes.iter().fold(Duration::ZERO, |total, (start, end)| {
+                total + (*end - *start) + sampling
+            });
+            assert_eq!(total, *duration);
+            for ((_, end), (start, _)) in passes.iter().tuple_windows() {
+                assert!(start > end, "{}: passes should be distinct", sv);
+            }
+        }
+        // nothing is visible above the zenith
+        assert!(rinex.sv_visibility(ground, 90.1, sampling).is_empty());
+    }
+    #[test]
     fn v3_amel00nld_r_2021() {
         let test_resource = env!("CARGO_MANIFEST_DIR").to_owned()
             + "/../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx";
@@ -1776,6 +1967,141 @@ mod test {
         }
     }
     // Computes TOE in said timescale
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn v2_cbw10010_21n_formatting() {
+        use crate::reader::BufferedReader;
+        use std::io::BufRead;
+        /*
+         * Returns all 8 line ephemeris frames contained in given file, sorted.
+         */
+        fn frames(path: &str) -> Vec<String> {
+            let reader = BufferedReader::new(path).unwrap();
+            let lines: Vec<String> = reader
+                .lines()
+                .map(|l| l.unwrap())
+                .skip_while(|l| !l.contains("END OF HEADER"))
+                .skip(1)
+                .collect();
+            lines
+                .chunks(8)
+                .map(|chunk| chunk.join("\n"))
+                .sorted()
+                .collect()
+        }
+        let path =
+            env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/NAV/V2/cbw10010.21n.gz";
+        let rinex = Rinex::from_file(&path).unwrap();
+
+        let tmp_path = "cbw10010-formatting.21n";
+        assert!(rinex.to_file(tmp_path).is_ok(), "failed to generate NAV V2");
+
+        let expected = frames(&path);
+        let generated = frames(tmp_path);
+        assert_eq!(generated.len(), expected.len(), "wrong number of frames");
+        for (generated, expected) in generated.iter().zip(expected.iter()) {
+            assert_eq!(generated, expected, "NAV V2 formatting mismatch");
+        }
+
+        // parse back
+        let parsed = Rinex::from_file(tmp_path);
+        assert!(parsed.is_ok(), "failed to parse back generated NAV V2");
+        assert_eq!(parsed.unwrap().record, rinex.record, "NAV V2 reciprocity");
+
+        let _ = std::fs::remove_file(tmp_path);
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn v2_v4_ionosphere_model() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V2/cbw10010.21n.gz").unwrap();
+        let model = rinex.ionosphere_model();
+        assert!(model.is_some(), "failed to retrieve V2 ionosphere model");
+        let kb = model.unwrap();
+        let kb = kb.as_klobuchar();
+        assert!(kb.is_some(), "V2 only provides Klobuchar models");
+        let kb = kb.unwrap();
+        assert!((kb.alpha.0 - 0.7451E-08).abs() < 1.0E-12);
+        assert!((kb.beta.0 - 0.9011E+05).abs() < 1.0E-6);
+        assert_eq!(
+            rinex.nav_ion_frames().count(),
+            0,
+            "V2 does not contain ION frames"
+        );
+
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+                .unwrap();
+        assert!(
+            rinex.nav_ion_frames().count() > 0,
+            "failed to identify V4 ION frames"
+        );
+        let (_, (_, _, first)) = rinex.nav_ion_frames().next().unwrap();
+        assert_eq!(rinex.ionosphere_model(), Some(*first));
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn v3_esbc00dnk_sv_health() {
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // E14 and E18 are declared unhealthy
+        let e14 = SV::from_str("E14").unwrap();
+        let e18 = SV::from_str("E18").unwrap();
+        let toc = Epoch::from_str("2020-06-24T19:50:00 GST").unwrap();
+        let health = rinex.health(e14, toc).expect("missing E14 health");
+        assert!(matches!(health, SvHealth::Galileo(_)));
+        assert!(!health.is_healthy());
+
+        for (toc, (_, sv, _)) in rinex.ephemeris() {
+            let health = rinex.health(sv, *toc);
+            match sv.constellation {
+                Constellation::GPS | Constellation::BeiDou | Constellation::Glonass => {
+                    let health = health.unwrap_or_else(|| panic!("missing {} health", sv));
+                    assert!(health.is_healthy(), "{} should be healthy", sv);
+                },
+                Constellation::Galileo => {
+                    let health = health.unwrap_or_else(|| panic!("missing {} health", sv));
+                    assert_eq!(health.is_healthy(), sv != e14 && sv != e18);
+                },
+                _ => {},
+            }
+        }
+
+        // default behavior is unchanged
+        let total = rinex.ephemeris().count();
+        let unhealthy = rinex
+            .ephemeris()
+            .filter(|(_, (_, sv, _))| *sv == e14 || *sv == e18)
+            .count();
+        assert_eq!(unhealthy, 80);
+
+        let healthy = rinex.retain_healthy_sv(true);
+        assert_eq!(healthy.ephemeris().count(), total - unhealthy);
+        assert!(healthy
+            .ephemeris()
+            .all(|(_, (_, sv, _))| sv != e14 && sv != e18));
+
+        // SBAS health is unknown
+        let sbas = rinex
+            .ephemeris()
+            .filter(|(_, (_, sv, _))| sv.constellation.is_sbas())
+            .count();
+        assert!(sbas > 0);
+        let strict = rinex.retain_healthy_sv(false);
+        assert!(strict.ephemeris().count() < healthy.ephemeris().count());
+        for (toc, (_, sv, _)) in strict.ephemeris() {
+            if let Some(health) = strict.health(sv, *toc) {
+                assert!(health.is_healthy(), "{} should have been dropped", sv);
+            }
+        }
+
+        let mut rinex = rinex.clone();
+        rinex.retain_healthy_sv_mut(true);
+        assert_eq!(rinex, healthy);
+    }
     fn toe_helper(week: f64, week_s: f64, ts: TimeScale) -> Epoch {
         if ts == TimeScale::GST {
             Epoch::from_duration((week - 1024.0) * Unit::Week + week_s * Unit::Second, ts)
@@ -1783,4 +2109,94 @@ mod test {
             Epoch::from_duration(week * Unit::Week + week_s * Unit::Second, ts)
         }
     }
+    #[test]
+    fn v3_amel00nld_r_2021_no_observables() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        assert!(rinex.observables_per_constellation().is_empty());
+        assert!(rinex.observables().is_empty());
+    }
+    #[test]
+    fn v3_amel00nld_r_2021_constellations() {
+        let rinex = Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+            .unwrap();
+        assert_eq!(rinex.header.constellation, Some(Constellation::Mixed));
+
+        let mut expected = vec![
+            Constellation::Galileo,
+            Constellation::BeiDou,
+            Constellation::Glonass,
+        ];
+        expected.sort();
+        assert_eq!(rinex.constellations(), expected);
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn v3_esbc00dnk_ephemeris_selection() {
+        let mut rinex =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+        let g06 = sv!("G06");
+
+        // G06 frames: 05:59:44, 06:00:00, 07:59:44, 10:00:00, 18:00:00..
+        let t = Epoch::from_str("2020-06-25T06:30:00 GPST").unwrap();
+        let (toe, eph) = rinex.ephemeris_valid_at(g06, t).unwrap();
+        assert_eq!(toe, eph.toe_gpst(TimeScale::GPST).unwrap());
+        assert_eq!(toe, Epoch::from_str("2020-06-25T06:00:00 GPST").unwrap());
+        // beyond validity period, on both sides
+        let t = Epoch::from_str("2020-06-25T14:00:00 GPST").unwrap();
+        assert!(rinex.ephemeris_valid_at(g06, t).is_none());
+
+        // synthesize unhealthy frames
+        let unhealthy = [
+            Epoch::from_str("2020-06-25T05:59:44 GPST").unwrap(),
+            Epoch::from_str("2020-06-25T06:00:00 GPST").unwrap(),
+        ];
+        let record = rinex.record.as_mut_nav().unwrap();
+        for toc in unhealthy {
+            for fr in record.get_mut(&toc).unwrap() {
+                if let Some((_, sv, eph)) = fr.as_mut_eph() {
+                    if sv == g06 {
+                        eph.orbits
+                            .insert("health".to_string(), OrbitItem::Health(Health::L1Healthy));
+                    }
+                }
+            }
+        }
+        for toc in unhealthy {
+            assert!(!rinex.health(g06, toc).unwrap().is_healthy());
+        }
+        // next healthy frame, within validity period
+        let t = Epoch::from_str("2020-06-25T06:30:00 GPST").unwrap();
+        let (toe, eph) = rinex.ephemeris_valid_at(g06, t).unwrap();
+        assert_eq!(toe, eph.toe_gpst(TimeScale::GPST).unwrap());
+        assert_eq!(toe, Epoch::from_str("2020-06-25T07:59:44 GPST").unwrap());
+        let t = Epoch::from_str("2020-06-25T05:00:00 GPST").unwrap();
+        assert!(rinex.ephemeris_valid_at(g06, t).is_none());
+
+        // BeiDou GEO ephemeris are valid for 1 hour only
+        for (sv, valid) in [(sv!("C05"), false), (sv!("C11"), true)] {
+            let last_toe = rinex
+                .ephemeris()
+                .filter_map(|(_, (_, svnn, eph))| {
+                    if svnn == sv {
+                        eph.toe_gpst(TimeScale::BDT)
+                    } else {
+                        None
+                    }
+                })
+                .max()
+                .unwrap_or_else(|| panic!("no {} ephemeris", sv));
+            let t = last_toe + Duration::from_seconds(1800.0);
+            assert!(rinex.ephemeris_valid_at(sv, t).is_some(), "{} @ {}", sv, t);
+            let t = last_toe + Duration::from_seconds(5400.0);
+            assert_eq!(
+                rinex.ephemeris_valid_at(sv, t).is_some(),
+                valid,
+                "{} @ {}",
+                sv,
+                t
+            );
+        }
+    }
 }
@@ -5,13 +5,17 @@ mod test {
     use crate::observable;
     use crate::observation::SNR;
     use crate::preprocessing::*;
+    use crate::record::ParsingWarning;
     use crate::tests::toolkit::obsrinex_check_observables;
     use crate::tests::toolkit::test_observation_rinex;
+    use crate::Merge;
+    use crate::Split;
     use crate::{erratic_time_frame, evenly_spaced_time_frame, tests::toolkit::TestTimeFrame};
     use crate::{observation::*, prelude::*};
     use gnss_rs::prelude::SV;
     use gnss_rs::sv;
     use itertools::Itertools;
+    use std::collections::{BTreeMap, HashMap};
     use std::path::Path;
     use std::str::FromStr;
     #[test]
@@ -508,6 +512,12 @@ mod test {
         assert!(rinex.is_ok());
         let rinex = rinex.unwrap();
 
+        // MARKER NUMBER is a DOMES number
+        let domes = rinex.header.domes().unwrap();
+        assert_eq!(domes.area, 126);
+        assert_eq!(domes.site, 21);
+        assert_eq!(domes.sequential, 1);
+
         test_observation_rinex(
             &rinex,
             "3.02",
@@ -1113,6 +1123,7 @@ mod test {
             header.geodetic_marker.is_some(),
             "failed to parse geodetic marker"
         );
+        assert_eq!(header.marker_type(), Some(MarkerType::Geodetic));
         let marker = header.geodetic_marker.unwrap();
         assert_eq!(marker.name, "ESBC00DNK");
         assert_eq!(marker.number(), Some("10118M001".to_string()));
@@ -1462,4 +1473,2071 @@ mod test {
             test_combinations(combinations, signals);
         }
     */
+    #[test]
+    fn v3_duth0630_space_vehicule_filter() {
+        let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert!(rnx.constellations().len() > 1);
+        let (g01, g02, g03) = (sv!("G01"), sv!("G02"), sv!("G03"));
+
+        // G01 loses track at 2nd epoch
+        let t1 = rnx.epoch().nth(1).unwrap();
+        let record = rnx.record.as_mut_obs().unwrap();
+        for ((t, _), (_, vehicles)) in record.iter_mut() {
+            if *t == t1 {
+                vehicles.remove(&g01);
+            }
+        }
+
+        let filtered = rnx.space_vehicule_filter(vec![g01, g02]);
+        assert_eq!(filtered.space_vehicules(), vec![g01]);
+        assert_eq!(filtered.constellations(), vec![Constellation::GPS]);
+        assert_eq!(
+            filtered.epoch().count(),
+            2,
+            "emptied epoch should be dropped"
+        );
+        assert!(filtered.epoch().all(|t| t != t1));
+
+        // composes with constellation filter
+        let filtered = rnx
+            .constellation_filter(vec![Constellation::GPS])
+            .space_vehicule_filter(vec![g03, sv!("R01"), sv!("R02")]);
+        assert_eq!(filtered.space_vehicules(), vec![g03]);
+        assert_eq!(filtered.epoch().count(), 3);
+    }
+    #[test]
+    fn v3_duth0630_observation_views() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let t0 = Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+        let t2 = Epoch::from_str("2022-03-04T00:57:00 GPST").unwrap();
+
+        let (_, vehicles) = rnx.observation_at(t0).unwrap();
+        assert_eq!(vehicles.len(), 18);
+        assert!(rnx
+            .observation_at(Epoch::from_str("2022-03-04T00:00:01 GPST").unwrap())
+            .is_none());
+
+        let (t, _) = rnx
+            .observation_nearest(Epoch::from_str("2022-03-04T00:50:00 GPST").unwrap())
+            .unwrap();
+        assert_eq!(t, t2);
+        let (t, _) = rnx
+            .observation_nearest(Epoch::from_str("2022-03-03T00:00:00 GPST").unwrap())
+            .unwrap();
+        assert_eq!(t, t0);
+
+        let g01 = sv!("G01");
+        let observations = rnx.sv_observations(g01);
+        assert_eq!(
+            observations.keys().copied().collect::<Vec<_>>(),
+            vec![t0, t1, t2]
+        );
+        assert_eq!(observations[&t1].len(), 8);
+
+        let series = rnx.observable_series(g01, &observable!("L2W"));
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[&t0], 82893846.800);
+        assert_eq!(series[&t2], 88667150.382);
+
+        // not observed
+        assert!(rnx.observable_series(g01, &observable!("C2P")).is_empty());
+        assert!(rnx.sv_observations(sv!("E01")).is_empty());
+    }
+    #[test]
+    fn v3_duth0630_data_scaling() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let (g01, r01) = (sv!("G01"), sv!("R01"));
+        let (l1c, s1c) = (observable!("L1C"), observable!("S1C"));
+        assert!(!rnx.observable_series(r01, &l1c).is_empty());
+
+        let scaled = rnx.apply_data_scaling(Constellation::GPS, 10);
+        let obs = scaled.header.obs.as_ref().unwrap();
+        assert_eq!(obs.data_scaling.get(&Constellation::GPS), Some(&10));
+        assert_eq!(obs.scaling_factor(Constellation::GPS, &l1c), Some(10));
+        assert_eq!(obs.scaling_factor(Constellation::Glonass, &l1c), None);
+
+        // GPS observations are scaled, other systems are untouched
+        let series = scaled.observable_series(g01, &l1c);
+        for (t, value) in rnx.observable_series(g01, &l1c) {
+            assert!((series[&t] - value * 10.0).abs() < 1.0E-6);
+        }
+        assert_eq!(
+            scaled.observable_series(r01, &l1c),
+            rnx.observable_series(r01, &l1c)
+        );
+
+        // per observable factors take precedence
+        let mut precise = scaled.clone();
+        precise
+            .header
+            .obs
+            .as_mut()
+            .unwrap()
+            .with_scaling(Constellation::GPS, s1c.clone(), 100);
+        let obs = precise.header.obs.as_ref().unwrap();
+        assert_eq!(obs.scaling_factor(Constellation::GPS, &s1c), Some(100));
+        assert_eq!(obs.scaling_factor(Constellation::GPS, &l1c), Some(10));
+
+        // factors are described in the header
+        let content = scaled.to_string().unwrap();
+        assert!(content.contains("SYS / SCALE FACTOR"));
+        let parsed = Rinex::from_reader(content.as_bytes()).unwrap();
+        assert_eq!(
+            parsed.header.obs.as_ref().unwrap().data_scaling,
+            scaled.header.obs.as_ref().unwrap().data_scaling
+        );
+        let parsed_series = parsed.observable_series(g01, &l1c);
+        for (t, value) in series {
+            assert!((parsed_series[&t] - value).abs() < 1.0E-3);
+        }
+
+        // scaling and unscaling are symmetrical
+        let unscaled = scaled.remove_data_scaling();
+        let obs = unscaled.header.obs.as_ref().unwrap();
+        assert!(obs.data_scaling.is_empty());
+        assert!(obs.scaling.is_empty());
+        let record = unscaled.record.as_obs().unwrap();
+        for (k, (_, vehicles)) in rnx.record.as_obs().unwrap() {
+            let (_, unscaled_vehicles) = record.get(k).unwrap();
+            for (sv, observations) in vehicles {
+                for (observable, data) in observations {
+                    let value = unscaled_vehicles[sv][observable].obs;
+                    assert!((value - data.obs).abs() < 1.0E-6);
+                }
+            }
+        }
+        // a unit factor removes system wide scaling
+        let unscaled = scaled.apply_data_scaling(Constellation::GPS, 1);
+        let obs = unscaled.header.obs.as_ref().unwrap();
+        assert!(obs.data_scaling.is_empty());
+        let series = unscaled.observable_series(g01, &s1c);
+        for (t, value) in rnx.observable_series(g01, &s1c) {
+            assert!((series[&t] - value).abs() < 1.0E-6);
+        }
+    }
+    #[test]
+    fn v3_acor00esp_tighten_constellation() {
+        let rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        assert_eq!(rnx.header.constellation, Some(Constellation::Mixed));
+        // nothing to tighten
+        assert_eq!(rnx.tighten_constellation(), rnx);
+
+        let galileo = rnx.constellation_filter(vec![Constellation::Galileo]);
+        assert_eq!(galileo.constellations(), vec![Constellation::Galileo]);
+        assert_eq!(galileo.header.constellation, Some(Constellation::Galileo));
+        assert_eq!(galileo.first_epoch(), rnx.first_epoch());
+
+        // single constellation file is parsed back identically
+        let tmp_path = "acor00esp-galileo.rnx";
+        galileo.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert_eq!(parsed.header.constellation, Some(Constellation::Galileo));
+        assert_eq!(parsed.first_epoch(), galileo.first_epoch());
+        assert_eq!(parsed.epoch().count(), galileo.epoch().count());
+
+        // header and record are expressed in the constellation timescale
+        let glonass = rnx.constellation_filter(vec![Constellation::Glonass]);
+        let ts = Constellation::Glonass.timescale().unwrap();
+        let obs = glonass.header.obs.as_ref().unwrap();
+        let time_of_first_obs = obs.time_of_first_obs.unwrap();
+        assert_eq!(time_of_first_obs.time_scale, ts);
+        assert_eq!(
+            time_of_first_obs,
+            rnx.header.obs.as_ref().unwrap().time_of_first_obs.unwrap()
+        );
+        let time_of_last_obs = obs.time_of_last_obs.unwrap();
+        assert_eq!(time_of_last_obs.time_scale, ts);
+        assert_eq!(
+            time_of_last_obs,
+            rnx.header.obs.as_ref().unwrap().time_of_last_obs.unwrap()
+        );
+        assert!(glonass.epoch().all(|t| t.time_scale == ts));
+        assert!(glonass.comments.keys().all(|t| t.time_scale == ts));
+    }
+    #[test]
+    fn v2_aopr0010_17o_accessors() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+
+        assert_eq!(rnx.constellations(), vec![Constellation::GPS]);
+
+        let vehicles = rnx.space_vehicules();
+        assert_eq!(vehicles, rnx.sv().sorted().collect::<Vec<_>>());
+        assert_eq!(vehicles.len(), 19);
+        assert_eq!(vehicles[0], sv!("G01"));
+        assert_eq!(vehicles[18], sv!("G32"));
+
+        let expected: Vec<Observable> = ["C1", "L1", "L2", "P1", "P2"]
+            .iter()
+            .map(|code| Observable::from_str(code).unwrap())
+            .sorted()
+            .collect();
+        assert_eq!(rnx.observables(), expected);
+    }
+    #[test]
+    fn common_epochs_and_vehicles() {
+        let aopr = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+        let npaz = Rinex::from_file("../test_resources/OBS/V2/npaz3550.21o").unwrap();
+
+        // overlapping: identical files
+        let epochs = aopr.common_epochs(&aopr);
+        assert_eq!(epochs, aopr.epoch().unique().collect::<Vec<_>>());
+        assert_eq!(aopr.common_space_vehicules(&aopr), aopr.space_vehicules());
+
+        // disjoint: different days
+        assert!(aopr.common_epochs(&npaz).is_empty());
+        assert!(npaz.common_epochs(&aopr).is_empty());
+
+        // common vehicles are present in both files
+        for sv in aopr.common_space_vehicules(&npaz) {
+            assert!(aopr.space_vehicules().contains(&sv));
+            assert!(npaz.space_vehicules().contains(&sv));
+        }
+
+        // not applicable to Meteo RINEX
+        let meteo = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m").unwrap();
+        assert!(aopr.common_space_vehicules(&meteo).is_empty());
+    }
+    #[test]
+    fn intersection_with_jitter() {
+        let aopr = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+        let epochs: Vec<Epoch> = aopr.epoch().unique().collect();
+
+        // same content, timestamps offset by 20 ms
+        let mut shifted = aopr.clone();
+        let record = shifted.record.as_mut_obs().unwrap();
+        *record = std::mem::take(record)
+            .into_iter()
+            .map(|((t, flag), content)| ((t + Duration::from_milliseconds(20.0), flag), content))
+            .collect();
+
+        assert_eq!(aopr.common_epochs(&shifted), epochs);
+        assert_eq!(shifted.common_epochs(&aopr).len(), epochs.len());
+        assert!(aopr.agrees_with(&shifted));
+        assert!(aopr
+            .common_epochs_within(&shifted, Duration::from_milliseconds(10.0))
+            .is_empty());
+
+        // differencing is not impacted by the jitter
+        assert_eq!(aopr.substract(&shifted).epoch().count(), epochs.len());
+
+        // drop one epoch and one vehicle from the reference
+        let g27 = SV::from_str("G27").unwrap();
+        let record = shifted.record.as_mut_obs().unwrap();
+        let first = *record.keys().next().unwrap();
+        record.remove(&first);
+        for (_, (_, vehicles)) in record.iter_mut() {
+            vehicles.remove(&g27);
+        }
+        let intersection = aopr.intersect(&shifted);
+        assert_eq!(intersection.epoch().count(), epochs.len() - 1);
+        assert!(!intersection.space_vehicules().contains(&g27));
+        assert!(!intersection.agrees_with(&aopr));
+        assert!(intersection.agrees_with(&shifted));
+    }
+    #[test]
+    fn v2_zegv0010_prn_obs_counts() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/zegv0010.21o").unwrap();
+        let obs = rnx.header.obs.as_ref().unwrap();
+        assert_eq!(obs.nb_satellites, Some(54));
+        assert_eq!(obs.prn_obs_counts.len(), 54);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let counts = obs.prn_obs_counts.get(&g01).unwrap();
+        assert_eq!(counts.len(), 11);
+        assert_eq!(counts.get("C1"), Some(&1020));
+        assert_eq!(counts.get("S1"), Some(&1020));
+        // continuation line
+        assert_eq!(counts.get("S2"), Some(&984));
+        assert_eq!(counts.get("S5"), Some(&1036));
+        // blank fields
+        let counts = obs.prn_obs_counts.get(&g02).unwrap();
+        assert_eq!(counts.len(), 8);
+        assert!(counts.get("C5").is_none());
+        assert_eq!(counts.get("S2"), Some(&1161));
+
+        // round trip
+        let path = format!("zegv-{}.21o", crate::tests::toolkit::random_name(5));
+        rnx.to_file(&path).unwrap();
+        let parsed = Rinex::from_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let parsed_obs = parsed.header.obs.as_ref().unwrap();
+        assert_eq!(parsed_obs.nb_satellites, obs.nb_satellites);
+        assert_eq!(parsed_obs.prn_obs_counts, obs.prn_obs_counts);
+
+        // recomputation, after filtering
+        let g07 = SV::from_str("G07").unwrap();
+        let g07_count = |rnx: &Rinex, code: &str| -> u32 {
+            rnx.record
+                .as_obs()
+                .unwrap()
+                .values()
+                .filter_map(|(_, vehicles)| vehicles.get(&g07))
+                .filter(|observations| observations.keys().any(|k| k.to_string() == code))
+                .count() as u32
+        };
+        let mut filtered = rnx.clone();
+        filtered
+            .record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, _), _| *t < Epoch::from_str("2021-01-01T00:05:00 GPST").unwrap());
+        filtered.rebuild_obs_counts_mut();
+        let obs = filtered.header.obs.as_ref().unwrap();
+        assert_eq!(
+            obs.nb_satellites,
+            Some(filtered.space_vehicules().len() as u16)
+        );
+        assert!(obs.prn_obs_counts.get(&g01).is_none());
+        let counts = obs.prn_obs_counts.get(&g07).unwrap();
+        assert_eq!(counts.get("C1"), Some(&g07_count(&filtered, "C1")));
+        assert_eq!(counts.get("L2"), Some(&g07_count(&filtered, "L2")));
+        assert!(*counts.get("C1").unwrap() <= 10);
+    }
+    #[test]
+    fn v2_npaz3550_lli_partition() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/npaz3550.21o").unwrap();
+        let count = |rinex: &Rinex| -> usize {
+            rinex
+                .record
+                .as_obs()
+                .unwrap()
+                .iter()
+                .map(|(_, (_, vehicles))| vehicles.values().map(|obs| obs.len()).sum::<usize>())
+                .sum()
+        };
+
+        let epoch = (
+            Epoch::from_str("2021-12-21T00:00:00 GPST").unwrap(),
+            EpochFlag::Ok,
+        );
+        let g08 = SV::from_str("G08").unwrap();
+        let c1 = Observable::from_str("C1").unwrap();
+        let l1 = Observable::from_str("L1").unwrap();
+        let l2 = Observable::from_str("L2").unwrap();
+
+        let (spoofed, others) = rnx.partition_by_lli(LliFlags::UNDER_ANTI_SPOOFING);
+        assert!(count(&spoofed) > 0);
+        assert!(count(&others) > 0);
+        assert_eq!(count(&spoofed) + count(&others), count(&rnx));
+
+        // G08 L2 is flagged, L1 and C1 are not: epoch is preserved in both sets
+        let (_, vehicles) = spoofed.record.as_obs().unwrap().get(&epoch).unwrap();
+        let observations = vehicles.get(&g08).unwrap();
+        assert!(observations.contains_key(&l2));
+        assert!(!observations.contains_key(&l1));
+        assert!(!observations.contains_key(&c1));
+        let (_, vehicles) = others.record.as_obs().unwrap().get(&epoch).unwrap();
+        let observations = vehicles.get(&g08).unwrap();
+        assert!(!observations.contains_key(&l2));
+        assert!(observations.contains_key(&l1));
+        assert!(observations.contains_key(&c1));
+
+        for (_, (_, vehicles)) in spoofed.record.as_obs().unwrap() {
+            for (_, observations) in vehicles {
+                for (_, data) in observations {
+                    assert!(data.lli.unwrap().intersects(LliFlags::UNDER_ANTI_SPOOFING));
+                }
+            }
+        }
+
+        // empty mask: observations with no LLI bit set
+        let (clean, flagged) = rnx.partition_by_lli(LliFlags::OK_OR_UNKNOWN);
+        assert_eq!(count(&clean) + count(&flagged), count(&rnx));
+        for (_, (_, vehicles)) in clean.record.as_obs().unwrap() {
+            for (_, observations) in vehicles {
+                for (_, data) in observations {
+                    assert!(data.lli.unwrap_or(LliFlags::OK_OR_UNKNOWN).is_empty());
+                }
+            }
+        }
+        let (_, vehicles) = clean.record.as_obs().unwrap().get(&epoch).unwrap();
+        let observations = vehicles.get(&g08).unwrap();
+        assert!(observations.contains_key(&l1));
+        assert!(observations.contains_key(&c1));
+        assert!(!observations.contains_key(&l2));
+    }
+    #[test]
+    fn v2_ajac3550_sig_strength() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+
+        let (min, max) = rnx.sig_strength_range().unwrap();
+        assert_eq!(min, 32.95);
+        assert_eq!(max, 55.2);
+
+        let per_sv = rnx.sig_strength_per_sv();
+        assert_eq!(per_sv.len(), 26);
+        // (min, mean, max) over the S1, S2, S5, S7 and S8 observations of both epochs
+        for (sv, expected) in [
+            (sv!("G07"), (32.95, 35.2, 37.35)),
+            (sv!("G10"), (53.6, 54.2, 55.2)),
+            (sv!("R19"), (33.2, 34.725, 36.1)),
+            (sv!("E04"), (40.5, 41.64375, 43.55)),
+            (sv!("S36"), (48.95, 49.025, 49.1)),
+        ] {
+            let (sv_min, sv_mean, sv_max) = per_sv[&sv];
+            assert_eq!(sv_min, expected.0, "bad min for {}", sv);
+            assert!((sv_mean - expected.1).abs() < 1.0E-9, "bad mean for {}", sv);
+            assert_eq!(sv_max, expected.2, "bad max for {}", sv);
+        }
+        for (sv_min, _, sv_max) in per_sv.values() {
+            assert!(*sv_min >= min && *sv_max <= max);
+        }
+
+        assert_eq!(Ssi::from_str("5"), Ok(Ssi::DbHz30_35));
+        assert!(Ssi::DbHz30_35 > Ssi::DbHz24_29);
+
+        let filtered = rnx.minimum_sig_strength_filter(Ssi::DbHz30_35, true);
+        for (_, _, _, ssi) in filtered.snr() {
+            assert!(ssi >= Ssi::DbHz30_35);
+        }
+
+        let filtered = rnx.minimum_sig_strength_filter(Ssi::DbHz30_35, false);
+        for (_, (_, vehicles)) in filtered.observation() {
+            for (_, observations) in vehicles {
+                for (_, data) in observations {
+                    assert!(data.snr.is_some(), "unknown SSI should have been dropped");
+                }
+            }
+        }
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn v3_esbc00dnk_spp_multi_gnss() {
+        let mut obs =
+            Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .unwrap();
+        let nav =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // mixed GPS + GAL context
+        for (_, (_, vehicles)) in obs.record.as_mut_obs().unwrap().iter_mut() {
+            vehicles.retain(|sv, _| {
+                sv.constellation == Constellation::GPS || sv.constellation == Constellation::Galileo
+            });
+        }
+
+        let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let solution = obs.single_point_position_multi(&nav, t);
+        assert!(solution.is_some(), "failed to resolve SPP @ {}", t);
+
+        let solution = solution.unwrap();
+        assert_eq!(
+            solution.clock_biases.keys().collect::<Vec<_>>(),
+            vec![&Constellation::GPS, &Constellation::Galileo],
+            "expecting one clock state per constellation"
+        );
+
+        let (x, y, z) = solution.position;
+        let (x0, y0, z0) = obs.header.ground_position.unwrap().to_ecef_wgs84();
+        let error = ((x - x0).powi(2) + (y - y0).powi(2) + (z - z0).powi(2)).sqrt();
+        assert!(
+            error < 100.0,
+            "implausible SPP position: {:?} (error: {} m)",
+            solution.position,
+            error
+        );
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn v3_esbc00dnk_spp_code_selection() {
+        let mut obs =
+            Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .unwrap();
+        let nav =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // GPS L1 (C1C, C1W) and L2 (C2L, C2W) are tracked with several codes
+        let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        obs.record.as_mut_obs().unwrap().retain(|(e, _), _| *e == t);
+        for (_, (_, vehicles)) in obs.record.as_mut_obs().unwrap().iter_mut() {
+            vehicles.retain(|sv, _| {
+                sv.constellation == Constellation::GPS || sv.constellation == Constellation::Galileo
+            });
+        }
+        let solution = obs.single_point_position_multi(&nav, t).unwrap();
+
+        // solution does not depend on the storage order
+        for _ in 0..4 {
+            let mut shuffled = obs.clone();
+            for (_, (_, vehicles)) in shuffled.record.as_mut_obs().unwrap().iter_mut() {
+                for (_, observations) in vehicles.iter_mut() {
+                    let mut drained = observations.drain().collect::<Vec<_>>();
+                    drained.reverse();
+                    *observations = drained.into_iter().collect();
+                }
+            }
+            assert_eq!(
+                shuffled.single_point_position_multi(&nav, t).unwrap(),
+                solution
+            );
+        }
+
+        // preferred codes: C1C and C2W
+        let retain_codes = |codes: [&str; 2]| {
+            let mut rinex = obs.clone();
+            for (_, (_, vehicles)) in rinex.record.as_mut_obs().unwrap().iter_mut() {
+                for (sv, observations) in vehicles.iter_mut() {
+                    if sv.constellation == Constellation::GPS {
+                        observations.retain(|observable, _| {
+                            !observable.is_pseudorange_observable()
+                                || codes.contains(&observable.to_string().as_str())
+                        });
+                    }
+                }
+            }
+            rinex
+        };
+        let preferred = retain_codes(["C1C", "C2W"]);
+        assert_eq!(
+            preferred.single_point_position_multi(&nav, t).unwrap(),
+            solution
+        );
+        let others = retain_codes(["C1W", "C2L"]);
+        assert_ne!(
+            others.single_point_position_multi(&nav, t).unwrap(),
+            solution
+        );
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn v3_esbc00dnk_sat_angle_rates() {
+        let mut obs =
+            Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .unwrap();
+        let nav =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // GPS, first hour
+        let t_end = Epoch::from_str("2020-06-25T01:00:00 GPST").unwrap();
+        let record = obs.record.as_mut_obs().unwrap();
+        record.retain(|(t, _), _| *t < t_end);
+        for (_, (_, vehicles)) in record.iter_mut() {
+            vehicles.retain(|sv, _| sv.constellation == Constellation::GPS);
+        }
+
+        let ground = obs.header.ground_position.unwrap().to_ecef_wgs84();
+        let rates = obs.sat_angle_rates(&nav, ground);
+        assert!(!rates.is_empty(), "failed to compute angle rates");
+
+        let mut rising = 0;
+        for (sv, rates) in &rates {
+            assert!(!rates.is_empty(), "no rates computed for {}", sv);
+            for (t, (elev_rate, _)) in rates {
+                // GPS orbital period is about 12h: angular rates remain small
+                assert!(
+                    elev_rate.abs() < 0.02,
+                    "implausible elevation rate {} for {} @ {}",
+                    elev_rate,
+                    sv,
+                    t
+                );
+            }
+            // a rising vehicle has a positive elevation rate
+            if rates.values().all(|(elev_rate, _)| *elev_rate > 0.0) {
+                rising += 1;
+            }
+        }
+        assert!(rising > 0, "no rising vehicle identified");
+    }
+    #[test]
+    fn v3_duth0630_flags_cleanup() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_flags.22O").unwrap();
+        assert_eq!(rnx.epoch().count(), 3);
+        assert_eq!(rnx.epoch_ok().count(), 2);
+
+        let anomalies: Vec<_> = rnx.epoch_anomalies().collect();
+        assert_eq!(
+            anomalies,
+            vec![(
+                Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap(),
+                EpochFlag::PowerFailure
+            )]
+        );
+
+        let cleaned = rnx.cleanup();
+        assert_eq!(cleaned.epoch().count(), 2);
+        assert_eq!(cleaned.epoch_anomalies().count(), 0);
+        assert!(cleaned.epoch_flag().all(|(_, flag)| flag.is_ok()));
+
+        let mut rnx = rnx.clone();
+        rnx.cleanup_mut();
+        assert_eq!(rnx, cleaned);
+    }
+    #[test]
+    fn v3_duth0630_flags_epoch_filters() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_flags.22O").unwrap();
+        let epochs: Vec<_> = rnx.epoch_flag().collect();
+
+        let ok = rnx.epoch_ok_filter(false).unwrap();
+        let nok = rnx.epoch_nok_filter().unwrap();
+        assert_eq!(ok, rnx.cleanup());
+        assert!(ok.epoch_flag().all(|(_, flag)| flag.is_ok()));
+        assert!(nok.epoch_flag().all(|(_, flag)| !flag.is_ok()));
+
+        // ok and nok filters partition the original epochs
+        let mut partition: Vec<_> = ok.epoch_flag().chain(nok.epoch_flag()).collect();
+        partition.sort();
+        assert_eq!(partition, epochs);
+        assert_eq!(
+            nok.epoch_flag().collect::<Vec<_>>(),
+            rnx.epoch_anomalies().collect::<Vec<_>>()
+        );
+
+        // power failure epochs still carry observations
+        let ok = rnx.epoch_ok_filter(true).unwrap();
+        assert_eq!(ok.epoch_flag().collect::<Vec<_>>(), epochs);
+
+        let mut dut = rnx.clone();
+        dut.epoch_nok_filter_mut().unwrap();
+        assert_eq!(dut, nok);
+        dut.epoch_ok_filter_mut(false).unwrap();
+        assert_eq!(dut.epoch().count(), 0);
+
+        // other RINEX do not have epoch flags
+        let mut nav =
+            Rinex::from_file("../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx")
+                .unwrap();
+        let original = nav.clone();
+        assert!(matches!(
+            nav.epoch_ok_filter_mut(false),
+            Err(crate::Error::NoEpochFlags(RinexType::NavigationData))
+        ));
+        assert!(nav.epoch_nok_filter().is_err());
+        assert_eq!(nav, original);
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn v3_esbc00dnk_match_nav_to_obs() {
+        let mut obs =
+            Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .unwrap();
+        let nav =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // first hour
+        let t_end = Epoch::from_str("2020-06-25T01:00:00 GPST").unwrap();
+        obs.record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, _), _| *t < t_end);
+
+        let frames: Vec<(Epoch, SV)> = nav
+            .ephemeris()
+            .map(|(toc, (_, sv, _))| (*toc, sv))
+            .collect();
+
+        let tolerance = Duration::from_seconds(3600.0);
+        let matches = obs.match_nav_to_obs(&nav, tolerance);
+        assert_eq!(
+            matches.len(),
+            obs.epoch().count(),
+            "all epochs should be paired"
+        );
+
+        for (t, vehicles) in &matches {
+            assert!(!vehicles.is_empty());
+            for (sv, toc) in vehicles {
+                let dt = (*t - *toc).abs();
+                assert!(
+                    dt <= tolerance,
+                    "{} @ {} paired with {} out of tolerance",
+                    sv,
+                    t,
+                    toc
+                );
+                assert!(
+                    frames.contains(&(*toc, *sv)),
+                    "{} @ {}: non existing frame",
+                    sv,
+                    toc
+                );
+                // closest frame was selected
+                let closest = frames
+                    .iter()
+                    .filter(|(_, svnn)| svnn == sv)
+                    .map(|(e, _)| (*t - *e).abs())
+                    .min()
+                    .unwrap();
+                assert_eq!(dt, closest, "{} @ {}: closest frame not selected", sv, t);
+            }
+        }
+
+        // tighter tolerance only reduces pairing
+        let tight = obs.match_nav_to_obs(&nav, Duration::from_seconds(60.0));
+        for (t, vehicles) in &tight {
+            for (sv, toc) in vehicles {
+                assert_eq!(matches[t].get(sv), Some(toc));
+            }
+        }
+    }
+    #[test]
+    fn v3_duth0630_same_timestamp_event() {
+        let mut rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let t1 = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+
+        // external event sharing its timestamp with a data epoch
+        rnx.record
+            .as_mut_obs()
+            .unwrap()
+            .insert((t1, EpochFlag::ExternalEvent), (None, Default::default()));
+        assert_eq!(rnx.record.as_obs().unwrap().len(), 4);
+
+        // timestamp is only reported once
+        assert_eq!(rnx.epoch().count(), 3);
+        assert_eq!(rnx.epoch_flag().count(), 4);
+        assert_eq!(
+            rnx.epoch_anomalies().collect::<Vec<_>>(),
+            vec![(t1, EpochFlag::ExternalEvent)]
+        );
+        assert_eq!(
+            rnx.sampling_histogram().collect::<Vec<_>>(),
+            vec![(Duration::from_seconds(1710.0), 2)],
+            "same timestamp should not introduce null intervals"
+        );
+        assert_eq!(rnx.data_gaps(None).count(), 0);
+
+        // cleanup only drops the event
+        let cleaned = rnx.cleanup();
+        assert_eq!(cleaned.record.as_obs().unwrap().len(), 3);
+        assert!(cleaned.epoch_flag().all(|(_, flag)| flag.is_ok()));
+        assert_eq!(cleaned.epoch().count(), 3);
+
+        // merge does not duplicate entries
+        let merged = rnx.merge(&rnx).unwrap();
+        assert_eq!(merged.record, rnx.record);
+        assert_eq!(merged.epoch().count(), 3);
+        let merged = cleaned.merge(&rnx).unwrap();
+        assert_eq!(merged.record, rnx.record);
+    }
+    #[test]
+    fn v3_duth0630_csv_export() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+
+        let tmp_path = "duth0630.csv";
+        rnx.to_csv(tmp_path, &[l1c.clone(), c1c.clone()]).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 4, "expecting header + 3 epochs");
+
+        // columns: sorted by SV, then by observable
+        let header: Vec<&str> = lines[0].split(',').collect();
+        assert_eq!(
+            header[..5],
+            ["epoch", "G01:C1C", "G01:L1C", "G03:C1C", "G03:L1C"]
+        );
+        assert_eq!(header.len(), 1 + 2 * rnx.sv().count());
+        assert_eq!(*header.last().unwrap(), "R24:L1C");
+
+        let row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(row.len(), header.len());
+        assert_eq!(
+            Epoch::from_str(row[0]).unwrap(),
+            Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap()
+        );
+        assert_eq!(f64::from_str(row[1]).unwrap(), 20243517.560);
+        assert_eq!(f64::from_str(row[2]).unwrap(), 106380411.418);
+
+        // G32 is only observed at the first epoch
+        let g32 = header.iter().position(|col| *col == "G32:C1C").unwrap();
+        assert_eq!(f64::from_str(row[g32]).unwrap(), 24991723.280);
+        let row: Vec<&str> = lines[2].split(',').collect();
+        assert_eq!(row.len(), header.len());
+        assert!(row[g32].is_empty(), "missing observation should be empty");
+
+        // matrix view
+        let (rows, columns, values) = rnx.observation_matrix(&[c1c.clone(), l1c.clone()]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(columns.len(), header.len() - 1);
+        assert!(values[1][g32 - 1].is_nan());
+    }
+    #[test]
+    fn v3_duth0630_sentinel_values() {
+        let content = std::fs::read_to_string("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        // corrupt G01 C1C at the first epoch with a sentinel value
+        let content = content.replacen("  20243517.560", "9.99999999E+99", 1);
+        let tmp_path = "duth0630-sentinel.22O";
+        std::fs::write(tmp_path, content).unwrap();
+
+        let rnx = Rinex::from_file(tmp_path);
+        let limited = Rinex::from_file_with_max_magnitude(tmp_path, 1.0E8);
+        let _ = std::fs::remove_file(tmp_path);
+
+        let rnx = rnx.unwrap();
+        let t0 = Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+
+        let record = rnx.record.as_obs().unwrap();
+        let (_, vehicles) = record.get(&(t0, EpochFlag::Ok)).unwrap();
+        let g01_obs = vehicles.get(&g01).unwrap();
+        assert!(
+            g01_obs.get(&c1c).is_none(),
+            "sentinel value should be dropped"
+        );
+        assert_eq!(g01_obs.get(&l1c).unwrap().obs, 106380411.418);
+        assert_eq!(g01_obs.len(), 7, "neighboring values should remain");
+        let g03_obs = vehicles.get(&g03).unwrap();
+        assert_eq!(g03_obs.get(&c1c).unwrap().obs, 20619020.680);
+
+        // no other value was dropped
+        let nominal = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let count = |rnx: &Rinex| -> usize {
+            rnx.observation()
+                .map(|(_, (_, vehicles))| vehicles.values().map(|obs| obs.len()).sum::<usize>())
+                .sum()
+        };
+        assert_eq!(count(&rnx) + 1, count(&nominal));
+
+        // custom limit: phase observations exceed 1.0E8 cycles
+        let limited = limited.unwrap();
+        let record = limited.record.as_obs().unwrap();
+        let (_, vehicles) = record.get(&(t0, EpochFlag::Ok)).unwrap();
+        let g03_obs = vehicles.get(&g03).unwrap();
+        assert!(g03_obs.get(&l1c).is_none());
+        assert_eq!(g03_obs.get(&c1c).unwrap().obs, 20619020.680);
+    }
+    #[test]
+    #[cfg(feature = "plot")]
+    fn v3_duth0630_plot_snr() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let tmp_path = std::env::temp_dir().join("duth0630-g01-snr.png");
+        rnx.plot_snr(sv!("G01"), tmp_path.to_str().unwrap())
+            .unwrap();
+        let metadata = std::fs::metadata(&tmp_path);
+        let _ = std::fs::remove_file(&tmp_path);
+        let metadata = metadata.expect("png file should have been created");
+        assert!(metadata.len() > 0, "png file should not be empty");
+    }
+    #[test]
+    fn v2_npaz3550_record_comments() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/npaz3550_comments.21o").unwrap();
+        assert!(rnx.is_merged());
+
+        let e0 = Epoch::from_str("2021-12-21T00:00:00 GPST").unwrap();
+        let e1 = Epoch::from_str("2021-12-21T00:00:30 GPST").unwrap();
+        let e2 = Epoch::from_str("2021-12-21T00:01:00 GPST").unwrap();
+
+        // comments are attached to the epoch they precede
+        let mut expected = crate::record::Comments::new();
+        expected.insert(
+            e0,
+            vec![
+                "RECEIVER RESTARTED AT 00:00:00".to_string(),
+                "DATA STREAM RESUMED".to_string(),
+            ],
+        );
+        expected.insert(e1, vec!["ANTENNA CABLE CHECKED".to_string()]);
+        expected.insert(
+            e2,
+            vec![
+                "teqc: SV G16 TRACKING GAP".to_string(),
+                "teqc: SV R06 TRACKING GAP".to_string(),
+            ],
+        );
+        assert_eq!(rnx.comments, expected);
+
+        // comments following the last epoch
+        let trailing = vec![
+            "END OF SESSION".to_string(),
+            "teqc: 3 EPOCHS PROCESSED".to_string(),
+        ];
+        assert_eq!(rnx.trailing_comments, trailing);
+
+        // trailing comments are written after the last epoch
+        let content = rnx.to_string().unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[lines.len() - 2..],
+            [
+                "END OF SESSION                                              COMMENT",
+                "teqc: 3 EPOCHS PROCESSED                                    COMMENT",
+            ]
+        );
+
+        // dump and parse back
+        let tmp_path = std::env::temp_dir().join("npaz3550-comments.21o");
+        let tmp_path = tmp_path.to_string_lossy();
+        assert!(rnx.to_file(&tmp_path).is_ok(), "failed to generate file");
+
+        let parsed = Rinex::from_file(&tmp_path);
+        assert!(parsed.is_ok(), "failed to parse back generated file");
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.comments, expected, "record comments not preserved");
+        assert_eq!(
+            parsed.trailing_comments, trailing,
+            "trailing comments not preserved"
+        );
+        assert!(parsed.is_merged(), "is_merged() should be preserved");
+        assert_eq!(parsed.epoch().count(), 3);
+
+        let _ = std::fs::remove_file(tmp_path.as_ref());
+    }
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_parsing() {
+        let paths = [
+            "../test_resources/OBS/V2/AJAC3550.21O",
+            "../test_resources/OBS/V3/DUTH0630.22O",
+            "../test_resources/CRNX/V3/KMS300DNK_R_20221591000_01H_30S_MO.crx",
+            "../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx",
+        ];
+        let parsed = Rinex::from_files(&paths);
+        assert_eq!(parsed.len(), paths.len());
+        for (path, parsed) in paths.iter().zip(parsed.iter()) {
+            let parsed = parsed.as_ref().unwrap();
+            let rinex = Rinex::from_file(path).unwrap();
+            assert_eq!(parsed.header, rinex.header, "{}", path);
+            assert_eq!(parsed.record, rinex.record, "{}", path);
+            assert_eq!(parsed.comments, rinex.comments, "{}", path);
+            assert!(parsed.epoch().tuple_windows().all(|(a, b)| a < b));
+        }
+    }
+    #[test]
+    fn v3_vlns0010_receiver_clock_drift() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/VLNS0010.22O").unwrap();
+        let offsets = rnx.recvr_clock().count();
+        assert_eq!(offsets, 3);
+        let drift = rnx.receiver_clock_drift();
+        assert_eq!(drift.len(), offsets - 1);
+        assert!(drift.values().all(|drift| *drift == 0.0));
+
+        // synthetic offsets
+        let c1c = Observable::from_str("C1C").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let mut builder =
+            ObservationBuilder::new(crate::version::Version::new(3, 4), Constellation::GPS)
+                .with_codes(Constellation::GPS, &[c1c.clone()]);
+        for (i, offset) in [1.0E-6, 2.5E-6, 2.0E-6].iter().enumerate() {
+            let t = t0 + i as f64 * Duration::from_seconds(30.0);
+            builder.add_epoch(t, Some(*offset));
+            builder.add_observation(t, g01, c1c.clone(), 20243517.560, None, None);
+        }
+        let rnx = builder.build().unwrap();
+        let drift = rnx.receiver_clock_drift();
+        assert_eq!(drift.len(), 2);
+        let t1 = t0 + Duration::from_seconds(30.0);
+        let t2 = t0 + Duration::from_seconds(60.0);
+        assert!((drift[&t1] - 5.0E-8).abs() < 1.0E-15);
+        assert!((drift[&t2] + 5.0E-8 / 3.0).abs() < 1.0E-15);
+    }
+    #[test]
+    fn v3_duth0630_antenna_deltas() {
+        let content = std::fs::read_to_string("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let hen =
+            "        0.1830        0.0000        0.0000                  ANTENNA: DELTA H/E/N\n";
+        let xyz =
+            "        1.2500       -0.5000        0.0780                  ANTENNA: DELTA X/Y/Z\n";
+        let tmp_path = "duth0630-delta-xyz.22O";
+        std::fs::write(
+            tmp_path,
+            content.replacen(hen, &format!("{}{}", hen, xyz), 1),
+        )
+        .unwrap();
+        let rinex = Rinex::from_file(tmp_path);
+        let _ = std::fs::remove_file(tmp_path);
+
+        let rinex = rinex.unwrap();
+        let antenna = rinex.header.rcvr_antenna.as_ref().unwrap();
+        assert_eq!(antenna.delta_hen, Some((0.1830, 0.0, 0.0)));
+        assert_eq!(antenna.delta_xyz, Some((1.25, -0.5, 0.078)));
+
+        // both survive a round trip
+        let tmp_path = "duth0630-delta-xyz-copy.22O";
+        rinex.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path);
+        let _ = std::fs::remove_file(tmp_path);
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.header.rcvr_antenna, rinex.header.rcvr_antenna);
+    }
+    #[test]
+    fn v3_duth0630_phase_to_meters() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let meters = rnx.observation_phase_to_meters().unwrap();
+
+        let t0 = Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let record = meters.record.as_obs().unwrap();
+        let (_, vehicles) = record.get(&(t0, EpochFlag::Ok)).unwrap();
+        let observations = vehicles.get(&g01).unwrap();
+        let expected = 106380411.418 * 299_792_458.0 / 1575.42E6;
+        assert!((observations.get(&l1c).unwrap().obs - expected).abs() < 1.0E-6);
+        // pseudo range is left untouched
+        assert_eq!(observations.get(&c1c).unwrap().obs, 20243517.560);
+
+        // unknown phase observable: nothing is converted
+        let mut rnx = rnx.clone();
+        let bogus = Observable::from_str("L9Z").unwrap();
+        if let Some(record) = rnx.record.as_mut_obs() {
+            let (_, vehicles) = record.get_mut(&(t0, EpochFlag::Ok)).unwrap();
+            let observations = vehicles.get_mut(&g01).unwrap();
+            observations.insert(bogus, ObservationData::new(1.0, None, None));
+        }
+        let original = rnx.clone();
+        assert!(rnx.observation_phase_to_meters_mut().is_err());
+        assert_eq!(rnx.record, original.record);
+    }
+    #[test]
+    fn v3_acor00esp_reproducible_production() {
+        let path = "../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx";
+        let mut produced = Vec::new();
+        // each parsing builds a new (randomly seeded) observables map
+        for (i, tmp_path) in ["acor00esp-0.rnx", "acor00esp-1.rnx"].iter().enumerate() {
+            let rinex = Rinex::from_file(path).unwrap();
+            rinex.to_file(tmp_path).unwrap();
+            let content = std::fs::read(tmp_path);
+            let _ = std::fs::remove_file(tmp_path);
+            produced.push(content.unwrap());
+            assert_eq!(produced[i], produced[0], "production is not reproducible");
+        }
+        let content = String::from_utf8(produced[0].clone()).unwrap();
+        let systems: Vec<&str> = content
+            .lines()
+            .filter(|line| line.ends_with("SYS / # / OBS TYPES"))
+            .filter_map(|line| line.get(0..1))
+            .filter(|c| *c != " ")
+            .collect();
+        assert!(systems.len() > 1);
+        assert!(systems.windows(2).all(|w| w[0] < w[1]));
+    }
+    #[test]
+    fn v3_acor00esp_code_multipath_mp12() {
+        let rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let multipath = rnx.code_multipath_mp12();
+        let g01_mp = multipath.get(&g01).unwrap();
+        assert_eq!(g01_mp.len(), 25);
+
+        // C1C, L1C, C2W and L2W are combined; values computed offline
+        for (epoch, expected_mp1, expected_mp2) in [
+            (
+                "2021-12-21T00:00:00 GPST",
+                -0.3980820876359932,
+                -0.2146266555786127,
+            ),
+            (
+                "2021-12-21T00:00:30 GPST",
+                -0.009351084828375988,
+                -0.1760192817449564,
+            ),
+            (
+                "2021-12-21T00:01:00 GPST",
+                0.2791114795207985,
+                0.22909398317337093,
+            ),
+            (
+                "2021-12-21T00:12:00 GPST",
+                -0.18272323429584425,
+                -0.023157090544700054,
+            ),
+        ] {
+            let epoch = Epoch::from_str(epoch).unwrap();
+            let (mp1, mp2) = g01_mp.get(&epoch).unwrap();
+            assert!((mp1.unwrap() - expected_mp1).abs() < 1.0E-6, "{}", epoch);
+            assert!((mp2.unwrap() - expected_mp2).abs() < 1.0E-6, "{}", epoch);
+        }
+        // zero centered
+        let mean = g01_mp.values().filter_map(|(mp1, _)| *mp1).sum::<f64>() / 25.0;
+        assert!(mean.abs() < 1.0E-6);
+
+        let summary = rnx.code_multipath_mp12_summary();
+        let g01_summary = summary.get(&g01).unwrap();
+        assert_eq!(g01_summary.arcs, 1);
+        assert!((g01_summary.mp1_rms.unwrap() - 0.20670782561697199).abs() < 1.0E-6);
+        assert!((g01_summary.mp2_rms.unwrap() - 0.3263801151851691).abs() < 1.0E-6);
+
+        // Glonass: C1C, L1C, C2P and L2P combined on R04 channel (+6) frequencies
+        let r04 = SV::from_str("R04").unwrap();
+        let r04_mp = multipath.get(&r04).unwrap();
+        assert_eq!(r04_mp.len(), 25);
+        for (epoch, expected_mp1, expected_mp2) in [
+            (
+                "2021-12-21T00:00:00 GPST",
+                0.6271904253959655,
+                0.9510437422990803,
+            ),
+            (
+                "2021-12-21T00:06:00 GPST",
+                -0.41706630647182474,
+                -0.99680627644062,
+            ),
+        ] {
+            let epoch = Epoch::from_str(epoch).unwrap();
+            let (mp1, mp2) = r04_mp.get(&epoch).unwrap();
+            assert!((mp1.unwrap() - expected_mp1).abs() < 1.0E-6, "{}", epoch);
+            assert!((mp2.unwrap() - expected_mp2).abs() < 1.0E-6, "{}", epoch);
+        }
+        let r04_summary = summary.get(&r04).unwrap();
+        assert_eq!(r04_summary.arcs, 1);
+        assert!((r04_summary.mp1_rms.unwrap() - 0.5977119030956481).abs() < 1.0E-6);
+        assert!((r04_summary.mp2_rms.unwrap() - 0.4910980786547756).abs() < 1.0E-6);
+    }
+    #[test]
+    fn repair_ms_jumps() {
+        let c1c = Observable::from_str("C1C").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let g01_model = |secs: f64| 20_000_000.0 + 500.0 * secs + 0.1 * secs.powi(2);
+        let g02_model = |secs: f64| 22_000_000.0 - 300.0 * secs;
+
+        // receiver clock steering: +1 ms jump on all vehicles at 5th epoch
+        let mut builder =
+            ObservationBuilder::new(crate::version::Version::new(3, 4), Constellation::GPS)
+                .with_codes(Constellation::GPS, &[c1c.clone()]);
+        for i in 0..10 {
+            let t = t0 + i as f64 * dt;
+            let secs = (t - t0).to_seconds();
+            let jump = if i >= 5 { 299_792.458 } else { 0.0 };
+            builder.add_observation(t, g01, c1c.clone(), g01_model(secs) + jump, None, None);
+            builder.add_observation(t, g02, c1c.clone(), g02_model(secs) + jump, None, None);
+        }
+        let rinex = builder.build().unwrap();
+        let repaired = rinex.repair_ms_jumps();
+
+        let record = repaired.record.as_obs().unwrap();
+        for (i, (_, (_, vehicles))) in record.iter().enumerate() {
+            let secs = i as f64 * 30.0;
+            let g01_pr = vehicles[&g01][&c1c].obs;
+            assert!((g01_pr - g01_model(secs)).abs() < 1.0E-6, "epoch #{}", i);
+            let g02_pr = vehicles[&g02][&c1c].obs;
+            assert!((g02_pr - g02_model(secs)).abs() < 1.0E-6, "epoch #{}", i);
+        }
+        // provenance
+        let comments: Vec<_> = repaired
+            .header
+            .comments
+            .iter()
+            .filter(|c| c.contains("MS JUMP"))
+            .collect();
+        assert_eq!(comments.len(), 1);
+        assert!(comments[0].contains("+1ms 20230101 000230 GPST"));
+
+        // isolated jump on a single vehicle: left untouched
+        let mut builder =
+            ObservationBuilder::new(crate::version::Version::new(3, 4), Constellation::GPS)
+                .with_codes(Constellation::GPS, &[c1c.clone()]);
+        for i in 0..10 {
+            let t = t0 + i as f64 * dt;
+            let secs = (t - t0).to_seconds();
+            let jump = if i >= 5 { 299_792.458 } else { 0.0 };
+            builder.add_observation(t, g01, c1c.clone(), g01_model(secs) + jump, None, None);
+            builder.add_observation(t, g02, c1c.clone(), g02_model(secs), None, None);
+        }
+        let rinex = builder.build().unwrap();
+        let repaired = rinex.repair_ms_jumps();
+        assert_eq!(repaired.record, rinex.record);
+        assert!(!repaired
+            .header
+            .comments
+            .iter()
+            .any(|c| c.contains("MS JUMP")));
+    }
+    #[test]
+    fn rtcm_msm_summary() {
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+        let d1c = Observable::from_str("D1C").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let r01 = SV::from_str("R01").unwrap();
+        let t0 = Epoch::from_str("2023-01-01T00:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2023-01-01T00:00:30 GPST").unwrap();
+
+        let mut builder =
+            ObservationBuilder::new(crate::version::Version::new(3, 4), Constellation::Mixed)
+                .with_codes(Constellation::GPS, &[c1c.clone(), l1c.clone(), d1c.clone()])
+                .with_codes(Constellation::Glonass, &[c1c.clone(), l1c.clone()]);
+        builder.add_observation(t0, g01, c1c.clone(), 20243517.560, None, None);
+        builder.add_observation(t0, g01, l1c.clone(), 106380411.418, None, None);
+        builder.add_observation(t0, g01, d1c.clone(), -1250.5, None, None);
+        builder.add_observation(t0, r01, c1c.clone(), 21243517.560, None, None);
+        builder.add_observation(t0, r01, l1c.clone(), 113380411.418, None, None);
+        // GPS code only, Glonass with C/N0
+        builder.add_observation(t1, g01, c1c.clone(), 20244226.380, None, None);
+        builder.add_observation(
+            t1,
+            r01,
+            c1c.clone(),
+            21244226.380,
+            None,
+            Some(SNR::DbHz42_47),
+        );
+        builder.add_observation(t1, r01, l1c.clone(), 113384135.418, None, None);
+        let rinex = builder.build().unwrap();
+
+        let messages = rinex.rtcm_msm_summary();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[&t0], vec![1075, 1083]);
+        assert_eq!(messages[&t1], vec![1071, 1084]);
+
+        // not applicable to Meteo RINEX
+        let meteo = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m").unwrap();
+        assert!(meteo.rtcm_msm_summary().is_empty());
+    }
+    #[test]
+    fn v3_acor00esp_gf_combination() {
+        let rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+
+        // L1C - L2W: ionosphere only, flat over this short arc
+        let gf = rnx.gf_combination(false);
+        let g01_gf = gf.get(&g01).unwrap();
+        assert_eq!(g01_gf.len(), 25);
+        let first = g01_gf.values().next().unwrap();
+        assert!((first - -3.7909861467778683).abs() < 1.0E-6);
+        let min = g01_gf.values().fold(f64::MAX, |min, v| min.min(*v));
+        let max = g01_gf.values().fold(f64::MIN, |max, v| max.max(*v));
+        assert!(max - min < 0.25, "gf spread: {}", max - min);
+
+        // C2W - C1C: code noise and multipath dominate
+        let gf = rnx.gf_combination(true);
+        let g01_gf = gf.get(&g01).unwrap();
+        assert_eq!(g01_gf.len(), 25);
+        let (t0, first) = g01_gf.iter().next().unwrap();
+        let (_, (_, vehicles)) = rnx
+            .record
+            .as_obs()
+            .unwrap()
+            .iter()
+            .find(|((t, _), _)| t == t0)
+            .unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let c2w = Observable::from_str("C2W").unwrap();
+        let expected = vehicles[&g01][&c2w].obs - vehicles[&g01][&c1c].obs;
+        assert!((first - expected).abs() < 1.0E-9);
+        let min = g01_gf.values().fold(f64::MAX, |min, v| min.min(*v));
+        let max = g01_gf.values().fold(f64::MIN, |max, v| max.max(*v));
+        assert!(max - min < 5.0, "gf spread: {}", max - min);
+
+        // IF, WL and NL are defined on the same epochs
+        assert_eq!(rnx.if_combination(false).get(&g01).unwrap().len(), 25);
+        assert_eq!(rnx.wl_combination(false).get(&g01).unwrap().len(), 25);
+        assert_eq!(rnx.nl_combination(true).get(&g01).unwrap().len(), 25);
+    }
+    #[test]
+    fn v3_acor00esp_level_phase_to_code() {
+        let rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let record = rnx.record.as_obs().unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let c2w = Observable::from_str("C2W").unwrap();
+
+        let leveled = rnx.level_phase_to_code();
+        let g01_leveled = leveled.get(&g01).unwrap();
+        assert_eq!(g01_leveled.len(), 25);
+
+        for (phase, code) in [("L1C", &c1c), ("L2W", &c2w)] {
+            // leveled phase and code have the same mean, on each arc:
+            // so do they on the whole (common) time frame
+            let (mut phase_sum, mut code_sum, mut count) = (0.0, 0.0, 0);
+            for ((epoch, _), (_, vehicles)) in record.iter() {
+                let code = match vehicles.get(&g01).and_then(|obs| obs.get(code)) {
+                    Some(code) => code.obs,
+                    None => continue,
+                };
+                let leveled = g01_leveled
+                    .get(epoch)
+                    .and_then(|phases| phases.get(phase))
+                    .unwrap();
+                assert!(
+                    (leveled - code).abs() < 10.0,
+                    "{} {} leveled too far from code: {} {}",
+                    epoch,
+                    phase,
+                    leveled,
+                    code
+                );
+                phase_sum += leveled;
+                code_sum += code;
+                count += 1;
+            }
+            assert_eq!(count, 25);
+            let mean_err = (phase_sum - code_sum) / count as f64;
+            assert!(
+                mean_err.abs() < 1.0E-6,
+                "{} mean error: {}",
+                phase,
+                mean_err
+            );
+        }
+    }
+    #[test]
+    fn v3_acor00esp_code_carrier_divergence() {
+        let mut rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+
+        // quiet arc: first epoch does not produce a value
+        let divergence = rnx.code_carrier_divergence();
+        let g01_div = divergence.get(&g01).unwrap();
+        assert_eq!(g01_div.len(), 24);
+        for (epoch, rate) in g01_div.iter() {
+            assert!(rate.abs() < 0.1, "{} divergence @ {}: {}", g01, epoch, rate);
+        }
+
+        // inject a 30m disturbance on one epoch
+        let disturbed = *g01_div.keys().nth(10).unwrap();
+        let record = rnx.record.as_mut_obs().unwrap();
+        for ((epoch, _), (_, vehicles)) in record.iter_mut() {
+            if *epoch == disturbed {
+                let data = vehicles
+                    .get_mut(&g01)
+                    .and_then(|obs| obs.get_mut(&c1c))
+                    .unwrap();
+                data.obs += 30.0;
+            }
+        }
+        let divergence = rnx.code_carrier_divergence();
+        let g01_div = divergence.get(&g01).unwrap();
+        for (epoch, rate) in g01_div.iter() {
+            if *epoch == disturbed {
+                assert!(*rate > 0.9, "{} divergence @ {}: {}", g01, epoch, rate);
+            } else if *epoch > disturbed && *epoch - disturbed <= Duration::from_seconds(30.0) {
+                assert!(*rate < -0.9, "{} divergence @ {}: {}", g01, epoch, rate);
+            } else {
+                assert!(rate.abs() < 0.1, "{} divergence @ {}: {}", g01, epoch, rate);
+            }
+        }
+    }
+    #[test]
+    fn v3_acor00esp_retain_top_snr() {
+        let rinex =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let filtered = rinex.retain_top_snr(25.0);
+        let record = rinex.record.as_obs().unwrap();
+        let filtered_record = filtered.record.as_obs().unwrap();
+        assert_eq!(filtered_record.len(), record.len());
+
+        for (key, (_, vehicles)) in record {
+            let snrs: Vec<SNR> = vehicles
+                .values()
+                .flat_map(|observations| observations.values().filter_map(|data| data.snr))
+                .collect();
+            let (_, filtered_vehicles) = filtered_record.get(key).unwrap();
+            let kept: Vec<SNR> = filtered_vehicles
+                .values()
+                .flat_map(|observations| observations.values().filter_map(|data| data.snr))
+                .collect();
+            let fraction = kept.len() as f64 / snrs.len() as f64;
+            assert!(
+                (fraction - 0.25).abs() < 0.01,
+                "{:?}: kept {}",
+                key,
+                fraction
+            );
+            // weakest retained signal is at least as strong as the median
+            let median = snrs.iter().sorted().nth(snrs.len() / 2).unwrap();
+            assert!(kept.iter().all(|snr| snr >= median));
+        }
+    }
+    #[test]
+    fn v3_acor00esp_filter_observables() {
+        let rnx =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+
+        // L1 only: dual band file reduced to *1* codes
+        let l1 = rnx.filter_observables_by_band(&[Carrier::L1]);
+        let codes = &l1.header.obs.as_ref().unwrap().codes;
+        assert!(!codes[&Constellation::GPS].is_empty());
+        for (constellation, codes) in codes.iter() {
+            for code in codes.iter() {
+                assert_eq!(
+                    code.to_string().chars().nth(1),
+                    Some('1'),
+                    "{} {} should have been dropped",
+                    constellation,
+                    code
+                );
+            }
+        }
+        // Glonass FDMA signals are not L1
+        assert!(codes.get(&Constellation::Glonass).is_none());
+
+        let record = l1.record.as_obs().unwrap();
+        assert!(!record.is_empty());
+        for (_, (_, vehicles)) in record.iter() {
+            for (sv, observations) in vehicles.iter() {
+                assert_ne!(sv.constellation, Constellation::Glonass);
+                for observable in observations.keys() {
+                    assert_eq!(observable.to_string().chars().nth(1), Some('1'));
+                }
+            }
+        }
+
+        // phase only
+        let phase = rnx.filter_observables_by_physics(&[ObsPhysics::Phase]);
+        for codes in phase.header.obs.as_ref().unwrap().codes.values() {
+            assert!(!codes.is_empty());
+            assert!(codes.iter().all(|code| code.is_phase_observable()));
+        }
+        for (_, (_, vehicles)) in phase.record.as_obs().unwrap().iter() {
+            for observations in vehicles.values() {
+                assert!(observations.keys().all(|code| code.is_phase_observable()));
+            }
+        }
+
+        // L1 phase
+        let l1_phase = l1.filter_observables_by_physics(&[ObsPhysics::Phase]);
+        let gps = &l1_phase.header.obs.as_ref().unwrap().codes[&Constellation::GPS];
+        assert!(gps.iter().all(|code| code.to_string().starts_with("L1")));
+    }
+    #[test]
+    fn sbas_vehicles() {
+        // SBAS vehicles share the single (gnss-rs) Constellation definition:
+        // the augmentation system is deduced from the PRN (+100),
+        // and vehicles are formatted back to their RINEX identity
+        for desc in ["S20", "S23", "S36"] {
+            let sv = SV::from_str(desc).unwrap();
+            assert!(sv.constellation.is_sbas(), "{} is not SBAS", desc);
+            assert_eq!(format!("{:x}", sv), desc);
+        }
+    }
+    #[test]
+    fn v3_duth0630_convert_version() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+
+        let v2 = rinex.convert_version(Version::new(2, 11)).unwrap();
+        let codes = &v2.header.obs.as_ref().unwrap().codes;
+        let expected = ["C1", "L1", "D1", "S1", "P2", "L2", "D2", "S2"]
+            .iter()
+            .map(|code| Observable::from_str(code).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(codes.get(&Constellation::GPS), Some(&expected));
+        assert_eq!(codes.get(&Constellation::Glonass), Some(&expected));
+        assert_eq!(v2.epoch().count(), rinex.epoch().count());
+
+        // V2 epochs are accepted by our parser
+        v2.to_file("duth0630-v2.22o").unwrap();
+        let parsed = Rinex::from_file("duth0630-v2.22o").unwrap();
+        assert_eq!(parsed.header.version.major, 2);
+        assert_eq!(parsed.epoch().count(), rinex.epoch().count());
+        assert_eq!(parsed.observable().count(), v2.observable().count());
+        let _ = std::fs::remove_file("duth0630-v2.22o");
+
+        // common tracking modes are recovered
+        let v3 = v2.convert_version(Version::new(3, 5)).unwrap();
+        assert_eq!(v3.header.version, Version::new(3, 5));
+        assert_eq!(v3.record, rinex.record);
+
+        // same major: only the revision is modified
+        let v4 = rinex.convert_version(Version::new(4, 0)).unwrap();
+        assert_eq!(v4.header.version, Version::new(4, 0));
+        assert_eq!(v4.record, rinex.record);
+    }
+    #[test]
+    fn v3_duth0630_v2_conversion_snapshot() {
+        // frozen V2 conversion of DUTH0630.22O (crate output, not validated by third party tools)
+        let snapshot = "../test_resources/OBS/V2/duth0630.22o";
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let v2 = rinex.convert_version(Version::new(2, 11)).unwrap();
+
+        // record is written exactly as in the snapshot
+        let content = v2.to_string().unwrap();
+        let expected = std::fs::read_to_string(snapshot).unwrap();
+        let record = |content: &str| -> Vec<String> {
+            content
+                .lines()
+                .skip_while(|line| !line.contains("END OF HEADER"))
+                .skip(1)
+                .map(|line| line.to_string())
+                .collect()
+        };
+        let (written, expected) = (record(&content), record(&expected));
+        assert_eq!(written.len(), expected.len());
+        for (written, expected) in written.iter().zip(expected.iter()) {
+            assert_eq!(written, expected);
+        }
+
+        // snapshot header lines describe the converted header
+        let parsed = Rinex::from_file(snapshot).unwrap();
+        let (header, expected) = (&parsed.header, &v2.header);
+        assert_eq!(header.version, Version::new(2, 11));
+        assert_eq!(header.rinex_type, expected.rinex_type);
+        assert_eq!(header.constellation, expected.constellation);
+        assert_eq!(header.program, expected.program);
+        assert_eq!(header.run_by, expected.run_by);
+        assert_eq!(header.date, expected.date);
+        assert_eq!(header.observer, expected.observer);
+        assert_eq!(header.agency, expected.agency);
+        assert_eq!(header.geodetic_marker, expected.geodetic_marker);
+        assert_eq!(header.rcvr, expected.rcvr);
+        assert_eq!(header.rcvr_antenna, expected.rcvr_antenna);
+        assert_eq!(header.ground_position, expected.ground_position);
+        assert_eq!(header.sampling_interval, expected.sampling_interval);
+        assert_eq!(
+            header.leap.map(|leap| leap.leap),
+            expected.leap.map(|leap| leap.leap)
+        );
+        let (obs, expected) = (header.obs.as_ref().unwrap(), expected.obs.as_ref().unwrap());
+        assert_eq!(obs.time_of_first_obs, expected.time_of_first_obs);
+        assert_eq!(obs.time_of_last_obs, expected.time_of_last_obs);
+        assert_eq!(obs.clock_offset_applied, expected.clock_offset_applied);
+        for constellation in [Constellation::GPS, Constellation::Glonass] {
+            assert_eq!(
+                obs.codes.get(&constellation),
+                expected.codes.get(&constellation)
+            );
+        }
+        assert_eq!(parsed.record, v2.record);
+    }
+    #[test]
+    fn v3_acor00esp_convert_version() {
+        let rinex =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        match rinex.convert_version(Version::new(2, 11)) {
+            Err(crate::Error::ObservableConversion(version, issues)) => {
+                assert_eq!(version, Version::new(2, 11));
+                // several GPS L2 tracking modes
+                assert!(issues.contains("L2W"), "{}", issues);
+                // BeiDou is not supported in V2
+                assert!(issues.contains("C C2I"), "{}", issues);
+            },
+            other => panic!("conversion should have failed: {:?}", other.map(|_| ())),
+        }
+    }
+    #[test]
+    fn convert_to_version_mut() {
+        let rinex = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+
+        let mut v2 = rinex.clone();
+        v2.convert_to_version_mut(Version::new(2, 11)).unwrap();
+        assert_eq!(v2, rinex.convert_version(Version::new(2, 11)).unwrap());
+
+        v2.convert_to_version_mut(Version::new(3, 5)).unwrap();
+        assert_eq!(v2.header.version, Version::new(3, 5));
+        assert_eq!(v2.record, rinex.record);
+
+        // rejected conversions leave the file untouched
+        let rinex =
+            Rinex::from_file("../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx")
+                .unwrap();
+        let mut converted = rinex.clone();
+        assert!(converted
+            .convert_to_version_mut(Version::new(2, 11))
+            .is_err());
+        assert_eq!(converted, rinex);
+
+        let mut meteo = Rinex::from_file("../test_resources/MET/V2/abvi0010.15m").unwrap();
+        assert!(matches!(
+            meteo.convert_to_version_mut(Version::new(3, 0)),
+            Err(crate::Error::UnsupportedConversion(_, _))
+        ));
+    }
+    #[test]
+    fn v3_duth0630_equipment_changes() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_rcvr_swap.22O").unwrap();
+        let t = Epoch::from_str("2022-03-04T00:15:00 GPST").unwrap();
+
+        let changes = rnx.equipment_changes();
+        assert_eq!(changes.len(), 1);
+        let (epoch, change) = &changes[0];
+        assert_eq!(*epoch, t);
+        assert_eq!(change.flag, EpochFlag::HeaderInformationFollows);
+        let receiver = change.receiver.as_ref().unwrap();
+        assert_eq!(receiver.model, "LEICA GR50");
+        assert_eq!(receiver.sn, "470123");
+        assert_eq!(receiver.firmware, "4.31/7.403");
+        assert!(change.antenna.is_none());
+
+        // header still describes the initial setup
+        assert_eq!(
+            rnx.header.rcvr.as_ref().unwrap().model,
+            "LEICA GRX1200GGPRO"
+        );
+
+        // event is not stored as observations
+        assert!(rnx.epoch().all(|e| e != t));
+        assert_eq!(rnx.epoch().count(), 3);
+        assert_eq!(rnx.epoch_ok().count(), 3);
+
+        // event block is formatted back
+        let tmp_path = "duth0630_rcvr_swap.22O";
+        rnx.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert!(content
+            .lines()
+            .any(|line| line == "> 2022 03 04 00 15  0.0000000  4  1"));
+        assert_eq!(parsed.equipment_changes(), changes);
+        assert_eq!(parsed.record, rnx.record);
+    }
+    #[test]
+    fn v3_duth0630_merged_equipment_changes() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert!(rnx.equipment_changes().is_empty());
+        let t1 = Epoch::from_str("2022-03-04T00:28:30 GPST").unwrap();
+
+        let mut lhs = rnx.clone();
+        lhs.record.as_mut_obs().unwrap().retain(|(t, _), _| *t < t1);
+        let mut rhs = rnx.clone();
+        rhs.record
+            .as_mut_obs()
+            .unwrap()
+            .retain(|(t, _), _| *t >= t1);
+
+        // identical setups: no change
+        let merged = lhs.merge(&rhs).unwrap();
+        assert!(merged.equipment_changes().is_empty());
+
+        // receiver swap between both files
+        if let Some(rcvr) = &mut rhs.header.rcvr {
+            rcvr.model = "LEICA GR50".to_string();
+        }
+        let merged = lhs.merge(&rhs).unwrap();
+        let changes = merged.equipment_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, t1);
+        assert_eq!(changes[0].1.receiver.as_ref().unwrap().model, "LEICA GR50");
+        assert!(changes[0].1.antenna.is_none());
+    }
+    #[test]
+    fn v2_aopr0010_clock_offsets() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010_clk.17o").unwrap();
+        let offsets = rnx.recvr_clock().map(|(_, clk)| clk).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![-0.000123456, 0.000234567, -0.000345678]);
+
+        // offsets are formatted back (F12.9, columns 69-80)
+        let tmp_path = "aopr0010_clk.17o";
+        rnx.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert!(content.lines().any(|line| line.len() == 80
+            && line.starts_with(" 17  1  1  3 33 40.0000000  0  9")
+            && line.ends_with("  0.000234567")));
+        assert_eq!(parsed.record, rnx.record);
+    }
+    #[test]
+    fn v3_vlns0010_clock_offsets() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/VLNS0010_clk.22O").unwrap();
+        let offsets = rnx.recvr_clock().map(|(_, clk)| clk).collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            vec![-0.000123456789, 0.000234567891, -0.000345678912]
+        );
+
+        // round trip
+        let tmp_path = "vlns0010_clk.22O";
+        rnx.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert_eq!(parsed.record, rnx.record);
+
+        // offsets are preserved by merge, split and decimation
+        let t1 = Epoch::from_str("2022-01-01T00:00:30 GPST").unwrap();
+        let (lhs, rhs) = rnx.split(t1).unwrap();
+        assert_eq!(lhs.recvr_clock().count(), 1);
+        assert_eq!(rhs.recvr_clock().count(), 2);
+        let merged = lhs.merge(&rhs).unwrap();
+        assert_eq!(merged.record, rnx.record);
+        let decimated = rnx.decimate_by_ratio(2);
+        assert_eq!(
+            decimated
+                .recvr_clock()
+                .map(|(_, clk)| clk)
+                .collect::<Vec<_>>(),
+            vec![-0.000123456789, -0.000345678912]
+        );
+    }
+    #[test]
+    fn receiver_clock_offsets_injection() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert_eq!(rnx.recvr_clock().count(), 0);
+        let offsets = rnx
+            .epoch()
+            .enumerate()
+            .map(|(i, t)| (t, i as f64 * 1.0E-9))
+            .collect::<BTreeMap<_, _>>();
+        let rnx = rnx.with_receiver_clock_offsets(&offsets);
+        assert_eq!(
+            rnx.recvr_clock().map(|(_, clk)| clk).collect::<Vec<_>>(),
+            vec![0.0, 1.0E-9, 2.0E-9]
+        );
+
+        let tmp_path = "duth0630_clk.22O";
+        rnx.to_file(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert_eq!(parsed.record, rnx.record);
+    }
+    #[test]
+    fn v2_aopr0010_wavelength_factors() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+        let wavelengths = rnx.header.wavelengths.as_ref().unwrap();
+        assert_eq!(wavelengths.default, (1, 1));
+        assert!(wavelengths.sv.is_empty());
+
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010_wlf.17o").unwrap();
+        let wavelengths = rnx.header.wavelengths.as_ref().unwrap();
+        assert_eq!(wavelengths.default, (1, 1));
+        assert_eq!(wavelengths.sv.len(), 10);
+        for prn in [1, 3, 7, 8, 9, 11, 14, 16, 17] {
+            assert_eq!(
+                wavelengths.factors(SV::new(Constellation::GPS, prn)),
+                (1, 2)
+            );
+        }
+        // blank system identifier
+        assert_eq!(wavelengths.factors(sv!("G22")), (2, 2));
+        assert_eq!(wavelengths.factors(sv!("G31")), (1, 1));
+
+        // round trip: continuation lines are generated
+        let tmp_path = "aopr0010_wlf.17o";
+        rnx.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        assert_eq!(
+            content
+                .lines()
+                .filter(|line| line.ends_with("WAVELENGTH FACT L1/2"))
+                .collect::<Vec<_>>(),
+            vec![
+                "     1     1                                                WAVELENGTH FACT L1/2",
+                "     1     2     7   G 1   G 3   G 7   G 8   G 9   G11   G14WAVELENGTH FACT L1/2",
+                "     1     2     2   G16   G17                              WAVELENGTH FACT L1/2",
+                "     2     2     1   G22                                    WAVELENGTH FACT L1/2",
+            ]
+        );
+        assert_eq!(parsed.header.wavelengths, rnx.header.wavelengths);
+
+        // not described in modern RINEX
+        let mut v3 = rnx.clone();
+        v3.header.version = Version::new(3, 5);
+        assert!(!v3.header.to_string().contains("WAVELENGTH FACT"));
+    }
+    #[test]
+    fn v3_duth0630_sv_epoch_presence() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_rcvr_swap.22O").unwrap();
+        let (epochs, presence) = rnx.sv_epoch_presence();
+        // event does not describe any vehicle
+        assert_eq!(epochs, rnx.epoch_ok().collect::<Vec<_>>());
+        assert_eq!(presence.len(), rnx.sv().count());
+        for (sv, presence) in presence.iter() {
+            assert_eq!(presence.len(), epochs.len());
+            for (epoch, present) in epochs.iter().zip(presence.iter()) {
+                let expected = rnx
+                    .sv_epoch()
+                    .any(|(t, svnn)| t == *epoch && svnn.contains(sv));
+                assert_eq!(*present, expected, "{} @ {}", sv, epoch);
+            }
+        }
+    }
+    #[test]
+    fn truncated_files() {
+        for (fixture, marker) in [
+            ("../test_resources/OBS/V2/aopr0010.17o", " 17  1  1"),
+            ("../test_resources/OBS/V3/DUTH0630.22O", ">"),
+        ] {
+            let content = std::fs::read_to_string(fixture).unwrap();
+            let full = Rinex::from_file(fixture).unwrap();
+            assert!(full.warnings().is_empty());
+            let full = full.record.as_obs().unwrap();
+
+            // byte offset of each epoch line
+            let mut epochs = Vec::new();
+            let mut offset = 0;
+            for line in content.split_inclusive('\n') {
+                if line.starts_with(marker) {
+                    epochs.push(offset);
+                }
+                offset += line.len();
+            }
+            epochs.push(content.len());
+
+            let parse = |len: usize, name: &str| {
+                let path = format!("truncated_{}_{}", len, name);
+                std::fs::write(&path, &content[..len]).unwrap();
+                let rnx = Rinex::from_file(&path);
+                let _ = std::fs::remove_file(&path);
+                rnx
+            };
+
+            // mid header
+            let header_len = epochs[0] / 2;
+            let _ = parse(header_len, "header");
+
+            // empty final line
+            let path = format!("trailing_{}", fixture.split('/').last().unwrap());
+            std::fs::write(&path, content.clone() + "\n").unwrap();
+            let rnx = Rinex::from_file(&path);
+            let _ = std::fs::remove_file(&path);
+            assert_eq!(rnx.unwrap().record.as_obs().unwrap(), full);
+
+            for k in 1..epochs.len() - 1 {
+                // epoch line and two observation lines
+                let lines_len = content[epochs[k]..]
+                    .split_inclusive('\n')
+                    .take(3)
+                    .map(|line| line.len())
+                    .sum::<usize>();
+                for (len, case) in [
+                    (epochs[k] + 10, "epoch_line"),
+                    (epochs[k] + lines_len, "lines"),
+                    (epochs[k + 1] - 5, "observations"),
+                ] {
+                    let rnx = parse(len, case).unwrap_or_else(|e| {
+                        panic!("{} truncated @{} ({}): {}", fixture, len, case, e)
+                    });
+                    let record = rnx.record.as_obs().unwrap();
+                    let expected = full
+                        .iter()
+                        .take(k)
+                        .map(|(key, v)| (*key, v.clone()))
+                        .collect::<Record>();
+                    assert_eq!(
+                        record, &expected,
+                        "{} truncated @{} ({})",
+                        fixture, len, case
+                    );
+                    assert_eq!(
+                        rnx.warnings(),
+                        &[ParsingWarning::TruncatedRecord {
+                            last_good_epoch: expected.keys().last().map(|(t, _)| *t),
+                        }],
+                        "{} truncated @{} ({})",
+                        fixture,
+                        len,
+                        case
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn lock_loss_epochs() {
+        let g01 = SV::new(Constellation::GPS, 1);
+        let g02 = SV::new(Constellation::GPS, 2);
+        let l1c = Observable::from_str("L1C").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let t0 = Epoch::from_str("2021-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+
+        let mut record = Record::new();
+        // (epoch index, G02/L1C LLI)
+        for (index, lli) in [
+            (0, None),
+            (1, Some(0x01)),
+            (2, Some(0x02)),
+            (3, Some(0x05)),
+            (4, Some(0x00)),
+        ] {
+            let mut vehicles = BTreeMap::new();
+            vehicles.insert(
+                g01,
+                HashMap::from([(
+                    c1c.clone(),
+                    ObservationData::new(20.0E6, Some(LliFlags::OK_OR_UNKNOWN), None),
+                )]),
+            );
+            vehicles.insert(
+                g02,
+                HashMap::from([
+                    (c1c.clone(), ObservationData::new(21.0E6, None, None)),
+                    (
+                        l1c.clone(),
+                        ObservationData::new(110.0E6, lli.and_then(LliFlags::from_bits), None),
+                    ),
+                ]),
+            );
+            record.insert((t0 + index as f64 * dt, EpochFlag::Ok), (None, vehicles));
+        }
+        let rnx = Rinex::new(
+            Header::basic_obs(),
+            crate::record::Record::ObsRecord(record),
+        );
+        assert_eq!(
+            rnx.lock_loss_epochs().collect::<Vec<_>>(),
+            vec![t0 + dt, t0 + 3.0 * dt]
+        );
+        // nothing to report
+        let rnx = Rinex::from_file("../test_resources/OBS/V2/aopr0010.17o").unwrap();
+        assert_eq!(rnx.lock_loss_epochs().count(), 0);
+    }
+    #[test]
+    fn v3_duth0630_optional_descriptors() {
+        let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630_meta.22O").unwrap();
+        let header = &rnx.header;
+        assert_eq!(header.doi.as_deref(), Some("10.5281/zenodo.1234567"));
+        assert_eq!(
+            header.license.as_deref(),
+            Some("CC BY 4.0, https://creativecommons.org/licenses/by/4.0/")
+        );
+        assert_eq!(header.station_url, "https://www.example.org/stations/duth");
+        let obs = header.obs.as_ref().unwrap();
+        assert_eq!(obs.signal_strength_unit.as_deref(), Some("DBHZ"));
+
+        // round trip
+        let tmp_path = "DUTH0630_meta.22O";
+        rnx.to_file(tmp_path).unwrap();
+        let content = std::fs::read_to_string(tmp_path).unwrap();
+        let parsed = Rinex::from_file(tmp_path).unwrap();
+        let _ = std::fs::remove_file(tmp_path);
+        for line in [
+            "10.5281/zenodo.1234567                                      DOI",
+            "CC BY 4.0, https://creativecommons.org/licenses/by/4.0/     LICENSE OF USE",
+            "https://www.example.org/stations/duth                       STATION INFORMATION",
+            "DBHZ                                                        SIGNAL STRENGTH UNIT",
+        ] {
+            assert!(content.lines().any(|l| l == line), "missing \"{}\"", line);
+        }
+        assert_eq!(parsed.header.doi, header.doi);
+        assert_eq!(parsed.header.license, header.license);
+        assert_eq!(parsed.header.station_url, header.station_url);
+        assert_eq!(
+            parsed.header.obs.as_ref().unwrap().signal_strength_unit,
+            obs.signal_strength_unit
+        );
+
+        // not described in old RINEX
+        let mut v2 = rnx.clone();
+        v2.header.version = Version::new(2, 11);
+        let v2 = v2.header.to_string();
+        for label in [
+            "DOI",
+            "LICENSE OF USE",
+            "STATION INFORMATION",
+            "SIGNAL STRENGTH UNIT",
+        ] {
+            assert!(!v2.lines().any(|l| l.ends_with(label)), "{}", label);
+        }
+    }
+    #[test]
+    fn split_by_physics() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/OBS/V3/DUTH0630.22O";
+        let rinex = Rinex::from_file(&path).unwrap();
+        let subsets = rinex.split_by_physics();
+        assert_eq!(
+            subsets.len(),
+            4,
+            "DUTH0630 has all four kinds of observations"
+        );
+        for (physics, subset) in &subsets {
+            let codes = &subset.header.obs.as_ref().unwrap().codes;
+            assert!(!codes.is_empty());
+            for (constell, codes) in codes {
+                assert!(!codes.is_empty(), "{} has no {:?} codes", constell, physics);
+                for code in codes {
+                    assert_eq!(code.physics(), Some(*physics), "bad header code {}", code);
+                }
+            }
+            assert!(subset.observable().count() > 0);
+            for observable in subset.observable() {
+                assert_eq!(
+                    observable.physics(),
+                    Some(*physics),
+                    "bad observable {}",
+                    observable
+                );
+            }
+        }
+        let pr = &subsets[&ObsPhysics::PseudoRange];
+        let codes = &pr.header.obs.as_ref().unwrap().codes;
+        let gps = codes.get(&Constellation::GPS).unwrap();
+        assert_eq!(
+            gps,
+            &vec![
+                Observable::from_str("C1C").unwrap(),
+                Observable::from_str("C2W").unwrap()
+            ]
+        );
+
+        // not relevant on other RINEX types
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/MET/V2/abvi0010.15m";
+        let rinex = Rinex::from_file(&path).unwrap();
+        assert!(rinex.split_by_physics().is_empty());
+    }
+    #[test]
+    fn user_defined_marker_type() {
+        let content = std::fs::read_to_string("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        // user defined marker types do not prevent parsing
+        for (code, expected) in [
+            ("SPACEBORNE", Some(MarkerType::Spaceborne)),
+            ("ROOFTOP_MAST", None),
+        ] {
+            let marker_type = format!("{:<60}MARKER TYPE\n", code);
+            let content = content.replacen(
+                "12621M001                                                   MARKER NUMBER\n",
+                &format!(
+                    "12621M001                                                   MARKER NUMBER\n{}",
+                    marker_type
+                ),
+                1,
+            );
+            let rinex = Rinex::from_reader(content.as_bytes()).unwrap();
+            assert_eq!(rinex.header.marker_type(), expected, "\"{}\"", code);
+            assert_eq!(rinex.epoch().count(), 3);
+        }
+        // MARKER TYPE does not exist in RINEX2
+        let mut rinex = Rinex::from_file("../test_resources/OBS/V2/AJAC3550.21O").unwrap();
+        if let Some(marker) = &mut rinex.header.geodetic_marker {
+            marker.marker_type = Some(MarkerType::Geodetic);
+        }
+        assert!(!rinex.header.to_string().contains("MARKER TYPE"));
+        rinex.header.version = crate::version::Version::new(3, 5);
+        assert!(rinex.header.to_string().contains("MARKER TYPE"));
+    }
+    #[test]
+    fn v3_merged_file() {
+        let content = std::fs::read_to_string("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        let marker_number =
+            "12621M001                                                   MARKER NUMBER\n";
+        let merged_file =
+            "   2                                                        MERGED FILE\n";
+        let content = content.replacen(
+            marker_number,
+            &format!("{}{}", marker_number, merged_file),
+            1,
+        );
+        let rinex = Rinex::from_reader(content.as_bytes()).unwrap();
+        assert_eq!(rinex.header.merged_files, Some(2));
+
+        // formatting and parsing back
+        let formatted = rinex.to_string().unwrap();
+        assert!(formatted.contains(merged_file));
+        let parsed = Rinex::from_reader(formatted.as_bytes()).unwrap();
+        assert_eq!(parsed.header.merged_files, Some(2));
+
+        // merging counts the files
+        let merged = rinex.merge(&rinex).unwrap();
+        assert_eq!(merged.header.merged_files, Some(4));
+        let duth = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O").unwrap();
+        assert!(duth.header.merged_files.is_none());
+        let merged = duth.merge(&duth).unwrap();
+        assert_eq!(merged.header.merged_files, Some(2));
+
+        // invalid content
+        let content = content.replace(merged_file, &merged_file.replace("   2", "  AB"));
+        assert!(Rinex::from_reader(content.as_bytes()).is_err());
+    }
 }
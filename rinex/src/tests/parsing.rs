@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod test {
+    use crate::header::ParsingError;
     use crate::navigation::NavMsgType;
     use crate::prelude::*;
     use crate::tests::toolkit::is_null_rinex;
@@ -199,4 +200,103 @@ mod test {
             }
         }
     }
+    #[test]
+    fn in_memory_parsing() {
+        let test_resources = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("../test_resources");
+        let mut files = vec![
+            "OBS/V3/DUTH0630.22O",
+            "CRNX/V3/DUTH0630.22D",
+            "NAV/V2/amel0010.21g",
+            "MET/V2/abvi0010.15m",
+        ];
+        if cfg!(feature = "flate2") {
+            // gzip is identified by its magic bytes
+            files.push("NAV/V2/cbw10010.21n.gz");
+        }
+        for file in files {
+            let path = test_resources.join(file);
+            let bytes = std::fs::read(&path).unwrap();
+            let from_bytes = Rinex::from_reader(bytes.as_slice())
+                .unwrap_or_else(|e| panic!("failed to parse \"{}\" from memory: {}", file, e));
+            let from_file = Rinex::from_path(&path).unwrap();
+            assert_eq!(from_bytes.header, from_file.header, "\"{}\"", file);
+            assert_eq!(from_bytes.record, from_file.record, "\"{}\"", file);
+            assert_eq!(from_bytes.comments, from_file.comments, "\"{}\"", file);
+        }
+        #[cfg(not(feature = "flate2"))]
+        {
+            let bytes = [0x1f, 0x8b, 0x08, 0x00];
+            assert!(Rinex::from_reader(&bytes[..]).is_err());
+        }
+    }
+    #[test]
+    fn truncated_headers() {
+        for fixture in [
+            "OBS/V2/AJAC3550.21O",
+            "OBS/V3/DUTH0630.22O",
+            "CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+            "MET/V2/abvi0010.15m",
+            "CLK/V3/USNO1.txt",
+        ] {
+            let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/" + fixture;
+            let content = std::fs::read_to_string(&path).unwrap();
+            let header_len = content.find("END OF HEADER").unwrap();
+            // interrupted at every line, and in the middle of every line
+            let mut cuts = Vec::new();
+            let mut offset = 0;
+            for line in content[..header_len].split_inclusive('\n') {
+                cuts.push(offset + line.len() / 2);
+                offset += line.len();
+                cuts.push(offset);
+            }
+            for len in cuts {
+                let tmp_path = format!("truncated-header-{}", fixture.replace('/', "-"));
+                std::fs::write(&tmp_path, &content[..len]).unwrap();
+                let rinex = Rinex::from_file(&tmp_path);
+                let _ = std::fs::remove_file(&tmp_path);
+                assert!(
+                    matches!(
+                        rinex,
+                        Err(Error::HeaderParsingError(ParsingError::MissingEndOfHeader))
+                    ),
+                    "\"{}\" truncated to {} bytes should not be parsed",
+                    fixture,
+                    len
+                );
+            }
+        }
+    }
+    #[test]
+    fn unterminated_last_line() {
+        for fixture in [
+            "OBS/V2/AJAC3550.21O",
+            "OBS/V3/DUTH0630.22O",
+            "CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+            "NAV/V2/amel0010.21g",
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+            "MET/V2/abvi0010.15m",
+            "CLK/V3/USNO1.txt",
+        ] {
+            let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/" + fixture;
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.ends_with('\n'));
+            let expected = Rinex::from_file(&path).unwrap();
+            assert!(expected.warnings().is_empty(), "\"{}\"", fixture);
+
+            // complete file, without final line feed
+            let tmp_path =
+                std::env::temp_dir().join(format!("unterminated-{}", fixture.replace('/', "-")));
+            let unterminated = content.trim_end_matches(|c| c == '\r' || c == '\n');
+            std::fs::write(&tmp_path, unterminated).unwrap();
+            let rinex = Rinex::from_file(tmp_path.to_str().unwrap());
+            let _ = std::fs::remove_file(&tmp_path);
+            let rinex = rinex.unwrap();
+            assert!(rinex.warnings().is_empty(), "\"{}\"", fixture);
+            assert_eq!(rinex.record, expected.record, "\"{}\"", fixture);
+            assert_eq!(rinex.comments, expected.comments, "\"{}\"", fixture);
+        }
+    }
 }
@@ -1,8 +1,10 @@
 #[cfg(test)]
 mod test {
+    use crate::prelude::SV;
     use crate::tests::toolkit::{random_name, test_against_model};
     use crate::*;
     use std::path::Path;
+    use std::str::FromStr;
     fn testbench(path: &str) {
         println!("running on \"{}\"", path);
         let rnx = Rinex::from_file(path).unwrap(); // already tested elsewhere
@@ -31,6 +33,8 @@ mod test {
         for file in [
             "AJAC3550.21O",
             "aopr0010.17o",
+            "aopr0010_clk.17o",
+            "aopr0010_wlf.17o",
             "barq071q.19o",
             "delf0010.21o",
             "npaz3550.21o",
@@ -128,4 +132,260 @@ mod test {
             testbench(fp.to_str().unwrap());
         }
     }
+    /*
+     * Vehicles of each epoch, in order of appearance,
+     * from the epoch description (V2) or the observation lines (V3)
+     */
+    fn produced_vehicles(content: &str, v2: bool) -> Vec<Vec<SV>> {
+        let body = content.split("END OF HEADER").nth(1).unwrap();
+        let mut epochs = Vec::<Vec<SV>>::new();
+        for line in body.lines() {
+            if v2 {
+                // epoch description and its continuation lines
+                let vehicles = match line.get(32..) {
+                    Some(vehicles) if line.starts_with(" 21 12 21") => {
+                        epochs.push(Vec::new());
+                        vehicles
+                    },
+                    Some(vehicles)
+                        if line.starts_with(&" ".repeat(32))
+                            && vehicles.starts_with(|c: char| c.is_ascii_uppercase()) =>
+                    {
+                        vehicles
+                    },
+                    _ => continue,
+                };
+                let vehicles = vehicles.get(..36).unwrap_or(vehicles);
+                for index in (0..vehicles.len()).step_by(3) {
+                    if let Some(sv) = vehicles.get(index..index + 3) {
+                        epochs.last_mut().unwrap().push(SV::from_str(sv).unwrap());
+                    }
+                }
+            } else if line.starts_with('>') {
+                epochs.push(Vec::new());
+            } else if let Some(sv) = line.get(..3).and_then(|sv| SV::from_str(sv).ok()) {
+                epochs.last_mut().unwrap().push(sv);
+            }
+        }
+        epochs
+    }
+    /*
+     * System letters of a list of vehicles, in order of appearance
+     */
+    fn system_letters(vehicles: &[SV]) -> String {
+        let mut letters = String::new();
+        for sv in vehicles {
+            let letter = format!("{:x}", sv).chars().next().unwrap();
+            if !letters.ends_with(letter) {
+                letters.push(letter);
+            }
+        }
+        letters
+    }
+    #[test]
+    fn deterministic_production() {
+        for file in [
+            "OBS/V2/rovn0010.21o",
+            "OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx",
+            "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx",
+        ] {
+            let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/" + file;
+            let rnx = Rinex::from_file(&path).unwrap();
+            // same record produced twice: identical bytes
+            let (first, second) = (
+                std::env::temp_dir().join(format!("test-{}.rnx", random_name(5))),
+                std::env::temp_dir().join(format!("test-{}.rnx", random_name(5))),
+            );
+            rnx.to_file(first.to_str().unwrap()).unwrap();
+            rnx.to_file(second.to_str().unwrap()).unwrap();
+            let (first_content, second_content) = (
+                std::fs::read(&first).unwrap(),
+                std::fs::read(&second).unwrap(),
+            );
+            let _ = std::fs::remove_file(&first);
+            let _ = std::fs::remove_file(&second);
+            assert_eq!(first_content, second_content, "\"{}\"", file);
+        }
+    }
+    #[test]
+    fn vehicle_production_order() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned()
+            + "/../test_resources/OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx";
+        let rnx = Rinex::from_file(&path).unwrap();
+
+        // V3: observation lines follow the G, R, E, C, J, S, I system order, then PRN
+        let content = rnx.to_string().unwrap();
+        let epochs = produced_vehicles(&content, false);
+        assert_eq!(epochs.len(), 25);
+        for vehicles in epochs {
+            assert_eq!(system_letters(&vehicles), "GREC", "{:?}", vehicles);
+            assert!(
+                vehicles
+                    .windows(2)
+                    .all(|w| formatter::sv_order(&w[0]) < formatter::sv_order(&w[1])),
+                "{:?}",
+                vehicles
+            );
+        }
+
+        // V2: same order in the epoch description
+        let mut v2 = rnx.clone();
+        v2.header.version = Version::new(2, 11);
+        let content = v2.to_string().unwrap();
+        let epochs = produced_vehicles(&content, true);
+        assert_eq!(epochs.len(), 25);
+        for (vehicles, (_, (_, record))) in epochs.iter().zip(rnx.observation()) {
+            assert_eq!(vehicles.len(), record.len());
+            assert_eq!(system_letters(vehicles), "GREC", "{:?}", vehicles);
+            assert!(
+                vehicles
+                    .windows(2)
+                    .all(|w| formatter::sv_order(&w[0]) < formatter::sv_order(&w[1])),
+                "{:?}",
+                vehicles
+            );
+        }
+
+        // NAV: frames of an epoch follow the same order, whatever the parsing order
+        let mut nav = Rinex::from_file(
+            &(env!("CARGO_MANIFEST_DIR").to_owned()
+                + "/../test_resources/NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx"),
+        )
+        .unwrap();
+        let record = nav.record.as_mut_nav().unwrap();
+        let (_, frames) = record.iter_mut().next().unwrap();
+        for sv in ["E05", "R05", "G05"] {
+            let mut frame = frames[0].clone();
+            if let NavFrame::Eph(_, vehicle, _) = &mut frame {
+                *vehicle = SV::from_str(sv).unwrap();
+            }
+            frames.push(frame);
+        }
+        let content = nav.to_string().unwrap();
+        let body = content.split("END OF HEADER").nth(1).unwrap();
+        let vehicles = body
+            .lines()
+            .filter_map(|line| SV::from_str(line.get(..3)?).ok())
+            .take(4)
+            .collect::<Vec<_>>();
+        assert_eq!(system_letters(&vehicles), "GREC", "{:?}", vehicles);
+    }
+    #[test]
+    fn in_memory_production() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/OBS/V2/rovn0010.21o";
+        let rnx = Rinex::from_file(&path).unwrap();
+        let content = rnx.to_string().unwrap();
+        assert!(content.contains("END OF HEADER"));
+
+        let parsed = Rinex::from_reader(content.as_bytes()).unwrap();
+        if parsed != rnx {
+            test_against_model(&parsed, &rnx, &path, 1.0E-6);
+        }
+        // identical to file production
+        let tmp_path = format!("test-{}.rnx", random_name(5));
+        rnx.to_file(&tmp_path).unwrap();
+        let file_content = std::fs::read_to_string(&tmp_path).unwrap();
+        let _ = std::fs::remove_file(&tmp_path);
+        assert_eq!(content, file_content);
+    }
+    #[test]
+    fn validation_issues() {
+        let resources = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/";
+        let tmp_path = format!("test-{}.rnx", random_name(5));
+
+        let obs = Rinex::from_file(&(resources.clone() + "OBS/V3/DUTH0630.22O")).unwrap();
+        assert!(obs.validate().is_empty());
+
+        // missing constellation (OBS)
+        let mut rnx = obs.clone();
+        rnx.header.constellation = None;
+        assert_eq!(
+            rnx.validate(),
+            vec![ValidationIssue::MissingConstellation(
+                types::Type::ObservationData
+            )]
+        );
+        match rnx.to_file(&tmp_path) {
+            Err(FormattingError::InvalidContent(issue)) => assert_eq!(
+                issue,
+                ValidationIssue::MissingConstellation(types::Type::ObservationData)
+            ),
+            _ => panic!("should have failed on missing constellation"),
+        }
+        assert!(!Path::new(&tmp_path).exists(), "nothing should be written");
+        // header formatting remains possible
+        let header = rnx.header.to_string();
+        assert!(header.contains("OBSERVATION DATA"));
+
+        // missing observables
+        let mut rnx = obs.clone();
+        rnx.header.obs = None;
+        assert_eq!(rnx.validate(), vec![ValidationIssue::MissingObservables]);
+        let mut rnx = obs.clone();
+        if let Some(obs) = &mut rnx.header.obs {
+            obs.codes.clear();
+        }
+        assert_eq!(rnx.validate(), vec![ValidationIssue::MissingObservables]);
+
+        // record/header mismatch
+        let mut rnx = obs.clone();
+        rnx.header.rinex_type = types::Type::NavigationData;
+        assert_eq!(
+            rnx.validate(),
+            vec![ValidationIssue::RecordTypeMismatch(
+                types::Type::NavigationData
+            )]
+        );
+        assert!(rnx.to_file(&tmp_path).is_err());
+
+        // missing constellation (NAV)
+        let mut nav =
+            Rinex::from_file(&(resources.clone() + "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx"))
+                .unwrap();
+        assert!(nav.validate().is_empty());
+        nav.header.constellation = None;
+        assert_eq!(
+            nav.validate(),
+            vec![ValidationIssue::MissingConstellation(
+                types::Type::NavigationData
+            )]
+        );
+        let header = nav.header.to_string();
+        assert!(header.contains("NAVIGATION DATA"));
+
+        // missing observables (METEO)
+        let meteo = Rinex::from_file(&(resources.clone() + "MET/V2/abvi0010.15m")).unwrap();
+        assert!(meteo.validate().is_empty());
+        let mut rnx = meteo.clone();
+        rnx.header.meteo = None;
+        assert_eq!(
+            rnx.validate(),
+            vec![ValidationIssue::MissingMeteoObservables]
+        );
+        let mut rnx = meteo.clone();
+        if let Some(meteo) = &mut rnx.header.meteo {
+            meteo.codes.clear();
+        }
+        assert_eq!(
+            rnx.validate(),
+            vec![ValidationIssue::MissingMeteoObservables]
+        );
+        match rnx.to_file(&tmp_path) {
+            Err(FormattingError::InvalidContent(issue)) => {
+                assert_eq!(issue, ValidationIssue::MissingMeteoObservables)
+            },
+            _ => panic!("should have failed on missing meteo observables"),
+        }
+
+        // unsupported formats
+        let atx =
+            Rinex::from_file(&(resources.clone() + "ATX/V1/TROSAR25.R4__LEIT_2020_09_23.atx"))
+                .unwrap();
+        assert_eq!(
+            atx.validate(),
+            vec![ValidationIssue::UnsupportedType(types::Type::AntennaData)]
+        );
+        assert!(atx.header.to_string().contains("ANTEX VERSION / SYST"));
+        assert!(!Path::new(&tmp_path).exists(), "nothing should be written");
+    }
 }
@@ -2,6 +2,7 @@
 mod sampling {
     use crate::prelude::*;
     use crate::preprocessing::*;
+    use crate::GapAnalysis;
     use itertools::Itertools;
     use std::path::Path;
     use std::str::FromStr;
@@ -132,4 +133,232 @@ mod sampling {
         let rinex = Rinex::from_file(&path).unwrap();
         assert!(!rinex.steady_sampling());
     }
+    #[test]
+    fn sampling_interval() {
+        let prefix = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/";
+
+        // header INTERVAL
+        let rinex =
+            Rinex::from_file(&(prefix.clone() + "OBS/V3/ACOR00ESP_R_20213550000_01D_30S_MO.rnx"))
+                .unwrap();
+        assert_eq!(
+            rinex.sampling_interval(),
+            Some(Duration::from_seconds(30.0))
+        );
+
+        // header INTERVAL prevails
+        let mut rinex = Rinex::from_file(&(prefix.clone() + "MET/V2/abvi0010.15m")).unwrap();
+        assert_eq!(
+            rinex.sampling_interval(),
+            Some(Duration::from_seconds(60.0))
+        );
+        rinex.header.sampling_interval = Some(Duration::from_seconds(30.0));
+        assert_eq!(
+            rinex.sampling_interval(),
+            Some(Duration::from_seconds(30.0))
+        );
+
+        // NAV: most frequent interval
+        #[cfg(feature = "flate2")]
+        {
+            let rinex = Rinex::from_file(&(prefix.clone() + "NAV/V2/ijmu3650.21n.gz")).unwrap();
+            assert!(rinex.header.sampling_interval.is_none());
+            assert_eq!(
+                rinex.sampling_interval(),
+                Some(Duration::from_seconds(16.0))
+            );
+        }
+
+        // single epoch
+        let rinex = Rinex::from_file(&(prefix.clone() + "NAV/V2/dlf10010.21g")).unwrap();
+        assert_eq!(rinex.epoch().count(), 1);
+        assert!(rinex.sampling_interval().is_none());
+
+        // OBS: events are not considered
+        let mut rinex =
+            Rinex::from_file(&(prefix.clone() + "OBS/V3/DUTH0630_rcvr_swap.22O")).unwrap();
+        rinex.header.sampling_interval = None;
+        assert_eq!(
+            rinex.sampling_interval(),
+            Some(Duration::from_seconds(28.0 * 60.0 + 30.0))
+        );
+    }
+    #[test]
+    fn gap_analysis() {
+        let prefix = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/";
+
+        // OBS: two gaps, events are not considered
+        let rinex = Rinex::from_file(&(prefix.clone() + "OBS/V3/DUTH0630_rcvr_swap.22O")).unwrap();
+        let analysis = rinex.gap_analysis(None);
+        let t0 = Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap();
+        let gap = Duration::from_seconds(28.0 * 60.0 + 30.0);
+        assert_eq!(analysis.nominal, Some(Duration::from_seconds(30.0)));
+        assert_eq!(analysis.span, 2.0 * gap);
+        assert_eq!(analysis.epochs, 3);
+        assert_eq!(analysis.gaps, 2);
+        assert_eq!(analysis.cumulative_gap, 2.0 * gap);
+        assert_eq!(analysis.largest_gap, Some((t0, gap)));
+        assert!((analysis.completeness - 3.0 / 115.0).abs() < 1.0E-9);
+        assert_eq!(rinex.largest_data_gap_duration(), Some(gap));
+
+        // nominal interval prevails
+        let analysis = rinex.gap_analysis(Some(gap));
+        assert_eq!(analysis.gaps, 0);
+        assert_eq!(analysis.cumulative_gap, Duration::ZERO);
+        assert!(analysis.largest_gap.is_none());
+        assert_eq!(analysis.completeness, 1.0);
+
+        // METEO: two gaps larger than 4 hours
+        let rinex = Rinex::from_file(&(prefix.clone() + "MET/V2/abvi0010.15m")).unwrap();
+        let analysis = rinex.gap_analysis(Some(Duration::from_hours(4.0)));
+        assert_eq!(analysis.gaps, 2);
+        assert_eq!(
+            analysis.cumulative_gap,
+            Duration::from_seconds((8.0 + 10.0) * 3600.0 + (51.0 + 21.0) * 60.0)
+        );
+        assert_eq!(
+            analysis.largest_gap,
+            Some((
+                Epoch::from_str("2015-01-01T09:04:00 UTC").unwrap(),
+                Duration::from_seconds(10.0 * 3600.0 + 21.0 * 60.0)
+            ))
+        );
+        // identical to the data gaps iterator
+        let analysis = rinex.gap_analysis(None);
+        assert_eq!(analysis.gaps, rinex.data_gaps(None).count());
+        assert_eq!(
+            analysis.cumulative_gap,
+            rinex
+                .data_gaps(None)
+                .fold(Duration::ZERO, |total, (_, dt)| total + dt)
+        );
+
+        // not relevant
+        let rinex =
+            Rinex::from_file(&(prefix.clone() + "NAV/V3/AMEL00NLD_R_20210010000_01D_MN.rnx"))
+                .unwrap();
+        assert_eq!(rinex.gap_analysis(None), GapAnalysis::default());
+    }
+    #[test]
+    fn resampling() {
+        use crate::clock::{ClockKey, ClockProfile, ClockProfileType, ClockType};
+        use std::collections::{BTreeMap, HashMap};
+        let t0 = Epoch::from_str("2022-01-01T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let (c1c, l1c, s1c) = (
+            Observable::from_str("C1C").unwrap(),
+            Observable::from_str("L1C").unwrap(),
+            Observable::from_str("S1C").unwrap(),
+        );
+        let code = |x: f64| 2.0E7 + 100.0 * x;
+        let phase = |x: f64| 1.0E8 + 500.0 * x;
+        let bias = |x: f64| 1.0E-4 + 1.0E-9 * x + 1.0E-12 * x * x;
+
+        // synthetic OBS: 10 epochs at 30s, 8th epoch is missing
+        let mut obs = crate::observation::Record::new();
+        for k in (0..10).filter(|k| *k != 7) {
+            let t = t0 + dt * k as f64;
+            let x = (t - t0).to_seconds();
+            let mut observations = HashMap::new();
+            observations.insert(c1c.clone(), ObservationData::new(code(x), None, None));
+            observations.insert(
+                l1c.clone(),
+                ObservationData::new(phase(x), Some(LliFlags::OK_OR_UNKNOWN), None),
+            );
+            observations.insert(
+                s1c.clone(),
+                ObservationData::new(40.0 + k as f64, None, None),
+            );
+            let mut vehicles = BTreeMap::new();
+            vehicles.insert(g01, observations);
+            obs.insert((t, EpochFlag::Ok), (None, vehicles));
+        }
+        let resampled = obs.resample(Duration::from_seconds(10.0), InterpMethod::Linear);
+        assert_eq!(resampled.len(), 28, "missing epoch should be bridged");
+        for ((t, flag), (_, vehicles)) in &resampled {
+            assert!(flag.is_ok());
+            let x = (*t - t0).to_seconds();
+            let observations = vehicles.get(&g01).unwrap();
+            let c = observations.get(&c1c).unwrap().obs;
+            assert!((c - code(x)).abs() < 1.0E-6, "C1C@{} {} {}", t, c, code(x));
+            let l = observations.get(&l1c).unwrap();
+            assert!((l.obs - phase(x)).abs() < 1.0E-6, "L1C@{}", t);
+            assert_eq!(l.lli, Some(LliFlags::OK_OR_UNKNOWN));
+            // SSI: nearest sample
+            let s = observations.get(&s1c).unwrap().obs;
+            assert_eq!(s.fract(), 0.0, "S1C@{} is not interpolated", t);
+        }
+        // twice the nominal period is bridged: not larger gaps
+        let resampled = obs.resample_with_max_gap(
+            Duration::from_seconds(10.0),
+            InterpMethod::Linear,
+            Duration::from_seconds(30.0),
+        );
+        assert_eq!(resampled.len(), 28 - 5, "gap should not be bridged");
+
+        // synthetic CLK: quadratic bias is exactly recovered by Lagrange(3)
+        let key = ClockKey {
+            clock_type: ClockType::SV(g01),
+            profile_type: ClockProfileType::AS,
+        };
+        let mut clk = crate::clock::Record::new();
+        for k in 0..6 {
+            let t = t0 + Duration::from_seconds(300.0) * k as f64;
+            let mut keys = BTreeMap::new();
+            keys.insert(
+                key.clone(),
+                ClockProfile {
+                    bias: bias((t - t0).to_seconds()),
+                    ..Default::default()
+                },
+            );
+            clk.insert(t, keys);
+        }
+        let resampled = clk.resample(Duration::from_seconds(30.0), InterpMethod::Lagrange(3));
+        assert_eq!(resampled.len(), 51);
+        for (t, keys) in &resampled {
+            let b = keys.get(&key).unwrap().bias;
+            let expected = bias((*t - t0).to_seconds());
+            assert!(
+                (b - expected).abs() < 1.0E-15,
+                "bias@{} {} {}",
+                t,
+                b,
+                expected
+            );
+        }
+        // linear method: error on the midpoint is driven by curvature
+        let resampled = clk.resample(Duration::from_seconds(150.0), InterpMethod::Linear);
+        let t = t0 + Duration::from_seconds(150.0);
+        let b = resampled.get(&t).unwrap().get(&key).unwrap().bias;
+        let expected = bias(150.0) + 1.0E-12 * 150.0 * 150.0;
+        assert!((b - expected).abs() < 1.0E-15);
+        // null interval: untouched
+        assert_eq!(clk.resample(Duration::ZERO, InterpMethod::Linear), clk);
+
+        // METEO file
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/MET/V2/abvi0010.15m";
+        let rinex = Rinex::from_file(&path).unwrap();
+        let resampled = rinex.resample(Duration::from_seconds(30.0), InterpMethod::Linear);
+        assert_eq!(
+            resampled.header.sampling_interval,
+            Some(Duration::from_seconds(30.0))
+        );
+        let midpoint = rinex.first_epoch().unwrap() + Duration::from_seconds(30.0);
+        let (first, second) = (
+            rinex.meteo().next().unwrap().1,
+            rinex.meteo().nth(1).unwrap().1,
+        );
+        let resampled = resampled
+            .meteo()
+            .find(|(t, _)| **t == midpoint)
+            .map(|(_, observations)| observations)
+            .unwrap();
+        for (observable, value) in resampled {
+            let expected = (first[observable] + second[observable]) / 2.0;
+            assert!((value - expected).abs() < 1.0E-9, "{}", observable);
+        }
+        assert_eq!(rinex.resample(Duration::ZERO, InterpMethod::Linear), rinex);
+    }
 }
@@ -0,0 +1,37 @@
+//! RINEX content validation, prior formatting
+use crate::types::Type;
+use thiserror::Error;
+
+/// [ValidationIssue]s describe inconsistent [crate::Rinex] content
+/// that would not be formatted correctly. See [crate::Rinex::validate].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// NAV, OBS (and CRINEX) headers require a constellation definition
+    #[error("{0} requires a constellation definition")]
+    MissingConstellation(Type),
+    /// OBS header does not specify any observable
+    #[error("observation header does not define any observable")]
+    MissingObservables,
+    /// METEO header does not specify any observable
+    #[error("meteo header does not define any observable")]
+    MissingMeteoObservables,
+    /// Record does not match the [Type] declared in the header
+    #[error("record does not match {0} header")]
+    RecordTypeMismatch(Type),
+    /// Formatting is not supported for this [Type]
+    #[error("{0} formatting is not supported")]
+    UnsupportedType(Type),
+}
+
+/// Errors that may arise when formatting [crate::Rinex] content
+#[derive(Error, Debug)]
+pub enum FormattingError {
+    #[error("file i/o error")]
+    IoError(#[from] std::io::Error),
+    #[error("invalid content: {0}")]
+    InvalidContent(#[from] ValidationIssue),
+    #[error("navigation epoch formatting error: {0}")]
+    NavEpochError(#[from] crate::navigation::Error),
+    #[error("meteo epoch formatting error: {0}")]
+    MeteoEpochError(#[from] crate::meteo::record::Error),
+}
@@ -51,7 +51,7 @@ fn main() -> Result<(), Error> {
     // output path
     let output_path = match cli.output_path() {
         Some(path) => path.clone(), // use customized name
-        _ => rinex.standard_filename(cli.matches.get_flag("short"), None, None),
+        _ => rinex.standard_filename(cli.matches.get_flag("short"), None, None, None),
     };
 
     rinex.to_file(&output_path)?;